@@ -0,0 +1,32 @@
+use crate::{tauri_events, AppState};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use tracker_core::config_files::ConfigFile;
+
+/// Jak často se kontroluje čas změny hlídaných souborů
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+fn modified(file: ConfigFile) -> Option<SystemTime> {
+    std::fs::metadata(file.path()).and_then(|m| m.modified()).ok()
+}
+
+/// Hlídá soubory na pozadí - po změně (i smazání) je tracker ověří a načte znovu.
+/// Existující soubory se ověří hned po startu.
+pub fn spawn(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let events = tauri_events::events(&app);
+        let mut seen: [Option<SystemTime>; 2] = [None; 2];
+        loop {
+            for (i, file) in ConfigFile::ALL.into_iter().enumerate() {
+                let current = modified(file);
+                if current != seen[i] {
+                    seen[i] = current;
+                    let tracker = app.state::<AppState>().tracker.clone();
+                    tracker.reload_config_file(&events, file).await;
+                }
+            }
+            tokio::time::sleep(WATCH_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,129 @@
+use crate::{tauri_events, AppState};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tracker_core::hotkey_bindings::{HotkeyAction, HotkeyBindings};
+
+/// Zapnuté zkratky rozparsované, chyba = neplatná zkratka nebo jedna zkratka pro dvě akce
+fn parse(bindings: &HotkeyBindings) -> Result<Vec<(HotkeyAction, Shortcut)>, String> {
+    let mut parsed: Vec<(HotkeyAction, Shortcut)> = Vec::new();
+    for action in HotkeyAction::ALL {
+        let Some(binding) = bindings.binding(action) else {
+            continue;
+        };
+        let shortcut = Shortcut::from_str(binding)
+            .map_err(|e| format!("Neplatná zkratka '{}' ({}): {}", binding, action.label(), e))?;
+        if let Some((other, _)) = parsed.iter().find(|(_, s)| *s == shortcut) {
+            return Err(format!("Zkratka '{}' je nastavená pro {} i {}", binding, other.label(), action.label()));
+        }
+        parsed.push((action, shortcut));
+    }
+    Ok(parsed)
+}
+
+/// Ověří zkratky z nastavení
+pub fn validate(bindings: &HotkeyBindings) -> Result<(), String> {
+    parse(bindings).map(|_| ())
+}
+
+/// Zaregistrované zkratky - handler podle nich pozná akci
+static REGISTERED: Mutex<Vec<(HotkeyAction, Shortcut)>> = Mutex::new(Vec::new());
+
+/// Zaregistruje zkratky v systému (předchozí zruší). Zkratku obsazenou jinou
+/// aplikací přeskočí a vrátí chybu, ostatní zůstanou funkční.
+pub fn apply(app: &AppHandle, bindings: &HotkeyBindings) -> Result<(), String> {
+    let parsed = parse(bindings)?;
+    let manager = app.global_shortcut();
+    manager
+        .unregister_all()
+        .map_err(|e| format!("Nelze zrušit klávesové zkratky: {}", e))?;
+
+    let mut registered = Vec::new();
+    let mut failed = Vec::new();
+    for (action, shortcut) in parsed {
+        match manager.register(shortcut) {
+            Ok(()) => registered.push((action, shortcut)),
+            Err(e) => failed.push(format!("{} ({})", action.label(), e)),
+        }
+    }
+    if let Ok(mut current) = REGISTERED.lock() {
+        *current = registered;
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Klávesové zkratky nezaregistrovány: {}", failed.join(", ")))
+    }
+}
+
+/// Stisk zkratky - stejné metody trackeru jako tlačítka v UI a menu v liště
+pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = REGISTERED
+        .lock()
+        .ok()
+        .and_then(|registered| registered.iter().find(|(_, s)| s == shortcut).map(|(a, _)| *a));
+    let Some(action) = action else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let tracker = app.state::<AppState>().tracker.clone();
+        let events = tauri_events::events(&app);
+        let result = match action {
+            HotkeyAction::ToggleTracking => {
+                if tracker.get_tracking_status().await.running {
+                    tracker.stop(events).await
+                } else {
+                    tracker.start(events).await
+                }
+            }
+            HotkeyAction::Rescan => tracker.rescan_now().await,
+            HotkeyAction::QuickSwitch => events.show_main_window().map(|_| {
+                let _ = app.emit("quick-switcher", ());
+            }),
+        };
+        if let Err(e) = result {
+            tracing::warn!(?action, error = %e, "akce klávesové zkratky selhala");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings() {
+        let defaults = parse(&HotkeyBindings::default()).unwrap();
+        assert_eq!(defaults.len(), 3);
+
+        // Prázdná zkratka akci vypne
+        let bindings = HotkeyBindings {
+            rescan: Some(String::new()),
+            quick_switch: Some("Alt+Space".to_string()),
+            ..Default::default()
+        };
+        let parsed = parse(&bindings).unwrap();
+        assert_eq!(
+            parsed.iter().map(|(a, _)| *a).collect::<Vec<_>>(),
+            vec![HotkeyAction::ToggleTracking, HotkeyAction::QuickSwitch]
+        );
+
+        let duplicate = HotkeyBindings {
+            rescan: Some("CommandOrControl+Shift+T".to_string()),
+            ..Default::default()
+        };
+        assert!(validate(&duplicate).unwrap_err().contains("i okamžitá analýza"));
+        let invalid = HotkeyBindings {
+            toggle_tracking: Some("Ctrl+".to_string()),
+            ..Default::default()
+        };
+        assert!(validate(&invalid).is_err());
+    }
+}
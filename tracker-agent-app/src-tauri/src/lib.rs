@@ -1,33 +1,41 @@
-mod freelo;
-mod screenshot;
-mod tracker;
-mod ocr;
-mod text_matcher;
-mod ai_matcher;
-
-use serde::{Deserialize, Serialize};
+mod file_watch;
+mod hotkeys;
+mod tauri_events;
+mod tray;
+
+use serde::Serialize;
+use std::path::Path;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
-use tracker::{Tracker, TrackerConfig};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+use tauri_events::events;
+use tracker_core::settings::Settings;
+use tracker_core::tracker::{Tracker, TrackerConfig};
+use tracker_core::{
+    ai_matcher, ai_usage, backend, comparison, config_check, connection_check, debug_artifacts, demo, errors,
+    evaluation, export, freelo, history, matcher_stats, notifications, ocr, offline, profiles, recovery, resources,
+    review, settings, summary, task_budget, task_rules, tessdata, tracker, verification,
+};
 
 // --- Data Structures ---
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Settings {
-    interval: u64,
-    freelo_email: String,
-    freelo_key: String,
-    openrouter_key: Option<String>,
-}
-
 #[derive(Debug, Clone, Serialize)]
 struct LogEvent {
     level: String,
     message: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+struct HealthReport {
+    ocr: ocr::OcrHealth,
+}
+
 // --- Application State ---
 
+/// Jak dlouho smí při ukončení aplikace trvat zastavení běžícího segmentu
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 struct AppState {
     tracker: Arc<Tracker>,
 }
@@ -39,7 +47,7 @@ async fn start_tracking(
     state: tauri::State<'_, AppState>,
     app: AppHandle,
 ) -> Result<(), String> {
-    state.tracker.start(app).await
+    state.tracker.start(events(&app)).await
 }
 
 #[tauri::command]
@@ -47,7 +55,31 @@ async fn stop_tracking(
     state: tauri::State<'_, AppState>,
     app: AppHandle,
 ) -> Result<(), String> {
-    state.tracker.stop(app).await
+    state.tracker.stop(events(&app)).await
+}
+
+/// Ukázkový režim bez přihlašovacích údajů - vestavěné tasky, nahrané obrazovky a vlastní profil
+#[tauri::command]
+async fn start_demo(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<(), String> {
+    let config = TrackerConfig {
+        interval_seconds: demo::DEMO_INTERVAL_SECONDS,
+        backend: backend::BackendConfig::Demo,
+        profile: demo::DEMO_PROFILE.to_string(),
+        ..Settings::default().tracker_config()?
+    };
+    state.tracker.start_demo(events(&app), config).await
+}
+
+/// Pozastaví tracking bez ukončení smyčky (Freelo timer se zastaví)
+#[tauri::command]
+async fn pause_tracking(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<(), String> {
+    state.tracker.pause(&events(&app)).await
+}
+
+/// Obnoví pozastavený tracking na přerušeném tasku
+#[tauri::command]
+async fn resume_tracking(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<(), String> {
+    state.tracker.resume(&events(&app)).await
 }
 
 #[tauri::command]
@@ -56,15 +88,36 @@ async fn save_settings(
     settings: Settings,
     app: AppHandle,
 ) -> Result<(), String> {
-    // Convert to TrackerConfig
-    let config = TrackerConfig {
-        interval_seconds: settings.interval,
-        freelo_email: settings.freelo_email.clone(),
-        freelo_api_key: settings.freelo_key.clone(),
-        openrouter_api_key: settings.openrouter_key.clone(),
-    };
+    settings.validate()?;
+    hotkeys::validate(&settings.hotkeys)?;
+    let config = settings.tracker_config()?;
+    Tracker::apply_focus_calendar(&config)?;
+    notifications::set_desktop(config.desktop_notifications);
+    // Trénovací data potřebuje jen Tesseract
+    if config.ocr_engine.resolve() == ocr::OcrEngineKind::Tesseract {
+        download_missing_languages(&app, &config.ocr_languages);
+    }
+    let debug_root = settings
+        .debug_dir
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+        .map(std::path::PathBuf::from);
+    let migrated = debug_artifacts::set_debug_root(debug_root)?;
+    if migrated > 0 {
+        app.emit("log-event", LogEvent {
+            level: "info".to_string(),
+            message: format!("📁 Debug artefakty přesunuty do {:?} ({} souborů)", debug_artifacts::debug_root(), migrated),
+        }).map_err(|e| e.to_string())?;
+    }
 
     state.tracker.set_config(config).await;
+    settings::save(&settings)?;
+    if let Err(e) = hotkeys::apply(&app, &settings.hotkeys) {
+        app.emit("log-event", LogEvent {
+            level: "warning".to_string(),
+            message: format!("⌨️  {}", e),
+        }).map_err(|e| e.to_string())?;
+    }
 
     // Emit log event
     app.emit("log-event", LogEvent {
@@ -75,29 +128,837 @@ async fn save_settings(
     Ok(())
 }
 
+/// Nastavení uložené na disku (None = první spuštění)
+#[tauri::command]
+async fn load_settings() -> Result<Option<Settings>, String> {
+    Ok(settings::load())
+}
+
+/// Katalog modelů OpenRouter pro výběr AI modelu v nastavení
+#[tauri::command]
+async fn list_available_models() -> Result<Vec<ai_matcher::AiModel>, String> {
+    ai_matcher::list_models().await
+}
+
+/// Ověření nastavení a přihlašovacích údajů z formuláře (před uložením i startem trackingu)
+#[tauri::command]
+async fn test_connection(settings: Settings) -> Result<connection_check::ConnectionReport, String> {
+    Ok(connection_check::check(&settings).await)
+}
+
+#[tauri::command]
+async fn switch_task(
+    state: tauri::State<'_, AppState>,
+    task_id: i32,
+    app: AppHandle,
+) -> Result<(), String> {
+    state.tracker.switch_task(events(&app), task_id).await
+}
+
+/// Načtené tasky pro rychlý výběr tasku
+#[tauri::command]
+async fn get_tasks(state: tauri::State<'_, AppState>) -> Result<Vec<freelo::FreeloTask>, String> {
+    Ok(state.tracker.get_tasks().await)
+}
+
+/// Okamžitá analýza obrazovky (jinak až při dalším ticku)
+#[tauri::command]
+async fn rescan_now(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.tracker.rescan_now().await
+}
+
+/// Ruční obnova seznamu tasků z Freelo (nové tasky se hned matchují)
+#[tauri::command]
+async fn refresh_tasks(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<tracker::TasksUpdate, String> {
+    state.tracker.refresh_tasks(&events(&app)).await
+}
+
+/// Projekty ve Freelo s tasklisty (výběr projektů, do kterých se matchuje)
+#[tauri::command]
+async fn get_projects(state: tauri::State<'_, AppState>) -> Result<Vec<freelo::FreeloProject>, String> {
+    state.tracker.get_projects().await
+}
+
+/// Aktivní tasklisty projektu ve Freelo
+#[tauri::command]
+async fn get_tasklists(state: tauri::State<'_, AppState>, project_id: i32) -> Result<Vec<freelo::Tasklist>, String> {
+    state.tracker.get_tasklists(project_id).await
+}
+
+/// Aktuální chyby pro banner (např. po znovunačtení okna)
+#[tauri::command]
+fn get_active_errors() -> Vec<errors::ErrorEvent> {
+    errors::active()
+}
+
+/// Uživatel banner zavřel - při opakování chyby se zobrazí znovu
+#[tauri::command]
+fn dismiss_error(dedup_key: String) {
+    errors::resolve(&dedup_key);
+}
+
+/// Šablony poznámek podle tasku (task_id → šablona)
+#[tauri::command]
+async fn get_note_templates(
+    state: tauri::State<'_, AppState>,
+) -> Result<std::collections::BTreeMap<i32, String>, String> {
+    Ok(state.tracker.get_note_templates().await)
+}
+
+/// Nastaví šablonu poznámky tasku (např. "Sprint 14 — {activity}"), prázdná = smazat
+#[tauri::command]
+async fn set_note_template(
+    state: tauri::State<'_, AppState>,
+    task_id: i32,
+    template: Option<String>,
+) -> Result<std::collections::BTreeMap<i32, String>, String> {
+    state.tracker.set_note_template(task_id, template.as_deref()).await
+}
+
+/// Zamkne tracking na task (duration_minutes = None → do zrušení)
+#[tauri::command]
+async fn set_manual_task(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+    task_id: i32,
+    duration_minutes: Option<u64>,
+) -> Result<tracker::ManualLock, String> {
+    state.tracker.set_manual_task(events(&app), task_id, duration_minutes).await
+}
+
+#[tauri::command]
+async fn clear_manual_task(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<bool, String> {
+    Ok(state.tracker.clear_manual_task(&events(&app)).await)
+}
+
+#[tauri::command]
+async fn get_manual_lock(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Option<tracker::ManualLock>, String> {
+    Ok(state.tracker.get_manual_lock(&events(&app)).await)
+}
+
+#[tauri::command]
+async fn annotate_current_segment(
+    state: tauri::State<'_, AppState>,
+    text: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    state.tracker.annotate_current_segment(events(&app), &text).await
+}
+
+#[tauri::command]
+async fn export_rule_pack(
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<(), String> {
+    state.tracker.export_rule_pack(Path::new(&path)).await
+}
+
+#[tauri::command]
+async fn import_rule_pack(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let changed = state.tracker.import_rule_pack(Path::new(&path)).await?;
+
+    app.emit("log-event", LogEvent {
+        level: "success".to_string(),
+        message: format!("📦 Rule pack importován ({} změn)", changed),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(changed)
+}
+
+/// Pravidla "aplikace + titulek + slova → task" (vyhodnocují se před matchingem)
+#[tauri::command]
+async fn list_task_rules(state: tauri::State<'_, AppState>) -> Result<Vec<task_rules::TaskRule>, String> {
+    Ok(state.tracker.list_task_rules().await)
+}
+
+#[tauri::command]
+async fn save_task_rule(
+    state: tauri::State<'_, AppState>,
+    rule: task_rules::TaskRule,
+) -> Result<Vec<task_rules::TaskRule>, String> {
+    state.tracker.save_task_rule(rule).await
+}
+
+#[tauri::command]
+async fn delete_task_rule(
+    state: tauri::State<'_, AppState>,
+    name: String,
+) -> Result<Vec<task_rules::TaskRule>, String> {
+    state.tracker.delete_task_rule(&name).await
+}
+
+/// Timer, který zůstal běžet po pádu aplikace (UI nabídne zastavení nebo převzetí)
+#[tauri::command]
+async fn get_orphaned_tracking(state: tauri::State<'_, AppState>) -> Result<Option<recovery::RunningTimer>, String> {
+    Ok(state.tracker.get_orphaned_tracking().await)
+}
+
+#[tauri::command]
+async fn recover_tracking(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+    action: recovery::RecoveryAction,
+) -> Result<(), String> {
+    state.tracker.recover_tracking(&events(&app), action).await
+}
+
+#[tauri::command]
+async fn get_health() -> Result<HealthReport, String> {
+    let settings = settings::load();
+    let engine = settings.as_ref().and_then(|s| s.ocr_engine).unwrap_or_default();
+    let languages = settings
+        .and_then(|s| s.ocr_language_codes().ok())
+        .unwrap_or_else(|| vec![ocr::DEFAULT_OCR_LANGUAGES.to_string()]);
+    let ocr = tokio::task::spawn_blocking(move || {
+        let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+        ocr::check_ocr_health(engine, &languages)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(HealthReport { ocr })
+}
+
+#[tauri::command]
+async fn list_ocr_languages() -> Result<Vec<tessdata::LanguagePack>, String> {
+    Ok(tessdata::list_languages())
+}
+
+/// Stáhne trénovací data jazyka, průběh posílá jako `ocr-language-progress`
+#[tauri::command]
+async fn download_ocr_language(language: String, app: AppHandle) -> Result<String, String> {
+    let progress_app = app.clone();
+    let path = tessdata::download(&language, |progress| {
+        let _ = progress_app.emit("ocr-language-progress", progress);
+    })
+    .await?;
+
+    app.emit("log-event", LogEvent {
+        level: "success".to_string(),
+        message: format!("🌐 OCR jazyk '{}' stažen a ověřen", language),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Chybějící trénovací data nastavených jazyků se stáhnou na pozadí,
+/// do té doby OCR končí chybou s návodem
+fn download_missing_languages(app: &AppHandle, languages: &str) {
+    let languages: Vec<&str> = languages.split('+').collect();
+    let (_, missing) = ocr::missing_languages(&languages);
+    if missing.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        for language in missing {
+            let _ = app.emit("log-event", LogEvent {
+                level: "info".to_string(),
+                message: format!("⬇️  Stahuji chybějící OCR jazyk '{}'...", language),
+            });
+            let progress_app = app.clone();
+            let result = tessdata::download(&language, |progress| {
+                let _ = progress_app.emit("ocr-language-progress", progress);
+            })
+            .await;
+            let (level, message) = match result {
+                Ok(_) => ("success", format!("🌐 OCR jazyk '{}' stažen a ověřen", language)),
+                Err(e) => (
+                    "error",
+                    format!("Nelze stáhnout OCR jazyk '{}': {}. Stáhněte ho ručně v nastavení OCR jazyků.", language, e),
+                ),
+            };
+            let _ = app.emit("log-event", LogEvent {
+                level: level.to_string(),
+                message,
+            });
+        }
+    });
+}
+
+#[tauri::command]
+async fn remove_ocr_language(language: String) -> Result<(), String> {
+    tessdata::remove(&language)
+}
+
+#[tauri::command]
+async fn get_matcher_stats(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<matcher_stats::MatcherAccuracy>, String> {
+    Ok(state.tracker.get_matcher_stats().await)
+}
+
+#[tauri::command]
+async fn start_focus_block(
+    state: tauri::State<'_, AppState>,
+    minutes: i64,
+    app: AppHandle,
+) -> Result<notifications::FocusStatus, String> {
+    state.tracker.start_focus_block(&events(&app), minutes)
+}
+
+#[tauri::command]
+async fn end_focus_block(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<(), String> {
+    state.tracker.end_focus_block(&events(&app))
+}
+
+#[tauri::command]
+async fn get_focus_status(state: tauri::State<'_, AppState>) -> Result<notifications::FocusStatus, String> {
+    Ok(state.tracker.get_focus_status())
+}
+
+#[tauri::command]
+async fn get_focus_review(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<notifications::DeferredNotification>, String> {
+    Ok(state.tracker.take_focus_review())
+}
+
+#[tauri::command]
+async fn set_write_routing(
+    state: tauri::State<'_, AppState>,
+    routing: freelo::WriteRouting,
+    app: AppHandle,
+) -> Result<offline::FreeloStatus, String> {
+    state.tracker.set_write_routing(&events(&app), routing).await
+}
+
+/// Datum z UI (YYYY-MM-DD)
+fn parse_date(value: Option<String>) -> Result<Option<chrono::NaiveDate>, String> {
+    value
+        .map(|v| chrono::NaiveDate::parse_from_str(&v, "%Y-%m-%d").map_err(|e| format!("Neplatné datum '{}': {}", v, e)))
+        .transpose()
+}
+
+#[tauri::command]
+async fn list_history_segments(
+    state: tauri::State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<history::HistorySegment>, String> {
+    Ok(state.tracker.list_history_segments(parse_date(from)?, parse_date(to)?).await)
+}
+
+#[tauri::command]
+async fn edit_history_segments(
+    state: tauri::State<'_, AppState>,
+    ids: Vec<String>,
+    edit: history::SegmentEdit,
+    push_to_freelo: bool,
+    app: AppHandle,
+) -> Result<history::BulkResult, String> {
+    state.tracker.edit_history_segments(&events(&app), &ids, &edit, push_to_freelo).await
+}
+
+#[tauri::command]
+async fn delete_history_segments(
+    state: tauri::State<'_, AppState>,
+    ids: Vec<String>,
+    push_to_freelo: bool,
+    app: AppHandle,
+) -> Result<history::BulkResult, String> {
+    state.tracker.delete_history_segments(&events(&app), &ids, push_to_freelo).await
+}
+
+/// Segmenty s nízkou confidence čekající na kontrolu (s návrhy tasků)
+#[tauri::command]
+async fn get_review_queue(state: tauri::State<'_, AppState>) -> Result<Vec<review::ReviewItem>, String> {
+    Ok(state.tracker.get_review_queue().await)
+}
+
+/// Potvrdí segment (task_id = None) nebo ho přeřadí na zvolený task
+#[tauri::command]
+async fn resolve_review(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+    segment_id: String,
+    task_id: Option<i32>,
+    push_to_freelo: bool,
+) -> Result<(), String> {
+    state.tracker.resolve_review(&events(&app), &segment_id, task_id, push_to_freelo).await
+}
+
+/// Kontrola rule packu / nastavení před nasazením, volitelně se simulací na vzorovém OCR textu
+#[tauri::command]
+async fn validate_config(path: String, sample_text: Option<String>) -> Result<config_check::ConfigReport, String> {
+    config_check::check_file(std::path::Path::new(&path), sample_text.as_deref())
+}
+
+/// Porovnání textového a AI matcheru nad uloženými OCR texty za rozsah dní
+/// (AI nejvýše `max_ai_calls` requestů, bez zadání 50)
+#[tauri::command]
+async fn evaluate_matchers(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+    max_ai_calls: Option<usize>,
+) -> Result<evaluation::EvaluationReport, String> {
+    let max_ai_calls = max_ai_calls.unwrap_or(evaluation::DEFAULT_MAX_AI_CALLS);
+    state.tracker.evaluate_matchers(&events(&app), parse_date(from)?, parse_date(to)?, max_ai_calls).await
+}
+
+/// Porovnání historie s očekávanými hodinami z CSV (datum, projekt, hodiny),
+/// odchylky nad toleranci (bez zadání 15 min) jsou označené
+#[tauri::command]
+async fn compare_with_expected(
+    state: tauri::State<'_, AppState>,
+    csv: String,
+    tolerance_minutes: Option<i64>,
+) -> Result<comparison::ComparisonReport, String> {
+    let tolerance = tolerance_minutes.unwrap_or(comparison::DEFAULT_TOLERANCE_MINUTES);
+    state.tracker.compare_with_expected(&csv, tolerance).await
+}
+
+/// Výkaz odpracovaného času do CSV/XLSX, cestu vybere uživatel v dialogu.
+/// Vrací uloženou cestu, None = uživatel dialog zrušil.
+#[tauri::command]
+async fn export_report(
+    state: tauri::State<'_, AppState>,
+    range: export::ExportRange,
+    format: export::ExportFormat,
+    include_freelo: Option<bool>,
+    app: AppHandle,
+) -> Result<Option<String>, String> {
+    let file = app
+        .dialog()
+        .file()
+        .set_file_name(format!("vykaz-{}-{}.{}", range.from, range.to, format.extension()))
+        .add_filter(format.label(), &[format.extension()])
+        .blocking_save_file();
+    let Some(file) = file else {
+        return Ok(None);
+    };
+    let path = file.into_path().map_err(|e| e.to_string())?;
+
+    state
+        .tracker
+        .export_report(&events(&app), range, format, include_freelo.unwrap_or(false), &path)
+        .await?;
+    Ok(Some(path.display().to_string()))
+}
+
+/// Čerpání rozpočtů hodin podle tasků (z nastavení, případně odhady z Freelo)
+#[tauri::command]
+async fn get_task_budgets(state: tauri::State<'_, AppState>) -> Result<Vec<task_budget::BudgetStatus>, String> {
+    let settings = settings::load().unwrap_or_default();
+    Ok(state
+        .tracker
+        .get_task_budgets(&settings.task_budgets, settings.budget_from_estimates)
+        .await)
+}
+
+/// Text z OCR posledního ticku pro podporu/ladění (bez zadání 2000 znaků), v režimu soukromí nedostupný
+#[tauri::command]
+async fn get_last_ocr_text(
+    state: tauri::State<'_, AppState>,
+    max_chars: Option<usize>,
+) -> Result<ocr::OcrTextPreview, String> {
+    state
+        .tracker
+        .get_last_ocr_text(max_chars.unwrap_or(ocr::DEFAULT_OCR_PREVIEW_CHARS))
+        .await
+}
+
+/// Audit rozhodnutí trackeru (každý tick) za rozsah dní
+#[tauri::command]
+async fn get_history(
+    state: tauri::State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<history::TickRecord>, String> {
+    state.tracker.get_history(parse_date(from)?, parse_date(to)?).await
+}
+
+/// Ruční oprava špatně přiřazeného ticku - tracker se z ní učí
+#[tauri::command]
+async fn submit_correction(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+    tick_id: i64,
+    correct_task_id: i32,
+) -> Result<(), String> {
+    state.tracker.submit_correction(&events(&app), tick_id, correct_task_id).await
+}
+
+/// Souhrn dne (datum YYYY-MM-DD, bez data dnešek)
+#[tauri::command]
+async fn get_daily_summary(
+    state: tauri::State<'_, AppState>,
+    date: Option<String>,
+) -> Result<summary::DailySummary, String> {
+    let date = parse_date(date)?.unwrap_or_else(|| chrono::Local::now().date_naive());
+    state.tracker.get_daily_summary(date).await
+}
+
+/// Proč v rozsahu chybí sledovaný čas (zastavený tracker, nečinnost, zámek, chyby...)
+#[tauri::command]
+async fn explain_gap(
+    state: tauri::State<'_, AppState>,
+    from: String,
+    to: String,
+) -> Result<summary::GapExplanation, String> {
+    state.tracker.explain_gap(parse_datetime(&from)?, parse_datetime(&to)?).await
+}
+
+/// Čas jako RFC 3339 nebo lokální "RRRR-MM-DD HH:MM"
+fn parse_datetime(value: &str) -> Result<chrono::DateTime<chrono::Local>, String> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&chrono::Local));
+    }
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .ok()
+        .and_then(|time| time.and_local_timezone(chrono::Local).earliest())
+        .ok_or_else(|| format!("Neplatný čas '{}' (očekáváno RFC 3339 nebo RRRR-MM-DD HH:MM)", value))
+}
+
+#[tauri::command]
+async fn clear_history(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<usize, String> {
+    let deleted = state.tracker.clear_history().await?;
+
+    app.emit("log-event", LogEvent {
+        level: "info".to_string(),
+        message: format!("🧹 Historie rozhodnutí smazána ({} ticků, týden jde obnovit)", deleted),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(deleted)
+}
+
+/// Obnoví smazanou historii rozhodnutí (než ji údržba odstraní natrvalo)
+#[tauri::command]
+async fn restore_history(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<usize, String> {
+    let restored = state.tracker.restore_history().await?;
+
+    app.emit("log-event", LogEvent {
+        level: "info".to_string(),
+        message: format!("♻️  Historie rozhodnutí obnovena ({} ticků)", restored),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(restored)
+}
+
+/// Ruční údržba historie (jinak běží při každém startu trackingu)
+#[tauri::command]
+async fn compact_history(state: tauri::State<'_, AppState>, app: AppHandle) -> Result<history::RetentionResult, String> {
+    state.tracker.compact_history(&events(&app)).await
+}
+
+/// Denní souhrny starých ticků za rozsah dní
+#[tauri::command]
+async fn get_history_days(
+    state: tauri::State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<history::TickDay>, String> {
+    state.tracker.get_history_days(parse_date(from)?, parse_date(to)?).await
+}
+
+/// Záznamy práce ve Freelo za den (výchozí dnes)
+#[tauri::command]
+async fn list_worklog(
+    state: tauri::State<'_, AppState>,
+    date: Option<String>,
+) -> Result<Vec<freelo::WorkReport>, String> {
+    let date = parse_date(date)?.unwrap_or_else(|| chrono::Local::now().date_naive());
+    state.tracker.list_worklog(date).await
+}
+
+/// Oprava záznamu práce ve Freelo (task, poznámka, délka)
+#[tauri::command]
+async fn edit_worklog_entry(
+    state: tauri::State<'_, AppState>,
+    date: Option<String>,
+    report_id: i32,
+    edit: freelo::WorklogEdit,
+    app: AppHandle,
+) -> Result<freelo::WorkReport, String> {
+    let date = parse_date(date)?.unwrap_or_else(|| chrono::Local::now().date_naive());
+    state.tracker.edit_worklog_entry(&events(&app), date, report_id, &edit).await
+}
+
+/// Smazání záznamu práce ve Freelo
+#[tauri::command]
+async fn delete_worklog_entry(
+    state: tauri::State<'_, AppState>,
+    report_id: i32,
+    app: AppHandle,
+) -> Result<(), String> {
+    state.tracker.delete_worklog_entry(&events(&app), report_id).await
+}
+
+/// Spotřeba AI (tokeny, cena) po dnech a stav denního rozpočtu
+#[tauri::command]
+async fn get_ai_usage(
+    state: tauri::State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<ai_usage::AiUsageReport, String> {
+    state.tracker.get_ai_usage(parse_date(from)?, parse_date(to)?).await
+}
+
+/// Import work reportů z Freelo do lokální historie (výchozí rozsah: posledních 90 dní)
+#[tauri::command]
+async fn import_freelo_history(
+    state: tauri::State<'_, AppState>,
+    from: Option<String>,
+    to: Option<String>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let to = parse_date(to)?.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let from = parse_date(from)?.unwrap_or(to - chrono::Duration::days(history::INITIAL_IMPORT_DAYS));
+    state.tracker.import_freelo_history(&events(&app), from, to).await
+}
+
+/// Kdy agent znovu analyzuje obrazovku
+#[tauri::command]
+async fn get_next_tick_eta(state: tauri::State<'_, AppState>) -> Result<tracker::TickEta, String> {
+    Ok(state.tracker.get_next_tick_eta().await)
+}
+
+#[tauri::command]
+async fn get_tracking_status(state: tauri::State<'_, AppState>) -> Result<tracker::TrackingStatus, String> {
+    Ok(state.tracker.get_tracking_status().await)
+}
+
+#[tauri::command]
+async fn get_freelo_status(state: tauri::State<'_, AppState>) -> Result<offline::FreeloStatus, String> {
+    Ok(state.tracker.get_freelo_status().await)
+}
+
+#[tauri::command]
+async fn get_pending_corrections(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<verification::PendingCorrection>, String> {
+    Ok(state.tracker.get_pending_corrections().await)
+}
+
+#[tauri::command]
+async fn export_evidence(
+    state: tauri::State<'_, AppState>,
+    destination: String,
+    app: AppHandle,
+) -> Result<String, String> {
+    let (target, count) = state.tracker.export_evidence(Path::new(&destination)).await?;
+
+    app.emit("log-event", LogEvent {
+        level: "warning".to_string(),
+        message: format!("🔓 Exportováno {} důkazních screenshotů do {:?}", count, target),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn redact_debug_artifacts(
+    state: tauri::State<'_, AppState>,
+    destination: String,
+    app: AppHandle,
+) -> Result<String, String> {
+    let (target, count) = state.tracker.redact_debug_artifacts(Path::new(&destination)).await?;
+
+    app.emit("log-event", LogEvent {
+        level: "success".to_string(),
+        message: format!("🙈 {} debug artefaktů zakryto do {:?}", count, target),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Přepne aktivní profil - nastavení starého profilu se odloží k němu, nový profil
+/// převezme svoje uložené nastavení (nebo aktuální, pokud ho ještě nemá).
+/// `auto_start` None = tracking běží dál, pokud běžel.
+#[tauri::command]
+async fn switch_profile(
+    state: tauri::State<'_, AppState>,
+    app: AppHandle,
+    name: String,
+    auto_start: Option<bool>,
+) -> Result<bool, String> {
+    profiles::validate_profile_name(&name)?;
+    let current = settings::load().unwrap_or_default();
+    let current_profile = current
+        .profile
+        .clone()
+        .unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string());
+    if current_profile == name {
+        return Err(format!("Profil '{}' je už aktivní", name));
+    }
+
+    let mut next = settings::load_profile(&name).unwrap_or_else(|| current.clone());
+    next.profile = Some(name.clone());
+    next.validate()?;
+    let config = next.tracker_config()?;
+
+    settings::save_profile(&current_profile, &current)?;
+    let running = state.tracker.switch_profile(&events(&app), config, auto_start).await?;
+    settings::save(&next)?;
+    app.emit("profile-switched", &name).map_err(|e| e.to_string())?;
+    Ok(running)
+}
+
+#[tauri::command]
+async fn list_profiles() -> Result<Vec<profiles::ProfileInfo>, String> {
+    Ok(profiles::list_profiles())
+}
+
+#[tauri::command]
+async fn export_profile_data(profile: String, destination: String) -> Result<String, String> {
+    let target = profiles::export_profile(&profile, Path::new(&destination))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn delete_profile_data(
+    state: tauri::State<'_, AppState>,
+    profile: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    state.tracker.delete_profile_data(&profile).await?;
+
+    app.emit("log-event", LogEvent {
+        level: "warning".to_string(),
+        message: format!("🗑️  Data profilu '{}' smazána", profile),
+    }).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 // --- Main Entry Point ---
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize tracing
+    // Initialize tracing - RUST_LOG přebíjí výchozí "info", zavřené spany logují svou délku
     tracing_subscriber::fmt()
-        .with_env_filter("info")
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
         .init();
 
     tracing::info!("🚀 Tracker Agent starting...");
+    resources::limit_ocr_threads();
 
     let tracker = Arc::new(Tracker::new());
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(hotkeys::handle)
+                .build(),
+        )
         .manage(AppState {
             tracker,
         })
+        .setup(|app| {
+            tray::setup(app.handle())?;
+            // Zkratky platí hned po startu, i když se okno ještě nenačetlo
+            let bindings = settings::load().map(|s| s.hotkeys).unwrap_or_default();
+            if let Err(e) = hotkeys::apply(app.handle(), &bindings) {
+                tracing::warn!(error = %e, "klávesové zkratky nezaregistrovány");
+            }
+            // Ruční úpravy pravidel a šablony promptu se projeví bez restartu
+            file_watch::spawn(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_tracking,
+            start_demo,
             stop_tracking,
+            pause_tracking,
+            resume_tracking,
             save_settings,
+            load_settings,
+            test_connection,
+            list_available_models,
+            switch_task,
+            refresh_tasks,
+            get_tasks,
+            rescan_now,
+            get_projects,
+            get_tasklists,
+            get_active_errors,
+            dismiss_error,
+            get_note_templates,
+            set_note_template,
+            set_manual_task,
+            clear_manual_task,
+            get_manual_lock,
+            annotate_current_segment,
+            export_rule_pack,
+            import_rule_pack,
+            list_task_rules,
+            save_task_rule,
+            delete_task_rule,
+            get_orphaned_tracking,
+            recover_tracking,
+            get_health,
+            get_pending_corrections,
+            get_freelo_status,
+            get_next_tick_eta,
+            get_tracking_status,
+            get_matcher_stats,
+            list_ocr_languages,
+            download_ocr_language,
+            remove_ocr_language,
+            export_evidence,
+            redact_debug_artifacts,
+            switch_profile,
+            list_profiles,
+            export_profile_data,
+            delete_profile_data,
+            start_focus_block,
+            end_focus_block,
+            get_focus_status,
+            get_focus_review,
+            set_write_routing,
+            list_history_segments,
+            edit_history_segments,
+            delete_history_segments,
+            import_freelo_history,
+            get_history,
+            submit_correction,
+            evaluate_matchers,
+            compare_with_expected,
+            export_report,
+            get_task_budgets,
+            get_last_ocr_text,
+            get_daily_summary,
+            explain_gap,
+            validate_config,
+            get_review_queue,
+            resolve_review,
+            clear_history,
+            restore_history,
+            compact_history,
+            get_history_days,
+            get_ai_usage,
+            list_worklog,
+            edit_worklog_entry,
+            delete_worklog_entry,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Zavření okna, Cmd+Q i "Ukončit" z lišty - ve Freelo nesmí zůstat běžet timer
+            if let tauri::RunEvent::Exit = event {
+                let tracker = app.state::<AppState>().tracker.clone();
+                tauri::async_runtime::block_on(async {
+                    if tokio::time::timeout(SHUTDOWN_TIMEOUT, tracker.shutdown(&events(app))).await.is_err() {
+                        tracing::warn!("zastavení trackingu při ukončení nestihlo doběhnout");
+                    }
+                });
+            }
+        });
 }
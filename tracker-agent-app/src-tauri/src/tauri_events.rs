@@ -0,0 +1,91 @@
+use crate::tray;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+use tauri_plugin_notification::NotificationExt;
+use tracker_core::events::{EventSink, Events, MainWindow, TrayState};
+use tracker_core::screenshot::ScreenRect;
+
+/// Label hlavního okna aplikace
+const MAIN_WINDOW: &str = "main";
+
+/// Eventy trackeru jdou do webview, lišta a okno přes Tauri
+pub struct TauriEvents(AppHandle);
+
+/// Sink pro metody trackeru volané z příkazů, lišty a zkratek
+pub fn events(app: &AppHandle) -> Events {
+    Arc::new(TauriEvents(app.clone()))
+}
+
+fn window_rect(window: &WebviewWindow) -> Option<ScreenRect> {
+    let position = window.outer_position().ok()?;
+    let size = window.outer_size().ok()?;
+    Some(ScreenRect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+impl TauriEvents {
+    fn window(&self) -> Option<WebviewWindow> {
+        self.0.get_webview_window(MAIN_WINDOW)
+    }
+}
+
+impl EventSink for TauriEvents {
+    fn emit_value(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+        self.0.emit(event, payload).map_err(|e| e.to_string())
+    }
+
+    fn set_tray_state(&self, state: TrayState) {
+        tray::set_state(&self.0, state);
+    }
+
+    fn set_tray_task(&self, task: Option<&str>) {
+        tray::set_task(&self.0, task);
+    }
+
+    fn notify(&self, title: &str, body: &str) -> Result<(), String> {
+        self.0
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+            .map_err(|e| e.to_string())
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        self.0.cursor_position().ok().map(|p| (p.x as i32, p.y as i32))
+    }
+
+    fn main_window(&self) -> Option<MainWindow> {
+        let window = self.window()?;
+        Some(MainWindow {
+            rect: window_rect(&window),
+            visible: window.is_visible().unwrap_or(true) && !window.is_minimized().unwrap_or(false),
+        })
+    }
+
+    fn set_content_protected(&self, protected: bool) -> Result<(), String> {
+        match self.window() {
+            Some(window) => window.set_content_protected(protected).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    fn hide_main_window(&self) -> Result<(), String> {
+        match self.window() {
+            Some(window) => window.hide().map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    fn show_main_window(&self) -> Result<(), String> {
+        match self.window() {
+            Some(window) => window.show().map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{tauri_events, AppState};
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager};
+use tracker_core::events::TrayState;
+
+/// ID ikony v systémové liště
+const TRAY_ID: &str = "tracker";
+
+/// Velikost generované ikony (barevná tečka) v pixelech
+const ICON_SIZE: u32 = 32;
+
+/// Barva ikony podle stavu trackingu
+fn color(state: TrayState) -> [u8; 3] {
+    match state {
+        TrayState::Stopped => [156, 163, 175],
+        TrayState::Running => [16, 185, 129],
+        TrayState::Paused => [245, 158, 11],
+    }
+}
+
+struct TrayStatus {
+    state: TrayState,
+    task: Option<String>,
+}
+
+static STATUS: Mutex<TrayStatus> = Mutex::new(TrayStatus {
+    state: TrayState::Stopped,
+    task: None,
+});
+
+/// RGBA plná kruhová tečka s vyhlazeným okrajem
+fn dot_rgba(color: [u8; 3], size: u32) -> Vec<u8> {
+    let center = (size as f32 - 1.0) / 2.0;
+    let radius = size as f32 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+
+    for y in 0..size {
+        for x in 0..size {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let alpha = (radius + 0.5 - distance).clamp(0.0, 1.0);
+            rgba.extend_from_slice(&[color[0], color[1], color[2], (alpha * 255.0) as u8]);
+        }
+    }
+    rgba
+}
+
+fn tooltip(status: &TrayStatus) -> String {
+    match (&status.task, status.state) {
+        (Some(task), TrayState::Running) => format!("Tracker Agent - {}", task),
+        _ => format!("Tracker Agent - {}", status.state.label()),
+    }
+}
+
+/// Překreslí ikonu a tooltip podle aktuálního stavu
+fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let Ok(status) = STATUS.lock() else {
+        return;
+    };
+    let icon = Image::new_owned(dot_rgba(color(status.state), ICON_SIZE), ICON_SIZE, ICON_SIZE);
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        tracing::warn!(error = %e, "nelze nastavit ikonu v liště");
+    }
+    let _ = tray.set_tooltip(Some(tooltip(&status)));
+}
+
+/// Změna stavu trackingu - ikona v liště a event `tracking-state` pro UI
+pub fn set_state(app: &AppHandle, state: TrayState) {
+    if let Ok(mut status) = STATUS.lock() {
+        status.state = state;
+        if state == TrayState::Stopped {
+            status.task = None;
+        }
+    }
+    refresh(app);
+    let _ = app.emit("tracking-state", state);
+}
+
+/// Právě sledovaný task v tooltipu
+pub fn set_task(app: &AppHandle, task: Option<&str>) {
+    if let Ok(mut status) = STATUS.lock() {
+        if status.task.as_deref() == task {
+            return;
+        }
+        status.task = task.map(str::to_string);
+    }
+    refresh(app);
+}
+
+/// Akce z menu v liště - stejné metody trackeru jako tlačítka v UI
+fn handle_menu(app: &AppHandle, id: &str) {
+    let app = app.clone();
+    let id = id.to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let tracker = app.state::<AppState>().tracker.clone();
+        let events = tauri_events::events(&app);
+        let result = match id.as_str() {
+            "start" => tracker.start(events).await,
+            "pause" => match STATUS.lock().map(|s| s.state).unwrap_or(TrayState::Stopped) {
+                TrayState::Paused => tracker.resume(&events).await,
+                _ => tracker.pause(&events).await,
+            },
+            "stop" => tracker.stop(events).await,
+            "show" => events.show_main_window(),
+            "quit" => {
+                // Běžící segment zastaví `RunEvent::Exit` handler v lib.rs
+                app.exit(0);
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::warn!(action = %id, error = %e, "akce z lišty selhala");
+        }
+    });
+}
+
+/// Vytvoří ikonu v systémové liště s menu Start/Pauza/Stop/Ukončit
+pub fn setup(app: &AppHandle) -> tauri::Result<()> {
+    let start = MenuItem::with_id(app, "start", "▶️ Spustit tracking", true, None::<&str>)?;
+    let pause = MenuItem::with_id(app, "pause", "☕ Pauza / pokračovat", true, None::<&str>)?;
+    let stop = MenuItem::with_id(app, "stop", "⏹️ Zastavit tracking", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "Zobrazit okno", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Ukončit", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let menu = Menu::with_items(app, &[&start, &pause, &stop, &separator, &show, &quit])?;
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(Image::new_owned(dot_rgba(color(TrayState::Stopped), ICON_SIZE), ICON_SIZE, ICON_SIZE))
+        .tooltip("Tracker Agent - zastaveno")
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(|app, event| handle_menu(app, event.id().as_ref()))
+        .build(app)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_rgba() {
+        let rgba = dot_rgba([16, 185, 129], 32);
+        assert_eq!(rgba.len(), 32 * 32 * 4);
+
+        let pixel = |x: usize, y: usize| &rgba[(y * 32 + x) * 4..(y * 32 + x) * 4 + 4];
+        assert_eq!(pixel(16, 16), &[16, 185, 129, 255]);
+        assert_eq!(pixel(0, 0)[3], 0);
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Local};
+
+/// Kratší bloky se nekomentují - jen by zahltily diskuzi u tasku
+pub const MIN_COMMENT_MINUTES: i64 = 10;
+
+/// Max. počet aktivit v komentáři (nejdřívější první)
+const MAX_ACTIVITIES: usize = 8;
+
+/// Freelo komentáře jsou HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Komentář k tasku shrnující blok práce, None = blok je krátký nebo bez popisu aktivity
+pub fn comment(activities: &[String], started_at: DateTime<Local>, stopped_at: DateTime<Local>) -> Option<String> {
+    let minutes = (stopped_at - started_at).num_minutes();
+    let activities: Vec<&str> = activities.iter().map(|a| a.trim()).filter(|a| !a.is_empty()).collect();
+    if minutes < MIN_COMMENT_MINUTES || activities.is_empty() {
+        return None;
+    }
+
+    let mut items: Vec<String> = activities
+        .iter()
+        .take(MAX_ACTIVITIES)
+        .map(|a| format!("<li>{}</li>", escape_html(a)))
+        .collect();
+    if activities.len() > MAX_ACTIVITIES {
+        items.push(format!("<li>… a {} dalších</li>", activities.len() - MAX_ACTIVITIES));
+    }
+    Some(format!(
+        "<p>Souhrn práce {}–{} ({} min):</p><ul>{}</ul>",
+        started_at.format("%d.%m. %H:%M"),
+        stopped_at.format("%H:%M"),
+        minutes,
+        items.join("")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_comment() {
+        let start = Local.with_ymd_and_hms(2025, 3, 10, 9, 15, 0).unwrap();
+        let activities = vec![
+            "Code - oprava <LoginForm>".to_string(),
+            " ".to_string(),
+            "Firefox - review PR #42".to_string(),
+        ];
+
+        assert_eq!(comment(&activities, start, start + chrono::Duration::minutes(5)), None);
+        assert_eq!(comment(&[], start, start + chrono::Duration::minutes(60)), None);
+        assert_eq!(
+            comment(&activities, start, start + chrono::Duration::minutes(75)).unwrap(),
+            "<p>Souhrn práce 10.03. 09:15–10:30 (75 min):</p>\
+             <ul><li>Code - oprava &lt;LoginForm&gt;</li><li>Firefox - review PR #42</li></ul>"
+        );
+
+        let many: Vec<String> = (0..10).map(|i| format!("Aktivita {}", i)).collect();
+        let long = comment(&many, start, start + chrono::Duration::minutes(30)).unwrap();
+        assert!(long.contains("Aktivita 7") && !long.contains("Aktivita 8") && long.contains("… a 2 dalších"));
+    }
+}
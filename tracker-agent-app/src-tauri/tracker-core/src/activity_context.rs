@@ -0,0 +1,126 @@
+use crate::text_matcher::activity_key;
+use chrono::{DateTime, Duration, Local};
+
+/// Jak daleko do minulosti shrnutí sahá
+const WINDOW_MINUTES: i64 = 60;
+
+/// Max. počet řádků shrnutí - prompt zůstává omezený i při častém přepínání
+const MAX_LINES: usize = 8;
+
+/// Popis aktivity se ve shrnutí zkracuje
+const MAX_ACTIVITY_CHARS: usize = 80;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    application: String,
+    activity: String,
+    task: Option<String>,
+    first_seen: DateTime<Local>,
+    last_seen: DateTime<Local>,
+    ticks: u32,
+}
+
+/// Klouzavé shrnutí poslední hodiny aktivity pro AI prompt - opakované ticky
+/// se stejnou aktivitou se slučují do jednoho řádku
+#[derive(Debug, Clone, Default)]
+pub struct ActivityContext {
+    entries: Vec<Entry>,
+}
+
+impl ActivityContext {
+    /// Zaznamená výsledek ticku
+    pub fn record(&mut self, at: DateTime<Local>, application: &str, activity: &str, task: Option<&str>) {
+        let key = activity_key(&format!("{} {}", application, activity));
+        if key.is_empty() {
+            return;
+        }
+        self.entries.retain(|e| at - e.last_seen < Duration::minutes(WINDOW_MINUTES));
+
+        match self.entries.iter_mut().find(|e| e.key == key) {
+            Some(entry) => {
+                entry.last_seen = at;
+                entry.ticks += 1;
+                if task.is_some() {
+                    entry.task = task.map(str::to_string);
+                }
+            }
+            None => self.entries.push(Entry {
+                key,
+                application: application.to_string(),
+                activity: activity.chars().take(MAX_ACTIVITY_CHARS).collect(),
+                task: task.map(str::to_string),
+                first_seen: at,
+                last_seen: at,
+                ticks: 1,
+            }),
+        }
+    }
+
+    /// Shrnutí do promptu (nejnovější nahoře), None = za poslední hodinu nic
+    pub fn summary(&self, now: DateTime<Local>) -> Option<String> {
+        let mut recent: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|e| now - e.last_seen < Duration::minutes(WINDOW_MINUTES))
+            .collect();
+        if recent.is_empty() {
+            return None;
+        }
+        recent.sort_by_key(|e| std::cmp::Reverse(e.last_seen));
+
+        let mut lines: Vec<String> = recent
+            .iter()
+            .take(MAX_LINES)
+            .map(|e| {
+                let task = e.task.as_deref().map(|t| format!(" → {}", t)).unwrap_or_default();
+                format!(
+                    "- {}-{} ({}×) {}: {}{}",
+                    e.first_seen.format("%H:%M"),
+                    e.last_seen.format("%H:%M"),
+                    e.ticks,
+                    e.application,
+                    e.activity,
+                    task
+                )
+            })
+            .collect();
+        if recent.len() > MAX_LINES {
+            lines.push(format!("- … a {} dalších aktivit", recent.len() - MAX_LINES));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_summary_dedup_and_window() {
+        let start = Local.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let mut context = ActivityContext::default();
+        assert_eq!(context.summary(start), None);
+
+        context.record(start, "Code", "Editace tracker.rs (12 chyb)", Some("Tracker"));
+        context.record(start + Duration::minutes(5), "Code", "Editace tracker.rs (3 chyb)", None);
+        context.record(start + Duration::minutes(10), "Firefox", "Dokumentace Tauri", None);
+
+        let summary = context.summary(start + Duration::minutes(10)).unwrap();
+        assert_eq!(
+            summary,
+            "- 09:10-09:10 (1×) Firefox: Dokumentace Tauri\n- 09:00-09:05 (2×) Code: Editace tracker.rs (12 chyb) → Tracker"
+        );
+
+        for i in 0..12 {
+            context.record(start + Duration::minutes(20 + i), "Slack", &format!("Kanál {}", char::from(b'a' + i as u8)), None);
+        }
+        let summary = context.summary(start + Duration::minutes(31)).unwrap();
+        assert_eq!(summary.lines().count(), MAX_LINES + 1);
+        assert!(summary.ends_with("a 6 dalších aktivit"));
+
+        // Po hodině bez aktivity shrnutí zmizí
+        assert_eq!(context.summary(start + Duration::minutes(95)), None);
+    }
+}
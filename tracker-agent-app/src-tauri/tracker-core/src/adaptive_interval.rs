@@ -0,0 +1,98 @@
+use crate::history::TickAction;
+
+/// Výchozí strop intervalu při stabilní práci v sekundách
+pub const DEFAULT_MAX_INTERVAL_SECONDS: u64 = 60;
+
+/// Nejkratší interval po změně kontextu (stejné minimum jako v nastavení)
+const MIN_INTERVAL_SECONDS: u64 = 5;
+
+/// Kolik ticků po změně se analyzuje zkráceným intervalem
+const FAST_TICKS_AFTER_CHANGE: u32 = 3;
+
+/// Po kolika stabilních ticích v řadě se interval začne prodlužovat
+const STABLE_TICKS_BEFORE_BACKOFF: u32 = 6;
+
+/// Prodloužení intervalu za každý další stabilní tick
+const BACKOFF_FACTOR: f64 = 1.5;
+
+/// Adaptivní interval ticku - po změně kontextu kratší, při dlouho stejné práci delší
+#[derive(Debug, Default)]
+pub struct AdaptiveInterval {
+    /// Ticky od poslední změny kontextu, None = změna ještě nenastala
+    since_change: Option<u32>,
+    /// Stabilní ticky v řadě
+    stable_ticks: u32,
+    /// Interval při prodlužování, None = základní
+    backoff: Option<u64>,
+}
+
+impl AdaptiveInterval {
+    /// Interval do dalšího ticku podle výsledku posledního
+    pub fn next(&mut self, action: TickAction, base: u64, max: u64) -> u64 {
+        let max = max.max(base);
+        match action {
+            TickAction::Started | TickAction::Switched | TickAction::Stopped | TickAction::Waiting => {
+                self.since_change = Some(0);
+                self.stable_ticks = 0;
+                self.backoff = None;
+            }
+            TickAction::Continued | TickAction::HeldByManual => {
+                self.since_change = self.since_change.map(|t| t + 1);
+                self.stable_ticks += 1;
+                if self.stable_ticks > STABLE_TICKS_BEFORE_BACKOFF {
+                    let current = self.backoff.unwrap_or(base);
+                    self.backoff = Some((((current as f64) * BACKOFF_FACTOR).round() as u64).min(max));
+                }
+            }
+            // Přeskočený tick nic neříká o stabilitě práce - začíná se znovu od základu
+            TickAction::Skipped | TickAction::Failed => {
+                *self = Self::default();
+            }
+        }
+
+        if self.since_change.is_some_and(|t| t < FAST_TICKS_AFTER_CHANGE) {
+            return (base / 2).max(MIN_INTERVAL_SECONDS).min(base);
+        }
+        self.backoff.unwrap_or(base)
+    }
+}
+
+/// Validace stropu intervalu z nastavení
+pub fn validate(interval: u64, max_interval: Option<u64>) -> Result<(), String> {
+    if let Some(max) = max_interval {
+        if max < interval {
+            return Err(format!("Maximální interval ({}s) nesmí být kratší než interval ({}s)", max, interval));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_interval() {
+        let mut adaptive = AdaptiveInterval::default();
+
+        // Bez změny kontextu a před prodlužováním platí základní interval
+        assert_eq!(adaptive.next(TickAction::Continued, 20, 60), 20);
+
+        // Po změně tři ticky zkráceně
+        assert_eq!(adaptive.next(TickAction::Switched, 20, 60), 10);
+        assert_eq!(adaptive.next(TickAction::Continued, 20, 60), 10);
+        assert_eq!(adaptive.next(TickAction::Continued, 20, 60), 10);
+        assert_eq!(adaptive.next(TickAction::Continued, 20, 60), 20);
+
+        // Stabilní práce - postupně až ke stropu
+        let intervals: Vec<u64> = (0..6).map(|_| adaptive.next(TickAction::Continued, 20, 60)).collect();
+        assert_eq!(intervals, vec![20, 20, 20, 30, 45, 60]);
+
+        // Změna okamžitě zkrátí, přeskočený tick vrátí základ
+        assert_eq!(adaptive.next(TickAction::Started, 20, 60), 10);
+        assert_eq!(adaptive.next(TickAction::Skipped, 20, 60), 20);
+        assert_eq!(AdaptiveInterval::default().next(TickAction::Waiting, 8, 60), 5);
+
+        assert!(validate(30, Some(20)).is_err());
+    }
+}
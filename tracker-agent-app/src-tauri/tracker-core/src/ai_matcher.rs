@@ -0,0 +1,779 @@
+use crate::ai_usage::{self, Usage};
+use crate::connection_check;
+use crate::freelo::FreeloTask;
+use crate::json_repair::parse_lenient;
+use crate::language::{self, TextLanguage};
+use crate::prompt_template::{self, PromptValues};
+use crate::window_info::WindowInfo;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn, Span};
+
+/// Výchozí model pro matching obrazovky (v nastavení lze zvolit jiný)
+pub const MATCH_MODEL: &str = "google/gemini-2.5-flash";
+
+/// Levný model pro návrhy tasků při kontrole segmentů (jen text)
+const SUGGESTION_MODEL: &str = "google/gemini-2.5-flash-lite";
+
+const OPENROUTER_CHAT_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
+
+/// Informace o API klíči - nic nestojí, hodí se na ověření klíče
+const OPENROUTER_KEY_URL: &str = "https://openrouter.ai/api/v1/key";
+
+/// Katalog modelů - veřejný, bez API klíče
+const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
+
+/// Výchozí adresa lokální Ollamy
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Výchozí lokální model - umí text i screenshot
+pub const DEFAULT_OLLAMA_MODEL: &str = "llama3.2-vision";
+
+/// AI služba zvolená v nastavení
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProviderKind {
+    #[default]
+    OpenRouter,
+    Ollama,
+}
+
+/// Kam se posílají AI requesty
+#[derive(Debug, Clone, PartialEq)]
+pub enum LlmProvider {
+    /// OpenRouter (cloud)
+    OpenRouter { api_key: String },
+    /// Lokální Ollama - obsah obrazovky neopustí počítač
+    Ollama { endpoint: String, model: String },
+}
+
+impl LlmProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LlmProvider::OpenRouter { .. } => "OpenRouter",
+            LlmProvider::Ollama { .. } => "Ollama",
+        }
+    }
+
+    /// Posílá data mimo počítač (pro pravidla skrývání citlivých údajů)
+    pub fn is_cloud(&self) -> bool {
+        matches!(self, LlmProvider::OpenRouter { .. })
+    }
+
+    /// OpenRouter API klíč - embeddingy zatím umí jen OpenRouter
+    pub fn openrouter_key(&self) -> Option<&str> {
+        match self {
+            LlmProvider::OpenRouter { api_key } => Some(api_key),
+            LlmProvider::Ollama { .. } => None,
+        }
+    }
+
+    /// Ollama má jeden zvolený model pro všechny requesty
+    fn model<'a>(&'a self, cloud_model: &'a str) -> &'a str {
+        match self {
+            LlmProvider::OpenRouter { .. } => cloud_model,
+            LlmProvider::Ollama { model, .. } => model,
+        }
+    }
+
+    /// Obě služby mají chat endpoint kompatibilní s OpenAI
+    fn chat_request(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        match self {
+            LlmProvider::OpenRouter { api_key } => client
+                .post(OPENROUTER_CHAT_URL)
+                .header("Authorization", format!("Bearer {}", api_key)),
+            LlmProvider::Ollama { endpoint, .. } => {
+                client.post(format!("{}/v1/chat/completions", endpoint.trim_end_matches('/')))
+            }
+        }
+    }
+
+    /// Ověří API klíč (OpenRouter) nebo dostupnost Ollamy a staženého modelu
+    #[instrument(name = "ai", skip_all, fields(op = "check_connection", status))]
+    pub async fn check_connection(&self) -> Result<(), String> {
+        let client = reqwest::Client::new();
+        let request = match self {
+            LlmProvider::OpenRouter { api_key } => {
+                client.get(OPENROUTER_KEY_URL).header("Authorization", format!("Bearer {}", api_key))
+            }
+            LlmProvider::Ollama { endpoint, .. } => client.get(format!("{}/api/tags", endpoint.trim_end_matches('/'))),
+        };
+        let response = request
+            .timeout(connection_check::CHECK_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP chyba: {}", e))?;
+        Span::current().record("status", response.status().as_u16());
+        connection_check::check_status(response.status())?;
+
+        if let LlmProvider::Ollama { model, .. } = self {
+            let tags: OllamaTags = response.json().await.map_err(|e| format!("JSON parse error: {}", e))?;
+            if !ollama_has_model(&tags, model) {
+                return Err(format!("Model {} v Ollamě chybí (ollama pull {})", model, model));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Seznam stažených modelů z `/api/tags`
+#[derive(Debug, Deserialize)]
+struct OllamaTags {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+/// Ollama vypisuje modely s tagem ("llama3.2-vision:latest"), v nastavení bývá bez něj
+fn ollama_has_model(tags: &OllamaTags, model: &str) -> bool {
+    tags.models
+        .iter()
+        .any(|m| m.name == model || (!model.contains(':') && m.name.strip_suffix(":latest") == Some(model)))
+}
+
+/// Model z katalogu OpenRouter pro výběr v nastavení
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AiModel {
+    pub id: String,
+    pub name: String,
+    pub context_length: Option<u64>,
+    /// Cena v USD za milion tokenů (vstup, výstup), None = neuvedeno
+    pub prompt_price: Option<f64>,
+    pub completion_price: Option<f64>,
+    /// Umí obrázky - potřeba pro analýzu screenshotu
+    pub vision: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelCatalog {
+    data: Vec<CatalogModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogModel {
+    id: String,
+    #[serde(default)]
+    name: String,
+    context_length: Option<u64>,
+    pricing: Option<CatalogPricing>,
+    architecture: Option<CatalogArchitecture>,
+}
+
+/// OpenRouter posílá ceny jako řetězce v USD za token
+#[derive(Debug, Deserialize)]
+struct CatalogPricing {
+    prompt: Option<String>,
+    completion: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogArchitecture {
+    #[serde(default)]
+    input_modalities: Vec<String>,
+}
+
+impl From<CatalogModel> for AiModel {
+    fn from(model: CatalogModel) -> Self {
+        let per_million = |price: Option<&String>| price.and_then(|p| p.parse::<f64>().ok()).map(|p| p * 1_000_000.0);
+        Self {
+            name: if model.name.is_empty() { model.id.clone() } else { model.name },
+            id: model.id,
+            context_length: model.context_length,
+            prompt_price: per_million(model.pricing.as_ref().and_then(|p| p.prompt.as_ref())),
+            completion_price: per_million(model.pricing.as_ref().and_then(|p| p.completion.as_ref())),
+            vision: model
+                .architecture
+                .is_some_and(|a| a.input_modalities.iter().any(|m| m == "image")),
+        }
+    }
+}
+
+/// Modely dostupné přes OpenRouter seřazené podle ID
+#[instrument(name = "ai", skip_all, fields(op = "list_models", status))]
+pub async fn list_models() -> Result<Vec<AiModel>, String> {
+    let response = reqwest::Client::new()
+        .get(OPENROUTER_MODELS_URL)
+        .send()
+        .await
+        .map_err(|e| format!("OpenRouter request failed: {}", e))?;
+    Span::current().record("status", response.status().as_u16());
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("OpenRouter API error {}: {}", status, error_text));
+    }
+
+    let catalog: ModelCatalog = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OpenRouter models: {}", e))?;
+    let mut models: Vec<AiModel> = catalog.data.into_iter().map(AiModel::from).collect();
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+
+    // Ceník pro odhad ceny ticku (rozpočet) - přesnější než vestavěné ceny
+    ai_usage::cache_catalog_prices(
+        models
+            .iter()
+            .filter_map(|m| Some((m.id.clone(), (m.prompt_price?, m.completion_price.unwrap_or(0.0)))))
+            .collect(),
+    );
+    Ok(models)
+}
+
+/// Validace adresy Ollamy z nastavení
+pub fn validate_ollama_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Neplatná adresa Ollamy '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Adresa Ollamy musí začínat http:// nebo https:// (zadáno {})", url));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct OpenRouterRequest {
+    model: String,
+    messages: Vec<Message>,
+    temperature: f32,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    /// OpenRouter pošle v odpovědi i cenu requestu
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<UsageRequest>,
+}
+
+#[derive(Debug, Serialize)]
+struct UsageRequest {
+    include: bool,
+}
+
+/// Parametry requestu na AI (nastavitelné v pokročilém nastavení)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiParams {
+    /// OpenRouter model (Ollama používá model z nastavení Ollamy)
+    pub model: String,
+    pub temperature: f32,
+    /// Příliš nízký limit usekne JSON odpověď uprostřed objektu
+    pub max_tokens: u32,
+    /// None = výchozí hodnota modelu
+    pub top_p: Option<f32>,
+}
+
+impl Default for AiParams {
+    fn default() -> Self {
+        Self {
+            model: MATCH_MODEL.to_string(),
+            temperature: 0.3,
+            max_tokens: 500,
+            top_p: None,
+        }
+    }
+}
+
+/// Validace AI parametrů z nastavení
+pub fn validate_params(params: &AiParams) -> Result<(), String> {
+    if !params.model.contains('/') || params.model.trim() != params.model {
+        return Err(format!("AI model musí být ID z OpenRouter ve tvaru 'poskytovatel/model' (zadáno '{}')", params.model));
+    }
+    if !(0.0..=2.0).contains(&params.temperature) {
+        return Err(format!("AI temperature musí být 0.0-2.0 (zadáno {})", params.temperature));
+    }
+    if !(50..=8000).contains(&params.max_tokens) {
+        return Err(format!("AI max_tokens musí být 50-8000 (zadáno {})", params.max_tokens));
+    }
+    if let Some(top_p) = params.top_p {
+        if !(top_p > 0.0 && top_p <= 1.0) {
+            return Err(format!("AI top_p musí být v rozsahu (0, 1] (zadáno {})", top_p));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Message {
+    role: String,
+    content: MessageContent,
+}
+
+impl Message {
+    fn text(role: &str, content: String) -> Self {
+        Self {
+            role: role.to_string(),
+            content: MessageContent::Text(content),
+        }
+    }
+}
+
+/// Obsah zprávy - prostý text, nebo části (text + obrázek) pro multimodální model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ImageUrl {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenRouterResponse {
+    choices: Vec<Choice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AIMatchResult {
+    pub task_id: Option<i32>,
+    pub confidence: f32,
+    pub reasoning: String,
+    pub activity_description: String, // Krátký popis co uživatel dělá
+}
+
+/// Jeden návrh tasku od AI se zdůvodněním
+#[derive(Debug, Clone, Deserialize)]
+pub struct AiSuggestion {
+    pub task_id: i32,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AiSuggestions {
+    #[serde(default)]
+    suggestions: Vec<AiSuggestion>,
+}
+
+/// Pošle konverzaci do AI a vrátí text odpovědi
+async fn send_chat(
+    client: &reqwest::Client,
+    provider: &LlmProvider,
+    messages: &[Message],
+    params: &AiParams,
+) -> Result<String, String> {
+    let request = OpenRouterRequest {
+        model: provider.model(&params.model).to_string(),
+        messages: messages.to_vec(),
+        temperature: params.temperature,
+        max_tokens: params.max_tokens,
+        top_p: params.top_p,
+        usage: provider.is_cloud().then_some(UsageRequest { include: true }),
+    };
+
+    let response = provider
+        .chat_request(client)
+        .header("Content-Type", "application/json")
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", provider.name(), e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("{} API error {}: {}", provider.name(), status, error_text));
+    }
+
+    let openrouter_response: OpenRouterResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {} response: {}", provider.name(), e))?;
+    if let Some(ref usage) = openrouter_response.usage {
+        ai_usage::record(&request.model, usage, provider.is_cloud());
+    }
+
+    // Extrahuj AI odpověď
+    match openrouter_response
+        .choices
+        .into_iter()
+        .next()
+        .ok_or("No choices in OpenRouter response")?
+        .message
+        .content
+    {
+        MessageContent::Text(text) => Ok(text),
+        MessageContent::Parts(parts) => Ok(parts
+            .into_iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text),
+                ContentPart::ImageUrl { .. } => None,
+            })
+            .collect()),
+    }
+}
+
+/// Pošle matching request a naparsuje odpověď - při neplatném JSON jednou požádá model o opravu
+async fn request_match(mut messages: Vec<Message>, provider: &LlmProvider, params: &AiParams) -> Result<AIMatchResult, String> {
+    let client = reqwest::Client::new();
+    let ai_response = send_chat(&client, provider, &messages, params).await?;
+    debug!(response = %ai_response, "AI odpověď");
+
+    // Tolerantní parse (markdown, čárky, uvozovky, useknutý konec)
+    let result: AIMatchResult = match parse_lenient(&ai_response) {
+        Ok(result) => result,
+        Err(e) => {
+            // Jeden pokus o opravu - pošli modelu jeho odpověď i chybu
+            warn!(error = %e, "neplatný JSON od AI, žádám o opravu");
+            messages.push(Message::text("assistant", ai_response));
+            messages.push(Message::text(
+                "user",
+                format!(
+                    "Tvoje odpověď není platný JSON ({}). Pošli ji znovu jako JEDEN platný JSON objekt ve stejném formátu, bez dalšího textu.",
+                    e
+                ),
+            ));
+
+            let fixed_response = send_chat(&client, provider, &messages, params).await?;
+            debug!(response = %fixed_response, "AI odpověď po opravě");
+
+            parse_lenient(&fixed_response).map_err(|e| {
+                format!("Failed to parse AI JSON response: {}. Response was: {}", e, fixed_response)
+            })?
+        }
+    };
+
+    Span::current()
+        .record("task_id", result.task_id)
+        .record("confidence", result.confidence);
+    debug!(reasoning = %result.reasoning, "AI match");
+
+    Ok(result)
+}
+
+/// Seznam tasků do promptu
+fn tasks_text(tasks: &[FreeloTask]) -> String {
+    tasks
+        .iter()
+        .map(|t| format!("ID: {}, Název: {}, Projekt: {}", t.id, t.name, t.project_name))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formát odpovědi společný pro matching z textu i ze screenshotu
+const MATCH_ANSWER_FORMAT: &str = r#"INSTRUKCE:
+1. Zjisti co uživatel právě dělá (aktivní okno je spolehlivější než zbytek obrazovky)
+2. Vyber task který nejlépe odpovídá této aktivitě
+3. Pokud žádný task neodpovídá dobře, vrať task_id: null
+4. Confidence je 0-100 (jak moc si jsi jistý)
+5. VŽDY napiš krátký popis aktivity (max 100 znaků) do activity_description
+
+Odpověz POUZE v tomto JSON formátu (bez markdown bloků):
+{
+  "task_id": 123,
+  "confidence": 85,
+  "reasoning": "Uživatel pracuje na...",
+  "activity_description": "Editace kódu v tracker-agent-app"
+}
+
+Nebo pokud žádný task neodpovídá:
+{
+  "task_id": null,
+  "confidence": 0,
+  "reasoning": "Žádný task neodpovídá aktivitě...",
+  "activity_description": "Prohlížení dokumentace na webu"
+}"#;
+
+/// Použije AI (OpenRouter nebo lokální Ollama) pro matching OCR textu s Freelo tasky
+#[instrument(
+    name = "match",
+    skip_all,
+    fields(matcher = "ai", chars = ocr_text.len(), tasks = tasks.len(), task_id, confidence)
+)]
+pub async fn match_task_with_ai(
+    ocr_text: &str,
+    window: Option<&WindowInfo>,
+    tasks: &[FreeloTask],
+    recent_activity: Option<&str>,
+    examples: Option<&str>,
+    provider: &LlmProvider,
+    params: &AiParams,
+) -> Result<AIMatchResult, String> {
+    let window = window_context(window);
+    let language_hint = language_hint(ocr_text);
+    let ocr_text = ocr_text.chars().take(3000).collect::<String>(); // Limit na 3000 znaků
+    let recent_activity = recent_activity_section(recent_activity);
+    let examples = examples_section(examples);
+    let tasks = tasks_text(tasks);
+
+    // Vlastní šablona ze souboru má přednost před vestavěným promptem
+    let prompt = match prompt_template::active() {
+        Some(template) => prompt_template::render(
+            &template,
+            &PromptValues {
+                window: &window,
+                ocr_text: &ocr_text,
+                language_hint: &language_hint,
+                recent_activity: &recent_activity,
+                examples: &examples,
+                tasks: &tasks,
+                answer_format: MATCH_ANSWER_FORMAT,
+            },
+        ),
+        None => format!(
+            r#"Analyzuj aktivní okno a OCR text z obrazovky uživatele a vyber nejlepší matching Freelo task.
+
+AKTIVNÍ OKNO (aplikace | titulek):
+```
+{}
+```
+
+OCR TEXT (co uživatel vidí na obrazovce):
+```
+{}
+```
+{}{}{}
+DOSTUPNÉ FREELO TASKY:
+```
+{}
+```
+
+{}"#,
+            window, ocr_text, language_hint, recent_activity, examples, tasks, MATCH_ANSWER_FORMAT
+        ),
+    };
+
+    request_match(vec![Message::text("user", prompt)], provider, params).await
+}
+
+/// Matching přímo ze screenshotu (JPEG v base64) multimodálním modelem - bez OCR
+#[instrument(
+    name = "match",
+    skip_all,
+    fields(matcher = "vision", bytes = image_base64.len(), tasks = tasks.len(), task_id, confidence)
+)]
+pub async fn analyze_screenshot(
+    image_base64: &str,
+    window: Option<&WindowInfo>,
+    tasks: &[FreeloTask],
+    recent_activity: Option<&str>,
+    provider: &LlmProvider,
+    params: &AiParams,
+) -> Result<AIMatchResult, String> {
+    let prompt = format!(
+        r#"Na přiloženém screenshotu je obrazovka uživatele. Podle ní a aktivního okna vyber nejlepší matching Freelo task.
+
+AKTIVNÍ OKNO (aplikace | titulek):
+```
+{}
+```
+{}
+DOSTUPNÉ FREELO TASKY:
+```
+{}
+```
+
+{}"#,
+        window_context(window),
+        recent_activity_section(recent_activity),
+        tasks_text(tasks),
+        MATCH_ANSWER_FORMAT
+    );
+
+    let message = Message {
+        role: "user".to_string(),
+        content: MessageContent::Parts(vec![
+            ContentPart::Text { text: prompt },
+            ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: format!("data:image/jpeg;base64,{}", image_base64),
+                },
+            },
+        ]),
+    };
+    request_match(vec![message], provider, params).await
+}
+
+/// Navrhne nejpravděpodobnější tasky pro ukončený segment (podle poznámky a aplikace).
+/// Vrací návrhy tak, jak je poslala AI - ID je potřeba ověřit proti seznamu tasků.
+#[instrument(name = "suggest", skip_all, fields(tasks = tasks.len()))]
+pub async fn suggest_tasks(
+    segment_context: &str,
+    tasks: &[FreeloTask],
+    count: usize,
+    provider: &LlmProvider,
+) -> Result<Vec<AiSuggestion>, String> {
+    let prompt = format!(
+        r#"Tracker si nebyl jistý, ke kterému Freelo tasku patří tento úsek práce.
+
+ÚSEK PRÁCE:
+```
+{}
+```
+
+DOSTUPNÉ FREELO TASKY:
+```
+{}
+```
+
+Vyber {} nejpravděpodobnější tasky (od nejlepšího) a ke každému napiš jednořádkové zdůvodnění (max 80 znaků).
+
+Odpověz POUZE v tomto JSON formátu (bez markdown bloků):
+{{
+  "suggestions": [
+    {{"task_id": 123, "reason": "Poznámka zmiňuje opravu přihlášení"}}
+  ]
+}}"#,
+        segment_context,
+        tasks_text(tasks),
+        count
+    );
+
+    let params = AiParams {
+        model: SUGGESTION_MODEL.to_string(),
+        temperature: 0.2,
+        max_tokens: 400,
+        top_p: None,
+    };
+    let messages = vec![Message::text("user", prompt)];
+
+    let response = send_chat(&reqwest::Client::new(), provider, &messages, &params).await?;
+    debug!(response = %response, "AI návrhy tasků");
+
+    let parsed: AiSuggestions =
+        parse_lenient(&response).map_err(|e| format!("Failed to parse AI suggestions: {}. Response was: {}", e, response))?;
+    Ok(parsed.suggestions)
+}
+
+/// Převládající jazyk OCR textu - názvy tasků mohou být v jiném jazyce než obrazovka
+fn language_hint(ocr_text: &str) -> String {
+    match language::detect(ocr_text) {
+        TextLanguage::Other => String::new(),
+        detected => format!(
+            "Jazyk OCR textu: {} (OCR může zkomolit diakritiku, tasky porovnávej podle významu i napříč jazyky)\n",
+            detected.label()
+        ),
+    }
+}
+
+/// Shrnutí poslední hodiny do promptu - pomáhá držet stejný task mezi ticky
+fn recent_activity_section(recent_activity: Option<&str>) -> String {
+    match recent_activity {
+        Some(summary) => format!(
+            "\nPOSLEDNÍ HODINA (předchozí aktivita, jen kontext - rozhoduje aktuální obrazovka):\n```\n{}\n```\n",
+            summary
+        ),
+        None => String::new(),
+    }
+}
+
+/// Dřívější opravy přiřazení od uživatele - ukazují, jak podobné obrazovky rozlišovat
+fn examples_section(examples: Option<&str>) -> String {
+    match examples {
+        Some(examples) => format!(
+            "\nOPRAVY OD UŽIVATELE (dříve špatně přiřazené obrazovky a jejich správný task):\n```\n{}\n```\n",
+            examples
+        ),
+        None => String::new(),
+    }
+}
+
+/// Popis aktivního okna do promptu
+fn window_context(window: Option<&WindowInfo>) -> String {
+    match window {
+        Some(w) if !w.app_name.is_empty() || !w.title.is_empty() => {
+            format!("{} | {}", w.app_name, w.title.chars().take(300).collect::<String>())
+        }
+        _ => "neznámé".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_params() {
+        assert!(validate_params(&AiParams::default()).is_ok());
+        assert!(validate_params(&AiParams { temperature: 2.5, ..AiParams::default() }).is_err());
+        assert!(validate_params(&AiParams { max_tokens: 10, ..AiParams::default() }).is_err());
+        assert!(validate_params(&AiParams { top_p: Some(0.0), ..AiParams::default() }).is_err());
+        assert!(validate_params(&AiParams { top_p: Some(0.9), ..AiParams::default() }).is_ok());
+        assert!(validate_params(&AiParams { model: "gpt-4o".to_string(), ..AiParams::default() }).is_err());
+    }
+
+    #[test]
+    fn test_model_catalog() {
+        let catalog: ModelCatalog = serde_json::from_str(
+            r#"{"data": [
+                {"id": "google/gemini-2.5-flash", "name": "Google: Gemini 2.5 Flash", "context_length": 1048576,
+                 "pricing": {"prompt": "0.0000003", "completion": "0.0000025"},
+                 "architecture": {"input_modalities": ["text", "image", "file"]}},
+                {"id": "meta-llama/llama-3.1-8b-instruct", "pricing": {"prompt": "free"}}
+            ]}"#,
+        )
+        .unwrap();
+        let models: Vec<AiModel> = catalog.data.into_iter().map(AiModel::from).collect();
+
+        assert!(models[0].vision);
+        assert!((models[0].prompt_price.unwrap() - 0.30).abs() < 1e-9);
+        assert!((models[0].completion_price.unwrap() - 2.50).abs() < 1e-9);
+        assert_eq!(models[1].name, "meta-llama/llama-3.1-8b-instruct");
+        assert_eq!((models[1].prompt_price, models[1].vision), (None, false));
+    }
+
+    #[test]
+    fn test_ollama_provider() {
+        let ollama = LlmProvider::Ollama {
+            endpoint: "http://localhost:11434/".to_string(),
+            model: "llava".to_string(),
+        };
+        assert_eq!(ollama.model(MATCH_MODEL), "llava");
+        assert!(!ollama.is_cloud() && ollama.openrouter_key().is_none());
+        let request = ollama.chat_request(&reqwest::Client::new()).build().unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:11434/v1/chat/completions");
+        assert!(request.headers().get("Authorization").is_none());
+
+        assert!(validate_ollama_url(DEFAULT_OLLAMA_URL).is_ok());
+        assert!(validate_ollama_url("localhost:11434").is_err());
+
+        let tags: OllamaTags =
+            serde_json::from_str(r#"{"models": [{"name": "llama3.2-vision:latest"}, {"name": "qwen2.5vl:7b"}]}"#).unwrap();
+        assert!(ollama_has_model(&tags, DEFAULT_OLLAMA_MODEL));
+        assert!(ollama_has_model(&tags, "qwen2.5vl:7b"));
+        assert!(!ollama_has_model(&tags, "qwen2.5vl"));
+        assert!(validate_ollama_url("ftp://server").is_err());
+    }
+
+    #[test]
+    fn test_message_content_format() {
+        let message = Message {
+            role: "user".to_string(),
+            content: MessageContent::Parts(vec![
+                ContentPart::Text { text: "Co je na obrazovce?".to_string() },
+                ContentPart::ImageUrl {
+                    image_url: ImageUrl { url: "data:image/jpeg;base64,AAAA".to_string() },
+                },
+            ]),
+        };
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "Co je na obrazovce?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/jpeg;base64,AAAA"}}
+                ]
+            })
+        );
+
+        let response: Message = serde_json::from_str(r#"{"role": "assistant", "content": "{}"}"#).unwrap();
+        assert!(matches!(response.content, MessageContent::Text(ref text) if text == "{}"));
+    }
+}
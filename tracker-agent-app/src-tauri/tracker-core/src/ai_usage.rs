@@ -0,0 +1,165 @@
+use crate::storage;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Ceny modelů v USD za milion tokenů (vstup, výstup) podle ceníku OpenRouter -
+/// záloha, dokud není stažený katalog modelů
+const MODEL_PRICES: [(&str, f64, f64); 3] = [
+    ("google/gemini-2.5-flash", 0.30, 2.50),
+    ("google/gemini-2.5-flash-lite", 0.10, 0.40),
+    ("openai/text-embedding-3-small", 0.02, 0.0),
+];
+
+/// Neznámý model v záznamu spotřeby bez ceny z odpovědi - počítá se cenou dražšího z nich
+const FALLBACK_PRICE: (f64, f64) = (0.30, 2.50);
+
+/// Ceník z katalogu modelů OpenRouter v datovém adresáři aplikace
+const CATALOG_PRICES_FILE: &str = "model_prices.json";
+
+/// Kolik dní zpět vrací přehled spotřeby bez zadaného rozsahu
+pub const DEFAULT_USAGE_DAYS: i64 = 30;
+
+/// Spotřeba z pole `usage` v odpovědi (OpenRouter posílá i cenu, Ollama jen tokeny)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    #[serde(default)]
+    pub completion_tokens: u64,
+    pub cost: Option<f64>,
+}
+
+/// Jedno volání AI čekající na zápis do historie
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiCall {
+    pub day: NaiveDate,
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Denní součet spotřeby jednoho modelu
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AiUsageDay {
+    pub day: NaiveDate,
+    pub model: String,
+    pub calls: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Přehled pro UI - dny od nejnovějšího a stav denního rozpočtu
+#[derive(Debug, Clone, Serialize)]
+pub struct AiUsageReport {
+    pub days: Vec<AiUsageDay>,
+    pub today_cost_usd: f64,
+    pub daily_budget_usd: Option<f64>,
+    pub budget_exhausted: bool,
+}
+
+/// Volání od posledního zápisu (AI se volá z trackeru i z příkazů UI)
+static PENDING: Mutex<Vec<AiCall>> = Mutex::new(Vec::new());
+
+/// Ceník z posledního načtení katalogu (None = ještě nenačtený z disku)
+static CATALOG_PRICES: Mutex<Option<HashMap<String, (f64, f64)>>> = Mutex::new(None);
+
+/// Zapamatuje ceník z katalogu modelů (i na disk pro příští spuštění)
+pub fn cache_catalog_prices(prices: HashMap<String, (f64, f64)>) {
+    if let Err(e) = storage::save_json(&storage::data_file(CATALOG_PRICES_FILE), &prices) {
+        tracing::warn!(error = %e, "nelze uložit ceník modelů");
+    }
+    if let Ok(mut catalog) = CATALOG_PRICES.lock() {
+        *catalog = Some(prices);
+    }
+}
+
+fn catalog_price(model: &str) -> Option<(f64, f64)> {
+    let mut catalog = CATALOG_PRICES.lock().ok()?;
+    catalog
+        .get_or_insert_with(|| storage::load_json(&storage::data_file(CATALOG_PRICES_FILE)).unwrap_or_default())
+        .get(model)
+        .copied()
+}
+
+/// Cena modelu (vstup, výstup) - z katalogu OpenRouter, jinak z vestavěného ceníku.
+/// None = cenu neznáme.
+pub fn model_price(model: &str) -> Option<(f64, f64)> {
+    catalog_price(model).or_else(|| {
+        MODEL_PRICES
+            .iter()
+            .find(|(name, _, _)| *name == model)
+            .map(|&(_, input, output)| (input, output))
+    })
+}
+
+fn cost_at((input_price, output_price): (f64, f64), prompt_tokens: u64, completion_tokens: u64) -> f64 {
+    (prompt_tokens as f64 * input_price + completion_tokens as f64 * output_price) / 1_000_000.0
+}
+
+/// Odhad ceny podle tokenů, None = model bez známé ceny
+pub fn estimate_cost(model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+    model_price(model).map(|price| cost_at(price, prompt_tokens, completion_tokens))
+}
+
+/// Zaznamená volání - cena z odpovědi, jinak odhad (lokální model je zdarma)
+pub fn record(model: &str, usage: &Usage, cloud: bool) {
+    let cost_usd = match usage.cost {
+        _ if !cloud => 0.0,
+        Some(cost) => cost,
+        None => estimate_cost(model, usage.prompt_tokens, usage.completion_tokens)
+            .unwrap_or_else(|| cost_at(FALLBACK_PRICE, usage.prompt_tokens, usage.completion_tokens)),
+    };
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.push(AiCall {
+            day: Local::now().date_naive(),
+            model: model.to_string(),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            cost_usd,
+        });
+    }
+}
+
+/// Vybere volání čekající na zápis
+pub fn take_pending() -> Vec<AiCall> {
+    PENDING.lock().map(|mut pending| std::mem::take(&mut *pending)).unwrap_or_default()
+}
+
+/// Validace denního rozpočtu z nastavení
+pub fn validate_daily_budget(budget: Option<f64>) -> Result<(), String> {
+    if budget.is_some_and(|b| !b.is_finite() || b < 0.0) {
+        return Err("Denní rozpočet AI nesmí být záporný".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_usage() {
+        let usage: Usage = serde_json::from_str(r#"{"prompt_tokens": 2000, "completion_tokens": 100, "total_tokens": 2100}"#).unwrap();
+        let estimate = estimate_cost("google/gemini-2.5-flash", 2000, 100).unwrap();
+        assert!((estimate - 0.00085).abs() < 1e-9);
+        assert!(estimate_cost("google/gemini-2.5-flash-lite", 2000, 100).unwrap() < estimate);
+        assert_eq!(estimate_cost("neznamy/model-x", 2000, 100), None);
+
+        take_pending();
+        record("google/gemini-2.5-flash", &usage, true);
+        record("google/gemini-2.5-flash", &Usage { cost: Some(0.0012), ..usage.clone() }, true);
+        record("llama3.2-vision", &usage, false);
+        // Bez ceny v odpovědi i v ceníku se spotřeba radši nadhodnotí
+        record("neznamy/model-x", &usage, true);
+
+        let calls = take_pending();
+        let costs: Vec<f64> = calls.iter().map(|c| c.cost_usd).collect();
+        assert_eq!(costs, vec![estimate, 0.0012, 0.0, estimate]);
+        assert!(take_pending().is_empty());
+        assert!(validate_daily_budget(Some(-1.0)).is_err());
+    }
+}
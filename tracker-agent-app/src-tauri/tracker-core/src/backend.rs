@@ -0,0 +1,211 @@
+use crate::clockify::ClockifyClient;
+use crate::demo::DemoBackend;
+use crate::freelo::{FreeloClient, FreeloTask, WriteRouting};
+use crate::toggl::TogglClient;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Služba, do které se zapisuje čas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    #[default]
+    Freelo,
+    Toggl,
+    Clockify,
+}
+
+/// Přihlašovací údaje zvolené služby (Freelo používá vlastní pole konfigurace)
+#[derive(Debug, Clone, Default)]
+pub enum BackendConfig {
+    #[default]
+    Freelo,
+    Toggl {
+        api_token: String,
+        /// None = výchozí workspace účtu
+        workspace_id: Option<i64>,
+    },
+    Clockify {
+        api_key: String,
+        /// None = aktivní workspace uživatele
+        workspace_id: Option<String>,
+    },
+    /// Ukázkový režim - vestavěné tasky, nic se neodesílá
+    Demo,
+}
+
+/// Společné rozhraní služeb pro sledování času - tasky se předávají jako `FreeloTask`
+// Implementace jsou jen v tomto crate, Send futures hlídá až tokio::spawn v trackeru
+#[allow(async_fn_in_trait)]
+pub trait TimeTrackingBackend {
+    /// Aktivní tasky, ke kterým lze zapisovat čas
+    async fn list_tasks(&self) -> Result<Vec<FreeloTask>, String>;
+    /// Spustí timer (None = bez tasku), vrací ID běžícího záznamu
+    async fn start(&self, task_id: Option<&str>, note: &str) -> Result<String, String>;
+    async fn stop(&self, entry_id: &str) -> Result<(), String>;
+    /// Přepíše poznámku běžícího záznamu
+    async fn annotate(&self, entry_id: &str, note: &str) -> Result<(), String>;
+}
+
+#[derive(Clone)]
+pub enum Backend {
+    Freelo(FreeloClient),
+    Toggl(TogglClient),
+    Clockify(ClockifyClient),
+    Demo(DemoBackend),
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Freelo(_) => "Freelo",
+            Backend::Toggl(_) => "Toggl",
+            Backend::Clockify(_) => "Clockify",
+            Backend::Demo(_) => "Demo",
+        }
+    }
+
+    pub fn write_routing(&self) -> WriteRouting {
+        match self {
+            Backend::Freelo(client) => client.write_routing(),
+            Backend::Toggl(client) => client.write_routing(),
+            Backend::Clockify(client) => client.write_routing(),
+            // Demo nikam nezapisuje, segmenty se ale ukládají do historie profilu demo
+            Backend::Demo(_) => WriteRouting::Live,
+        }
+    }
+
+    /// Freelo klient pro funkce, které jiné služby nemají (zpětné výkazy, ověření, metadata projektů)
+    pub fn freelo(&self) -> Option<&FreeloClient> {
+        match self {
+            Backend::Freelo(client) => Some(client),
+            _ => None,
+        }
+    }
+
+    /// Ukázkový režim - tick místo screenshotu přehraje nahranou obrazovku
+    pub fn demo(&self) -> Option<&DemoBackend> {
+        match self {
+            Backend::Demo(demo) => Some(demo),
+            _ => None,
+        }
+    }
+}
+
+impl TimeTrackingBackend for Backend {
+    async fn list_tasks(&self) -> Result<Vec<FreeloTask>, String> {
+        match self {
+            Backend::Freelo(client) => client.list_tasks().await,
+            Backend::Toggl(client) => client.list_tasks().await,
+            Backend::Clockify(client) => client.list_tasks().await,
+            Backend::Demo(demo) => demo.list_tasks().await,
+        }
+    }
+
+    async fn start(&self, task_id: Option<&str>, note: &str) -> Result<String, String> {
+        match self {
+            Backend::Freelo(client) => client.start(task_id, note).await,
+            Backend::Toggl(client) => client.start(task_id, note).await,
+            Backend::Clockify(client) => client.start(task_id, note).await,
+            Backend::Demo(demo) => demo.start(task_id, note).await,
+        }
+    }
+
+    async fn stop(&self, entry_id: &str) -> Result<(), String> {
+        match self {
+            Backend::Freelo(client) => client.stop(entry_id).await,
+            Backend::Toggl(client) => client.stop(entry_id).await,
+            Backend::Clockify(client) => client.stop(entry_id).await,
+            Backend::Demo(demo) => demo.stop(entry_id).await,
+        }
+    }
+
+    async fn annotate(&self, entry_id: &str, note: &str) -> Result<(), String> {
+        match self {
+            Backend::Freelo(client) => client.annotate(entry_id, note).await,
+            Backend::Toggl(client) => client.annotate(entry_id, note).await,
+            Backend::Clockify(client) => client.annotate(entry_id, note).await,
+            Backend::Demo(demo) => demo.annotate(entry_id, note).await,
+        }
+    }
+}
+
+/// Cíl zápisu v jiné službě než Freelo (ID tam nejsou i32)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalTask {
+    pub workspace_id: String,
+    pub project_id: String,
+    pub task_id: Option<String>,
+}
+
+/// Lokální ID → externí task, plní se při načtení tasků
+static EXTERNAL_TASKS: Mutex<BTreeMap<i32, ExternalTask>> = Mutex::new(BTreeMap::new());
+
+/// Stabilní kladné lokální ID (FNV-1a) - stejný task má po restartu stejné ID
+fn local_id(external: &ExternalTask) -> i32 {
+    let key = format!(
+        "{}/{}/{}",
+        external.workspace_id,
+        external.project_id,
+        external.task_id.as_deref().unwrap_or_default()
+    );
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in key.bytes() {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    (hash & 0x7fff_ffff).max(1) as i32
+}
+
+/// Zapamatuje externí task a vrátí jeho lokální ID
+pub fn register(external: ExternalTask) -> i32 {
+    let id = local_id(&external);
+    if let Ok(mut tasks) = EXTERNAL_TASKS.lock() {
+        tasks.insert(id, external);
+    }
+    id
+}
+
+/// Externí task pro lokální ID z klíče segmentu
+pub fn resolve(task_id: &str) -> Option<ExternalTask> {
+    let id: i32 = task_id.parse().ok()?;
+    EXTERNAL_TASKS.lock().ok()?.get(&id).cloned()
+}
+
+/// ID záznamu jiné služby nese i workspace ("workspace/záznam")
+pub fn entry_id(workspace_id: &str, id: &str) -> String {
+    format!("{}/{}", workspace_id, id)
+}
+
+pub fn split_entry_id(entry_id: &str) -> Result<(&str, &str), String> {
+    entry_id
+        .split_once('/')
+        .ok_or_else(|| format!("Neplatné ID záznamu: {}", entry_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_external_task() {
+        let task = ExternalTask {
+            workspace_id: "ws1".to_string(),
+            project_id: "5f1a2b".to_string(),
+            task_id: Some("6c3d4e".to_string()),
+        };
+        let id = register(task.clone());
+        assert!(id > 0);
+        assert_eq!(register(task.clone()), id);
+        assert_eq!(resolve(&id.to_string()), Some(task.clone()));
+
+        let project_only = ExternalTask { task_id: None, ..task };
+        assert_ne!(register(project_only), id);
+        assert_eq!(resolve("general_work"), None);
+
+        let entry = entry_id("123", "987");
+        assert_eq!(split_entry_id(&entry), Ok(("123", "987")));
+        assert!(split_entry_id("dry-run-1").is_err());
+    }
+}
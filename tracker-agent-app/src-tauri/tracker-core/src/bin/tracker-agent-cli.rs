@@ -0,0 +1,5 @@
+// Konzolový nástroj bez GUI - agent pro servery, jeho ovládání a kontrola konfigurace
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(tracker_core::headless::cli(&args));
+}
@@ -0,0 +1,224 @@
+use crate::ai_usage;
+use crate::freelo::FreeloTask;
+use serde::{Deserialize, Serialize};
+
+/// Hrubý odhad - zhruba 4 znaky textu na token
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Odhad tokenů za jeden screenshot
+const IMAGE_TOKENS: u64 = 1300;
+
+/// OCR text v promptu je oříznutý na 3000 znaků, k tomu instrukce a formát odpovědi
+const OCR_PROMPT_CHARS: usize = 3000;
+const PROMPT_OVERHEAD_CHARS: usize = 1500;
+
+/// Váha nového měření v klouzavém průměru délky fází
+const TIMING_WEIGHT: f64 = 0.3;
+
+/// Snížení rozlišení kvůli rozpočtu (OCR běží nad menším snímkem)
+pub const BUDGET_SCALE_FACTOR: f32 = 0.5;
+
+/// Tvrdé limity jednoho ticku, None = bez limitu
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TickBudget {
+    pub max_seconds: Option<f64>,
+    pub max_cost_usd: Option<f64>,
+}
+
+impl TickBudget {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_seconds.is_none() && self.max_cost_usd.is_none()
+    }
+}
+
+/// Validace limitů z nastavení
+pub fn validate(budget: &TickBudget) -> Result<(), String> {
+    if budget.max_seconds.is_some_and(|s| !s.is_finite() || s <= 0.0) {
+        return Err("Časový limit ticku musí být kladné číslo sekund".to_string());
+    }
+    if budget.max_cost_usd.is_some_and(|c| !c.is_finite() || c < 0.0) {
+        return Err("Cenový limit ticku nesmí být záporný".to_string());
+    }
+    Ok(())
+}
+
+/// Fáze ticku, jejichž délka se měří
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Capture,
+    Ocr,
+    Ai,
+}
+
+/// Klouzavý průměr délky fází (s), None = ještě neměřeno
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StageTimings {
+    capture: Option<f64>,
+    ocr: Option<f64>,
+    ai: Option<f64>,
+}
+
+impl StageTimings {
+    pub fn record(&mut self, stage: Stage, seconds: f64) {
+        let slot = match stage {
+            Stage::Capture => &mut self.capture,
+            Stage::Ocr => &mut self.ocr,
+            Stage::Ai => &mut self.ai,
+        };
+        *slot = Some(match *slot {
+            Some(avg) => avg + TIMING_WEIGHT * (seconds - avg),
+            None => seconds,
+        });
+    }
+}
+
+/// Odhad délky promptu pro AI matching podle seznamu tasků
+pub fn prompt_chars(tasks: &[FreeloTask]) -> usize {
+    let tasks_chars: usize = tasks.iter().map(|t| t.name.len() + t.project_name.len() + 16).sum();
+    tasks_chars + OCR_PROMPT_CHARS + PROMPT_OVERHEAD_CHARS
+}
+
+/// Odhad ceny jednoho AI requestu v USD (výstup počítá s plným limitem tokenů), None = neznámá cena modelu
+pub fn estimate_ai_cost(model: &str, prompt_chars: usize, with_image: bool, max_output_tokens: u32) -> Option<f64> {
+    let input_tokens = (prompt_chars / CHARS_PER_TOKEN) as u64 + if with_image { IMAGE_TOKENS } else { 0 };
+    ai_usage::estimate_cost(model, input_tokens, max_output_tokens as u64)
+}
+
+/// Jak tick dodrží rozpočet - výchozí hodnota nic neomezuje
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BudgetPlan {
+    pub allow_ai: bool,
+    pub allow_vision: bool,
+    /// Snížit rozlišení snímku (násobek měřítka)
+    pub scale_factor: f32,
+    /// Použít minulé rozhodnutí i při změně obrazovky (ne při jiném okně)
+    pub prefer_cache: bool,
+    /// Co rozpočet omezil, prázdné = tick běží naplno
+    pub reasons: Vec<String>,
+}
+
+impl Default for BudgetPlan {
+    fn default() -> Self {
+        Self {
+            allow_ai: true,
+            allow_vision: true,
+            scale_factor: 1.0,
+            prefer_cache: false,
+            reasons: Vec::new(),
+        }
+    }
+}
+
+/// Strategie ticku podle limitů - nejdřív cena (AI nad screenshotem, pak AI vůbec),
+/// potom čas: bez AI, menší snímek, minulé rozhodnutí.
+/// `costs` = odhad ceny AI nad textem a nad screenshotem, None = model bez známé ceny.
+pub fn plan(budget: &TickBudget, timings: &StageTimings, costs: Option<(f64, f64)>) -> BudgetPlan {
+    let mut plan = BudgetPlan::default();
+
+    match (budget.max_cost_usd, costs) {
+        // Bez ceny modelu nejde limit dodržet - AI se radši vynechá, než aby se cena hádala
+        (Some(_), None) => {
+            plan.allow_ai = false;
+            plan.allow_vision = false;
+            plan.reasons.push("AI vynechána (cena modelu není v ceníku)".to_string());
+        }
+        (Some(max_cost), Some((ai_cost, vision_cost))) => {
+            if vision_cost > max_cost {
+                plan.allow_vision = false;
+                plan.reasons.push(format!("AI nad screenshotem vynechána (odhad ${:.4} > ${:.4})", vision_cost, max_cost));
+            }
+            if ai_cost > max_cost {
+                plan.allow_ai = false;
+                plan.allow_vision = false;
+                plan.reasons.push(format!("AI vynechána (odhad ${:.4} > ${:.4})", ai_cost, max_cost));
+            }
+        }
+        (None, _) => {}
+    }
+
+    let Some(max_seconds) = budget.max_seconds else {
+        return plan;
+    };
+    let capture = timings.capture.unwrap_or(0.0);
+    let mut ocr = timings.ocr.unwrap_or(0.0);
+    let ai = timings.ai.unwrap_or(0.0);
+    let predicted = |plan: &BudgetPlan, ocr: f64| capture + ocr + if plan.allow_ai { ai } else { 0.0 };
+
+    if predicted(&plan, ocr) > max_seconds && plan.allow_ai && ai > 0.0 {
+        plan.reasons.push(format!("AI vynechána (odhad {:.1}s > {:.1}s)", predicted(&plan, ocr), max_seconds));
+        plan.allow_ai = false;
+        plan.allow_vision = false;
+    }
+    if predicted(&plan, ocr) > max_seconds && ocr > 0.0 {
+        plan.reasons.push(format!("snímek zmenšen (odhad {:.1}s > {:.1}s)", predicted(&plan, ocr), max_seconds));
+        plan.scale_factor = BUDGET_SCALE_FACTOR;
+        // OCR trvá zhruba úměrně počtu pixelů
+        ocr *= (BUDGET_SCALE_FACTOR * BUDGET_SCALE_FACTOR) as f64;
+    }
+    if predicted(&plan, ocr) > max_seconds {
+        plan.reasons.push(format!("použito minulé rozhodnutí (odhad {:.1}s > {:.1}s)", predicted(&plan, ocr), max_seconds));
+        plan.prefer_cache = true;
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_matcher::MATCH_MODEL;
+
+    #[test]
+    fn test_budget_plan() {
+        let mut timings = StageTimings::default();
+        timings.record(Stage::Capture, 0.5);
+        timings.record(Stage::Ocr, 4.0);
+        timings.record(Stage::Ai, 3.0);
+        timings.record(Stage::Ai, 5.0);
+
+        // Bez limitů se nic neomezuje
+        assert_eq!(plan(&TickBudget::default(), &timings, Some((1.0, 1.0))), BudgetPlan::default());
+
+        // 0.5 + 4 + 3.6 > 8 - stačí vynechat AI
+        let fast = TickBudget { max_seconds: Some(8.0), max_cost_usd: None };
+        let p = plan(&fast, &timings, Some((0.0, 0.0)));
+        assert!(!p.allow_ai && p.scale_factor == 1.0 && !p.prefer_cache);
+
+        // 0.5 + 4 > 3 - i menší snímek, 0.5 + 1 se vejde
+        let faster = TickBudget { max_seconds: Some(3.0), max_cost_usd: None };
+        let p = plan(&faster, &timings, Some((0.0, 0.0)));
+        assert_eq!((p.allow_ai, p.scale_factor, p.prefer_cache), (false, BUDGET_SCALE_FACTOR, false));
+        assert_eq!(p.reasons.len(), 2);
+
+        let p = plan(&TickBudget { max_seconds: Some(1.0), max_cost_usd: None }, &timings, Some((0.0, 0.0)));
+        assert!(p.prefer_cache);
+
+        // Cena: screenshot je dražší než text
+        let tasks: Vec<FreeloTask> = (0..50)
+            .map(|id| FreeloTask {
+                id,
+                name: "Implementace přihlášení".to_string(),
+                project_name: "Web".to_string(),
+                estimate_minutes: None,
+                project_id: 1,
+            })
+            .collect();
+        let chars = prompt_chars(&tasks);
+        let text_cost = estimate_ai_cost(MATCH_MODEL, chars, false, 500).unwrap();
+        let vision_cost = estimate_ai_cost(MATCH_MODEL, chars, true, 500).unwrap();
+        assert!(text_cost > 0.001 && text_cost < vision_cost);
+
+        let cheap = TickBudget { max_seconds: None, max_cost_usd: Some((text_cost + vision_cost) / 2.0) };
+        let p = plan(&cheap, &StageTimings::default(), Some((text_cost, vision_cost)));
+        assert!(p.allow_ai && !p.allow_vision);
+        let p = plan(&TickBudget { max_seconds: None, max_cost_usd: Some(0.0) }, &StageTimings::default(), Some((0.0, 0.0)));
+        assert!(p.allow_ai, "Ollama je zdarma");
+
+        // Neznámá cena modelu - s cenovým limitem bez AI, bez limitu se nic neomezuje
+        assert_eq!(estimate_ai_cost("neznamy/model-x", chars, false, 500), None);
+        let p = plan(&cheap, &StageTimings::default(), None);
+        assert!(!p.allow_ai && !p.allow_vision && p.reasons.len() == 1);
+        assert!(plan(&fast, &StageTimings::default(), None).allow_ai);
+
+        assert!(validate(&TickBudget { max_seconds: Some(0.0), max_cost_usd: None }).is_err());
+    }
+}
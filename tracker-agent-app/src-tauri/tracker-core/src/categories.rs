@@ -0,0 +1,137 @@
+use crate::note_templates::{self, NoteContext};
+use crate::text_matcher::activity_key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Klíč segmentu kategorie s Freelo timerem bez tasku
+const CATEGORY_PREFIX: &str = "category:";
+
+/// Klíč segmentu kategorie, která se do Freelo nezapisuje
+const LOCAL_PREFIX: &str = "local:";
+
+/// Kam se zapisuje čas kategorie
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CategoryTarget {
+    /// Freelo timer bez tasku (čas projektu/workspace)
+    #[default]
+    Freelo,
+    /// Pevný task (např. "Administrativa" v interním projektu)
+    Task { task_id: i32 },
+    /// Jen lokální historie, do Freelo nic
+    Local,
+}
+
+/// Kategorie práce mimo tasky (administrativa, interní porady...) - má přednost před matcherem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkCategory {
+    pub name: String,
+    /// Slova v názvu aplikace, titulku okna nebo popisu aktivity
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub target: CategoryTarget,
+    /// Šablona poznámky (stejné symboly jako šablony tasků), None = "<název>: {activity}"
+    pub note: Option<String>,
+}
+
+pub fn validate(categories: &[WorkCategory]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for category in categories {
+        if category.name.trim().is_empty() {
+            return Err("Kategorie práce musí mít název".to_string());
+        }
+        if !names.insert(category.name.as_str()) {
+            return Err(format!("Kategorie '{}' je zadaná vícekrát", category.name));
+        }
+        if category.keywords.iter().all(|k| activity_key(k).is_empty()) {
+            return Err(format!("Kategorie '{}' nemá žádné klíčové slovo", category.name));
+        }
+        if let Some(ref note) = category.note {
+            note_templates::validate(note).map_err(|e| format!("Kategorie '{}': {}", category.name, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// První kategorie, jejíž klíčové slovo (celé slovo/fráze, bez ohledu na diakritiku) je v textu
+pub fn find<'a>(categories: &'a [WorkCategory], text: &str) -> Option<&'a WorkCategory> {
+    let text = format!(" {} ", activity_key(text));
+    categories.iter().find(|category| {
+        category
+            .keywords
+            .iter()
+            .map(|k| activity_key(k))
+            .any(|k| !k.is_empty() && text.contains(&format!(" {} ", k)))
+    })
+}
+
+/// Klíč segmentu - task ID, nebo kategorie s prefixem
+pub fn tracking_key(category: &WorkCategory) -> String {
+    match category.target {
+        CategoryTarget::Freelo => format!("{}{}", CATEGORY_PREFIX, category.name),
+        CategoryTarget::Task { task_id } => task_id.to_string(),
+        CategoryTarget::Local => format!("{}{}", LOCAL_PREFIX, category.name),
+    }
+}
+
+/// Segment se do Freelo nezapisuje
+pub fn is_local(tracking_key: &str) -> bool {
+    tracking_key.starts_with(LOCAL_PREFIX)
+}
+
+/// Název kategorie z klíče segmentu (None = task nebo obecná práce)
+pub fn label(tracking_key: &str) -> Option<&str> {
+    tracking_key
+        .strip_prefix(CATEGORY_PREFIX)
+        .or_else(|| tracking_key.strip_prefix(LOCAL_PREFIX))
+}
+
+pub fn note(category: &WorkCategory, activity: &str) -> String {
+    let template = category
+        .note
+        .clone()
+        .unwrap_or_else(|| format!("{}: {{activity}}", category.name));
+    note_templates::render(
+        &template,
+        &NoteContext {
+            activity,
+            task: &category.name,
+            project: "",
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_category() {
+        let category = |name: &str, keywords: &[&str], target: CategoryTarget| WorkCategory {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            target,
+            note: None,
+        };
+        let categories = vec![
+            category("Porady", &["Google Meet", "porada"], CategoryTarget::Freelo),
+            category("Administrativa", &["fakturoid"], CategoryTarget::Task { task_id: 42 }),
+            category("Osobní", &["banka"], CategoryTarget::Local),
+        ];
+        assert!(validate(&categories).is_ok());
+
+        let found = find(&categories, "Chrome Meet - Týdenní PORADA týmu").unwrap();
+        assert_eq!(found.name, "Porady");
+        assert_eq!(tracking_key(found), "category:Porady");
+        assert_eq!(note(found, "Týdenní porada"), "Porady: Týdenní porada");
+
+        assert_eq!(tracking_key(find(&categories, "Fakturoid | Faktury").unwrap()), "42");
+        let local = tracking_key(find(&categories, "Internetová banka").unwrap());
+        assert!(is_local(&local));
+        assert_eq!(label(&local), Some("Osobní"));
+
+        // Jen celá slova - "bankovni" neni "banka"
+        assert!(find(&categories, "bankovní převod v VS Code").is_none());
+        assert!(validate(&[category("X", &["  "], CategoryTarget::Freelo)]).is_err());
+    }
+}
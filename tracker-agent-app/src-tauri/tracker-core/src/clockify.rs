@@ -0,0 +1,258 @@
+use crate::backend::{self, ExternalTask, TimeTrackingBackend};
+use crate::connection_check;
+use crate::freelo::{FreeloTask, SharedWriteRouting, WriteRouting};
+use chrono::{SecondsFormat, Utc};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::Deserialize;
+use tracing::{info, instrument, Span};
+
+const API_URL: &str = "https://api.clockify.me/api/v1";
+
+/// Clockify - tasky projektů, projekt bez tasků se nabízí jako celek
+#[derive(Clone)]
+pub struct ClockifyClient {
+    client: Client,
+    api_key: String,
+    workspace_id: Option<String>,
+    routing: SharedWriteRouting,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    id: String,
+    active_workspace: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Project {
+    id: String,
+    name: String,
+    #[serde(default)]
+    tasks: Vec<Task>,
+}
+
+#[derive(Deserialize)]
+struct Task {
+    id: String,
+    name: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeInterval {
+    start: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeEntry {
+    id: String,
+    time_interval: Option<TimeInterval>,
+    project_id: Option<String>,
+    task_id: Option<String>,
+}
+
+impl ClockifyClient {
+    pub fn new(api_key: String, workspace_id: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key,
+            workspace_id,
+            routing: SharedWriteRouting::default(),
+        }
+    }
+
+    /// Zápisy se řídí sdíleným přepínačem - sandbox je Freelo workspace, tady = dry-run
+    pub fn with_write_routing(mut self, routing: SharedWriteRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    pub fn write_routing(&self) -> WriteRouting {
+        self.routing.read().map(|r| *r).unwrap_or_default()
+    }
+
+    fn writes_enabled(&self, op: &str) -> bool {
+        let enabled = self.write_routing() == WriteRouting::Live;
+        if !enabled {
+            info!(op, "dry-run: zápis do Clockify neodeslán");
+        }
+        enabled
+    }
+
+    async fn send(&self, builder: RequestBuilder, op: &str) -> Result<Response, String> {
+        let response = builder
+            .header("X-Api-Key", &self.api_key)
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .send()
+            .await
+            .map_err(|e| format!("HTTP chyba: {}", e))?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Clockify {} error {}: {}", op, status, text));
+        }
+        Ok(response)
+    }
+
+    /// Ověří API klíč dotazem na profil
+    #[instrument(name = "clockify", skip_all, fields(op = "check_connection", status))]
+    pub async fn check_connection(&self) -> Result<(), String> {
+        let response = self
+            .client
+            .get(format!("{}/user", API_URL))
+            .header("X-Api-Key", &self.api_key)
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .timeout(connection_check::CHECK_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP chyba: {}", e))?;
+        Span::current().record("status", response.status().as_u16());
+
+        connection_check::check_status(response.status())
+    }
+
+    async fn user(&self) -> Result<User, String> {
+        self.send(self.client.get(format!("{}/user", API_URL)), "user")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))
+    }
+
+    async fn workspace_id(&self) -> Result<String, String> {
+        match self.workspace_id {
+            Some(ref id) => Ok(id.clone()),
+            None => Ok(self.user().await?.active_workspace),
+        }
+    }
+}
+
+impl TimeTrackingBackend for ClockifyClient {
+    #[instrument(name = "clockify", skip_all, fields(op = "list_tasks", status))]
+    async fn list_tasks(&self) -> Result<Vec<FreeloTask>, String> {
+        let workspace_id = self.workspace_id().await?;
+        let url = format!(
+            "{}/workspaces/{}/projects?archived=false&hydrated=true&page-size=500",
+            API_URL, workspace_id
+        );
+        let projects: Vec<Project> = self
+            .send(self.client.get(url), "projects")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        let mut tasks = Vec::new();
+        for project in projects {
+            let project_id = backend::register(ExternalTask {
+                workspace_id: workspace_id.clone(),
+                project_id: project.id.clone(),
+                task_id: None,
+            });
+            let active: Vec<Task> = project.tasks.into_iter().filter(|t| t.status == "ACTIVE").collect();
+            if active.is_empty() {
+                tasks.push(FreeloTask {
+                    id: project_id,
+                    name: project.name.clone(),
+                    project_id,
+                    project_name: project.name,
+                    estimate_minutes: None,
+                });
+                continue;
+            }
+            for task in active {
+                tasks.push(FreeloTask {
+                    id: backend::register(ExternalTask {
+                        workspace_id: workspace_id.clone(),
+                        project_id: project.id.clone(),
+                        task_id: Some(task.id),
+                    }),
+                    name: task.name,
+                    project_id,
+                    project_name: project.name.clone(),
+                    estimate_minutes: None,
+                });
+            }
+        }
+        Ok(tasks)
+    }
+
+    #[instrument(name = "clockify", skip_all, fields(op = "start", status))]
+    async fn start(&self, task_id: Option<&str>, note: &str) -> Result<String, String> {
+        if !self.writes_enabled("start") {
+            return Ok(format!("dry-run-{}", chrono::Local::now().timestamp_millis()));
+        }
+        let target = match task_id {
+            Some(id) => Some(backend::resolve(id).ok_or_else(|| format!("Neznámý Clockify task {}", id))?),
+            None => None,
+        };
+        let workspace_id = match target {
+            Some(ref t) => t.workspace_id.clone(),
+            None => self.workspace_id().await?,
+        };
+
+        let body = serde_json::json!({
+            "start": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "description": note,
+            "projectId": target.as_ref().map(|t| &t.project_id),
+            "taskId": target.as_ref().and_then(|t| t.task_id.as_ref()),
+        });
+        let url = format!("{}/workspaces/{}/time-entries", API_URL, workspace_id);
+        let entry: TimeEntry = self
+            .send(self.client.post(url).json(&body), "start tracking")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        Ok(backend::entry_id(&workspace_id, &entry.id))
+    }
+
+    /// Clockify zastavuje běžící záznam uživatele, ne konkrétní ID
+    #[instrument(name = "clockify", skip_all, fields(op = "stop", status))]
+    async fn stop(&self, entry_id: &str) -> Result<(), String> {
+        if !self.writes_enabled("stop") {
+            return Ok(());
+        }
+        let (workspace_id, _) = backend::split_entry_id(entry_id)?;
+        let user = self.user().await?;
+        let url = format!("{}/workspaces/{}/user/{}/time-entries", API_URL, workspace_id, user.id);
+        let body = serde_json::json!({ "end": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true) });
+        self.send(self.client.patch(url).json(&body), "stop tracking").await?;
+        Ok(())
+    }
+
+    /// Úprava záznamu v Clockify vyžaduje i začátek a projekt - nejdřív se načte
+    #[instrument(name = "clockify", skip_all, fields(op = "annotate", status))]
+    async fn annotate(&self, entry_id: &str, note: &str) -> Result<(), String> {
+        if !self.writes_enabled("annotate") {
+            return Ok(());
+        }
+        let (workspace_id, id) = backend::split_entry_id(entry_id)?;
+        let url = format!("{}/workspaces/{}/time-entries/{}", API_URL, workspace_id, id);
+        let entry: TimeEntry = self
+            .send(self.client.get(&url), "get tracking")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        let start = entry
+            .time_interval
+            .map(|i| i.start)
+            .ok_or_else(|| format!("Clockify záznam {} nemá začátek", entry.id))?;
+
+        let body = serde_json::json!({
+            "start": start,
+            "description": note,
+            "projectId": entry.project_id,
+            "taskId": entry.task_id,
+        });
+        self.send(self.client.put(url).json(&body), "edit tracking").await?;
+        Ok(())
+    }
+}
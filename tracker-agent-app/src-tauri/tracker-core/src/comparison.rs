@@ -0,0 +1,218 @@
+use crate::freelo::FreeloTask;
+use crate::history::HistorySegment;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Odchylka do této hodnoty se nepovažuje za chybu (zaokrouhlování, přestávky)
+pub const DEFAULT_TOLERANCE_MINUTES: i64 = 15;
+
+/// Řádek očekávaných hodin z CSV
+#[derive(Debug, Clone, PartialEq)]
+struct ExpectedRow {
+    date: NaiveDate,
+    project: String,
+    minutes: i64,
+}
+
+/// Porovnání jednoho dne a projektu
+#[derive(Debug, Clone, Serialize)]
+pub struct Deviation {
+    pub date: NaiveDate,
+    pub project_id: Option<i32>,
+    pub project_name: String,
+    pub expected_minutes: i64,
+    pub tracked_minutes: i64,
+    /// Naměřeno minus očekáváno (záporné = agent nedotrackoval)
+    pub diff_minutes: i64,
+    /// Rozdíl je nad tolerancí
+    pub flagged: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonReport {
+    /// Podle data a projektu
+    pub rows: Vec<Deviation>,
+    pub expected_minutes: i64,
+    pub tracked_minutes: i64,
+    /// Kolik řádků je nad tolerancí
+    pub flagged: usize,
+    /// Projekty z CSV, které nejsou mezi načtenými tasky
+    pub unknown_projects: Vec<String>,
+}
+
+/// Hodiny "7,5", "7.5" nebo "7:30"
+fn parse_minutes(value: &str) -> Option<i64> {
+    if let Some((hours, minutes)) = value.split_once(':') {
+        let hours: i64 = hours.trim().parse().ok()?;
+        let minutes: i64 = minutes.trim().parse().ok()?;
+        return (hours >= 0 && (0..60).contains(&minutes)).then_some(hours * 60 + minutes);
+    }
+    let hours: f64 = value.trim().replace(',', ".").parse().ok()?;
+    (hours >= 0.0 && hours.is_finite()).then_some((hours * 60.0).round() as i64)
+}
+
+/// CSV "datum,projekt,hodiny" (oddělovač čárka nebo středník, hlavička volitelná)
+fn parse_expected(csv: &str) -> Result<Vec<ExpectedRow>, String> {
+    let mut rows = Vec::new();
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        let separator = if line.contains(';') { ';' } else { ',' };
+        let fields: Vec<&str> = line.split(separator).map(|f| f.trim().trim_matches('"')).collect();
+        let [date, project, hours] = fields[..] else {
+            return Err(format!("Řádek {}: očekávány 3 sloupce (datum, projekt, hodiny)", i + 1));
+        };
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            // Hlavička
+            if i == 0 && rows.is_empty() {
+                continue;
+            }
+            return Err(format!("Řádek {}: neplatné datum '{}' (očekáváno YYYY-MM-DD)", i + 1, date));
+        };
+        let minutes = parse_minutes(hours).ok_or_else(|| format!("Řádek {}: neplatné hodiny '{}'", i + 1, hours))?;
+        rows.push(ExpectedRow {
+            date,
+            project: project.to_string(),
+            minutes,
+        });
+    }
+    if rows.is_empty() {
+        return Err("CSV neobsahuje žádné řádky s očekávanými hodinami".to_string());
+    }
+    Ok(rows)
+}
+
+/// Porovná očekávané hodiny z CSV s historií segmentů ve dnech, které CSV pokrývá.
+/// Projekt v CSV je název nebo ID projektu z Freelo.
+pub fn compare(csv: &str, segments: &[HistorySegment], tasks: &[FreeloTask], tolerance_minutes: i64) -> Result<ComparisonReport, String> {
+    let expected = parse_expected(csv)?;
+    let mut projects: BTreeMap<i32, &str> = BTreeMap::new();
+    for task in tasks {
+        projects.insert(task.project_id, &task.project_name);
+    }
+    let resolve = |project: &str| -> Option<i32> {
+        project
+            .parse::<i32>()
+            .ok()
+            .filter(|id| projects.contains_key(id))
+            .or_else(|| projects.iter().find(|(_, name)| name.eq_ignore_ascii_case(project)).map(|(id, _)| *id))
+    };
+
+    // (den, projekt) -> (název, očekáváno, naměřeno); neznámý projekt se páruje jen podle názvu
+    let mut rows: BTreeMap<(NaiveDate, Option<i32>, String), (i64, i64)> = BTreeMap::new();
+    let mut unknown_projects: Vec<String> = Vec::new();
+    for row in &expected {
+        let project_id = resolve(&row.project);
+        let name = match project_id {
+            Some(id) => projects[&id].to_string(),
+            None => {
+                if !unknown_projects.contains(&row.project) {
+                    unknown_projects.push(row.project.clone());
+                }
+                row.project.clone()
+            }
+        };
+        rows.entry((row.date, project_id, name)).or_default().0 += row.minutes;
+    }
+
+    let days: Vec<NaiveDate> = expected.iter().map(|r| r.date).collect();
+    for segment in segments {
+        let day = segment.started_at.date_naive();
+        if !days.contains(&day) {
+            continue;
+        }
+        let task = segment.task_id.and_then(|id| tasks.iter().find(|t| t.id == id));
+        let (project_id, name) = match task {
+            Some(task) => (Some(task.project_id), task.project_name.clone()),
+            None => (None, "Obecná práce".to_string()),
+        };
+        rows.entry((day, project_id, name)).or_default().1 += segment.minutes();
+    }
+
+    let rows: Vec<Deviation> = rows
+        .into_iter()
+        .map(|((date, project_id, project_name), (expected_minutes, tracked_minutes))| {
+            let diff_minutes = tracked_minutes - expected_minutes;
+            Deviation {
+                date,
+                project_id,
+                project_name,
+                expected_minutes,
+                tracked_minutes,
+                diff_minutes,
+                flagged: diff_minutes.abs() > tolerance_minutes,
+            }
+        })
+        .collect();
+
+    Ok(ComparisonReport {
+        expected_minutes: rows.iter().map(|r| r.expected_minutes).sum(),
+        tracked_minutes: rows.iter().map(|r| r.tracked_minutes).sum(),
+        flagged: rows.iter().filter(|r| r.flagged).count(),
+        rows,
+        unknown_projects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Local, TimeZone};
+
+    #[test]
+    fn test_compare_with_expected() {
+        let at = |day: u32, h: u32| Local.with_ymd_and_hms(2025, 3, day, h, 0, 0).unwrap();
+        let segment = |task_id: Option<i32>, from: DateTime<Local>, to: DateTime<Local>| HistorySegment {
+            id: format!("{}", from.timestamp()),
+            task_id,
+            note: String::new(),
+            started_at: from,
+            stopped_at: to,
+            source: None,
+            freelo_report_id: None,
+        };
+        let segments = vec![
+            segment(Some(1), at(10, 9), at(10, 12)),
+            segment(Some(2), at(10, 13), at(10, 14)),
+            segment(None, at(10, 14), at(10, 15)),
+            segment(Some(1), at(11, 9), at(11, 17)),
+            // Den mimo CSV se nepočítá
+            segment(Some(1), at(12, 9), at(12, 17)),
+        ];
+        let task = |id: i32, project_id: i32, project_name: &str| FreeloTask {
+            id,
+            name: format!("Task {}", id),
+            project_id,
+            project_name: project_name.to_string(),
+            estimate_minutes: None,
+        };
+        let tasks = vec![task(1, 7, "Web"), task(2, 8, "Eshop")];
+
+        let csv = "datum;projekt;hodiny\n2025-03-10;web;3\n2025-03-10;8;2,5\n2025-03-11;Web;8:10\n2025-03-11;Interní;1\n";
+        let report = compare(csv, &segments, &tasks, DEFAULT_TOLERANCE_MINUTES).unwrap();
+
+        let row = |day: u32, name: &str| {
+            report
+                .rows
+                .iter()
+                .find(|r| r.date == at(day, 0).date_naive() && r.project_name == name)
+                .unwrap()
+        };
+        assert_eq!(row(10, "Web").diff_minutes, 0);
+        assert!(!row(10, "Web").flagged);
+        assert_eq!(row(10, "Eshop").diff_minutes, -90);
+        assert!(row(10, "Eshop").flagged);
+        assert_eq!(row(10, "Obecná práce").tracked_minutes, 60);
+        assert!(!row(11, "Web").flagged);
+        assert_eq!(row(11, "Interní").tracked_minutes, 0);
+        assert_eq!(report.unknown_projects, vec!["Interní".to_string()]);
+        assert_eq!(report.rows.len(), 5);
+        assert_eq!(report.flagged, 3);
+
+        assert!(compare("2025-03-10,Web", &segments, &tasks, 15).is_err());
+        assert!(compare("datum,projekt,hodiny\n", &segments, &tasks, 15).is_err());
+    }
+}
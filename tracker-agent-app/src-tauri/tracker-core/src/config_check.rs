@@ -0,0 +1,291 @@
+use crate::rule_pack::{RulePack, RULE_PACK_VERSION};
+use crate::settings::Settings;
+use crate::task_rules;
+use crate::text_matcher::{matching_signatures, AppSignature};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Kratší klíčové slovo se najde skoro v každém textu
+const MIN_KEYWORD_CHARS: usize = 3;
+
+/// Chyba nebo varování v konfiguraci (řádek/sloupec od 1, pokud jde určit)
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn at(line: Option<usize>, message: String) -> Self {
+        Self {
+            line,
+            column: None,
+            message,
+        }
+    }
+}
+
+/// Signatura, která by na vzorovém textu zabrala
+#[derive(Debug, Clone, Serialize)]
+pub struct FiredRule {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+/// Výsledek kontroly konfiguračního souboru
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReport {
+    /// "rule_pack" nebo "settings"
+    pub kind: String,
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+    /// Simulace na vzorovém OCR textu (prázdné bez vzorku)
+    pub fired: Vec<FiredRule>,
+    /// Aplikace, kterou by matcher detekoval (vyhrává první signatura)
+    pub detected_application: Option<String>,
+}
+
+impl ConfigReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Řádek n-tého výskytu textu (od 1)
+fn line_of(content: &str, needle: &str, occurrence: usize) -> Option<usize> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains(needle))
+        .nth(occurrence)
+        .map(|(i, _)| i + 1)
+}
+
+fn json_error(e: &serde_json::Error) -> ConfigIssue {
+    ConfigIssue {
+        line: Some(e.line()),
+        column: Some(e.column()),
+        message: e.to_string(),
+    }
+}
+
+/// Kontrola signatur rule packu (prázdné názvy/slova, duplicity, příliš obecná slova)
+fn check_signatures(content: &str, signatures: &[AppSignature], report: &mut ConfigReport) {
+    let mut seen: HashSet<&str> = HashSet::new();
+
+    for (i, signature) in signatures.iter().enumerate() {
+        let needle = serde_json::to_string(&signature.name).unwrap_or_default();
+        let occurrence = signatures[..i].iter().filter(|s| s.name == signature.name).count();
+        let line = line_of(content, &needle, occurrence);
+        let label = if signature.name.trim().is_empty() {
+            format!("Signatura #{}", i + 1)
+        } else {
+            format!("Signatura '{}'", signature.name)
+        };
+
+        if signature.name.trim().is_empty() {
+            report.errors.push(ConfigIssue::at(line, format!("{}: chybí název", label)));
+        } else if !seen.insert(signature.name.as_str()) {
+            report
+                .errors
+                .push(ConfigIssue::at(line, format!("{}: duplicitní název (přepíše předchozí)", label)));
+        }
+
+        if signature.keywords.is_empty() {
+            report.errors.push(ConfigIssue::at(line, format!("{}: nemá žádná klíčová slova", label)));
+        }
+        for keyword in &signature.keywords {
+            let chars = keyword.chars().filter(|c| c.is_alphanumeric()).count();
+            if chars == 0 {
+                report
+                    .errors
+                    .push(ConfigIssue::at(line, format!("{}: prázdné klíčové slovo '{}'", label, keyword)));
+            } else if chars < MIN_KEYWORD_CHARS {
+                report.warnings.push(ConfigIssue::at(
+                    line,
+                    format!("{}: klíčové slovo '{}' je příliš krátké, zabere skoro všude", label, keyword),
+                ));
+            }
+        }
+    }
+}
+
+/// Zkontroluje obsah konfigurace (rule pack nebo nastavení) a volitelně
+/// nasimuluje, které signatury by zabraly na vzorovém OCR textu
+pub fn check_content(content: &str, sample: Option<&str>) -> ConfigReport {
+    let mut report = ConfigReport {
+        kind: "unknown".to_string(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        fired: Vec::new(),
+        detected_application: None,
+    };
+
+    let value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => {
+            report.errors.push(json_error(&e));
+            return report;
+        }
+    };
+
+    let signatures = if value.get("app_signatures").is_some() || value.get("task_rules").is_some() || value.get("version").is_some() {
+        report.kind = "rule_pack".to_string();
+        let pack: RulePack = match serde_json::from_str(content) {
+            Ok(pack) => pack,
+            Err(e) => {
+                report.errors.push(json_error(&e));
+                return report;
+            }
+        };
+        if pack.version > RULE_PACK_VERSION {
+            report.errors.push(ConfigIssue::at(
+                line_of(content, "\"version\"", 0),
+                format!("Verze {} je novější, než podporuje aplikace ({})", pack.version, RULE_PACK_VERSION),
+            ));
+        }
+        check_signatures(content, &pack.app_signatures, &mut report);
+        for rule in &pack.task_rules {
+            if let Err(e) = task_rules::validate(rule) {
+                let needle = serde_json::to_string(&rule.name).unwrap_or_default();
+                report.errors.push(ConfigIssue::at(line_of(content, &needle, 0), e));
+            }
+        }
+        pack.app_signatures
+    } else if value.get("interval").is_some() || value.get("freelo_email").is_some() {
+        report.kind = "settings".to_string();
+        let settings: Settings = match serde_json::from_str(content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                report.errors.push(json_error(&e));
+                return report;
+            }
+        };
+        if let Err(e) = settings.validate() {
+            report.errors.push(ConfigIssue::at(None, e));
+        }
+        // Nastavení pravidla matcheru nenese - simuluje se aktivní rule pack
+        RulePack::load_shared().app_signatures
+    } else {
+        report.errors.push(ConfigIssue::at(
+            None,
+            "Neznámý typ konfigurace (očekáván rule pack nebo nastavení)".to_string(),
+        ));
+        return report;
+    };
+
+    if let Some(sample) = sample {
+        report.fired = matching_signatures(sample, &signatures)
+            .into_iter()
+            .map(|(signature, keywords)| FiredRule {
+                name: signature.name.clone(),
+                keywords,
+            })
+            .collect();
+        report.detected_application = report.fired.first().map(|f| f.name.clone());
+    }
+
+    report
+}
+
+/// Zkontroluje konfigurační soubor
+pub fn check_file(path: &Path, sample: Option<&str>) -> Result<ConfigReport, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Nelze přečíst {:?}: {}", path, e))?;
+    Ok(check_content(&content, sample))
+}
+
+const CLI_USAGE: &str = "Použití: tracker-agent-cli config check <soubor> [--sample <soubor s OCR textem>]";
+
+/// `tracker-agent-cli config check <path>` - vrací exit kód (0 = OK, 1 = chyby, 2 = špatné použití)
+pub fn cli(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (path, sample_path) = match args.as_slice() {
+        ["config", "check", path] => (*path, None),
+        ["config", "check", path, "--sample", sample] => (*path, Some(*sample)),
+        _ => {
+            eprintln!("{}", CLI_USAGE);
+            return 2;
+        }
+    };
+
+    let sample = match sample_path.map(std::fs::read_to_string).transpose() {
+        Ok(sample) => sample,
+        Err(e) => {
+            eprintln!("Nelze přečíst vzorový text: {}", e);
+            return 2;
+        }
+    };
+
+    let report = match check_file(Path::new(path), sample.as_deref()) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 2;
+        }
+    };
+
+    let location = |issue: &ConfigIssue| match (issue.line, issue.column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", path, line, column),
+        (Some(line), None) => format!("{}:{}", path, line),
+        _ => path.to_string(),
+    };
+    for issue in &report.errors {
+        println!("{}: chyba: {}", location(issue), issue.message);
+    }
+    for issue in &report.warnings {
+        println!("{}: varování: {}", location(issue), issue.message);
+    }
+    if sample.is_some() {
+        for fired in &report.fired {
+            println!("zabere: {} ({})", fired.name, fired.keywords.join(", "));
+        }
+        println!(
+            "detekovaná aplikace: {}",
+            report.detected_application.as_deref().unwrap_or("žádná")
+        );
+    }
+
+    if report.is_ok() {
+        println!("{} ({}): OK", path, report.kind);
+        0
+    } else {
+        println!("{} ({}): {} chyb", path, report.kind, report.errors.len());
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_rule_pack() {
+        let broken = "{\n  \"version\": 1,\n  \"app_signatures\": [\n    {\"name\": \"Slack\", \"keywords\": [\"slack\",]}\n  ]\n}";
+        let report = check_content(broken, None);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, Some(4));
+
+        let pack = r#"{
+  "version": 1,
+  "app_signatures": [
+    {"name": "Slack", "keywords": ["slack", "huddle"]},
+    {"name": "Jira", "keywords": ["jira", "js"]},
+    {"name": "Slack", "keywords": []}
+  ]
+}"#;
+        let report = check_content(pack, Some("Huddle ve Slacku k JIRA-123"));
+        assert_eq!(report.kind, "rule_pack");
+        let lines: Vec<Option<usize>> = report.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![Some(6), Some(6)]);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].line, Some(5));
+
+        let fired: Vec<&str> = report.fired.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(fired, vec!["Slack", "Jira"]);
+        assert_eq!(report.fired[0].keywords, vec!["slack", "huddle"]);
+        assert_eq!(report.detected_application.as_deref(), Some("Slack"));
+    }
+}
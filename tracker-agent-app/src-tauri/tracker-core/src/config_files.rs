@@ -0,0 +1,66 @@
+use crate::errors::ErrorCode;
+use crate::prompt_template::PROMPT_TEMPLATE_FILE;
+use crate::rule_pack::RulePack;
+use crate::storage;
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Chyba v ručně upraveném souboru (řádek a sloupec od 1, pokud jsou známé)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyntaxError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} (řádek {}, sloupec {})", self.message, line, column),
+            (Some(line), None) => write!(f, "{} (řádek {})", self.message, line),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Soubory, které uživatel může upravovat v externím editoru
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFile {
+    RulePack,
+    PromptTemplate,
+}
+
+impl ConfigFile {
+    pub const ALL: [ConfigFile; 2] = [ConfigFile::RulePack, ConfigFile::PromptTemplate];
+
+    pub fn path(self) -> PathBuf {
+        match self {
+            ConfigFile::RulePack => RulePack::path(),
+            ConfigFile::PromptTemplate => storage::data_file(PROMPT_TEMPLATE_FILE),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfigFile::RulePack => "pravidla (rule_pack.json)",
+            ConfigFile::PromptTemplate => "šablona promptu (match_prompt.txt)",
+        }
+    }
+
+    pub fn error_code(self) -> ErrorCode {
+        match self {
+            ConfigFile::RulePack => ErrorCode::RulePackInvalid,
+            ConfigFile::PromptTemplate => ErrorCode::PromptTemplateInvalid,
+        }
+    }
+}
+
+/// Payload eventu `config-file-event` - výsledek kontroly po změně souboru
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileEvent {
+    pub file: String,
+    pub path: String,
+    pub valid: bool,
+    pub error: Option<SyntaxError>,
+}
@@ -0,0 +1,129 @@
+use crate::backend::BackendConfig;
+use crate::clockify::ClockifyClient;
+use crate::freelo::FreeloClient;
+use crate::settings::Settings;
+use crate::toggl::TogglClient;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Kontrola připojení nesmí UI blokovat dlouho - bez opakování a s krátkým timeoutem
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Výsledek kontroly jedné služby
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServiceCheck {
+    pub service: String,
+    pub ok: bool,
+    pub message: String,
+    /// Délka dotazu, None = nic se neodesílalo (chybí údaje, služba vypnutá)
+    pub latency_ms: Option<u64>,
+}
+
+impl ServiceCheck {
+    fn from_result(service: &str, result: Result<(), String>, elapsed: Duration) -> Self {
+        let (ok, message) = match result {
+            Ok(()) => (true, "Připojeno".to_string()),
+            Err(e) => (false, e),
+        };
+        Self { service: service.to_string(), ok, message, latency_ms: Some(elapsed.as_millis() as u64) }
+    }
+
+    fn offline(service: &str, ok: bool, message: &str) -> Self {
+        Self { service: service.to_string(), ok, message: message.to_string(), latency_ms: None }
+    }
+}
+
+/// Výsledek `test_connection` pro UI - chyba nastavení a kontrola každé služby zvlášť
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionReport {
+    pub settings_error: Option<String>,
+    pub services: Vec<ServiceCheck>,
+    pub ok: bool,
+}
+
+impl ConnectionReport {
+    fn new(settings_error: Option<String>, services: Vec<ServiceCheck>) -> Self {
+        let ok = settings_error.is_none() && services.iter().all(|s| s.ok);
+        Self { settings_error, services, ok }
+    }
+}
+
+/// Chybový HTTP status jako srozumitelná zpráva
+pub fn check_status(status: StatusCode) -> Result<(), String> {
+    match status {
+        s if s.is_success() => Ok(()),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(format!("Neplatné přihlašovací údaje ({})", status)),
+        StatusCode::TOO_MANY_REQUESTS => Err("Příliš mnoho požadavků, zkuste to za chvíli".to_string()),
+        s if s.is_server_error() => Err(format!("Služba je nedostupná ({})", status)),
+        _ => Err(format!("Neočekávaná odpověď {}", status)),
+    }
+}
+
+async fn timed(service: &str, check: impl Future<Output = Result<(), String>>) -> ServiceCheck {
+    let started = Instant::now();
+    let result = check.await;
+    ServiceCheck::from_result(service, result, started.elapsed())
+}
+
+async fn check_freelo(service: &str, email: &str, api_key: &str) -> ServiceCheck {
+    if email.trim().is_empty() || api_key.trim().is_empty() {
+        return ServiceCheck::offline(service, false, "Chybí e-mail nebo API klíč");
+    }
+    let client = FreeloClient::new(email.to_string(), api_key.to_string());
+    timed(service, client.check_connection()).await
+}
+
+/// Ověří nastavení a přihlašovací údaje (i neuložené z formuláře) dřív, než se spustí tracking
+pub async fn check(settings: &Settings) -> ConnectionReport {
+    let mut services = Vec::new();
+
+    match settings.backend_config() {
+        Ok(BackendConfig::Freelo) => {
+            services.push(check_freelo("Freelo", &settings.freelo_email, &settings.freelo_key).await);
+        }
+        Ok(BackendConfig::Toggl { api_token, workspace_id }) => {
+            services.push(timed("Toggl", TogglClient::new(api_token, workspace_id).check_connection()).await);
+        }
+        Ok(BackendConfig::Clockify { api_key, workspace_id }) => {
+            services.push(timed("Clockify", ClockifyClient::new(api_key, workspace_id).check_connection()).await);
+        }
+        Ok(BackendConfig::Demo) => services.push(ServiceCheck::offline("Demo", true, "Nic se neodesílá")),
+        Err(e) => services.push(ServiceCheck::offline("Služba pro čas", false, &e)),
+    }
+
+    if let (Some(email), Some(api_key)) = (&settings.sandbox_freelo_email, &settings.sandbox_freelo_key) {
+        if !email.is_empty() || !api_key.is_empty() {
+            services.push(check_freelo("Freelo (sandbox)", email, api_key).await);
+        }
+    }
+
+    match settings.llm() {
+        Some(llm) => services.push(timed(llm.name(), llm.check_connection()).await),
+        None => services.push(ServiceCheck::offline("OpenRouter", true, "Bez API klíče - AI matching vypnutý")),
+    }
+
+    ConnectionReport::new(settings.validate().err(), services)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_report() {
+        assert!(check_status(StatusCode::OK).is_ok());
+        assert!(check_status(StatusCode::UNAUTHORIZED).unwrap_err().contains("Neplatné přihlašovací údaje"));
+        assert!(check_status(StatusCode::BAD_GATEWAY).unwrap_err().contains("nedostupná"));
+
+        let freelo = ServiceCheck::from_result("Freelo", Ok(()), Duration::from_millis(120));
+        assert_eq!((freelo.ok, freelo.latency_ms), (true, Some(120)));
+        let ai = ServiceCheck::offline("OpenRouter", true, "Bez API klíče - AI matching vypnutý");
+        assert!(ConnectionReport::new(None, vec![freelo.clone(), ai.clone()]).ok);
+
+        let failed = ServiceCheck::from_result("Freelo", check_status(StatusCode::FORBIDDEN), Duration::ZERO);
+        assert!(!ConnectionReport::new(None, vec![failed, ai]).ok);
+        assert!(!ConnectionReport::new(Some("Neplatný interval".to_string()), vec![freelo]).ok);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::browser;
+use crate::notifications;
+use crate::window_info::WindowInfo;
+use chrono::{DateTime, Local};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// OCR obrazovky - jádro ticku, vypnutím zůstane matching podle okna (nebo AI nad screenshotem)
+pub const OCR: &str = "ocr";
+/// Aktivní okno (aplikace a titulek)
+pub const WINDOW: &str = "window";
+pub const CALENDAR: &str = "calendar";
+pub const GIT: &str = "git";
+pub const PROCESSES: &str = "processes";
+/// URL a titulek aktivní záložky prohlížeče
+pub const BROWSER: &str = "browser";
+
+/// Všechny známé zdroje a jestli jsou zapnuté bez nastavení
+const DEFAULTS: [(&str, bool); 6] = [
+    (OCR, true),
+    (WINDOW, true),
+    (CALENDAR, true),
+    (GIT, false),
+    (PROCESSES, false),
+    (BROWSER, false),
+];
+
+/// Max. počet procesů v kontextu
+const MAX_PROCESSES: usize = 40;
+
+/// Co o ticku víme před matchingem
+pub struct TickInput {
+    pub window: Option<WindowInfo>,
+    pub now: DateTime<Local>,
+}
+
+/// Zdroj doplňujícího kontextu pro matching - výstup se přidá k OCR textu
+pub trait ContextProvider: Send + Sync {
+    /// ID pro zapínání v nastavení (`context_providers`)
+    fn id(&self) -> &'static str;
+    /// Krátký text do kontextu, None = nic k dispozici. Smí blokovat (volá se mimo async runtime).
+    fn collect(&self, input: &TickInput) -> Option<String>;
+}
+
+/// Zapnutí/vypnutí zdrojů podle nastavení, nezmíněné mají výchozí stav
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProviderToggles(BTreeMap<String, bool>);
+
+impl ProviderToggles {
+    pub fn new(toggles: BTreeMap<String, bool>) -> Self {
+        Self(toggles)
+    }
+
+    pub fn is_enabled(&self, id: &str) -> bool {
+        self.0
+            .get(id)
+            .copied()
+            .unwrap_or_else(|| DEFAULTS.iter().any(|(known, on)| *known == id && *on))
+    }
+}
+
+/// Validace nastavení - jen známé zdroje
+pub fn validate(toggles: &BTreeMap<String, bool>) -> Result<(), String> {
+    for id in toggles.keys() {
+        if !DEFAULTS.iter().any(|(known, _)| known == id) {
+            let known: Vec<&str> = DEFAULTS.iter().map(|(known, _)| *known).collect();
+            return Err(format!("Neznámý zdroj kontextu '{}' (známé: {})", id, known.join(", ")));
+        }
+    }
+    Ok(())
+}
+
+/// Úsek kontextu od jednoho zdroje
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextFragment {
+    pub provider: &'static str,
+    pub text: String,
+}
+
+/// Registr doplňujících zdrojů, tracker ho každý tick projde
+pub struct ProviderRegistry {
+    providers: Vec<Box<dyn ContextProvider>>,
+}
+
+impl ProviderRegistry {
+    /// Vestavěné zdroje (OCR a okno jsou součástí ticku, tady nejsou)
+    pub fn builtin(calendar: Option<PathBuf>) -> Self {
+        let mut registry = Self { providers: Vec::new() };
+        registry.register(Box::new(CalendarProvider { path: calendar }));
+        registry.register(Box::new(GitProvider));
+        registry.register(Box::new(ProcessProvider));
+        registry.register(Box::new(BrowserProvider));
+        registry
+    }
+
+    pub fn register(&mut self, provider: Box<dyn ContextProvider>) {
+        self.providers.push(provider);
+    }
+
+    /// Posbírá kontext ze zapnutých zdrojů
+    pub fn collect(&self, input: &TickInput, toggles: &ProviderToggles) -> Vec<ContextFragment> {
+        self.providers
+            .iter()
+            .filter(|p| toggles.is_enabled(p.id()))
+            .filter_map(|p| {
+                let text = p.collect(input)?;
+                (!text.trim().is_empty()).then_some(ContextFragment { provider: p.id(), text })
+            })
+            .collect()
+    }
+}
+
+/// Fragmenty jako text k OCR výstupu
+pub fn fragments_text(fragments: &[ContextFragment]) -> String {
+    fragments
+        .iter()
+        .map(|f| format!("[{}] {}", f.provider, f.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Právě probíhající událost z kalendáře (stejný iCalendar soubor jako focus bloky)
+struct CalendarProvider {
+    path: Option<PathBuf>,
+}
+
+impl ContextProvider for CalendarProvider {
+    fn id(&self) -> &'static str {
+        CALENDAR
+    }
+
+    fn collect(&self, input: &TickInput) -> Option<String> {
+        let events = notifications::load_calendar(self.path.as_ref()?, "")
+            .inspect_err(|e| tracing::warn!(error = %e, "kalendář pro kontext nedostupný"))
+            .ok()?;
+        let current: Vec<String> = events
+            .into_iter()
+            .filter(|e| e.start <= input.now && input.now < e.end)
+            .map(|e| e.title)
+            .collect();
+        (!current.is_empty()).then(|| format!("Událost v kalendáři: {}", current.join(", ")))
+    }
+}
+
+/// Git větev repozitáře, jehož cesta je v titulku okna (terminál, editor)
+struct GitProvider;
+
+impl ContextProvider for GitProvider {
+    fn id(&self) -> &'static str {
+        GIT
+    }
+
+    fn collect(&self, input: &TickInput) -> Option<String> {
+        git_context_from_title(&input.window.as_ref()?.title)
+    }
+}
+
+/// Cesty v titulku ("~/projekty/app: vim", "/home/jan/app — zsh", "C:\src\app")
+fn title_paths(title: &str) -> Vec<PathBuf> {
+    title
+        .split_whitespace()
+        .map(|token| token.trim_matches(|c: char| matches!(c, ':' | ',' | '(' | ')' | '[' | ']' | '"' | '\'')))
+        .filter_map(|token| {
+            if let Some(rest) = token.strip_prefix("~/") {
+                return dirs::home_dir().map(|home| home.join(rest));
+            }
+            let windows_drive = token.len() > 2 && token.as_bytes()[1] == b':' && token.as_bytes()[2] == b'\\';
+            (token.starts_with('/') || windows_drive).then(|| PathBuf::from(token))
+        })
+        .collect()
+}
+
+/// Aktuální větev z .git/HEAD nejbližšího nadřazeného repozitáře
+fn git_branch(path: &Path) -> Option<(String, String)> {
+    let repo = path.ancestors().find(|dir| dir.join(".git").join("HEAD").is_file())?;
+    let head = std::fs::read_to_string(repo.join(".git").join("HEAD")).ok()?;
+    let branch = match head.trim().strip_prefix("ref: refs/heads/") {
+        Some(branch) => branch.to_string(),
+        None => format!("detached {}", head.trim().chars().take(8).collect::<String>()),
+    };
+    let name = repo.file_name()?.to_string_lossy().to_string();
+    Some((name, branch))
+}
+
+fn git_context_from_title(title: &str) -> Option<String> {
+    title_paths(title)
+        .iter()
+        .find_map(|path| git_branch(path))
+        .map(|(repo, branch)| format!("Git repozitář {}, větev {}", repo, branch))
+}
+
+/// Aktivní záložka, když je v popředí prohlížeč - OCR často mine adresní řádek
+struct BrowserProvider;
+
+impl ContextProvider for BrowserProvider {
+    fn id(&self) -> &'static str {
+        BROWSER
+    }
+
+    fn collect(&self, input: &TickInput) -> Option<String> {
+        let window = input.window.as_ref()?;
+        browser::active_tab(&window.app_name, &window.title).map(|tab| tab.context_text())
+    }
+}
+
+/// Názvy běžících aplikací (bez argumentů - ty mohou obsahovat citlivé údaje)
+struct ProcessProvider;
+
+impl ContextProvider for ProcessProvider {
+    fn id(&self) -> &'static str {
+        PROCESSES
+    }
+
+    fn collect(&self, _input: &TickInput) -> Option<String> {
+        let names = process_names()
+            .inspect_err(|e| tracing::warn!(error = %e, "seznam procesů nedostupný"))
+            .ok()?;
+        let names: BTreeSet<String> = names.into_iter().filter(|n| !n.is_empty()).collect();
+        (!names.is_empty()).then(|| {
+            format!(
+                "Běžící aplikace: {}",
+                names.into_iter().take(MAX_PROCESSES).collect::<Vec<_>>().join(", ")
+            )
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_names() -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir("/proc").map_err(|e| e.to_string())?;
+    Ok(entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        // Jádrová vlákna nemají cmdline
+        .filter(|e| std::fs::read(e.path().join("cmdline")).is_ok_and(|c| !c.is_empty()))
+        .filter_map(|e| std::fs::read_to_string(e.path().join("comm")).ok())
+        .map(|name| name.trim().to_string())
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+fn process_names() -> Result<Vec<String>, String> {
+    let output = std::process::Command::new("ps").args(["-axco", "comm="]).output().map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(|l| l.trim().to_string()).collect())
+}
+
+#[cfg(target_os = "windows")]
+fn process_names() -> Result<Vec<String>, String> {
+    let output = std::process::Command::new("tasklist").args(["/fo", "csv", "/nh"]).output().map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.split(',').next())
+        .map(|name| name.trim_matches('"').trim_end_matches(".exe").to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggles_and_git_context() {
+        let toggles = ProviderToggles::new(BTreeMap::from([(GIT.to_string(), true), (OCR.to_string(), false)]));
+        assert!(toggles.is_enabled(GIT));
+        assert!(!toggles.is_enabled(OCR));
+        assert!(toggles.is_enabled(WINDOW));
+        assert!(!toggles.is_enabled(PROCESSES));
+        assert!(validate(&BTreeMap::from([("slack".to_string(), true)])).is_err());
+
+        let repo = std::env::temp_dir().join(format!("tracker-git-{}", std::process::id()));
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join(".git").join("HEAD"), "ref: refs/heads/feature/export-faktur\n").unwrap();
+
+        let title = format!("jan@pc: {}/src — zsh", repo.display());
+        let name = repo.file_name().unwrap().to_string_lossy().to_string();
+        assert_eq!(
+            git_context_from_title(&title),
+            Some(format!("Git repozitář {}, větev feature/export-faktur", name))
+        );
+        assert_eq!(git_context_from_title("Mozilla Firefox"), None);
+
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
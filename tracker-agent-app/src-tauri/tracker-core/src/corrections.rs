@@ -0,0 +1,307 @@
+use crate::freelo::FreeloTask;
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Soubor s opravami přiřazení v adresáři profilu
+const CORRECTIONS_FILE: &str = "corrections.json";
+
+/// Váhy slov převzaté z rule packu kolegů (v adresáři profilu)
+const IMPORTED_WEIGHTS_FILE: &str = "imported_weights.json";
+
+/// `tick_id` opravy převzaté z rule packu - nepatří k žádnému ticku tohoto profilu
+pub const IMPORTED_TICK_ID: i64 = 0;
+
+/// Kolik posledních oprav držíme - starší už neodpovídají současným taskům
+const MAX_CORRECTIONS: usize = 500;
+
+/// Násobek váhy slova správného tasku za každou opravu
+const BOOST_FACTOR: f32 = 1.25;
+
+/// Násobek váhy slova, které matcher svedlo ke špatnému tasku
+const PENALTY_FACTOR: f32 = 0.8;
+
+/// Meze naučené váhy - jedno slovo nesmí task přebít ani úplně vypnout
+const MIN_WEIGHT: f32 = 0.2;
+const MAX_WEIGHT: f32 = 3.0;
+
+/// Délka ukázky OCR textu v příkladu pro AI
+const EXCERPT_CHARS: usize = 300;
+
+/// Kolik příkladů z oprav se přidá do AI promptu
+pub const MAX_FEW_SHOT_EXAMPLES: usize = 5;
+
+/// Ruční oprava přiřazení ticku
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Correction {
+    pub tick_id: i64,
+    pub at: DateTime<Local>,
+    pub application: Option<String>,
+    /// Co tick přiřadil (None = obecná práce)
+    pub wrong_task_id: Option<i32>,
+    pub correct_task_id: i32,
+    /// Slova správného tasku nalezená v OCR textu - jejich váha roste
+    #[serde(default)]
+    pub boosted: Vec<String>,
+    /// Slova špatného tasku nalezená v OCR textu - jejich váha klesá
+    #[serde(default)]
+    pub penalized: Vec<String>,
+    /// Začátek OCR textu bez citlivých údajů (None = text nebyl uložený nebo běží režim soukromí)
+    #[serde(default)]
+    pub excerpt: Option<String>,
+}
+
+pub fn load(profile: &str) -> Vec<Correction> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(CORRECTIONS_FILE)).unwrap_or_default()
+}
+
+/// Přidá opravu (novější oprava téhož ticku nahradí starší)
+pub fn record(profile: &str, correction: Correction) -> Result<(), String> {
+    let mut corrections = load(profile);
+    corrections.retain(|c| c.tick_id != correction.tick_id);
+    corrections.push(correction);
+    let overflow = corrections.len().saturating_sub(MAX_CORRECTIONS);
+    corrections.drain(..overflow);
+    profile_crypto::save_json(profile, &profile_dir(profile).join(CORRECTIONS_FILE), &corrections)
+}
+
+/// Zkrácený OCR text do příkladu
+pub fn excerpt(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(EXCERPT_CHARS)
+        .collect()
+}
+
+/// Násobky vah klíčových slov podle tasků pro textový matcher
+pub fn keyword_weights(corrections: &[Correction]) -> HashMap<i32, HashMap<String, f32>> {
+    let mut weights: HashMap<i32, HashMap<String, f32>> = HashMap::new();
+    let mut adjust = |task_id: i32, word: &str, factor: f32| {
+        let weight = weights.entry(task_id).or_default().entry(word.to_string()).or_insert(1.0);
+        *weight = (*weight * factor).clamp(MIN_WEIGHT, MAX_WEIGHT);
+    };
+    for correction in corrections {
+        for word in &correction.boosted {
+            adjust(correction.correct_task_id, word, BOOST_FACTOR);
+        }
+        if let Some(wrong_task_id) = correction.wrong_task_id {
+            for word in &correction.penalized {
+                adjust(wrong_task_id, word, PENALTY_FACTOR);
+            }
+        }
+    }
+    weights
+}
+
+/// Váhy slov po tascích, seřazené (stabilní export)
+pub type WeightTable = BTreeMap<i32, BTreeMap<String, f32>>;
+
+fn load_imported_weights(profile: &str) -> WeightTable {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(IMPORTED_WEIGHTS_FILE)).unwrap_or_default()
+}
+
+/// Váhy pro textový matcher profilu - převzaté váhy upravené vlastními opravami.
+/// Převzaté opravy se nezapočítávají, jejich vliv už je v převzatých vahách.
+pub fn profile_weights(profile: &str) -> HashMap<i32, HashMap<String, f32>> {
+    let own: Vec<Correction> = load(profile).into_iter().filter(|c| c.tick_id != IMPORTED_TICK_ID).collect();
+    let mut weights: HashMap<i32, HashMap<String, f32>> = load_imported_weights(profile)
+        .into_iter()
+        .map(|(task_id, words)| (task_id, words.into_iter().collect()))
+        .collect();
+    for (task_id, words) in keyword_weights(&own) {
+        let task = weights.entry(task_id).or_default();
+        for (word, factor) in words {
+            let weight = task.entry(word).or_insert(1.0);
+            *weight = (*weight * factor).clamp(MIN_WEIGHT, MAX_WEIGHT);
+        }
+    }
+    weights
+}
+
+/// Naučené asociace profilu pro sdílení v rule packu - ruční opravy (s ukázkou textu
+/// bez citlivých údajů) a výsledné váhy slov
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearnedAssociations {
+    #[serde(default)]
+    pub corrections: Vec<Correction>,
+    #[serde(default)]
+    pub keyword_weights: WeightTable,
+}
+
+impl LearnedAssociations {
+    pub fn of_profile(profile: &str) -> Self {
+        Self {
+            corrections: load(profile),
+            keyword_weights: profile_weights(profile)
+                .into_iter()
+                .map(|(task_id, words)| (task_id, words.into_iter().collect()))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.corrections.is_empty() && self.keyword_weights.is_empty()
+    }
+
+    /// Převezme asociace do profilu, vrací počet nových oprav a změněných vah.
+    /// Opravy slouží jako příklady pro AI, váhy přepíší dříve převzaté váhy stejných slov.
+    pub fn import(self, profile: &str) -> Result<usize, String> {
+        let mut changed = 0;
+
+        let mut corrections = load(profile);
+        for mut correction in self.corrections {
+            let known = corrections.iter().any(|c| {
+                c.at == correction.at
+                    && c.correct_task_id == correction.correct_task_id
+                    && c.wrong_task_id == correction.wrong_task_id
+                    && c.application == correction.application
+            });
+            if !known {
+                correction.tick_id = IMPORTED_TICK_ID;
+                corrections.push(correction);
+                changed += 1;
+            }
+        }
+        if changed > 0 {
+            corrections.sort_by_key(|c| c.at);
+            let overflow = corrections.len().saturating_sub(MAX_CORRECTIONS);
+            corrections.drain(..overflow);
+            profile_crypto::save_json(profile, &profile_dir(profile).join(CORRECTIONS_FILE), &corrections)?;
+        }
+
+        let mut weights = load_imported_weights(profile);
+        let before = changed;
+        for (task_id, words) in self.keyword_weights {
+            for (word, weight) in words {
+                let weight = weight.clamp(MIN_WEIGHT, MAX_WEIGHT);
+                let task = weights.entry(task_id).or_default();
+                if task.get(&word) != Some(&weight) {
+                    task.insert(word, weight);
+                    changed += 1;
+                }
+            }
+        }
+        if changed > before {
+            profile_crypto::save_json(profile, &profile_dir(profile).join(IMPORTED_WEIGHTS_FILE), &weights)?;
+        }
+        Ok(changed)
+    }
+}
+
+/// Příklady z nejnovějších oprav pro AI prompt - přednost mají opravy ze stejné aplikace.
+/// None = žádná oprava s textem k tasku, který je mezi načtenými.
+pub fn few_shot_examples(corrections: &[Correction], tasks: &[FreeloTask], application: Option<&str>) -> Option<String> {
+    let task_name = |id: i32| tasks.iter().find(|t| t.id == id).map(|t| t.name.as_str());
+    let mut candidates: Vec<&Correction> = corrections
+        .iter()
+        .rev()
+        .filter(|c| c.excerpt.is_some() && task_name(c.correct_task_id).is_some())
+        .collect();
+    // Stabilní řazení - v rámci skupiny zůstávají nejnovější první
+    candidates.sort_by_key(|c| application.is_none_or(|app| c.application.as_deref() != Some(app)));
+
+    let examples: Vec<String> = candidates
+        .into_iter()
+        .take(MAX_FEW_SHOT_EXAMPLES)
+        .map(|c| {
+            let wrong = match c.wrong_task_id {
+                Some(id) => format!("ID {}", id),
+                None => "žádný task".to_string(),
+            };
+            format!(
+                "Aplikace: {}\nText: {}\nSprávně: ID {} ({}), ne {}",
+                c.application.as_deref().unwrap_or("?"),
+                c.excerpt.as_deref().unwrap_or_default(),
+                c.correct_task_id,
+                task_name(c.correct_task_id).unwrap_or_default(),
+                wrong
+            )
+        })
+        .collect();
+    (!examples.is_empty()).then(|| examples.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::TestProfile;
+
+    fn correction(tick_id: i64, application: &str, wrong: Option<i32>, correct: i32, word: &str) -> Correction {
+        Correction {
+            tick_id,
+            at: Local::now(),
+            application: Some(application.to_string()),
+            wrong_task_id: wrong,
+            correct_task_id: correct,
+            boosted: vec![word.to_string()],
+            penalized: vec![word.to_string()],
+            excerpt: Some(format!("{} text", word)),
+        }
+    }
+
+    #[test]
+    fn test_weights_and_examples() {
+        let corrections = vec![
+            correction(1, "Code", Some(2), 1, "faktura"),
+            correction(2, "Code", Some(2), 1, "faktura"),
+            correction(3, "Firefox", None, 2, "eshop"),
+        ];
+        let weights = keyword_weights(&corrections);
+        assert!((weights[&1]["faktura"] - BOOST_FACTOR * BOOST_FACTOR).abs() < 1e-6);
+        assert!((weights[&2]["faktura"] - PENALTY_FACTOR * PENALTY_FACTOR).abs() < 1e-6);
+        // Z obecné práce se nepenalizuje nic, jen správný task roste
+        assert_eq!(weights[&2]["eshop"], BOOST_FACTOR);
+        assert_eq!(weights.len(), 2);
+
+        let many: Vec<Correction> = (0..20).map(|i| correction(i, "Code", Some(2), 1, "api")).collect();
+        assert_eq!(keyword_weights(&many)[&2]["api"], MIN_WEIGHT);
+        assert_eq!(keyword_weights(&many)[&1]["api"], MAX_WEIGHT);
+
+        let tasks = vec![FreeloTask {
+            id: 1,
+            name: "Fakturace".to_string(),
+            project_id: 1,
+            project_name: "Interní".to_string(),
+            estimate_minutes: None,
+        }];
+        // Task 2 není načtený - jeho příklad se vynechá
+        let examples = few_shot_examples(&corrections, &tasks, Some("Firefox")).unwrap();
+        assert_eq!(examples.matches("Správně: ID 1 (Fakturace), ne ID 2").count(), 2);
+        assert!(few_shot_examples(&corrections, &[], None).is_none());
+    }
+
+    #[test]
+    fn test_import_learned_associations() {
+        let test_profile = TestProfile::new("learned");
+        let profile = test_profile.name.as_str();
+        record(profile, correction(7, "Code", Some(2), 1, "faktura")).unwrap();
+
+        let shared = LearnedAssociations {
+            corrections: vec![correction(7, "Firefox", None, 3, "eshop")],
+            keyword_weights: WeightTable::from([(1, BTreeMap::from([("faktura".to_string(), 2.0), ("pdf".to_string(), 9.0)]))]),
+        };
+        assert_eq!(shared.clone().import(profile).unwrap(), 3);
+        // Opakovaný import nic nepřidá
+        assert_eq!(shared.import(profile).unwrap(), 0);
+
+        // Převzatá oprava nepřepsala vlastní opravu se stejným tick_id
+        let corrections = load(profile);
+        assert_eq!(corrections.len(), 2);
+        assert!(corrections.iter().any(|c| c.tick_id == 7 && c.correct_task_id == 1));
+        assert!(corrections.iter().any(|c| c.tick_id == IMPORTED_TICK_ID && c.correct_task_id == 3));
+
+        // Převzatá váha × vlastní oprava, převzatá oprava se do vah nepočítá
+        let weights = profile_weights(profile);
+        assert!((weights[&1]["faktura"] - 2.0 * BOOST_FACTOR).abs() < 1e-6);
+        assert_eq!(weights[&1]["pdf"], MAX_WEIGHT);
+        assert!(!weights.contains_key(&3));
+
+        let exported = LearnedAssociations::of_profile(profile);
+        assert_eq!(exported.corrections.len(), 2);
+        assert_eq!(exported.keyword_weights[&2]["faktura"], PENALTY_FACTOR);
+    }
+}
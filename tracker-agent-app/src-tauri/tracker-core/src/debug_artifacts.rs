@@ -0,0 +1,267 @@
+use crate::profile_crypto;
+use crate::screenshot::ImageArea;
+use crate::storage;
+use chrono::Local;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Původní adresář vedle aplikace (podle cwd) - jeho obsah se přesune do nového umístění
+const LEGACY_DIR_NAME: &str = "debug_screenshots";
+
+/// Kořen debug artefaktů z nastavení, None = datový adresář aplikace.
+/// Sdílený pro celý proces - `get_debug_dir` se volá i z OCR bez přístupu k nastavení.
+static DEBUG_ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Výchozí kořen debug artefaktů (nezávislý na cwd - z Finderu/Exploreru je cwd `/`)
+pub fn default_debug_root() -> PathBuf {
+    storage::app_data_dir().join("debug")
+}
+
+pub fn debug_root() -> PathBuf {
+    DEBUG_ROOT
+        .lock()
+        .ok()
+        .and_then(|root| root.clone())
+        .unwrap_or_else(default_debug_root)
+}
+
+/// Debug adresář profilu (klienta), vytvoří ho pokud neexistuje
+pub fn get_debug_dir(profile: &str) -> PathBuf {
+    let path = debug_root().join(profile);
+    if !path.exists() {
+        std::fs::create_dir_all(&path).ok();
+    }
+    path
+}
+
+/// Validace vlastního adresáře z nastavení
+pub fn validate_debug_dir(dir: &str) -> Result<(), String> {
+    if !Path::new(dir).is_absolute() {
+        return Err(format!("Adresář pro debug artefakty musí být absolutní cesta (zadáno '{}')", dir));
+    }
+    Ok(())
+}
+
+/// Nastaví kořen debug artefaktů a přesune do něj obsah předchozího umístění
+/// i starého adresáře `debug_screenshots` vedle aplikace. Vrací počet přesunutých souborů.
+pub fn set_debug_root(root: Option<PathBuf>) -> Result<usize, String> {
+    let previous = debug_root();
+    if let Ok(mut current) = DEBUG_ROOT.lock() {
+        *current = root;
+    }
+    let target = debug_root();
+
+    let mut sources = legacy_dirs();
+    if previous != target {
+        sources.push(previous);
+    }
+    let mut moved = 0;
+    for source in sources.iter().filter(|s| s.is_dir() && **s != target) {
+        moved += migrate_dir(source, &target)?;
+    }
+    Ok(moved)
+}
+
+/// Kde mohl starý `debug_screenshots` ležet (cwd, nebo nad src-tauri při vývoji)
+fn legacy_dirs() -> Vec<PathBuf> {
+    let Ok(cwd) = std::env::current_dir() else {
+        return Vec::new();
+    };
+    let mut dirs = vec![cwd.join(LEGACY_DIR_NAME)];
+    if cwd.ends_with("src-tauri") {
+        if let Some(parent) = cwd.parent() {
+            dirs.push(parent.join(LEGACY_DIR_NAME));
+        }
+    }
+    dirs
+}
+
+/// Přesune obsah adresáře (rekurzivně), existující soubory v cíli nepřepisuje.
+/// Prázdné zdrojové adresáře smaže.
+fn migrate_dir(from: &Path, to: &Path) -> Result<usize, String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("Nelze vytvořit {:?}: {}", to, e))?;
+
+    let mut moved = 0;
+    let entries = std::fs::read_dir(from).map_err(|e| format!("Nelze číst {:?}: {}", from, e))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if source.is_dir() {
+            moved += migrate_dir(&source, &target)?;
+        } else if !target.exists() {
+            // Rename nejde přes hranici disku - pak kopie a smazání
+            if std::fs::rename(&source, &target).is_err() {
+                std::fs::copy(&source, &target).map_err(|e| format!("Nelze přesunout {:?}: {}", source, e))?;
+                std::fs::remove_file(&source).map_err(|e| format!("Nelze smazat {:?}: {}", source, e))?;
+            }
+            moved += 1;
+        }
+    }
+
+    // Zůstanou jen soubory, které v cíli už byly
+    let _ = std::fs::remove_dir(from);
+    Ok(moved)
+}
+
+/// Výška titulku okna jako část výšky snímku (a minimum v pixelech)
+const TITLE_BAR_FRACTION: f32 = 0.03;
+const TITLE_BAR_MIN_PX: u32 = 24;
+
+/// Velikost bloku pixelizace - text v bloku této velikosti už nejde přečíst
+const PIXELATE_BLOCK: u32 = 24;
+
+/// Poloha okna k debug screenshotu (`X_0_original.png` → `X_0_original.window.json`)
+fn window_file(screenshot: &Path) -> PathBuf {
+    screenshot.with_extension("window.json")
+}
+
+/// Uloží polohu aktivního okna vedle debug screenshotu (šifrovaně jako snímek)
+pub fn save_window_area(profile: &str, screenshot: &Path, area: &ImageArea) -> Result<(), String> {
+    profile_crypto::save_json(profile, &window_file(screenshot), area)
+}
+
+/// Titulek okna ve snímku - horní pruh okna oříznutý na rozměry snímku
+fn title_bar(window: &ImageArea, width: u32, height: u32) -> Option<ImageArea> {
+    let bar = ((height as f32 * TITLE_BAR_FRACTION) as u32)
+        .max(TITLE_BAR_MIN_PX)
+        .min(window.height);
+    let x = window.x.min(width);
+    let y = window.y.min(height);
+    let area = ImageArea {
+        x,
+        y,
+        width: window.width.min(width - x),
+        height: bar.min(height - y),
+    };
+    (area.width > 0 && area.height > 0).then_some(area)
+}
+
+/// Rozmaže (pixelizuje) celý snímek kromě titulku okna `window`. Bez známé polohy
+/// okna (starší snímky) se rozmaže všechno.
+pub fn redact_image(img: &DynamicImage, window: Option<&ImageArea>) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let small = img.resize_exact(
+        (width / PIXELATE_BLOCK).max(1),
+        (height / PIXELATE_BLOCK).max(1),
+        FilterType::Triangle,
+    );
+    let mut redacted = small.resize_exact(width, height, FilterType::Nearest).to_rgba8();
+
+    if let Some(title) = window.and_then(|w| title_bar(w, width, height)) {
+        let original = img.crop_imm(title.x, title.y, title.width, title.height);
+        image::imageops::replace(&mut redacted, &original.to_rgba8(), title.x as i64, title.y as i64);
+    }
+    DynamicImage::ImageRgba8(redacted)
+}
+
+/// Zkopíruje debug artefakty profilu do nového adresáře se zakrytým obsahem obrazovky.
+/// Screenshoty se pixelizují (kromě titulku aktivního okna), OCR text se nahradí jen jeho délkou.
+pub fn redact(profile: &str, destination: &Path) -> Result<(PathBuf, usize), String> {
+    let source = get_debug_dir(profile);
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&source)
+        .map_err(|e| format!("Nelze číst {:?}: {}", source, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Err(format!("Profil '{}' nemá žádné debug artefakty", profile));
+    }
+
+    let target = destination.join(format!(
+        "debug-redacted-{}-{}",
+        profile,
+        Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    std::fs::create_dir_all(&target).map_err(|e| format!("Nelze vytvořit {:?}: {}", target, e))?;
+
+    let mut count = 0;
+    for file in &files {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        let output = target.join(name);
+
+        match file.extension().and_then(|e| e.to_str()) {
+            Some("png") | Some("jpg") | Some("jpeg") => {
+                let img = image::load_from_memory(&profile_crypto::read(profile, file)?)
+                    .map_err(|e| format!("Nelze načíst {:?}: {}", file, e))?;
+                let window: Option<ImageArea> = profile_crypto::load_json(profile, &window_file(file));
+                redact_image(&img, window.as_ref())
+                    .save(&output)
+                    .map_err(|e| format!("Nelze zapsat {:?}: {}", output, e))?;
+            }
+            Some("txt") => {
+                let chars = profile_crypto::read(profile, file)
+                    .map(|t| String::from_utf8_lossy(&t).chars().count())
+                    .unwrap_or(0);
+                std::fs::write(&output, format!("[OCR text skryt, {} znaků]\n", chars))
+                    .map_err(|e| format!("Nelze zapsat {:?}: {}", output, e))?;
+            }
+            // Neznámé soubory se radši nesdílí
+            _ => continue,
+        }
+        count += 1;
+    }
+
+    Ok((target, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_redact_keeps_window_title() {
+        // Šachovnice 1px - po pixelizaci se slije do šedé
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_fn(400, 1000, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        }));
+        let blurred = |pixel: Rgba<u8>| pixel[0] > 60 && pixel[0] < 200;
+        let window = ImageArea { x: 100, y: 200, width: 200, height: 500 };
+
+        let redacted = redact_image(&img, Some(&window));
+        assert_eq!(redacted.dimensions(), (400, 1000));
+        // Titulek okna (30 px) zůstal, horní lišta plochy i obsah okna jsou rozmazané
+        assert_eq!(redacted.get_pixel(100, 200), img.get_pixel(100, 200));
+        assert_eq!(redacted.get_pixel(299, 229), img.get_pixel(299, 229));
+        assert!(blurred(redacted.get_pixel(200, 240)));
+        assert!(blurred(redacted.get_pixel(200, 10)));
+        assert!(blurred(redacted.get_pixel(50, 210)));
+
+        // Bez polohy okna se rozmaže všechno
+        assert!(blurred(redact_image(&img, None).get_pixel(200, 210)));
+
+        // Okno přesahující snímek se ořízne
+        let outside = ImageArea { x: 350, y: 990, width: 200, height: 500 };
+        assert_eq!(title_bar(&outside, 400, 1000), Some(ImageArea { x: 350, y: 990, width: 50, height: 10 }));
+        assert_eq!(title_bar(&ImageArea { x: 500, ..outside }, 400, 1000), None);
+    }
+
+    #[test]
+    fn test_migrate_dir_keeps_existing() {
+        let base = std::env::temp_dir().join(format!("tracker-debug-migrate-{}", std::process::id()));
+        let (from, to) = (base.join(LEGACY_DIR_NAME), base.join("debug"));
+        std::fs::create_dir_all(from.join("default")).unwrap();
+        std::fs::create_dir_all(to.join("default")).unwrap();
+        std::fs::write(from.join("default").join("a.png"), "old a").unwrap();
+        std::fs::write(from.join("default").join("b.txt"), "old b").unwrap();
+        std::fs::write(to.join("default").join("b.txt"), "new b").unwrap();
+
+        assert_eq!(migrate_dir(&from, &to).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(to.join("default").join("a.png")).unwrap(), "old a");
+        assert_eq!(std::fs::read_to_string(to.join("default").join("b.txt")).unwrap(), "new b");
+        // Soubor, který v cíli už byl, zůstal ve zdroji
+        assert!(from.join("default").join("b.txt").exists());
+        assert!(!from.join("default").join("a.png").exists());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+/// Po kolika chybách v řadě se součást bere jako nefunkční
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Nefunkční součást se zkusí znovu jednou za tolik ticků
+const PROBE_EVERY_TICKS: u32 = 10;
+
+/// Stupně degradace od plné analýzy po pauzu:
+/// - `Full` - AI nad OCR textem / screenshotem
+/// - `OcrOnly` - AI nefunguje (nebo není nastavená), matching textem z OCR
+/// - `WindowOnly` - bez screenshotu nebo OCR, matching jen podle titulku okna
+/// - `Paused` - není screenshot ani okno, tick se přeskočí (jen občasný pokus o obnovu)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DegradationLevel {
+    Full,
+    OcrOnly,
+    WindowOnly,
+    Paused,
+}
+
+impl DegradationLevel {
+    pub fn label(self) -> &'static str {
+        match self {
+            DegradationLevel::Full => "AI + OCR",
+            DegradationLevel::OcrOnly => "jen OCR",
+            DegradationLevel::WindowOnly => "jen titulek okna",
+            DegradationLevel::Paused => "pozastaveno",
+        }
+    }
+}
+
+/// Součásti ticku, jejichž zdraví určuje stupeň
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    Ai,
+    Ocr,
+    Window,
+    Capture,
+}
+
+impl Component {
+    fn label(self) -> &'static str {
+        match self {
+            Component::Ai => "AI",
+            Component::Ocr => "OCR",
+            Component::Window => "aktivní okno",
+            Component::Capture => "screenshot",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComponentHealth {
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// Vypnuto v nastavení (AI bez klíče, OCR zdroj vypnutý) - nepočítá se jako chyba
+    pub disabled: bool,
+    #[serde(skip)]
+    skipped_ticks: u32,
+}
+
+impl ComponentHealth {
+    fn is_down(&self) -> bool {
+        self.disabled || self.consecutive_failures >= FAILURE_THRESHOLD
+    }
+}
+
+/// Stav pro UI a `get_tracking_status`
+#[derive(Debug, Clone, Serialize)]
+pub struct DegradationStatus {
+    pub level: DegradationLevel,
+    pub label: &'static str,
+    /// Proč agent neběží naplno, None = plný režim
+    pub reason: Option<String>,
+    pub since: Option<String>,
+    pub ai: ComponentHealth,
+    pub ocr: ComponentHealth,
+    pub window: ComponentHealth,
+    pub capture: ComponentHealth,
+}
+
+/// Změna stupně - tracker ji zaloguje a pošle do UI
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelChange {
+    pub from: DegradationLevel,
+    pub to: DegradationLevel,
+}
+
+/// Žebříček degradace - stupeň se počítá ze zdraví součástí, ne z jednotlivých chyb
+#[derive(Debug, Clone)]
+pub struct DegradationLadder {
+    ai: ComponentHealth,
+    ocr: ComponentHealth,
+    window: ComponentHealth,
+    capture: ComponentHealth,
+    level: DegradationLevel,
+    since: Option<DateTime<Local>>,
+}
+
+impl Default for DegradationLadder {
+    fn default() -> Self {
+        Self {
+            ai: ComponentHealth::default(),
+            ocr: ComponentHealth::default(),
+            window: ComponentHealth::default(),
+            capture: ComponentHealth::default(),
+            level: DegradationLevel::Full,
+            since: None,
+        }
+    }
+}
+
+impl DegradationLadder {
+    fn health(&self, component: Component) -> &ComponentHealth {
+        match component {
+            Component::Ai => &self.ai,
+            Component::Ocr => &self.ocr,
+            Component::Window => &self.window,
+            Component::Capture => &self.capture,
+        }
+    }
+
+    fn health_mut(&mut self, component: Component) -> &mut ComponentHealth {
+        match component {
+            Component::Ai => &mut self.ai,
+            Component::Ocr => &mut self.ocr,
+            Component::Window => &mut self.window,
+            Component::Capture => &mut self.capture,
+        }
+    }
+
+    pub fn level(&self) -> DegradationLevel {
+        self.level
+    }
+
+    pub fn is_down(&self, component: Component) -> bool {
+        self.health(component).is_down()
+    }
+
+    /// Co je vypnuté v nastavení (volá se na začátku ticku)
+    pub fn configure(&mut self, ai: bool, ocr: bool, now: DateTime<Local>) -> Option<LevelChange> {
+        self.ai.disabled = !ai;
+        self.ocr.disabled = !ocr;
+        self.update_level(now)
+    }
+
+    /// Zkusit součást v tomto ticku? Funkční vždy, nefunkční jen jednou za `PROBE_EVERY_TICKS`.
+    pub fn should_attempt(&mut self, component: Component) -> bool {
+        let health = self.health_mut(component);
+        if health.disabled {
+            return false;
+        }
+        if !health.is_down() {
+            return true;
+        }
+        health.skipped_ticks += 1;
+        if health.skipped_ticks >= PROBE_EVERY_TICKS {
+            health.skipped_ticks = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Výsledek pokusu o součást, Some = změnil se stupeň
+    pub fn record(&mut self, component: Component, result: Result<(), &str>, now: DateTime<Local>) -> Option<LevelChange> {
+        let health = self.health_mut(component);
+        match result {
+            Ok(()) => {
+                health.consecutive_failures = 0;
+                health.last_error = None;
+                health.skipped_ticks = 0;
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                health.last_error = Some(e.to_string());
+            }
+        }
+        self.update_level(now)
+    }
+
+    fn compute_level(&self) -> DegradationLevel {
+        if self.capture.is_down() && self.window.is_down() {
+            DegradationLevel::Paused
+        } else if self.capture.is_down() || self.ocr.is_down() {
+            DegradationLevel::WindowOnly
+        } else if self.ai.is_down() {
+            DegradationLevel::OcrOnly
+        } else {
+            DegradationLevel::Full
+        }
+    }
+
+    fn update_level(&mut self, now: DateTime<Local>) -> Option<LevelChange> {
+        let level = self.compute_level();
+        if level == self.level {
+            return None;
+        }
+        let change = LevelChange { from: self.level, to: level };
+        self.level = level;
+        self.since = (level != DegradationLevel::Full).then_some(now);
+        Some(change)
+    }
+
+    /// Proč agent neběží naplno - nefunkční součásti s poslední chybou
+    pub fn reason(&self) -> Option<String> {
+        let reasons: Vec<String> = [Component::Capture, Component::Window, Component::Ocr, Component::Ai]
+            .into_iter()
+            .filter(|c| self.is_down(*c))
+            .map(|c| {
+                let health = self.health(c);
+                match (&health.last_error, health.disabled) {
+                    (_, true) => format!("{} vypnuto v nastavení", c.label()),
+                    (Some(error), false) => format!("{}: {}× chyba ({})", c.label(), health.consecutive_failures, error),
+                    (None, false) => format!("{}: {}× chyba", c.label(), health.consecutive_failures),
+                }
+            })
+            .collect();
+        (!reasons.is_empty()).then(|| reasons.join("; "))
+    }
+
+    pub fn status(&self) -> DegradationStatus {
+        DegradationStatus {
+            level: self.level,
+            label: self.level.label(),
+            reason: self.reason(),
+            since: self.since.map(|t| t.format("%H:%M").to_string()),
+            ai: self.ai.clone(),
+            ocr: self.ocr.clone(),
+            window: self.window.clone(),
+            capture: self.capture.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ladder_transitions() {
+        let now = Local::now();
+        let mut ladder = DegradationLadder::default();
+        assert_eq!(ladder.configure(true, true, now), None);
+
+        // AI padá - až třetí chyba v řadě sníží stupeň
+        assert_eq!(ladder.record(Component::Ai, Err("timeout"), now), None);
+        assert_eq!(ladder.record(Component::Ai, Err("timeout"), now), None);
+        assert_eq!(
+            ladder.record(Component::Ai, Err("timeout"), now),
+            Some(LevelChange { from: DegradationLevel::Full, to: DegradationLevel::OcrOnly })
+        );
+        assert_eq!(ladder.reason().as_deref(), Some("AI: 3× chyba (timeout)"));
+
+        // Nefunkční AI se zkouší jen občas
+        let attempts = (0..PROBE_EVERY_TICKS * 2).filter(|_| ladder.should_attempt(Component::Ai)).count();
+        assert_eq!(attempts, 2);
+
+        // Bez screenshotu jen okno, bez okna pauza
+        for _ in 0..FAILURE_THRESHOLD {
+            ladder.record(Component::Capture, Err("no display"), now);
+        }
+        assert_eq!(ladder.level(), DegradationLevel::WindowOnly);
+        for _ in 0..FAILURE_THRESHOLD {
+            ladder.record(Component::Window, Err("no window"), now);
+        }
+        assert_eq!(ladder.level(), DegradationLevel::Paused);
+
+        // Úspěch obnoví stupeň hned
+        ladder.record(Component::Capture, Ok(()), now);
+        ladder.record(Component::Window, Ok(()), now);
+        assert_eq!(ladder.level(), DegradationLevel::OcrOnly);
+        assert_eq!(
+            ladder.record(Component::Ai, Ok(()), now),
+            Some(LevelChange { from: DegradationLevel::OcrOnly, to: DegradationLevel::Full })
+        );
+        assert_eq!(ladder.reason(), None);
+
+        // AI bez klíče = trvale jen OCR, nezkouší se vůbec
+        ladder.configure(false, true, now);
+        assert_eq!(ladder.level(), DegradationLevel::OcrOnly);
+        assert!(!ladder.should_attempt(Component::Ai));
+        assert_eq!(ladder.reason().as_deref(), Some("AI vypnuto v nastavení"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::backend::TimeTrackingBackend;
+use crate::freelo::FreeloTask;
+use crate::window_info::WindowInfo;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::info;
+
+/// Ukázková data se ukládají do vlastního profilu, ne k historii uživatele
+pub const DEMO_PROFILE: &str = "demo";
+
+/// Ukázka běží rychleji než běžný tracking, aby byly segmenty vidět hned
+pub const DEMO_INTERVAL_SECONDS: u64 = 10;
+
+/// Vestavěné tasky (id, název, id projektu, projekt)
+const TASKS: [(i32, &str, i32, &str); 5] = [
+    (101, "Oprava přihlášení přes Google", 10, "E-shop Kvítek"),
+    (102, "Export faktur do Pohody", 10, "E-shop Kvítek"),
+    (103, "Redesign úvodní stránky", 10, "E-shop Kvítek"),
+    (201, "Příprava nabídky pro klienta", 20, "Obchod"),
+    (202, "Týdenní report", 20, "Obchod"),
+];
+
+/// Nahraná obrazovka - aktivní okno a text, který z ní vrátilo OCR
+#[derive(Debug, Clone, Copy)]
+pub struct DemoScene {
+    pub app_name: &'static str,
+    pub title: &'static str,
+    pub text: &'static str,
+    /// Kolik ticků za sebou obrazovka vydrží
+    pub ticks: usize,
+}
+
+impl DemoScene {
+    pub fn window(&self) -> WindowInfo {
+        WindowInfo {
+            app_name: self.app_name.to_string(),
+            title: self.title.to_string(),
+            rect: None,
+        }
+    }
+}
+
+/// Pracovní den v kostce - editor, prohlížeč, tabulka a jedna obrazovka mimo tasky
+const SCENES: [DemoScene; 5] = [
+    DemoScene {
+        app_name: "Code",
+        title: "auth.rs - kvitek-eshop - Visual Studio Code",
+        text: "fn oprava_prihlaseni() Google OAuth přihlášení callback token chyba 401",
+        ticks: 4,
+    },
+    DemoScene {
+        app_name: "Firefox",
+        title: "Export faktur - Pohoda XML dokumentace - Mozilla Firefox",
+        text: "Export faktur do Pohody - XML dataPack invoice schéma",
+        ticks: 3,
+    },
+    DemoScene {
+        app_name: "Figma",
+        title: "Úvodní stránka v3 - Figma",
+        text: "Redesign úvodní stránky hero banner kategorie kytic CTA",
+        ticks: 3,
+    },
+    DemoScene {
+        app_name: "LibreOffice Calc",
+        title: "nabidka-klient.ods - LibreOffice Calc",
+        text: "Nabídka pro klienta položky cena hodiny příprava nabídky celkem",
+        ticks: 3,
+    },
+    DemoScene {
+        app_name: "Thunderbird",
+        title: "Doručená pošta - Thunderbird",
+        text: "Doručená pošta newsletter pozvánka oběd sobota",
+        ticks: 2,
+    },
+];
+
+pub fn tasks() -> Vec<FreeloTask> {
+    TASKS
+        .iter()
+        .map(|&(id, name, project_id, project_name)| FreeloTask {
+            id,
+            name: name.to_string(),
+            project_id,
+            project_name: project_name.to_string(),
+            estimate_minutes: None,
+        })
+        .collect()
+}
+
+/// Scéna pro daný tick - scény se přehrávají dokola
+pub fn scene_at(tick: usize) -> &'static DemoScene {
+    let cycle: usize = SCENES.iter().map(|s| s.ticks).sum();
+    let mut position = tick % cycle;
+    for scene in &SCENES {
+        if position < scene.ticks {
+            return scene;
+        }
+        position -= scene.ticks;
+    }
+    &SCENES[0]
+}
+
+/// Služba pro ukázkový režim - vestavěné tasky, zápisy se jen logují
+#[derive(Debug, Clone, Default)]
+pub struct DemoBackend {
+    tick: Arc<AtomicUsize>,
+}
+
+impl DemoBackend {
+    /// Další nahraná obrazovka místo screenshotu a OCR
+    pub fn next_scene(&self) -> &'static DemoScene {
+        scene_at(self.tick.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl TimeTrackingBackend for DemoBackend {
+    async fn list_tasks(&self) -> Result<Vec<FreeloTask>, String> {
+        Ok(tasks())
+    }
+
+    async fn start(&self, task_id: Option<&str>, note: &str) -> Result<String, String> {
+        info!(task_id, note, "demo: start timeru");
+        Ok(format!("demo-{}", chrono::Local::now().timestamp_millis()))
+    }
+
+    async fn stop(&self, entry_id: &str) -> Result<(), String> {
+        info!(entry_id, "demo: stop timeru");
+        Ok(())
+    }
+
+    async fn annotate(&self, entry_id: &str, note: &str) -> Result<(), String> {
+        info!(entry_id, note, "demo: poznámka");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_matcher::{find_best_matching_task, TaskIndex};
+
+    #[test]
+    fn test_scenes_cycle_and_match_tasks() {
+        assert_eq!(scene_at(0).app_name, "Code");
+        assert_eq!(scene_at(3).app_name, "Code");
+        assert_eq!(scene_at(4).app_name, "Firefox");
+        assert_eq!(scene_at(15).app_name, "Code");
+
+        // Každá pracovní scéna se textovým matchingem trefí do svého tasku, pošta do žádného
+        let index = TaskIndex::build(&tasks());
+        let matched: Vec<Option<i32>> = SCENES
+            .iter()
+            .map(|s| find_best_matching_task(s.text, &index, &[]).task_id)
+            .collect();
+        assert_eq!(matched, vec![Some(101), Some(102), Some(103), Some(201), None]);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::ai_usage::{self, Usage};
+use crate::freelo::FreeloTask;
+use crate::profile_crypto;
+use crate::profiles::profile_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, instrument, warn};
+
+/// Model pro embeddingy (OpenRouter, endpoint kompatibilní s OpenAI)
+const EMBEDDING_MODEL: &str = "openai/text-embedding-3-small";
+const EMBEDDINGS_URL: &str = "https://openrouter.ai/api/v1/embeddings";
+
+/// Cache embeddingů tasků v adresáři profilu (každý klient má jiné tasky)
+const CACHE_FILE: &str = "task_embeddings.json";
+
+/// Kolik textů jde v jednom requestu
+const EMBED_BATCH: usize = 100;
+
+/// OCR text se do embeddingu posílá zkrácený
+const MAX_QUERY_CHARS: usize = 4000;
+
+/// Podobnost, pod kterou jde o náhodnou shodu (confidence 0)
+const SIMILARITY_FLOOR: f32 = 0.2;
+
+/// Podobnost odpovídající plné confidence
+const SIMILARITY_CEIL: f32 = 0.6;
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Nejpodobnější task k textu obrazovky
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingMatch {
+    pub task_id: i32,
+    pub similarity: f32,
+    /// Podobnost přepočtená na 0-1
+    pub confidence: f32,
+}
+
+/// Sémantický matching - embeddingy názvů tasků (cachované na disku) porovnané
+/// kosinovou podobností s embeddingem OCR textu
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmbeddingMatcher {
+    model: String,
+    vectors: HashMap<String, Vec<f32>>,
+    /// Profil, do jehož adresáře se cache ukládá
+    #[serde(skip)]
+    profile: String,
+}
+
+/// Text tasku pro embedding (projekt dává názvu kontext)
+fn task_text(task: &FreeloTask) -> String {
+    format!("{} / {}", task.project_name, task.name)
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+fn similarity_confidence(similarity: f32) -> f32 {
+    ((similarity - SIMILARITY_FLOOR) / (SIMILARITY_CEIL - SIMILARITY_FLOOR)).clamp(0.0, 1.0)
+}
+
+impl EmbeddingMatcher {
+    /// Cache profilu z disku, embeddingy jiného modelu se zahodí
+    pub fn load(profile: &str) -> Self {
+        let cache = profile_crypto::load_json::<Self>(profile, &profile_dir(profile).join(CACHE_FILE))
+            .filter(|cache| cache.model == EMBEDDING_MODEL)
+            .unwrap_or_else(|| Self {
+                model: EMBEDDING_MODEL.to_string(),
+                ..Self::default()
+            });
+        Self {
+            profile: profile.to_string(),
+            ..cache
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        profile_crypto::save_json(&self.profile, &profile_dir(&self.profile).join(CACHE_FILE), self)
+    }
+
+    /// Nejpodobnější task podle už spočítaných embeddingů
+    fn best_match(&self, query: &[f32], tasks: &[FreeloTask]) -> Option<EmbeddingMatch> {
+        tasks
+            .iter()
+            .filter_map(|task| {
+                let vector = self.vectors.get(&task_text(task))?;
+                Some((task.id, cosine_similarity(query, vector)))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(task_id, similarity)| EmbeddingMatch {
+                task_id,
+                similarity,
+                confidence: similarity_confidence(similarity),
+            })
+    }
+
+    /// Dopočítá embeddingy nových tasků a z cache vyhodí tasky, které už nejsou
+    async fn update_tasks(&mut self, client: &reqwest::Client, api_key: &str, tasks: &[FreeloTask]) -> Result<(), String> {
+        let current: HashSet<String> = tasks.iter().map(task_text).collect();
+        let missing: Vec<String> = current.iter().filter(|t| !self.vectors.contains_key(*t)).cloned().collect();
+        let stale = self.vectors.len() + missing.len() > current.len();
+        if missing.is_empty() && !stale {
+            return Ok(());
+        }
+
+        debug!(count = missing.len(), "embeddingy nových tasků");
+        for batch in missing.chunks(EMBED_BATCH) {
+            let vectors = embed(client, api_key, batch).await?;
+            self.vectors.extend(batch.iter().cloned().zip(vectors));
+        }
+        self.vectors.retain(|text, _| current.contains(text));
+
+        if let Err(e) = self.save() {
+            warn!(error = %e, "nelze uložit cache embeddingů");
+        }
+        Ok(())
+    }
+
+    /// Najde sémanticky nejbližší task k textu obrazovky, None = bez tasků nebo textu
+    #[instrument(name = "embedding_match", skip_all, fields(tasks = tasks.len()))]
+    pub async fn find_match(&mut self, text: &str, tasks: &[FreeloTask], api_key: &str) -> Result<Option<EmbeddingMatch>, String> {
+        if tasks.is_empty() || text.trim().is_empty() {
+            return Ok(None);
+        }
+        let client = reqwest::Client::new();
+        self.update_tasks(&client, api_key, tasks).await?;
+
+        let query: String = text.chars().take(MAX_QUERY_CHARS).collect();
+        let query = embed(&client, api_key, &[query])
+            .await?
+            .pop()
+            .ok_or("Prázdná odpověď embeddingu")?;
+        Ok(self.best_match(&query, tasks))
+    }
+}
+
+/// Embeddingy textů ve stejném pořadí
+async fn embed(client: &reqwest::Client, api_key: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let response = client
+        .post(EMBEDDINGS_URL)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .json(&EmbeddingRequest {
+            model: EMBEDDING_MODEL,
+            input: inputs,
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Embedding request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("Embedding API error {}: {}", status, error_text));
+    }
+
+    let response = response
+        .json::<EmbeddingResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse embedding response: {}", e))?;
+    if let Some(ref usage) = response.usage {
+        ai_usage::record(EMBEDDING_MODEL, usage, true);
+    }
+    let mut data = response.data;
+    if data.len() != inputs.len() {
+        return Err(format!("Embedding API vrátilo {} vektorů místo {}", data.len(), inputs.len()));
+    }
+    data.sort_by_key(|d| d.index);
+    Ok(data.into_iter().map(|d| d.embedding).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_match_by_cosine() {
+        let task = |id: i32, name: &str| FreeloTask {
+            id,
+            name: name.to_string(),
+            project_id: 1,
+            project_name: "Web".to_string(),
+            estimate_minutes: None,
+        };
+        let tasks = vec![task(1, "Oprava přihlášení"), task(2, "Faktury"), task(3, "Bez embeddingu")];
+        let mut matcher = EmbeddingMatcher::default();
+        matcher.vectors.insert(task_text(&tasks[0]), vec![0.9, 0.1, 0.0]);
+        matcher.vectors.insert(task_text(&tasks[1]), vec![0.0, 0.2, 0.9]);
+
+        let best = matcher.best_match(&[1.0, 0.3, 0.1], &tasks).unwrap();
+        assert_eq!(best.task_id, 1);
+        assert!(best.similarity > 0.95 && best.confidence == 1.0);
+
+        // Kolmé vektory = žádná podobnost
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert!((similarity_confidence(0.4) - 0.5).abs() < 1e-6);
+        assert_eq!(similarity_confidence(0.1), 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Strojově čitelný kód chyby pro UI (banner, akce k nápravě)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    FreeloAuth,
+    FreeloRequest,
+    AiAuth,
+    CaptureFailed,
+    OcrFailed,
+    WindowInfo,
+    StorageFailed,
+    ConfigMissing,
+    KillSwitch,
+    RulePackInvalid,
+    PromptTemplateInvalid,
+}
+
+/// Závažnost - critical = tracking nemůže pokračovat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+    Critical,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::FreeloAuth => "freelo_auth",
+            ErrorCode::FreeloRequest => "freelo_request",
+            ErrorCode::AiAuth => "ai_auth",
+            ErrorCode::CaptureFailed => "capture_failed",
+            ErrorCode::OcrFailed => "ocr_failed",
+            ErrorCode::WindowInfo => "window_info",
+            ErrorCode::StorageFailed => "storage_failed",
+            ErrorCode::ConfigMissing => "config_missing",
+            ErrorCode::KillSwitch => "kill_switch",
+            ErrorCode::RulePackInvalid => "rule_pack_invalid",
+            ErrorCode::PromptTemplateInvalid => "prompt_template_invalid",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            ErrorCode::FreeloAuth | ErrorCode::ConfigMissing | ErrorCode::KillSwitch => Severity::Critical,
+            ErrorCode::AiAuth | ErrorCode::WindowInfo => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Doporučený krok k nápravě (zobrazí se v banneru)
+    pub fn remediation(self) -> &'static str {
+        match self {
+            ErrorCode::FreeloAuth => "Zkontrolujte Freelo e-mail a API klíč v nastavení",
+            ErrorCode::FreeloRequest => "Zkontrolujte připojení a stav Freelo, případně záznam opravte ručně",
+            ErrorCode::AiAuth => "Aktualizujte OpenRouter API klíč v nastavení (zatím se používá textové porovnání)",
+            ErrorCode::CaptureFailed => "Povolte aplikaci nahrávání obrazovky v nastavení systému",
+            ErrorCode::OcrFailed => "Ověřte instalaci Tesseractu a jazykových dat",
+            ErrorCode::WindowInfo => "Povolte aplikaci přístup k informacím o oknech (Accessibility)",
+            ErrorCode::StorageFailed => "Zkontrolujte volné místo a oprávnění datového adresáře",
+            ErrorCode::ConfigMissing => "Uložte nastavení a spusťte tracking znovu",
+            ErrorCode::KillSwitch => "Tracking zakázal administrátor - kontaktujte správce",
+            ErrorCode::RulePackInvalid => "Opravte rule_pack.json podle řádku a sloupce v chybě, do té doby platí poslední platná pravidla",
+            ErrorCode::PromptTemplateInvalid => {
+                "Opravte match_prompt.txt nebo ho smažte, do té doby platí poslední platná šablona"
+            }
+        }
+    }
+}
+
+/// Payload eventu `error-event`
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorEvent {
+    pub code: ErrorCode,
+    pub severity: Severity,
+    pub message: String,
+    pub remediation: String,
+    /// Stejný klíč = stejný problém, UI zobrazí jen jeden banner
+    pub dedup_key: String,
+}
+
+impl ErrorEvent {
+    pub fn new(code: ErrorCode, message: &str) -> Self {
+        Self {
+            code,
+            severity: code.severity(),
+            message: message.to_string(),
+            remediation: code.remediation().to_string(),
+            dedup_key: code.as_str().to_string(),
+        }
+    }
+}
+
+/// Aktuálně platné chyby (pro UI po znovunačtení okna)
+static ACTIVE: Mutex<Vec<ErrorEvent>> = Mutex::new(Vec::new());
+
+/// Zapamatuje chybu, stejný dedup klíč nahradí. Vrací true, pokud chyba ještě nebyla aktivní.
+pub fn raise(event: &ErrorEvent) -> bool {
+    let Ok(mut active) = ACTIVE.lock() else {
+        return false;
+    };
+    let before = active.len();
+    active.retain(|e| e.dedup_key != event.dedup_key);
+    let new = active.len() == before;
+    active.push(event.clone());
+    new
+}
+
+/// Odebere chybu, vrací true pokud byla aktivní
+pub fn resolve(dedup_key: &str) -> bool {
+    let Ok(mut active) = ACTIVE.lock() else {
+        return false;
+    };
+    let before = active.len();
+    active.retain(|e| e.dedup_key != dedup_key);
+    active.len() != before
+}
+
+pub fn active() -> Vec<ErrorEvent> {
+    ACTIVE.lock().map(|a| a.clone()).unwrap_or_default()
+}
+
+/// Odmítnuté přihlášení (401/403) v textu chyby API
+pub fn is_auth_error(error: &str) -> bool {
+    [" 401", " 403"].iter().any(|code| error.contains(code))
+}
+
+/// Kód pro chybu z Freelo API
+pub fn classify_freelo(error: &str) -> ErrorCode {
+    if is_auth_error(error) {
+        ErrorCode::FreeloAuth
+    } else {
+        ErrorCode::FreeloRequest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_freelo() {
+        assert_eq!(
+            classify_freelo("Freelo API error 401 Unauthorized: {}"),
+            ErrorCode::FreeloAuth
+        );
+        assert_eq!(
+            classify_freelo("Freelo start tracking error 403 Forbidden: no access"),
+            ErrorCode::FreeloAuth
+        );
+        assert_eq!(
+            classify_freelo("Freelo start tracking error 422 Unprocessable Entity: {}"),
+            ErrorCode::FreeloRequest
+        );
+
+        let event = ErrorEvent::new(ErrorCode::FreeloAuth, "Chyba při načítání tasků");
+        assert_eq!(event.severity, Severity::Critical);
+        assert_eq!(event.dedup_key, "freelo_auth");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap()["code"],
+            serde_json::json!("freelo_auth")
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::debug_artifacts::get_debug_dir;
+use crate::history::HistorySegment;
+use crate::profile_crypto;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+/// OCR text ticku uložený v debug adresáři ("20250312_091500_4_ocr_text.txt")
+const OCR_TEXT_SUFFIX: &str = "_4_ocr_text.txt";
+
+/// Výchozí strop AI requestů na jedno vyhodnocení (placené volání)
+pub const DEFAULT_MAX_AI_CALLS: usize = 50;
+
+/// Strop vzorků (nejnovější) - text matcher je rychlý, ale čtení souborů ne
+const MAX_SAMPLES: usize = 5000;
+
+/// Kolik neshod se vrací pro ruční prohlédnutí
+const MAX_DISAGREEMENTS: usize = 50;
+
+/// Jak daleko před zápisem ticku může být jeho OCR text (pomalé AI, stejný snímek)
+const SAMPLE_LOOKBACK_SECONDS: i64 = 120;
+
+/// Uložený OCR text jednoho ticku
+#[derive(Debug, Clone)]
+pub struct OcrSample {
+    pub at: DateTime<Local>,
+    pub text: String,
+}
+
+fn sample_time(file_name: &str) -> Option<DateTime<Local>> {
+    let stamp = file_name.strip_suffix(OCR_TEXT_SUFFIX)?;
+    NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S")
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+}
+
+/// OCR texty profilu za rozsah dní (včetně), od nejstaršího
+pub fn load_samples(profile: &str, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<OcrSample> {
+    let Ok(entries) = std::fs::read_dir(get_debug_dir(profile)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(DateTime<Local>, std::path::PathBuf)> = entries
+        .flatten()
+        .filter_map(|e| Some((sample_time(&e.file_name().to_string_lossy())?, e.path())))
+        .filter(|(at, _)| from.is_none_or(|d| at.date_naive() >= d) && to.is_none_or(|d| at.date_naive() <= d))
+        .collect();
+    files.sort_by_key(|(at, _)| *at);
+    let skip = files.len().saturating_sub(MAX_SAMPLES);
+
+    files
+        .into_iter()
+        .skip(skip)
+        .filter_map(|(at, path)| {
+            let text = String::from_utf8(profile_crypto::read(profile, &path).ok()?).ok()?;
+            Some(OcrSample { at, text })
+        })
+        .filter(|s| !s.text.trim().is_empty())
+        .collect()
+}
+
+/// OCR text uložený nejpozději `SAMPLE_LOOKBACK_SECONDS` před okamžikem (tick ho zapisuje až po OCR)
+pub fn sample_at(profile: &str, at: DateTime<Local>) -> Option<OcrSample> {
+    let day = at.date_naive();
+    load_samples(profile, Some(day), Some(day))
+        .into_iter()
+        .rev()
+        .find(|s| s.at <= at)
+        .filter(|s| (at - s.at).num_seconds() <= SAMPLE_LOOKBACK_SECONDS)
+}
+
+/// Konečné rozhodnutí pro okamžik - segment historie (včetně ručních oprav), který ho pokrývá.
+/// None = v tu chvíli žádný segment neběžel, vzorek nejde hodnotit.
+pub fn final_decision(segments: &[HistorySegment], at: DateTime<Local>) -> Option<Option<i32>> {
+    segments
+        .iter()
+        .find(|s| s.started_at <= at && at < s.stopped_at)
+        .map(|s| s.task_id)
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MatcherScore {
+    pub evaluated: usize,
+    pub correct: usize,
+    pub accuracy: Option<f32>,
+}
+
+impl MatcherScore {
+    fn record(&mut self, correct: bool) {
+        self.evaluated += 1;
+        self.correct += correct as usize;
+    }
+}
+
+/// Vzorek, kde se text a AI neshodly
+#[derive(Debug, Clone, Serialize)]
+pub struct Disagreement {
+    pub at: DateTime<Local>,
+    pub text_task_id: Option<i32>,
+    pub ai_task_id: Option<i32>,
+    pub final_task_id: Option<i32>,
+}
+
+/// Porovnání textového a AI matcheru nad uloženými OCR texty
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvaluationReport {
+    pub samples: usize,
+    /// Vzorky, pro které historie zná konečné rozhodnutí
+    pub with_decision: usize,
+    pub text: MatcherScore,
+    pub ai: MatcherScore,
+    /// Vzorky vyhodnocené oběma matchery
+    pub compared: usize,
+    pub agreed: usize,
+    pub agreement: Option<f32>,
+    /// Vzorky s rozhodnutím, kde AI neodpovědělo (strop requestů, AI není nastavená, chyba)
+    pub ai_skipped: usize,
+    /// Z toho chyby AI
+    pub ai_errors: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl EvaluationReport {
+    /// Výsledek jednoho vzorku - `ai` None = AI na vzorku neběželo
+    pub fn record(&mut self, at: DateTime<Local>, decision: Option<Option<i32>>, text: Option<i32>, ai: Option<Option<i32>>) {
+        self.samples += 1;
+        let Some(decision) = decision else {
+            return;
+        };
+        self.with_decision += 1;
+        self.text.record(text == decision);
+
+        let Some(ai) = ai else {
+            self.ai_skipped += 1;
+            return;
+        };
+        self.ai.record(ai == decision);
+        self.compared += 1;
+        if ai == text {
+            self.agreed += 1;
+        } else if self.disagreements.len() < MAX_DISAGREEMENTS {
+            self.disagreements.push(Disagreement {
+                at,
+                text_task_id: text,
+                ai_task_id: ai,
+                final_task_id: decision,
+            });
+        }
+    }
+
+    /// Dopočítá poměry po posledním vzorku
+    pub fn finish(&mut self) {
+        let ratio = |part: usize, total: usize| (total > 0).then(|| part as f32 / total as f32);
+        self.text.accuracy = ratio(self.text.correct, self.text.evaluated);
+        self.ai.accuracy = ratio(self.ai.correct, self.ai.evaluated);
+        self.agreement = ratio(self.agreed, self.compared);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_decisions_and_report() {
+        let at = |h: u32, m: u32| Local.with_ymd_and_hms(2025, 3, 12, h, m, 0).unwrap();
+        assert_eq!(sample_time("20250312_091500_4_ocr_text.txt"), Some(at(9, 15)));
+        assert_eq!(sample_time("20250312_091500_0_original.png"), None);
+
+        let segments = vec![HistorySegment {
+            id: "a".to_string(),
+            task_id: Some(7),
+            note: String::new(),
+            started_at: at(9, 0),
+            stopped_at: at(10, 0),
+            source: None,
+            freelo_report_id: None,
+        }];
+        assert_eq!(final_decision(&segments, at(9, 15)), Some(Some(7)));
+        assert_eq!(final_decision(&segments, at(10, 0)), None);
+
+        let mut report = EvaluationReport::default();
+        report.record(at(9, 15), Some(Some(7)), Some(7), Some(Some(7)));
+        report.record(at(9, 20), Some(Some(7)), None, Some(Some(7)));
+        report.record(at(9, 25), Some(Some(7)), Some(7), None);
+        report.record(at(11, 0), None, Some(3), None);
+        report.finish();
+
+        assert_eq!((report.samples, report.with_decision), (4, 3));
+        assert_eq!((report.text.correct, report.text.evaluated), (2, 3));
+        assert_eq!((report.ai.accuracy, report.ai_skipped), (Some(1.0), 1));
+        assert_eq!(report.agreement, Some(0.5));
+        assert_eq!(report.disagreements.len(), 1);
+        assert_eq!(report.disagreements[0].text_task_id, None);
+    }
+}
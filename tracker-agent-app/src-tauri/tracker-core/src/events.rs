@@ -0,0 +1,120 @@
+use crate::screenshot::ScreenRect;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Stav trackingu zobrazený v liště (barva ikony)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayState {
+    Stopped,
+    Running,
+    Paused,
+}
+
+impl TrayState {
+    pub fn label(self) -> &'static str {
+        match self {
+            TrayState::Stopped => "zastaveno",
+            TrayState::Running => "běží",
+            TrayState::Paused => "pozastaveno",
+        }
+    }
+}
+
+/// Hlavní okno aplikace v okamžiku dotazu
+#[derive(Debug, Clone, Copy)]
+pub struct MainWindow {
+    /// Poloha a velikost, None = nejde zjistit
+    pub rect: Option<ScreenRect>,
+    /// Okno je vidět (není skryté ani minimalizované)
+    pub visible: bool,
+}
+
+/// Kam tracker posílá eventy pro UI a co potřebuje od desktopového prostředí
+/// (lišta, oznámení, hlavní okno). Výchozí implementace odpovídají běhu bez GUI.
+pub trait EventSink: Send + Sync {
+    /// Event pro UI s payloadem převedeným na JSON
+    fn emit_value(&self, event: &str, payload: serde_json::Value) -> Result<(), String>;
+
+    fn set_tray_state(&self, _state: TrayState) {}
+
+    /// Právě sledovaný task v tooltipu lišty
+    fn set_tray_task(&self, _task: Option<&str>) {}
+
+    /// Systémové oznámení
+    fn notify(&self, _title: &str, _body: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Poloha kurzoru pro výběr snímaného monitoru
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Hlavní okno, None = aplikace žádné nemá
+    fn main_window(&self) -> Option<MainWindow> {
+        None
+    }
+
+    /// Ochrana obsahu - okno se vynechá ze snímků i ze sdílení obrazovky
+    fn set_content_protected(&self, _protected: bool) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn hide_main_window(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn show_main_window(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl dyn EventSink {
+    /// Odešle event se serializovatelným payloadem (obdoba `Emitter::emit` v Tauri)
+    pub fn emit<S: Serialize>(&self, event: &str, payload: S) -> Result<(), String> {
+        let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+        self.emit_value(event, payload)
+    }
+}
+
+/// Sdílený sink, který si tracker předává do úloh na pozadí
+pub type Events = Arc<dyn EventSink>;
+
+/// Sink pro běh bez UI - eventy zahazuje
+pub struct NoopSink;
+
+impl EventSink for NoopSink {
+    fn emit_value(&self, _event: &str, _payload: serde_json::Value) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, serde_json::Value)>>);
+
+    impl EventSink for Recorder {
+        fn emit_value(&self, event: &str, payload: serde_json::Value) -> Result<(), String> {
+            self.0.lock().unwrap().push((event.to_string(), payload));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_emit_serializes_payload() {
+        let recorder = Arc::new(Recorder::default());
+        let events: Events = recorder.clone();
+        events.emit("tracking-state", TrayState::Paused).unwrap();
+        events.emit("log-event", serde_json::json!({"level": "info"})).unwrap();
+        assert!(events.main_window().is_none());
+
+        let recorded = recorder.0.lock().unwrap();
+        assert_eq!(recorded[0], ("tracking-state".to_string(), serde_json::json!("paused")));
+        assert_eq!(recorded[1].1["level"], "info");
+    }
+}
@@ -0,0 +1,186 @@
+use crate::profile_crypto;
+use crate::profiles::profile_dir;
+use crate::storage;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Key, Nonce};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use image::DynamicImage;
+use std::path::{Path, PathBuf};
+
+/// Výchozí doba uchování důkazních screenshotů
+pub const DEFAULT_EVIDENCE_RETENTION_DAYS: u64 = 30;
+
+/// Důkazní screenshot se silně zmenší - má ukázat co bylo na obrazovce, ne detaily
+const EVIDENCE_MAX_SIZE: u32 = 640;
+const EVIDENCE_JPEG_QUALITY: u8 = 40;
+
+/// Společný klíč starších verzí - nové screenshoty se šifrují klíčem profilu
+const LEGACY_KEY_FILE: &str = "evidence.key";
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+const ENCRYPTED_EXTENSION: &str = "jpg.enc";
+const NONCE_LEN: usize = 12;
+
+fn evidence_dir(profile: &str) -> PathBuf {
+    profile_dir(profile).join("evidence")
+}
+
+/// Klíč screenshotů ze starších verzí (před šifrováním klíčem profilu), pokud existuje
+fn legacy_key() -> Option<Key<Aes256Gcm>> {
+    let bytes = std::fs::read(storage::data_file(LEGACY_KEY_FILE)).ok()?;
+    (bytes.len() == 32).then(|| *Key::<Aes256Gcm>::from_slice(&bytes))
+}
+
+/// Zašifruje data - výstup je nonce + ciphertext
+pub fn seal(key: &Key<Aes256Gcm>, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(key)
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| format!("Chyba při šifrování: {}", e))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+pub fn open(key: &Key<Aes256Gcm>, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < NONCE_LEN {
+        return Err("Zašifrovaná data jsou příliš krátká".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+
+    Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Nelze dešifrovat (jiný klíč nebo poškozený soubor)".to_string())
+}
+
+/// Čas pořízení z názvu souboru (20250312_091500.jpg.enc)
+fn taken_at(path: &Path) -> Option<DateTime<Local>> {
+    let name = path.file_name()?.to_str()?;
+    let stamp = name.strip_suffix(&format!(".{}", ENCRYPTED_EXTENSION))?;
+    let naive = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()?;
+    Local.from_local_datetime(&naive).single()
+}
+
+fn evidence_files(profile: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(evidence_dir(profile))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| taken_at(p).is_some())
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Uloží zmenšený a zašifrovaný screenshot
+fn store(profile: &str, img: &DynamicImage, now: DateTime<Local>) -> Result<PathBuf, String> {
+    let thumbnail = img.thumbnail(EVIDENCE_MAX_SIZE, EVIDENCE_MAX_SIZE).to_rgb8();
+
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, EVIDENCE_JPEG_QUALITY)
+        .encode_image(&thumbnail)
+        .map_err(|e| format!("Chyba při kódování JPEG: {}", e))?;
+
+    let sealed = seal(&profile_crypto::profile_key(profile)?, &jpeg)?;
+
+    let dir = evidence_dir(profile);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Nelze vytvořit {:?}: {}", dir, e))?;
+    let path = dir.join(format!("{}.{}", now.format(TIMESTAMP_FORMAT), ENCRYPTED_EXTENSION));
+    std::fs::write(&path, sealed).map_err(|e| format!("Nelze uložit {:?}: {}", path, e))?;
+
+    Ok(path)
+}
+
+/// Uloží důkazní screenshot z base64 JPEG (výstup capture)
+pub fn store_screenshot(profile: &str, screenshot_base64: &str, now: DateTime<Local>) -> Result<PathBuf, String> {
+    use base64::Engine;
+
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(screenshot_base64)
+        .map_err(|e| format!("Chyba při dekódování base64: {}", e))?;
+    let img = image::load_from_memory(&data).map_err(|e| format!("Chyba při načítání obrazu: {}", e))?;
+
+    store(profile, &img, now)
+}
+
+/// Smaže důkazní screenshoty starší než retention, vrací počet smazaných
+pub fn purge_expired(profile: &str, retention_days: u64, now: DateTime<Local>) -> usize {
+    let cutoff = now - chrono::Duration::days(retention_days as i64);
+
+    evidence_files(profile)
+        .into_iter()
+        .filter(|p| taken_at(p).is_some_and(|t| t < cutoff))
+        .filter(|p| std::fs::remove_file(p).is_ok())
+        .count()
+}
+
+/// Dešifruje všechny důkazní screenshoty profilu do cílového adresáře
+pub fn export(profile: &str, destination: &Path) -> Result<(PathBuf, usize), String> {
+    let files = evidence_files(profile);
+    if files.is_empty() {
+        return Err(format!("Profil '{}' nemá žádné důkazní screenshoty", profile));
+    }
+
+    let key = profile_crypto::profile_key(profile)?;
+    let legacy_key = legacy_key();
+    let target = destination.join(format!(
+        "evidence-{}-{}",
+        profile,
+        Local::now().format(TIMESTAMP_FORMAT)
+    ));
+    std::fs::create_dir_all(&target).map_err(|e| format!("Nelze vytvořit {:?}: {}", target, e))?;
+
+    for file in &files {
+        let sealed = std::fs::read(file).map_err(|e| format!("Nelze číst {:?}: {}", file, e))?;
+        let jpeg = match (open(&key, &sealed), &legacy_key) {
+            (Err(_), Some(legacy_key)) => open(legacy_key, &sealed)?,
+            (jpeg, _) => jpeg?,
+        };
+        let name = file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".enc"))
+            .unwrap_or("evidence.jpg");
+        std::fs::write(target.join(name), jpeg)
+            .map_err(|e| format!("Nelze zapsat {:?}: {}", target.join(name), e))?;
+    }
+
+    Ok((target, files.len()))
+}
+
+/// Zda je čas na další vzorek (jeden screenshot za N minut)
+pub fn is_due(last_sample: Option<DateTime<Local>>, now: DateTime<Local>, interval_minutes: u64) -> bool {
+    last_sample.is_none_or(|last| now - last >= chrono::Duration::minutes(interval_minutes as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let key = Aes256Gcm::generate_key(OsRng);
+        let sealed = seal(&key, b"screenshot").unwrap();
+
+        assert_ne!(&sealed[NONCE_LEN..], b"screenshot");
+        assert_eq!(open(&key, &sealed).unwrap(), b"screenshot");
+
+        let other_key = Aes256Gcm::generate_key(OsRng);
+        assert!(open(&other_key, &sealed).is_err());
+    }
+
+    #[test]
+    fn test_taken_at_and_due() {
+        let at = taken_at(Path::new("/tmp/20250312_091500.jpg.enc")).unwrap();
+        assert_eq!(at.format("%H:%M").to_string(), "09:15");
+        assert!(taken_at(Path::new("/tmp/notes.txt")).is_none());
+
+        assert!(is_due(None, at, 15));
+        assert!(!is_due(Some(at), at + chrono::Duration::minutes(10), 15));
+        assert!(is_due(Some(at), at + chrono::Duration::minutes(15), 15));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::freelo::FreeloTask;
+use crate::history::HistorySegment;
+use chrono::NaiveDate;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Oddělovač CSV - česká lokalizace Excelu čeká středník
+const CSV_SEPARATOR: char = ';';
+
+/// Rozsah exportu (oba dny včetně)
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ExportRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    /// Název filtru v dialogu pro uložení
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Xlsx => "Excel",
+        }
+    }
+}
+
+/// Řádek výkazu - jeden task v jednom dni
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportRow {
+    pub date: NaiveDate,
+    pub project_name: String,
+    /// None = obecná práce bez tasku
+    pub task_id: Option<i32>,
+    pub task_name: String,
+    pub minutes: i64,
+    pub segments: usize,
+}
+
+/// Segmenty z rozsahu seskupené podle dne, projektu a tasku (v tomto pořadí)
+pub fn report_rows(range: ExportRange, segments: &[HistorySegment], tasks: &[FreeloTask]) -> Vec<ReportRow> {
+    let mut rows: BTreeMap<(NaiveDate, String, String, Option<i32>), ReportRow> = BTreeMap::new();
+    for segment in segments {
+        let date = segment.started_at.date_naive();
+        if date < range.from || date > range.to {
+            continue;
+        }
+        let task = segment.task_id.and_then(|id| tasks.iter().find(|t| t.id == id));
+        let task_name = match (segment.task_id, task) {
+            (_, Some(task)) => task.name.clone(),
+            (Some(id), None) => format!("Task #{}", id),
+            (None, None) => "Obecná práce".to_string(),
+        };
+        let project_name = task.map(|t| t.project_name.clone()).unwrap_or_default();
+
+        let row = rows
+            .entry((date, project_name.clone(), task_name.clone(), segment.task_id))
+            .or_insert_with(|| ReportRow {
+                date,
+                project_name,
+                task_id: segment.task_id,
+                task_name,
+                minutes: 0,
+                segments: 0,
+            });
+        row.minutes += segment.minutes();
+        row.segments += 1;
+    }
+    rows.into_values().collect()
+}
+
+/// Hodiny s desetinnou čárkou ("1,25")
+fn hours(minutes: i64) -> String {
+    format!("{:.2}", minutes as f64 / 60.0).replace('.', ",")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([CSV_SEPARATOR, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSV s BOM, aby Excel poznal UTF-8 (diakritika v názvech tasků)
+pub fn to_csv(rows: &[ReportRow]) -> String {
+    let separator = CSV_SEPARATOR.to_string();
+    let mut csv = String::from("\u{feff}");
+    csv.push_str(&["Datum", "Projekt", "ID tasku", "Task", "Hodiny", "Minuty", "Segmenty"].join(&separator));
+    csv.push_str("\r\n");
+    for row in rows {
+        let fields = [
+            row.date.to_string(),
+            csv_field(&row.project_name),
+            row.task_id.map(|id| id.to_string()).unwrap_or_default(),
+            csv_field(&row.task_name),
+            hours(row.minutes),
+            row.minutes.to_string(),
+            row.segments.to_string(),
+        ];
+        csv.push_str(&fields.join(&separator));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Sešit s jedním listem výkazu a součtem na konci
+fn write_xlsx(rows: &[ReportRow], path: &Path) -> Result<(), rust_xlsxwriter::XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Výkaz")?;
+
+    let bold = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let hours_format = Format::new().set_num_format("0.00");
+
+    for (col, title) in ["Datum", "Projekt", "ID tasku", "Task", "Hodiny", "Minuty", "Segmenty"].into_iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, title, &bold)?;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let r = i as u32 + 1;
+        sheet.write_date_with_format(r, 0, row.date, &date_format)?;
+        sheet.write_string(r, 1, &row.project_name)?;
+        if let Some(task_id) = row.task_id {
+            sheet.write_number(r, 2, task_id)?;
+        }
+        sheet.write_string(r, 3, &row.task_name)?;
+        sheet.write_number_with_format(r, 4, row.minutes as f64 / 60.0, &hours_format)?;
+        sheet.write_number(r, 5, row.minutes as f64)?;
+        sheet.write_number(r, 6, row.segments as f64)?;
+    }
+
+    let total_row = rows.len() as u32 + 1;
+    let total: i64 = rows.iter().map(|r| r.minutes).sum();
+    sheet.write_string_with_format(total_row, 0, "Celkem", &bold)?;
+    sheet.write_number_with_format(total_row, 4, total as f64 / 60.0, &hours_format.set_bold())?;
+    sheet.write_number_with_format(total_row, 5, total as f64, &bold)?;
+
+    sheet.set_column_width(1, 30)?;
+    sheet.set_column_width(3, 40)?;
+    workbook.save(path)
+}
+
+/// Uloží výkaz ve zvoleném formátu
+pub fn save(rows: &[ReportRow], format: ExportFormat, path: &Path) -> Result<(), String> {
+    match format {
+        ExportFormat::Csv => std::fs::write(path, to_csv(rows)).map_err(|e| e.to_string()),
+        ExportFormat::Xlsx => write_xlsx(rows, path).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Nelze uložit výkaz do {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn segment(day: u32, hour: u32, minutes: i64, task_id: Option<i32>) -> HistorySegment {
+        let started_at = Local.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap();
+        HistorySegment {
+            id: format!("{}-{}", day, hour),
+            task_id,
+            note: String::new(),
+            started_at,
+            stopped_at: started_at + chrono::Duration::minutes(minutes),
+            source: None,
+            freelo_report_id: None,
+        }
+    }
+
+    #[test]
+    fn test_report_rows_and_csv() {
+        let tasks = vec![FreeloTask {
+            id: 1,
+            name: "Web; úpravy".to_string(),
+            project_id: 10,
+            project_name: "Klient".to_string(),
+            estimate_minutes: None,
+        }];
+        let segments = vec![
+            segment(3, 9, 60, Some(1)),
+            segment(3, 11, 30, Some(1)),
+            segment(3, 13, 15, None),
+            segment(4, 9, 45, Some(1)),
+            // Mimo rozsah
+            segment(10, 9, 60, Some(1)),
+        ];
+        let range = ExportRange {
+            from: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+            to: NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+        };
+
+        let rows = report_rows(range, &segments, &tasks);
+        let summary: Vec<_> = rows.iter().map(|r| (r.date.to_string(), r.task_name.as_str(), r.minutes, r.segments)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2025-03-03".to_string(), "Obecná práce", 15, 1),
+                ("2025-03-03".to_string(), "Web; úpravy", 90, 2),
+                ("2025-03-04".to_string(), "Web; úpravy", 45, 1),
+            ]
+        );
+
+        let csv = to_csv(&rows);
+        let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], "2025-03-03;Klient;1;\"Web; úpravy\";1,50;90;2");
+    }
+}
@@ -0,0 +1,867 @@
+use crate::backend::TimeTrackingBackend;
+use crate::connection_check;
+use crate::rate_limit::{RateLimiter, SendWithRetry};
+use crate::text_matcher::{activity_key, MatchSource};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tracing::{info, instrument, Span};
+
+/// Pojistka proti nekonečnému stránkování work reportů
+const MAX_REPORT_PAGES: usize = 200;
+
+/// Společný limit requestů na Freelo API (burst 5, pak 1 request/s) - při 429 se opakuje s backoffem
+static FREELO_LIMITER: RateLimiter = RateLimiter::new(5, 1);
+
+// Raw structure from Freelo API
+#[derive(Debug, Clone, Deserialize)]
+struct TaskDetailResponse {
+    data: TaskDetailData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TaskDetailData {
+    tasks: Vec<FreeloTaskRaw>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FreeloTaskRaw {
+    id: i32,
+    name: String,
+    project: ProjectInfo,
+    /// Odhad pracnosti zadaný ve Freelo
+    #[serde(default)]
+    total_time_estimate: Option<TimeEstimateRaw>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TimeEstimateRaw {
+    #[serde(default)]
+    minutes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProjectInfo {
+    id: i32,
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TasklistRaw {
+    id: i32,
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProjectRaw {
+    id: i32,
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    avatar_url: Option<String>,
+    /// Aktivní tasklisty projektu (/projects je vrací rovnou s projektem)
+    #[serde(default)]
+    tasklists: Vec<TasklistRaw>,
+}
+
+impl ProjectsResponse {
+    fn into_projects(self) -> Vec<FreeloProject> {
+        let raw = match self {
+            ProjectsResponse::List(projects) => projects,
+            ProjectsResponse::Paged { data } => data.projects,
+        };
+        raw.into_iter()
+            .map(|p| FreeloProject {
+                tasklists: p
+                    .tasklists
+                    .into_iter()
+                    .map(|t| Tasklist {
+                        id: t.id,
+                        name: t.name,
+                        project_id: p.id,
+                    })
+                    .collect(),
+                id: p.id,
+                name: p.name,
+                color: p.color,
+                avatar_url: p.avatar_url,
+            })
+            .collect()
+    }
+}
+
+// /projects vrací buď přímo pole, nebo stránkovanou odpověď s data.projects
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ProjectsResponse {
+    List(Vec<ProjectRaw>),
+    Paged { data: ProjectsData },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProjectsData {
+    projects: Vec<ProjectRaw>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WorkReportTaskRaw {
+    id: i32,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WorkReportRaw {
+    id: i32,
+    #[serde(default)]
+    minutes: i64,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    task: Option<WorkReportTaskRaw>,
+    /// "2025-03-12" nebo s časem, bereme jen datum
+    #[serde(default)]
+    date_reported: Option<String>,
+}
+
+impl From<WorkReportRaw> for WorkReport {
+    fn from(r: WorkReportRaw) -> Self {
+        WorkReport {
+            id: r.id,
+            task_id: r.task.as_ref().map(|t| t.id),
+            task_name: r.task.and_then(|t| t.name),
+            minutes: r.minutes,
+            note: r.note.unwrap_or_default(),
+            date: r
+                .date_reported
+                .and_then(|d| chrono::NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WorkReportsResponse {
+    data: WorkReportsData,
+    /// Stránkování - celkový počet záznamů (chybí = jediná stránka)
+    #[serde(default)]
+    total: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct WorkReportsData {
+    reports: Vec<WorkReportRaw>,
+}
+
+/// Záznam odpracovaného času, jak ho Freelo skutečně uložilo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkReport {
+    pub id: i32,
+    pub task_id: Option<i32>,
+    #[serde(default)]
+    pub task_name: Option<String>,
+    pub minutes: i64,
+    pub note: String,
+    #[serde(default)]
+    pub date: Option<chrono::NaiveDate>,
+}
+
+impl WorkReport {
+    /// Záznam po úpravě z UI - nezadané hodnoty zůstávají
+    pub fn edited(&self, edit: &WorklogEdit) -> Result<WorkReport, String> {
+        let mut report = self.clone();
+        if let Some(task_id) = edit.task_id.filter(|id| Some(*id) != self.task_id) {
+            report.task_id = Some(task_id);
+            report.task_name = None;
+        }
+        if let Some(minutes) = edit.minutes {
+            if !(1..=24 * 60).contains(&minutes) {
+                return Err(format!("Délka záznamu musí být 1-1440 minut (zadáno {})", minutes));
+            }
+            report.minutes = minutes;
+        }
+        if let Some(ref note) = edit.note {
+            report.note = note.trim().to_string();
+        }
+        Ok(report)
+    }
+}
+
+/// Oprava záznamu práce z aplikace (jiný task, poznámka, délka)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WorklogEdit {
+    pub task_id: Option<i32>,
+    pub note: Option<String>,
+    pub minutes: Option<i64>,
+}
+
+/// Metadata projektu pro UI (barva a avatar jako ve Freelo)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectMeta {
+    pub id: i32,
+    pub name: String,
+    pub color: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Tasklist projektu - do něj se zakládají nové tasky
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Tasklist {
+    pub id: i32,
+    pub name: String,
+    pub project_id: i32,
+}
+
+/// Projekt s aktivními tasklisty pro výběr v UI (filtr projektů, zakládání tasků)
+#[derive(Debug, Clone, Serialize)]
+pub struct FreeloProject {
+    pub id: i32,
+    pub name: String,
+    pub color: Option<String>,
+    pub avatar_url: Option<String>,
+    pub tasklists: Vec<Tasklist>,
+}
+
+impl FreeloProject {
+    pub fn meta(&self) -> ProjectMeta {
+        ProjectMeta {
+            id: self.id,
+            name: self.name.clone(),
+            color: self.color.clone(),
+            avatar_url: self.avatar_url.clone(),
+        }
+    }
+}
+
+// Simplified structure for our use
+#[derive(Debug, Clone, Serialize)]
+pub struct FreeloTask {
+    pub id: i32,
+    pub name: String,
+    pub project_id: i32,
+    pub project_name: String,
+    /// Odhad pracnosti z Freelo v minutách (None = bez odhadu, jiné služby ho nemají)
+    pub estimate_minutes: Option<i64>,
+}
+
+/// Strop zapamatovaných aktivit segmentu (OCR šum by jinak seznam nafukoval)
+const MAX_SEGMENT_ACTIVITIES: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct ActiveTracking {
+    pub task_id: String,
+    pub uuid: String,
+    pub start_time: std::time::SystemTime,
+    pub last_context: String,
+    pub last_application: String,
+    pub last_activity_description: String,
+    pub unstable_count: u32,
+    /// Aktuální poznámka Freelo záznamu (bez ručních anotací)
+    pub note: String,
+    /// Popis aktivity, ze kterého poznámka vznikla
+    pub note_activity: String,
+    /// Ruční poznámky uživatele k běžícímu segmentu
+    pub annotations: Vec<String>,
+    /// Různé popisy aktivity během segmentu (pro souhrnný komentář k tasku)
+    pub activities: Vec<String>,
+    /// Poslední známý vstup uživatele (klávesnice/myš) během segmentu
+    pub last_input_at: chrono::DateTime<chrono::Local>,
+    /// Segment už byl označen jako podezřele dlouhý bez aktivity
+    pub spike_flagged: bool,
+    /// Segment běží jen lokálně (Freelo bylo nedostupné), odešle se přes outbox
+    pub offline: bool,
+    /// Segment kategorie, která se do Freelo nezapisuje (jen lokální historie)
+    pub local_only: bool,
+    /// Matcher, který task zvolil (None = ruční volba)
+    pub source: Option<MatchSource>,
+    /// Confidence matcheru při zahájení (None = ruční volba)
+    pub confidence: Option<f32>,
+}
+
+impl ActiveTracking {
+    pub fn new(task_id: String, uuid: String, application: String, activity: String, note: String) -> Self {
+        Self {
+            task_id,
+            uuid,
+            start_time: std::time::SystemTime::now(),
+            last_context: application.clone(),
+            last_application: application,
+            note_activity: activity.clone(),
+            activities: vec![activity.clone()],
+            last_activity_description: activity,
+            unstable_count: 0,
+            note,
+            annotations: Vec::new(),
+            last_input_at: chrono::Local::now(),
+            spike_flagged: false,
+            offline: false,
+            local_only: false,
+            source: None,
+            confidence: None,
+        }
+    }
+
+    /// Zapamatuje popis aktivity, pokud se liší od dosavadních (čísla a diakritika se ignorují)
+    pub fn record_activity(&mut self, activity: &str) {
+        let key = activity_key(activity);
+        if !key.is_empty()
+            && self.activities.len() < MAX_SEGMENT_ACTIVITIES
+            && !self.activities.iter().any(|a| activity_key(a) == key)
+        {
+            self.activities.push(activity.to_string());
+        }
+    }
+
+    /// Výsledná poznámka pro Freelo - původní poznámka + ruční anotace
+    pub fn full_note(&self) -> String {
+        if self.annotations.is_empty() {
+            self.note.clone()
+        } else {
+            format!("{} | {}", self.note, self.annotations.join("; "))
+        }
+    }
+}
+
+/// Kam jdou zápisy do Freelo (čtení jde vždy do ostrého workspace)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteRouting {
+    #[default]
+    Live,
+    /// Zápisy do testovacího workspace (sandbox přihlašovací údaje)
+    Sandbox,
+    /// Nic se nezapisuje - tracker vede segmenty jen v lokální historii
+    DryRun,
+}
+
+/// Přepínač sdílený mezi trackerem a klienty - změna platí hned i pro běžící smyčku
+pub type SharedWriteRouting = Arc<RwLock<WriteRouting>>;
+
+#[derive(Debug, Clone)]
+pub struct FreeloCredentials {
+    pub email: String,
+    pub api_key: String,
+}
+
+#[derive(Clone)]
+pub struct FreeloClient {
+    client: Client,
+    email: String,
+    api_key: String,
+    sandbox: Option<FreeloCredentials>,
+    routing: SharedWriteRouting,
+}
+
+impl FreeloClient {
+    pub fn new(email: String, api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            email,
+            api_key,
+            sandbox: None,
+            routing: SharedWriteRouting::default(),
+        }
+    }
+
+    /// Zápisy se řídí sdíleným přepínačem (sandbox / dry-run)
+    pub fn with_write_routing(mut self, routing: SharedWriteRouting, sandbox: Option<FreeloCredentials>) -> Self {
+        self.routing = routing;
+        self.sandbox = sandbox;
+        self
+    }
+
+    pub fn write_routing(&self) -> WriteRouting {
+        self.routing.read().map(|r| *r).unwrap_or_default()
+    }
+
+    /// Přihlašovací údaje pro zápis, None = dry-run (sandbox bez údajů se nikdy
+    /// nepropadne do ostrého workspace)
+    fn write_credentials(&self, op: &str) -> Option<(&str, &str)> {
+        let credentials = match self.write_routing() {
+            WriteRouting::Live => Some((self.email.as_str(), self.api_key.as_str())),
+            WriteRouting::Sandbox => self.sandbox.as_ref().map(|c| (c.email.as_str(), c.api_key.as_str())),
+            WriteRouting::DryRun => None,
+        };
+        if credentials.is_none() {
+            info!(op, "dry-run: zápis do Freelo neodeslán");
+        }
+        credentials
+    }
+
+    #[instrument(name = "freelo", skip_all, fields(op = "get_active_tasks", status))]
+    pub async fn get_active_tasks(&self) -> Result<Vec<FreeloTask>, String> {
+        let url = "https://api.freelo.io/v1/all-tasks?states_ids[]=1&limit=100";
+
+        let response = self
+            .client
+            .get(url)
+            .basic_auth(&self.email, Some(&self.api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo API error {}: {}", status, text));
+        }
+
+        let task_response: TaskDetailResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        // Convert to simplified structure
+        let tasks = task_response
+            .data
+            .tasks
+            .into_iter()
+            .map(|t| FreeloTask {
+                id: t.id,
+                name: t.name,
+                project_id: t.project.id,
+                project_name: t.project.name,
+                estimate_minutes: t.total_time_estimate.and_then(|e| e.minutes),
+            })
+            .collect();
+
+        Ok(tasks)
+    }
+
+    /// Vlastní projekty s aktivními tasklisty
+    #[instrument(name = "freelo", skip_all, fields(op = "get_projects", status))]
+    pub async fn get_projects(&self) -> Result<Vec<FreeloProject>, String> {
+        let url = "https://api.freelo.io/v1/projects";
+
+        let response = self
+            .client
+            .get(url)
+            .basic_auth(&self.email, Some(&self.api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo API error {}: {}", status, text));
+        }
+
+        let projects_response: ProjectsResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        Ok(projects_response.into_projects())
+    }
+
+    /// Aktivní tasklisty jednoho projektu
+    pub async fn get_tasklists(&self, project_id: i32) -> Result<Vec<Tasklist>, String> {
+        self.get_projects()
+            .await?
+            .into_iter()
+            .find(|p| p.id == project_id)
+            .map(|p| p.tasklists)
+            .ok_or_else(|| format!("Projekt {} nenalezen mezi aktivními projekty", project_id))
+    }
+
+    /// Záznamy práce za daný den, volitelně jen pro jeden task
+    pub async fn get_work_reports(
+        &self,
+        date: chrono::NaiveDate,
+        task_id: Option<&str>,
+    ) -> Result<Vec<WorkReport>, String> {
+        self.get_work_reports_range(date, date, task_id).await
+    }
+
+    /// Záznamy práce za rozsah dní (včetně), načte všechny stránky
+    #[instrument(name = "freelo", skip_all, fields(op = "get_work_reports", status))]
+    pub async fn get_work_reports_range(
+        &self,
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+        task_id: Option<&str>,
+    ) -> Result<Vec<WorkReport>, String> {
+        let mut base = format!(
+            "https://api.freelo.io/v1/work-reports?date_reported_range[date_from]={}&date_reported_range[date_to]={}",
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d")
+        );
+        if let Some(id) = task_id {
+            base.push_str(&format!("&tasks_ids[]={}", id));
+        }
+
+        let mut reports: Vec<WorkReport> = Vec::new();
+        for page in 0..MAX_REPORT_PAGES {
+            let response = self
+                .client
+                .get(format!("{}&p={}", base, page))
+                .basic_auth(&self.email, Some(&self.api_key))
+                .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+                .send_with_retry(&FREELO_LIMITER)
+                .await?;
+            Span::current().record("status", response.status().as_u16());
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(format!("Freelo work reports error {}: {}", status, text));
+            }
+
+            let reports_response: WorkReportsResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("JSON parse error: {}", e))?;
+
+            let page_empty = reports_response.data.reports.is_empty();
+            reports.extend(reports_response.data.reports.into_iter().map(WorkReport::from));
+
+            let Some(total) = reports_response.total else {
+                break;
+            };
+            if page_empty || reports.len() >= total {
+                break;
+            }
+        }
+
+        Ok(reports)
+    }
+
+    /// Zapíše odpracovaný čas k tasku zpětně (segmenty z doby výpadku, opravy historie).
+    /// Vrací ID nového work reportu, pokud ho Freelo v odpovědi uvede.
+    #[instrument(name = "freelo", skip_all, fields(op = "create_work_report", status))]
+    pub async fn create_work_report(
+        &self,
+        task_id: i32,
+        minutes: i64,
+        note: &str,
+        date: chrono::NaiveDate,
+    ) -> Result<Option<i32>, String> {
+        let Some((email, api_key)) = self.write_credentials("create_work_report") else {
+            return Ok(None);
+        };
+        let url = format!("https://api.freelo.io/v1/task/{}/work-reports", task_id);
+
+        let body = serde_json::json!({
+            "minutes": minutes,
+            "note": note,
+            "date_reported": date.format("%Y-%m-%d").to_string(),
+        });
+
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo work report error {}: {}", status, text));
+        }
+
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let id = body
+            .get("id")
+            .or_else(|| body.pointer("/data/id"))
+            .and_then(|id| id.as_i64())
+            .map(|id| id as i32);
+
+        Ok(id)
+    }
+
+    /// Upraví délku, poznámku a datum work reportu (task změnit nejde - nový záznam a smazání)
+    #[instrument(name = "freelo", skip_all, fields(op = "update_work_report", status))]
+    pub async fn update_work_report(
+        &self,
+        report_id: i32,
+        minutes: i64,
+        note: &str,
+        date: chrono::NaiveDate,
+    ) -> Result<(), String> {
+        let Some((email, api_key)) = self.write_credentials("update_work_report") else {
+            return Ok(());
+        };
+        let url = format!("https://api.freelo.io/v1/work-reports/{}", report_id);
+
+        let body = serde_json::json!({
+            "minutes": minutes,
+            "note": note,
+            "date_reported": date.format("%Y-%m-%d").to_string(),
+        });
+
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo edit work report error {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+
+    /// Přidá komentář k tasku (HTML)
+    #[instrument(name = "freelo", skip_all, fields(op = "add_comment", status))]
+    pub async fn add_comment(&self, task_id: i32, content: &str) -> Result<(), String> {
+        let Some((email, api_key)) = self.write_credentials("add_comment") else {
+            return Ok(());
+        };
+        let url = format!("https://api.freelo.io/v1/task/{}/comments", task_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "content": content }))
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo comment error {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+
+    /// Ověří přihlašovací údaje lehkým dotazem na profil (bez opakování, ať UI nečeká)
+    #[instrument(name = "freelo", skip_all, fields(op = "check_connection", status))]
+    pub async fn check_connection(&self) -> Result<(), String> {
+        let response = self
+            .client
+            .get("https://api.freelo.io/v1/users/me")
+            .basic_auth(&self.email, Some(&self.api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .timeout(connection_check::CHECK_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP chyba: {}", e))?;
+        Span::current().record("status", response.status().as_u16());
+
+        connection_check::check_status(response.status())
+    }
+
+    /// Smaže work report (oprava lokální historie)
+    #[instrument(name = "freelo", skip_all, fields(op = "delete_work_report", status))]
+    pub async fn delete_work_report(&self, report_id: i32) -> Result<(), String> {
+        let Some((email, api_key)) = self.write_credentials("delete_work_report") else {
+            return Ok(());
+        };
+        let url = format!("https://api.freelo.io/v1/work-reports/{}", report_id);
+
+        let response = self
+            .client
+            .delete(&url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo delete work report error {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(name = "freelo", skip_all, fields(op = "start_tracking", status))]
+    pub async fn start_tracking(
+        &self,
+        task_id: Option<&str>,
+        note: &str,
+    ) -> Result<String, String> {
+        let Some((email, api_key)) = self.write_credentials("start_tracking") else {
+            return Ok(format!("dry-run-{}", chrono::Local::now().timestamp_millis()));
+        };
+        let url = "https://api.freelo.io/v1/timetracking/start";
+
+        let mut body = serde_json::json!({
+            "note": note,
+        });
+
+        if let Some(id) = task_id {
+            body["task_id"] = serde_json::json!(id);
+        }
+
+        let response = self
+            .client
+            .post(url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo start tracking error {}: {}", status, text));
+        }
+
+        #[derive(Deserialize)]
+        struct StartResponse {
+            uuid: String,
+        }
+
+        let result: StartResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        Ok(result.uuid)
+    }
+
+    #[instrument(name = "freelo", skip_all, fields(op = "update_tracking_note", status))]
+    pub async fn update_tracking_note(&self, uuid: &str, note: &str) -> Result<(), String> {
+        let Some((email, api_key)) = self.write_credentials("update_tracking_note") else {
+            return Ok(());
+        };
+        let url = "https://api.freelo.io/v1/timetracking/edit";
+
+        let body = serde_json::json!({
+            "uuid": uuid,
+            "note": note,
+        });
+
+        let response = self
+            .client
+            .post(url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo edit tracking error {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(name = "freelo", skip_all, fields(op = "stop_tracking", status))]
+    pub async fn stop_tracking(&self, uuid: &str) -> Result<(), String> {
+        let Some((email, api_key)) = self.write_credentials("stop_tracking") else {
+            return Ok(());
+        };
+        let url = "https://api.freelo.io/v1/timetracking/stop";
+
+        let body = serde_json::json!({
+            "uuid": uuid,
+        });
+
+        let response = self
+            .client
+            .post(url)
+            .basic_auth(email, Some(api_key))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send_with_retry(&FREELO_LIMITER)
+            .await?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Freelo stop tracking error {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+}
+
+
+impl TimeTrackingBackend for FreeloClient {
+    async fn list_tasks(&self) -> Result<Vec<FreeloTask>, String> {
+        self.get_active_tasks().await
+    }
+
+    async fn start(&self, task_id: Option<&str>, note: &str) -> Result<String, String> {
+        self.start_tracking(task_id, note).await
+    }
+
+    async fn stop(&self, entry_id: &str) -> Result<(), String> {
+        self.stop_tracking(entry_id).await
+    }
+
+    async fn annotate(&self, entry_id: &str, note: &str) -> Result<(), String> {
+        self.update_tracking_note(entry_id, note).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projects_with_tasklists() {
+        let list: ProjectsResponse = serde_json::from_str(
+            r##"[{"id": 10, "name": "E-shop", "color": "#ff0000",
+                  "tasklists": [{"id": 100, "name": "Backlog"}, {"id": 101, "name": "Hotovo"}]},
+                 {"id": 20, "name": "Obchod"}]"##,
+        )
+        .unwrap();
+        let projects = list.into_projects();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(
+            projects[0].tasklists[1],
+            Tasklist { id: 101, name: "Hotovo".to_string(), project_id: 10 }
+        );
+        assert!(projects[1].tasklists.is_empty());
+        assert_eq!(projects[0].meta().color.as_deref(), Some("#ff0000"));
+
+        let paged: ProjectsResponse =
+            serde_json::from_str(r#"{"data": {"projects": [{"id": 30, "name": "Interní"}]}}"#).unwrap();
+        assert_eq!(paged.into_projects()[0].name, "Interní");
+    }
+
+    #[test]
+    fn test_edit_work_report() {
+        let raw: WorkReportRaw = serde_json::from_str(
+            r#"{"id": 5, "minutes": 90, "note": "Login", "task": {"id": 7, "name": "API"}, "date_reported": "2025-03-12 00:00:00"}"#,
+        )
+        .unwrap();
+        let report = WorkReport::from(raw);
+        assert_eq!((report.task_name.as_deref(), report.date), (Some("API"), chrono::NaiveDate::from_ymd_opt(2025, 3, 12)));
+
+        let moved = report
+            .edited(&WorklogEdit { task_id: Some(8), note: Some(" Review ".to_string()), minutes: None })
+            .unwrap();
+        assert_eq!((moved.task_id, moved.task_name, moved.note.as_str(), moved.minutes), (Some(8), None, "Review", 90));
+
+        // Stejný task není změna tasku
+        assert_eq!(report.edited(&WorklogEdit { task_id: Some(7), ..Default::default() }).unwrap(), report);
+        assert!(report.edited(&WorklogEdit { minutes: Some(0), ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,410 @@
+use crate::events::{EventSink, Events};
+use crate::settings::{self, Settings};
+use crate::tracker::{Tracker, TrackingStatus};
+use crate::{ocr, resources, storage};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Socket pro ovládání běžícího agenta v datovém adresáři aplikace
+const CONTROL_SOCKET: &str = "tracker-agent.sock";
+
+/// Jak dlouho smí při ukončení trvat zastavení běžícího segmentu
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+const USAGE: &str = "Použití:
+  tracker-agent-cli run [--config <soubor>] [--no-start] [--socket <cesta>]
+  tracker-agent-cli start|stop|pause|resume|rescan|status|shutdown [--socket <cesta>]
+  tracker-agent-cli config check <soubor> [--sample <soubor s OCR textem>]";
+
+/// Příkaz pro běžícího agenta (řádek textu přes socket)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    Rescan,
+    Status,
+    /// Zastaví tracking a ukončí agenta
+    Shutdown,
+}
+
+impl ControlCommand {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "start" => Some(Self::Start),
+            "stop" => Some(Self::Stop),
+            "pause" => Some(Self::Pause),
+            "resume" => Some(Self::Resume),
+            "rescan" => Some(Self::Rescan),
+            "status" => Some(Self::Status),
+            "shutdown" => Some(Self::Shutdown),
+            _ => None,
+        }
+    }
+}
+
+/// Odpověď agenta na příkaz - jeden řádek JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlReply {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<AgentStatus>,
+}
+
+impl ControlReply {
+    fn from_result(result: Result<Option<AgentStatus>, String>) -> Self {
+        match result {
+            Ok(status) => Self { ok: true, error: None, status },
+            Err(e) => Self { ok: false, error: Some(e), status: None },
+        }
+    }
+}
+
+/// Stav trackingu a poslední rozhodnutí (obsah eventu `tracking-update`)
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentStatus {
+    #[serde(flatten)]
+    pub tracking: TrackingStatus,
+    pub last_update: Option<Value>,
+}
+
+/// Sink bez UI - logy už vypisuje tracing, pamatuje si jen poslední rozhodnutí pro `status`
+#[derive(Default)]
+pub struct ConsoleSink {
+    last_update: Mutex<Option<Value>>,
+}
+
+impl EventSink for ConsoleSink {
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), String> {
+        match event {
+            "tracking-update" => {
+                if let Ok(mut last) = self.last_update.lock() {
+                    *last = Some(payload);
+                }
+            }
+            "error-event" => {
+                if let Some(remediation) = payload["remediation"].as_str().filter(|r| !r.is_empty()) {
+                    tracing::warn!("náprava: {}", remediation);
+                }
+            }
+            _ => tracing::debug!(event, %payload, "event"),
+        }
+        Ok(())
+    }
+}
+
+/// Volby `tracker-agent-cli run`
+#[derive(Debug, Default, PartialEq)]
+struct RunOptions {
+    config: Option<PathBuf>,
+    socket: Option<PathBuf>,
+    no_start: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().map(PathBuf::from).ok_or(format!("{} vyžaduje cestu", name));
+        match arg.as_str() {
+            "--config" => options.config = Some(value("--config")?),
+            "--socket" => options.socket = Some(value("--socket")?),
+            "--no-start" => options.no_start = true,
+            other => return Err(format!("Neznámý přepínač '{}'", other)),
+        }
+    }
+    Ok(options)
+}
+
+fn socket_path(options: &RunOptions) -> PathBuf {
+    options.socket.clone().unwrap_or_else(|| storage::data_file(CONTROL_SOCKET))
+}
+
+/// Nastavení ze souboru (API klíče přímo v něm), bez souboru uložené nastavení aplikace
+fn load_settings(path: Option<&Path>) -> Result<Settings, String> {
+    let settings = match path {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).map_err(|e| format!("Nelze přečíst {:?}: {}", path, e))?;
+            serde_json::from_str(&content).map_err(|e| format!("Neplatné nastavení {:?}: {}", path, e))?
+        }
+        None => settings::load().ok_or("Nastavení ještě nebylo uloženo - spusťte aplikaci, nebo použijte --config")?,
+    };
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// Načte nastavení do trackeru (při startu i po SIGHUP)
+async fn apply_settings(tracker: &Tracker, path: Option<&Path>) -> Result<(), String> {
+    let config = load_settings(path)?.tracker_config()?;
+    Tracker::apply_focus_calendar(&config)?;
+    let languages: Vec<&str> = config.ocr_languages.split('+').collect();
+    let (_, missing) = ocr::missing_languages(&languages);
+    if config.ocr_engine.resolve() == ocr::OcrEngineKind::Tesseract && !missing.is_empty() {
+        tracing::warn!("chybí OCR jazyky {} - stáhněte je v aplikaci, do té doby OCR selže", missing.join(", "));
+    }
+    tracker.set_config(config).await;
+    Ok(())
+}
+
+async fn status(tracker: &Tracker, sink: &ConsoleSink) -> AgentStatus {
+    AgentStatus {
+        tracking: tracker.get_tracking_status().await,
+        last_update: sink.last_update.lock().ok().and_then(|last| last.clone()),
+    }
+}
+
+/// Provede příkaz ze socketu nebo signálu
+async fn execute(tracker: &Tracker, sink: &Arc<ConsoleSink>, command: ControlCommand) -> Result<Option<AgentStatus>, String> {
+    let events: Events = sink.clone();
+    match command {
+        ControlCommand::Start => tracker.start(events).await?,
+        ControlCommand::Stop => tracker.stop(events).await?,
+        ControlCommand::Pause => tracker.pause(&events).await?,
+        ControlCommand::Resume => tracker.resume(&events).await?,
+        ControlCommand::Rescan => tracker.rescan_now().await?,
+        ControlCommand::Status | ControlCommand::Shutdown => {}
+    }
+    Ok(Some(status(tracker, sink).await))
+}
+
+#[cfg(unix)]
+mod control {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{UnixListener, UnixStream};
+    use tokio::sync::mpsc;
+
+    /// Otevře socket - zbylý soubor po pádu smaže, běžícího agenta nepřepíše
+    pub fn bind(path: &Path) -> Result<UnixListener, String> {
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(format!("Agent už běží (socket {:?})", path));
+            }
+            std::fs::remove_file(path).map_err(|e| format!("Nelze smazat starý socket {:?}: {}", path, e))?;
+        }
+        UnixListener::bind(path).map_err(|e| format!("Nelze otevřít socket {:?}: {}", path, e))
+    }
+
+    /// Jeden příkaz na řádek, odpověď jako řádek JSON
+    pub async fn serve_client(stream: UnixStream, tracker: Arc<Tracker>, sink: Arc<ConsoleSink>, shutdown: mpsc::Sender<()>) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let reply = match ControlCommand::parse(&line) {
+                Some(command) => {
+                    let reply = ControlReply::from_result(execute(&tracker, &sink, command).await);
+                    if command == ControlCommand::Shutdown {
+                        let _ = shutdown.send(()).await;
+                    }
+                    reply
+                }
+                None => ControlReply::from_result(Err(format!("Neznámý příkaz '{}'", line.trim()))),
+            };
+            let mut json = serde_json::to_string(&reply).unwrap_or_default();
+            json.push('\n');
+            if writer.write_all(json.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Pošle příkaz běžícímu agentovi a vypíše odpověď
+    pub fn send(path: &Path, command: &str) -> i32 {
+        use std::io::{BufRead, Write};
+
+        let mut stream = match std::os::unix::net::UnixStream::connect(path) {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Agent neběží (socket {:?}: {})", path, e);
+                return 3;
+            }
+        };
+        let mut reply = String::new();
+        let result = writeln!(stream, "{}", command)
+            .and_then(|_| std::io::BufReader::new(&stream).read_line(&mut reply));
+        if let Err(e) = result {
+            eprintln!("Chyba komunikace s agentem: {}", e);
+            return 3;
+        }
+        println!("{}", reply.trim());
+        match serde_json::from_str::<Value>(&reply) {
+            Ok(reply) if reply["ok"] == true => 0,
+            _ => 1,
+        }
+    }
+}
+
+/// Smyčka agenta - příkazy ze socketu a signály, dokud nepřijde ukončení
+async fn serve(options: RunOptions) -> Result<(), String> {
+    let tracker = Arc::new(Tracker::new());
+    let sink = Arc::new(ConsoleSink::default());
+    let events: Events = sink.clone();
+    apply_settings(&tracker, options.config.as_deref()).await?;
+
+    #[cfg(unix)]
+    let (listener, socket) = {
+        let socket = socket_path(&options);
+        (control::bind(&socket)?, socket)
+    };
+    #[cfg(unix)]
+    let mut signals = {
+        use tokio::signal::unix::{signal, SignalKind};
+        let open = |kind| signal(kind).map_err(|e| format!("Nelze zachytit signál: {}", e));
+        (open(SignalKind::terminate())?, open(SignalKind::hangup())?, open(SignalKind::user_defined1())?, open(SignalKind::user_defined2())?)
+    };
+    let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
+
+    if !options.no_start {
+        tracker.start(events.clone()).await?;
+    }
+    tracing::info!("🚀 Tracker Agent běží bez UI");
+
+    loop {
+        #[cfg(unix)]
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = signals.0.recv() => break,
+            _ = shutdown_rx.recv() => break,
+            _ = signals.1.recv() => match apply_settings(&tracker, options.config.as_deref()).await {
+                Ok(()) => tracing::info!("nastavení znovu načteno"),
+                Err(e) => tracing::error!("nastavení nenačteno: {}", e),
+            },
+            _ = signals.2.recv() => {
+                if let Err(e) = execute(&tracker, &sink, ControlCommand::Start).await {
+                    tracing::warn!("start trackingu (SIGUSR1): {}", e);
+                }
+            }
+            _ = signals.3.recv() => {
+                if let Err(e) = execute(&tracker, &sink, ControlCommand::Stop).await {
+                    tracing::warn!("zastavení trackingu (SIGUSR2): {}", e);
+                }
+            }
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(control::serve_client(stream, tracker.clone(), sink.clone(), shutdown_tx.clone()));
+                }
+                Err(e) => tracing::warn!("spojení na socket selhalo: {}", e),
+            },
+        }
+        #[cfg(not(unix))]
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            _ = shutdown_rx.recv() => break,
+        }
+    }
+
+    tracing::info!("ukončuji agenta");
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, tracker.shutdown(&events)).await.is_err() {
+        tracing::warn!("zastavení trackingu při ukončení nestihlo doběhnout");
+    }
+    #[cfg(unix)]
+    let _ = std::fs::remove_file(&socket);
+    drop(shutdown_tx);
+    Ok(())
+}
+
+/// `tracker-agent-cli run` - stejná pipeline jako aplikace, jen bez okna
+fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+    resources::limit_ocr_threads();
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Nelze spustit runtime: {}", e);
+            return 1;
+        }
+    };
+    match runtime.block_on(serve(options)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Příkaz pro běžícího agenta, exit kód 0 = OK, 1 = agent příkaz odmítl, 3 = agent neběží
+fn control(command: &str, args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) if options.config.is_none() && !options.no_start => options,
+        Ok(_) => {
+            eprintln!("Přepínače --config a --no-start patří k 'run'\n{}", USAGE);
+            return 2;
+        }
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+    #[cfg(unix)]
+    {
+        control::send(&socket_path(&options), command)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (command, options);
+        eprintln!("Ovládání běžícího agenta je zatím jen na Linuxu a macOS");
+        2
+    }
+}
+
+/// Vstup `tracker-agent-cli` - agent bez UI, jeho ovládání, kontrola konfigurace
+/// a native messaging host rozšíření prohlížeče
+pub fn cli(args: &[String]) -> i32 {
+    if crate::browser::is_native_host_invocation(args) {
+        return crate::browser::run_native_host();
+    }
+    match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some(command) if ControlCommand::parse(command).is_some() => control(command, &args[1..]),
+        Some("config") => crate::config_check::cli(args),
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_protocol() {
+        assert_eq!(ControlCommand::parse("status\n"), Some(ControlCommand::Status));
+        assert_eq!(ControlCommand::parse("restart"), None);
+
+        let args: Vec<String> = ["--config", "/etc/tracker.json", "--no-start"].iter().map(|a| a.to_string()).collect();
+        let options = parse_options(&args).unwrap();
+        assert_eq!(options.config, Some(PathBuf::from("/etc/tracker.json")));
+        assert!(options.no_start && options.socket.is_none());
+        assert!(parse_options(&["--socket".to_string()]).is_err());
+
+        let sink = ConsoleSink::default();
+        sink.emit_value("tracking-update", serde_json::json!({"task": "Web"})).unwrap();
+        assert_eq!(sink.last_update.lock().unwrap().as_ref().unwrap()["task"], "Web");
+
+        let reply = ControlReply::from_result(Err("Tracker neběží".to_string()));
+        assert_eq!(serde_json::to_string(&reply).unwrap(), r#"{"ok":false,"error":"Tracker neběží"}"#);
+    }
+}
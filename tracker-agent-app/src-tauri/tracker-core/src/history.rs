@@ -0,0 +1,843 @@
+use crate::ai_usage::{AiCall, AiUsageDay};
+use crate::freelo::WorkReport;
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use crate::text_matcher::MatchSource;
+use chrono::{DateTime, Duration, Local, Months, NaiveDate, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Soubor s lokální historií segmentů v adresáři profilu
+const HISTORY_FILE: &str = "history.json";
+
+/// SQLite log rozhodnutí trackeru (jeden řádek za tick)
+pub const DECISIONS_DB: &str = "decisions.sqlite";
+
+/// Strop vrácených ticků - pár týdnů po 10 s by zahltilo UI
+pub const MAX_TICK_RECORDS: usize = 5000;
+
+/// Strop ticků pro vysvětlení mezery (týden při 10s intervalu)
+const MAX_GAP_TICKS: usize = 60_000;
+
+/// Kolik měsíců se drží jednotlivé ticky, starší se sloučí do denních souhrnů
+pub const DEFAULT_HISTORY_RETENTION_MONTHS: u32 = 12;
+
+/// Smazaný log rozhodnutí jde ještě tolik dní obnovit, pak ho údržba odstraní
+const DELETED_GRACE_DAYS: i64 = 7;
+
+/// Značka, že úvodní import z Freelo proběhl
+const IMPORT_MARKER_FILE: &str = "freelo_import.json";
+
+/// Kolik dní zpět stáhne úvodní import
+pub const INITIAL_IMPORT_DAYS: i64 = 90;
+
+/// Work reporty nemají čas začátku - importované segmenty řadíme za sebe od 9:00
+const IMPORT_DAY_START_HOUR: u32 = 9;
+
+/// Ukončený segment - lokální záznam je zdroj pravdy, Freelo se podle něj opravuje
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySegment {
+    /// UUID Freelo timeru (u lokálních segmentů offline-…)
+    pub id: String,
+    pub task_id: Option<i32>,
+    pub note: String,
+    pub started_at: DateTime<Local>,
+    pub stopped_at: DateTime<Local>,
+    pub source: Option<MatchSource>,
+    /// ID work reportu ve Freelo (doplní ověření záznamu), None = neznámé
+    pub freelo_report_id: Option<i32>,
+}
+
+impl HistorySegment {
+    pub fn minutes(&self) -> i64 {
+        (self.stopped_at - self.started_at).num_seconds().max(0) / 60
+    }
+
+    /// Segment po úpravě - nezadané hodnoty zůstávají
+    pub fn edited(&self, edit: &SegmentEdit) -> Result<HistorySegment, String> {
+        let mut segment = self.clone();
+        if let Some(task_id) = edit.task_id {
+            segment.task_id = Some(task_id);
+        }
+        if let Some(started_at) = edit.started_at {
+            segment.started_at = started_at;
+        }
+        if let Some(stopped_at) = edit.stopped_at {
+            segment.stopped_at = stopped_at;
+        }
+        if let Some(ref note) = edit.note {
+            segment.note = note.clone();
+        }
+
+        if segment.stopped_at <= segment.started_at {
+            return Err(format!("Segment {}: konec musí být po začátku", self.id));
+        }
+        Ok(segment)
+    }
+}
+
+/// Hromadná úprava segmentů
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SegmentEdit {
+    pub task_id: Option<i32>,
+    pub started_at: Option<DateTime<Local>>,
+    pub stopped_at: Option<DateTime<Local>>,
+    pub note: Option<String>,
+}
+
+/// Výsledek hromadné operace pro UI
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkResult {
+    pub changed: usize,
+    pub pushed: usize,
+    pub errors: Vec<String>,
+}
+
+pub fn load(profile: &str) -> Vec<HistorySegment> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(HISTORY_FILE)).unwrap_or_default()
+}
+
+pub fn save(profile: &str, segments: &[HistorySegment]) -> Result<(), String> {
+    profile_crypto::save_json(profile, &profile_dir(profile).join(HISTORY_FILE), &segments)
+}
+
+pub fn record(profile: &str, segment: HistorySegment) -> Result<(), String> {
+    let mut segments = load(profile);
+    segments.push(segment);
+    save(profile, &segments)
+}
+
+/// Propojí segment s work reportem nalezeným ve Freelo
+pub fn attach_report(profile: &str, id: &str, report_id: i32) -> Result<(), String> {
+    let mut segments = load(profile);
+    let Some(segment) = segments.iter_mut().find(|s| s.id == id) else {
+        return Ok(());
+    };
+    segment.freelo_report_id = Some(report_id);
+    save(profile, &segments)
+}
+
+/// Doplní work reporty z Freelo, které v historii ještě nejsou (podle ID reportu).
+/// Vrací počet nových segmentů.
+pub fn import_reports(segments: &mut Vec<HistorySegment>, reports: &[WorkReport]) -> usize {
+    let mut next_start: HashMap<NaiveDate, DateTime<Local>> = HashMap::new();
+    let mut imported = 0;
+
+    for report in reports {
+        let Some(date) = report.date else {
+            continue;
+        };
+        if report.minutes <= 0 || segments.iter().any(|s| s.freelo_report_id == Some(report.id)) {
+            continue;
+        }
+        let Some(day_start) = date
+            .and_hms_opt(IMPORT_DAY_START_HOUR, 0, 0)
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+        else {
+            continue;
+        };
+
+        let started_at = *next_start.entry(date).or_insert(day_start);
+        let stopped_at = started_at + Duration::minutes(report.minutes);
+        next_start.insert(date, stopped_at);
+
+        segments.push(HistorySegment {
+            id: format!("freelo-{}", report.id),
+            task_id: report.task_id,
+            note: report.note.clone(),
+            started_at,
+            stopped_at,
+            source: None,
+            freelo_report_id: Some(report.id),
+        });
+        imported += 1;
+    }
+
+    imported
+}
+
+pub fn initial_import_done(profile: &str) -> bool {
+    profile_dir(profile).join(IMPORT_MARKER_FILE).exists()
+}
+
+pub fn mark_initial_import(profile: &str) -> Result<(), String> {
+    profile_crypto::save_json(profile, &profile_dir(profile).join(IMPORT_MARKER_FILE), &Local::now())
+}
+
+/// Segmenty začínající v rozsahu dní (včetně), od nejnovějšího
+pub fn filter_by_date(segments: Vec<HistorySegment>, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<HistorySegment> {
+    let mut segments: Vec<HistorySegment> = segments
+        .into_iter()
+        .filter(|s| {
+            let day = s.started_at.date_naive();
+            from.is_none_or(|from| day >= from) && to.is_none_or(|to| day <= to)
+        })
+        .collect();
+    segments.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+    segments
+}
+
+/// Co tracker v ticku udělal s Freelo záznamem
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TickAction {
+    /// Tick přeskočen (důvod v `reason`)
+    Skipped,
+    Continued,
+    /// Kontext se mění, čeká se na stabilizaci
+    Waiting,
+    Started,
+    Switched,
+    /// Segment ukončen bez náhrady (důvod v `reason`)
+    Stopped,
+    /// Ruční volba má přednost před návrhem matcheru
+    HeldByManual,
+    Failed,
+}
+
+/// Jedno rozhodnutí trackeru pro audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickRecord {
+    /// Řádek v logu rozhodnutí, None = tick ještě není uložený
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub at: DateTime<Local>,
+    pub action: TickAction,
+    pub reason: Option<String>,
+    pub application: Option<String>,
+    pub ocr_chars: Option<usize>,
+    pub task_id: Option<i32>,
+    pub task_name: Option<String>,
+    pub confidence: Option<f32>,
+    pub source: Option<MatchSource>,
+    /// Freelo task (nebo general_work) a UUID timeru po ticku
+    pub freelo_task_id: Option<String>,
+    pub freelo_uuid: Option<String>,
+}
+
+impl TickRecord {
+    pub fn skipped(reason: &str) -> Self {
+        TickRecord {
+            id: None,
+            at: Local::now(),
+            action: TickAction::Skipped,
+            reason: Some(reason.to_string()),
+            application: None,
+            ocr_chars: None,
+            task_id: None,
+            task_name: None,
+            confidence: None,
+            source: None,
+            freelo_task_id: None,
+            freelo_uuid: None,
+        }
+    }
+}
+
+/// Enum jako text bez uvozovek ("ai", "held_by_manual")
+fn to_text<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok()?.as_str().map(str::to_string)
+}
+
+fn from_text<T: DeserializeOwned>(text: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(text.to_string())).ok()
+}
+
+fn init_decisions(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS ticks (
+            id INTEGER PRIMARY KEY,
+            at_ms INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            reason TEXT,
+            application TEXT,
+            ocr_chars INTEGER,
+            task_id INTEGER,
+            task_name TEXT,
+            confidence REAL,
+            source TEXT,
+            freelo_task_id TEXT,
+            freelo_uuid TEXT,
+            deleted_at_ms INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS ticks_at ON ticks (at_ms);
+        CREATE TABLE IF NOT EXISTS tick_days (
+            day TEXT NOT NULL,
+            task_id INTEGER,
+            task_name TEXT,
+            ticks INTEGER NOT NULL,
+            skipped INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS tick_days_day ON tick_days (day);
+        CREATE TABLE IF NOT EXISTS ai_usage (
+            day TEXT NOT NULL,
+            model TEXT NOT NULL,
+            calls INTEGER NOT NULL,
+            prompt_tokens INTEGER NOT NULL,
+            completion_tokens INTEGER NOT NULL,
+            cost_usd REAL NOT NULL,
+            PRIMARY KEY (day, model)
+        );",
+    )
+    .map_err(|e| format!("Nelze připravit historii rozhodnutí: {}", e))?;
+
+    // Databáze ze starších verzí nemají sloupec pro měkké smazání
+    let has_deleted = conn
+        .prepare("SELECT 1 FROM pragma_table_info('ticks') WHERE name = 'deleted_at_ms'")
+        .and_then(|mut stmt| stmt.exists([]))
+        .map_err(|e| format!("Nelze připravit historii rozhodnutí: {}", e))?;
+    if !has_deleted {
+        conn.execute("ALTER TABLE ticks ADD COLUMN deleted_at_ms INTEGER", [])
+            .map_err(|e| format!("Nelze připravit historii rozhodnutí: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Otevře databázi šifrovanou SQLCipherem (`key` ve tvaru x'hex')
+fn open_keyed(path: &Path, key: &str) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| format!("Nelze otevřít {:?}: {}", path, e))?;
+    conn.execute_batch(&format!("PRAGMA key = \"{}\";", key))
+        .map_err(|e| format!("Nelze odemknout {:?}: {}", path, e))?;
+    Ok(conn)
+}
+
+/// Zašifruje nešifrovanou databázi ze starší verze (kopie přes `sqlcipher_export`)
+fn encrypt_legacy_db(path: &Path, key: &str) -> Result<(), String> {
+    let sql_err = |e: rusqlite::Error| format!("Nelze zašifrovat {:?}: {}", path, e);
+    let tmp_path = path.with_extension("sqlite.tmp");
+    let _ = std::fs::remove_file(&tmp_path);
+
+    let plain = Connection::open(path).map_err(sql_err)?;
+    plain
+        .execute("ATTACH DATABASE ?1 AS encrypted KEY ?2", params![tmp_path.to_string_lossy(), key])
+        .map_err(sql_err)?;
+    plain.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(())).map_err(sql_err)?;
+    plain.execute("DETACH DATABASE encrypted", []).map_err(sql_err)?;
+    drop(plain);
+
+    std::fs::rename(&tmp_path, path).map_err(|e| format!("Nelze zašifrovat {:?}: {}", path, e))
+}
+
+fn open_encrypted(path: &Path, key: &str) -> Result<Connection, String> {
+    let conn = open_keyed(path, key)?;
+    // Nešifrovaný soubor (nebo jiný klíč) se projeví až při prvním čtení
+    if conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())).is_ok() {
+        return Ok(conn);
+    }
+    drop(conn);
+    encrypt_legacy_db(path, key)?;
+    open_keyed(path, key)
+}
+
+/// Nešifrovaná kopie logu rozhodnutí (export profilu)
+pub fn export_decisions(profile: &str, target: &Path) -> Result<(), String> {
+    let sql_err = |e: rusqlite::Error| format!("Nelze exportovat {:?}: {}", target, e);
+    let _ = std::fs::remove_file(target);
+
+    let conn = open_decisions(profile)?;
+    conn.execute("ATTACH DATABASE ?1 AS plain KEY ''", params![target.to_string_lossy()])
+        .map_err(sql_err)?;
+    conn.query_row("SELECT sqlcipher_export('plain')", [], |_| Ok(())).map_err(sql_err)?;
+    conn.execute("DETACH DATABASE plain", []).map_err(sql_err)?;
+    Ok(())
+}
+
+fn open_decisions(profile: &str) -> Result<Connection, String> {
+    let path = profile_dir(profile).join(DECISIONS_DB);
+    let conn = open_encrypted(&path, &profile_crypto::sqlite_key(profile)?)?;
+    init_decisions(&conn)?;
+    Ok(conn)
+}
+
+fn insert_tick(conn: &Connection, record: &TickRecord) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO ticks (at_ms, action, reason, application, ocr_chars, task_id, task_name,
+                            confidence, source, freelo_task_id, freelo_uuid)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            record.at.timestamp_millis(),
+            to_text(&record.action),
+            record.reason,
+            record.application,
+            record.ocr_chars.map(|c| c as i64),
+            record.task_id,
+            record.task_name,
+            record.confidence.map(f64::from),
+            record.source.as_ref().and_then(to_text),
+            record.freelo_task_id,
+            record.freelo_uuid,
+        ],
+    )
+    .map(|_| ())
+    .map_err(|e| format!("Nelze zapsat tick do historie: {}", e))
+}
+
+/// Ticky v rozsahu časů [from, to) v ms, od nejnovějšího
+fn query_ticks(conn: &Connection, from_ms: i64, to_ms: i64, limit: usize) -> Result<Vec<TickRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT at_ms, action, reason, application, ocr_chars, task_id, task_name,
+                    confidence, source, freelo_task_id, freelo_uuid, id
+             FROM ticks WHERE at_ms >= ?1 AND at_ms < ?2 AND deleted_at_ms IS NULL
+             ORDER BY at_ms DESC LIMIT ?3",
+        )
+        .map_err(|e| format!("Chyba dotazu na historii: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from_ms, to_ms, limit as i64], tick_from_row)
+        .map_err(|e| format!("Chyba dotazu na historii: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Chyba čtení historie: {}", e))
+}
+
+/// Tick z řádku se sloupci v pořadí jako v `query_ticks`
+fn tick_from_row(row: &rusqlite::Row) -> rusqlite::Result<TickRecord> {
+    let at_ms: i64 = row.get(0)?;
+    let action: String = row.get(1)?;
+    let source: Option<String> = row.get(8)?;
+    let ocr_chars: Option<i64> = row.get(4)?;
+    let confidence: Option<f64> = row.get(7)?;
+    Ok(TickRecord {
+        id: row.get(11)?,
+        at: Local.timestamp_millis_opt(at_ms).single().unwrap_or_default(),
+        action: from_text(&action).unwrap_or(TickAction::Skipped),
+        reason: row.get(2)?,
+        application: row.get(3)?,
+        ocr_chars: ocr_chars.map(|c| c as usize),
+        task_id: row.get(5)?,
+        task_name: row.get(6)?,
+        confidence: confidence.map(|c| c as f32),
+        source: source.as_deref().and_then(from_text),
+        freelo_task_id: row.get(9)?,
+        freelo_uuid: row.get(10)?,
+    })
+}
+
+/// Jeden tick podle ID (oprava z UI), None = neexistuje nebo je smazaný
+pub fn tick_by_id(profile: &str, id: i64) -> Result<Option<TickRecord>, String> {
+    open_decisions(profile)?
+        .query_row(
+            "SELECT at_ms, action, reason, application, ocr_chars, task_id, task_name,
+                    confidence, source, freelo_task_id, freelo_uuid, id
+             FROM ticks WHERE id = ?1 AND deleted_at_ms IS NULL",
+            params![id],
+            tick_from_row,
+        )
+        .optional()
+        .map_err(|e| format!("Chyba čtení historie: {}", e))
+}
+
+pub fn record_tick(profile: &str, record: &TickRecord) -> Result<(), String> {
+    insert_tick(&open_decisions(profile)?, record)
+}
+
+/// Rozhodnutí za rozsah dní (včetně), od nejnovějšího, nejvýše `MAX_TICK_RECORDS`
+pub fn ticks_in_range(profile: &str, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Vec<TickRecord>, String> {
+    let day_start_ms = |day: NaiveDate| {
+        day.and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+            .map(|t| t.timestamp_millis())
+    };
+    let from_ms = from.and_then(day_start_ms).unwrap_or(i64::MIN);
+    let to_ms = to
+        .and_then(|day| day.succ_opt())
+        .and_then(day_start_ms)
+        .unwrap_or(i64::MAX);
+
+    query_ticks(&open_decisions(profile)?, from_ms, to_ms, MAX_TICK_RECORDS)
+}
+
+/// Rozhodnutí v časovém rozsahu [from, to), od nejnovějšího (vysvětlení mezer - bez limitu dne)
+pub fn ticks_between(profile: &str, from: DateTime<Local>, to: DateTime<Local>) -> Result<Vec<TickRecord>, String> {
+    query_ticks(&open_decisions(profile)?, from.timestamp_millis(), to.timestamp_millis(), MAX_GAP_TICKS)
+}
+
+/// Smaže log rozhodnutí (segmenty zůstávají), vrací počet smazaných ticků.
+/// Ticky se jen označí - do údržby po `DELETED_GRACE_DAYS` jdou obnovit.
+pub fn clear_ticks(profile: &str) -> Result<usize, String> {
+    open_decisions(profile)?
+        .execute(
+            "UPDATE ticks SET deleted_at_ms = ?1 WHERE deleted_at_ms IS NULL",
+            params![Local::now().timestamp_millis()],
+        )
+        .map_err(|e| format!("Nelze smazat historii: {}", e))
+}
+
+/// Vrátí smazaný log rozhodnutí, vrací počet obnovených ticků
+pub fn restore_ticks(profile: &str) -> Result<usize, String> {
+    open_decisions(profile)?
+        .execute("UPDATE ticks SET deleted_at_ms = NULL WHERE deleted_at_ms IS NOT NULL", [])
+        .map_err(|e| format!("Nelze obnovit historii: {}", e))
+}
+
+/// Denní souhrn ticků jednoho tasku po sloučení starých záznamů
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TickDay {
+    pub day: NaiveDate,
+    pub task_id: Option<i32>,
+    pub task_name: Option<String>,
+    pub ticks: usize,
+    /// Z toho přeskočené ticky (nečinnost, pauza...)
+    pub skipped: usize,
+}
+
+/// Výsledek údržby historie
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetentionResult {
+    /// Ticky sloučené do denních souhrnů a smazané
+    pub aggregated_ticks: usize,
+    /// Dříve smazané ticky odstraněné natrvalo
+    pub purged_ticks: usize,
+}
+
+impl RetentionResult {
+    pub fn is_empty(&self) -> bool {
+        self.aggregated_ticks == 0 && self.purged_ticks == 0
+    }
+}
+
+/// Ticky starší než `cutoff_ms` sloučí po dnech a tascích do `tick_days` a smaže,
+/// měkce smazané před `deleted_before_ms` odstraní natrvalo
+fn apply_retention(conn: &mut Connection, cutoff_ms: i64, deleted_before_ms: i64) -> Result<RetentionResult, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Chyba údržby historie: {}", e))?;
+    let sql_err = |e: rusqlite::Error| format!("Chyba údržby historie: {}", e);
+
+    let purged_ticks = tx
+        .execute("DELETE FROM ticks WHERE deleted_at_ms < ?1", params![deleted_before_ms])
+        .map_err(sql_err)?;
+    tx.execute(
+        "INSERT INTO tick_days (day, task_id, task_name, ticks, skipped)
+         SELECT date(at_ms / 1000, 'unixepoch', 'localtime'), task_id, MAX(task_name),
+                COUNT(*), SUM(action = 'skipped')
+         FROM ticks WHERE at_ms < ?1 AND deleted_at_ms IS NULL
+         GROUP BY 1, task_id",
+        params![cutoff_ms],
+    )
+    .map_err(sql_err)?;
+    let aggregated_ticks = tx
+        .execute("DELETE FROM ticks WHERE at_ms < ?1 AND deleted_at_ms IS NULL", params![cutoff_ms])
+        .map_err(sql_err)?;
+    tx.commit().map_err(sql_err)?;
+
+    Ok(RetentionResult {
+        aggregated_ticks,
+        purged_ticks,
+    })
+}
+
+/// Údržba logu rozhodnutí - ticky starší než `retention_months` (0 = navždy) se sloučí
+/// do denních souhrnů, smazané po lhůtě se odstraní a databáze se zmenší (VACUUM)
+pub fn enforce_retention(profile: &str, retention_months: u32, now: DateTime<Local>) -> Result<RetentionResult, String> {
+    let mut conn = open_decisions(profile)?;
+    // Hranice na začátku dne, aby se den nikdy nesloučil jen zčásti
+    let cutoff_ms = match retention_months {
+        0 => i64::MIN,
+        months => now
+            .date_naive()
+            .checked_sub_months(Months::new(months))
+            .and_then(|day| day.and_hms_opt(0, 0, 0))
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+            .map(|t| t.timestamp_millis())
+            .unwrap_or(i64::MIN),
+    };
+    let deleted_before_ms = (now - Duration::days(DELETED_GRACE_DAYS)).timestamp_millis();
+
+    let result = apply_retention(&mut conn, cutoff_ms, deleted_before_ms)?;
+    if !result.is_empty() {
+        conn.execute_batch("VACUUM")
+            .map_err(|e| format!("Nelze zmenšit historii: {}", e))?;
+    }
+    Ok(result)
+}
+
+fn query_tick_days(conn: &Connection, from: &str, to: &str) -> Result<Vec<TickDay>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT day, task_id, task_name, ticks, skipped FROM tick_days
+             WHERE day >= ?1 AND day <= ?2 ORDER BY day DESC, ticks DESC",
+        )
+        .map_err(|e| format!("Chyba dotazu na historii: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            let day: String = row.get(0)?;
+            let ticks: i64 = row.get(3)?;
+            let skipped: i64 = row.get(4)?;
+            Ok(TickDay {
+                day: NaiveDate::parse_from_str(&day, "%Y-%m-%d").unwrap_or_default(),
+                task_id: row.get(1)?,
+                task_name: row.get(2)?,
+                ticks: ticks as usize,
+                skipped: skipped as usize,
+            })
+        })
+        .map_err(|e| format!("Chyba dotazu na historii: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Chyba čtení historie: {}", e))
+}
+
+/// Denní souhrny sloučených ticků za rozsah dní (včetně), od nejnovějšího
+pub fn tick_days_in_range(profile: &str, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Vec<TickDay>, String> {
+    let format = |day: Option<NaiveDate>, default: &str| day.map(|d| d.to_string()).unwrap_or(default.to_string());
+    query_tick_days(&open_decisions(profile)?, &format(from, "0000-01-01"), &format(to, "9999-12-31"))
+}
+
+fn insert_ai_usage(conn: &mut Connection, calls: &[AiCall]) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| format!("Nelze zapsat spotřebu AI: {}", e))?;
+    for call in calls {
+        tx.execute(
+            "INSERT INTO ai_usage (day, model, calls, prompt_tokens, completion_tokens, cost_usd)
+             VALUES (?1, ?2, 1, ?3, ?4, ?5)
+             ON CONFLICT (day, model) DO UPDATE SET
+                calls = calls + 1,
+                prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                completion_tokens = completion_tokens + excluded.completion_tokens,
+                cost_usd = cost_usd + excluded.cost_usd",
+            params![
+                call.day.to_string(),
+                call.model,
+                call.prompt_tokens as i64,
+                call.completion_tokens as i64,
+                call.cost_usd,
+            ],
+        )
+        .map_err(|e| format!("Nelze zapsat spotřebu AI: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("Nelze zapsat spotřebu AI: {}", e))
+}
+
+fn query_ai_usage(conn: &Connection, from: &str, to: &str) -> Result<Vec<AiUsageDay>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT day, model, calls, prompt_tokens, completion_tokens, cost_usd FROM ai_usage
+             WHERE day >= ?1 AND day <= ?2 ORDER BY day DESC, cost_usd DESC",
+        )
+        .map_err(|e| format!("Chyba dotazu na spotřebu AI: {}", e))?;
+
+    let rows = stmt
+        .query_map(params![from, to], |row| {
+            let day: String = row.get(0)?;
+            let calls: i64 = row.get(2)?;
+            let prompt_tokens: i64 = row.get(3)?;
+            let completion_tokens: i64 = row.get(4)?;
+            Ok(AiUsageDay {
+                day: NaiveDate::parse_from_str(&day, "%Y-%m-%d").unwrap_or_default(),
+                model: row.get(1)?,
+                calls: calls as u64,
+                prompt_tokens: prompt_tokens as u64,
+                completion_tokens: completion_tokens as u64,
+                cost_usd: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Chyba dotazu na spotřebu AI: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Chyba čtení spotřeby AI: {}", e))
+}
+
+/// Přičte volání AI k denním součtům (po dnech a modelech)
+pub fn record_ai_usage(profile: &str, calls: &[AiCall]) -> Result<(), String> {
+    insert_ai_usage(&mut open_decisions(profile)?, calls)
+}
+
+/// Spotřeba AI za rozsah dní (včetně), od nejnovějšího
+pub fn ai_usage_in_range(profile: &str, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Vec<AiUsageDay>, String> {
+    let format = |day: Option<NaiveDate>, default: &str| day.map(|d| d.to_string()).unwrap_or(default.to_string());
+    query_ai_usage(&open_decisions(profile)?, &format(from, "0000-01-01"), &format(to, "9999-12-31"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(id: &str, day: u32, hour: u32) -> HistorySegment {
+        HistorySegment {
+            id: id.to_string(),
+            task_id: Some(1),
+            note: "Editace kódu".to_string(),
+            started_at: Local.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap(),
+            stopped_at: Local.with_ymd_and_hms(2025, 3, day, hour + 1, 30, 0).unwrap(),
+            source: Some(MatchSource::Text),
+            freelo_report_id: None,
+        }
+    }
+
+    #[test]
+    fn test_edit_segment() {
+        let original = segment("a", 12, 9);
+        let edit = SegmentEdit {
+            task_id: Some(7),
+            note: Some("Code review".to_string()),
+            ..Default::default()
+        };
+
+        let edited = original.edited(&edit).unwrap();
+        assert_eq!(edited.task_id, Some(7));
+        assert_eq!(edited.note, "Code review");
+        assert_eq!(edited.minutes(), 90);
+
+        let invalid = SegmentEdit {
+            stopped_at: Some(original.started_at),
+            ..Default::default()
+        };
+        assert!(original.edited(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_import_reports() {
+        let mut segments = vec![HistorySegment { freelo_report_id: Some(1), ..segment("a", 12, 9) }];
+        let report = |id, minutes, day| WorkReport {
+            id,
+            task_id: Some(5),
+            task_name: None,
+            minutes,
+            note: "Import".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 3, day),
+        };
+        let reports = vec![report(1, 90, 12), report(2, 30, 11), report(3, 45, 11), report(4, 0, 11)];
+
+        assert_eq!(import_reports(&mut segments, &reports), 2);
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[1].started_at.format("%H:%M").to_string(), "09:00");
+        assert_eq!(segments[2].started_at, segments[1].stopped_at);
+        assert_eq!(segments[2].minutes(), 45);
+
+        // Opakovaný import nic nezdvojí
+        assert_eq!(import_reports(&mut segments, &reports), 0);
+    }
+
+    #[test]
+    fn test_tick_log_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_decisions(&conn).unwrap();
+
+        let at = |h| Local.with_ymd_and_hms(2025, 3, 12, h, 0, 0).unwrap();
+        let skipped = TickRecord { at: at(9), ..TickRecord::skipped("idle") };
+        let started = TickRecord {
+            id: None,
+            at: at(10),
+            action: TickAction::Started,
+            reason: None,
+            application: Some("Code".to_string()),
+            ocr_chars: Some(1200),
+            task_id: Some(42),
+            task_name: Some("API".to_string()),
+            confidence: Some(0.75),
+            source: Some(MatchSource::Ai),
+            freelo_task_id: Some("42".to_string()),
+            freelo_uuid: Some("abc".to_string()),
+        };
+        insert_tick(&conn, &skipped).unwrap();
+        insert_tick(&conn, &started).unwrap();
+
+        let ticks = query_ticks(&conn, i64::MIN, i64::MAX, 10).unwrap();
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].action, TickAction::Started);
+        assert_eq!(ticks[0].source, Some(MatchSource::Ai));
+        assert_eq!(ticks[0].ocr_chars, Some(1200));
+        assert!(ticks[0].id.is_some_and(|id| id != ticks[1].id.unwrap()));
+        assert_eq!(ticks[1].reason.as_deref(), Some("idle"));
+
+        let only_first = query_ticks(&conn, at(9).timestamp_millis(), at(10).timestamp_millis(), 10).unwrap();
+        assert_eq!(only_first.len(), 1);
+    }
+
+    #[test]
+    fn test_legacy_db_gets_encrypted() {
+        let path = std::env::temp_dir().join(format!("tracker-decisions-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let key = format!("x'{}'", "ab".repeat(32));
+
+        let plain = Connection::open(&path).unwrap();
+        init_decisions(&plain).unwrap();
+        insert_tick(&plain, &TickRecord::skipped("idle")).unwrap();
+        drop(plain);
+
+        let conn = open_encrypted(&path, &key).unwrap();
+        assert_eq!(query_ticks(&conn, i64::MIN, i64::MAX, 10).unwrap().len(), 1);
+        drop(conn);
+
+        // Bez klíče (nebo s jiným) už soubor nejde přečíst
+        let plain = Connection::open(&path).unwrap();
+        assert!(plain.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())).is_err());
+        let other = open_keyed(&path, &format!("x'{}'", "cd".repeat(32))).unwrap();
+        assert!(other.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_retention_aggregates_and_purges() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_decisions(&conn).unwrap();
+
+        let at = |day, h| Local.with_ymd_and_hms(2025, 3, day, h, 0, 0).unwrap();
+        let work = |day, h| TickRecord {
+            at: at(day, h),
+            action: TickAction::Continued,
+            task_id: Some(42),
+            task_name: Some("API".to_string()),
+            ..TickRecord::skipped("")
+        };
+        for record in [work(10, 9), work(10, 10), TickRecord { at: at(10, 11), ..TickRecord::skipped("idle") }, work(12, 9)] {
+            insert_tick(&conn, &record).unwrap();
+        }
+        let deleted_at = at(12, 10).timestamp_millis();
+        conn.execute("UPDATE ticks SET deleted_at_ms = ?1 WHERE at_ms = ?2", params![deleted_at, at(12, 9).timestamp_millis()])
+            .unwrap();
+        assert_eq!(query_ticks(&conn, i64::MIN, i64::MAX, 10).unwrap().len(), 3);
+
+        // Den 10. se sloučí, smazaný tick z 12. je ještě ve lhůtě
+        let result = apply_retention(&mut conn, at(11, 0).timestamp_millis(), at(12, 0).timestamp_millis()).unwrap();
+        assert_eq!(result, RetentionResult { aggregated_ticks: 3, purged_ticks: 0 });
+        assert!(query_ticks(&conn, i64::MIN, i64::MAX, 10).unwrap().is_empty());
+
+        let days = query_tick_days(&conn, "2025-03-01", "2025-03-31").unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].task_id, days[0].ticks, days[0].skipped), (Some(42), 2, 0));
+        assert_eq!((days[1].task_id, days[1].skipped), (None, 1));
+        assert_eq!(days[0].day, NaiveDate::from_ymd_opt(2025, 3, 10).unwrap());
+
+        let result = apply_retention(&mut conn, at(11, 0).timestamp_millis(), at(13, 0).timestamp_millis()).unwrap();
+        assert_eq!(result, RetentionResult { aggregated_ticks: 0, purged_ticks: 1 });
+    }
+
+    #[test]
+    fn test_ai_usage_daily_totals() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_decisions(&conn).unwrap();
+
+        let call = |day, model: &str, cost| AiCall {
+            day: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            model: model.to_string(),
+            prompt_tokens: 1000,
+            completion_tokens: 50,
+            cost_usd: cost,
+        };
+        insert_ai_usage(&mut conn, &[call(12, "flash", 0.001), call(12, "flash", 0.002), call(12, "lite", 0.0005)]).unwrap();
+        insert_ai_usage(&mut conn, &[call(13, "flash", 0.001)]).unwrap();
+
+        let days = query_ai_usage(&conn, "2025-03-12", "2025-03-12").unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].model.as_str(), days[0].calls, days[0].prompt_tokens), ("flash", 2, 2000));
+        assert!((days[0].cost_usd - 0.003).abs() < 1e-12);
+        assert_eq!(query_ai_usage(&conn, "2025-03-01", "2025-03-31").unwrap()[0].day, NaiveDate::from_ymd_opt(2025, 3, 13).unwrap());
+    }
+
+    #[test]
+    fn test_filter_by_date() {
+        let segments = vec![segment("a", 11, 9), segment("b", 12, 9), segment("c", 12, 14), segment("d", 13, 9)];
+        let day = NaiveDate::from_ymd_opt(2025, 3, 12);
+
+        let ids: Vec<String> = filter_by_date(segments.clone(), day, day).into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["c", "b"]);
+        assert_eq!(filter_by_date(segments, None, None).len(), 4);
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Akce dostupné přes globální klávesovou zkratku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Spustí tracking, nebo ho zastaví, pokud běží
+    ToggleTracking,
+    /// Okamžitá analýza obrazovky bez čekání na tick
+    Rescan,
+    /// Otevře okno s rychlým výběrem tasku
+    QuickSwitch,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 3] = [HotkeyAction::ToggleTracking, HotkeyAction::Rescan, HotkeyAction::QuickSwitch];
+
+    pub fn default_binding(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleTracking => "CommandOrControl+Shift+T",
+            HotkeyAction::Rescan => "CommandOrControl+Shift+R",
+            HotkeyAction::QuickSwitch => "CommandOrControl+Shift+K",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleTracking => "spuštění/zastavení",
+            HotkeyAction::Rescan => "okamžitá analýza",
+            HotkeyAction::QuickSwitch => "výběr tasku",
+        }
+    }
+}
+
+/// Zkratky z nastavení ("CommandOrControl+Shift+T"), None = výchozí, prázdné = vypnuto
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyBindings {
+    pub toggle_tracking: Option<String>,
+    pub rescan: Option<String>,
+    pub quick_switch: Option<String>,
+}
+
+impl HotkeyBindings {
+    pub fn binding(&self, action: HotkeyAction) -> Option<&str> {
+        let custom = match action {
+            HotkeyAction::ToggleTracking => &self.toggle_tracking,
+            HotkeyAction::Rescan => &self.rescan,
+            HotkeyAction::QuickSwitch => &self.quick_switch,
+        };
+        match custom.as_deref().map(str::trim) {
+            Some("") => None,
+            Some(custom) => Some(custom),
+            None => Some(action.default_binding()),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::freelo::ActiveTracking;
+use chrono::{DateTime, Local, NaiveTime};
+use std::time::Duration;
+use tracing::info;
+use user_idle::UserIdle;
+
+/// Okno kolem poledne, ve kterém se dlouhá nečinnost považuje za oběd
+const LUNCH_WINDOW_START: (u32, u32) = (11, 0);
+const LUNCH_WINDOW_END: (u32, u32) = (14, 30);
+
+/// Minimální délka nečinnosti, aby šlo o pauzu na oběd
+pub const LUNCH_BREAK_MIN_IDLE: Duration = Duration::from_secs(15 * 60);
+
+/// Pod touto hodnotou nečinnosti považujeme uživatele za zpět u počítače
+pub const ACTIVITY_RESUMED_IDLE: Duration = Duration::from_secs(60);
+
+/// Výchozí doba nečinnosti, po které se Freelo timer pozastaví
+pub const DEFAULT_IDLE_THRESHOLD_MINUTES: u64 = 10;
+
+/// Co udělat s trackingem podle aktuální nečinnosti
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdleAction {
+    Stay,
+    Pause,
+    Resume,
+}
+
+/// Pozastavený tracking (nečinnost nebo ruční pauza) - po návratu se naváže na přerušený segment
+#[derive(Debug, Clone)]
+pub struct IdlePause {
+    pub since: DateTime<Local>,
+    pub interrupted: Option<ActiveTracking>,
+}
+
+/// Rozhodnutí o pauze kvůli nečinnosti (nulový práh = vypnuto)
+pub fn idle_action(paused: bool, idle: Duration, threshold: Duration) -> IdleAction {
+    if paused {
+        if idle < ACTIVITY_RESUMED_IDLE {
+            IdleAction::Resume
+        } else {
+            IdleAction::Stay
+        }
+    } else if !threshold.is_zero() && idle >= threshold {
+        IdleAction::Pause
+    } else {
+        IdleAction::Stay
+    }
+}
+
+/// Doba od posledního vstupu (klávesnice/myš), None pokud ji platforma neumí zjistit
+pub fn idle_duration() -> Option<Duration> {
+    match UserIdle::get_time() {
+        Ok(idle) => Some(idle.duration()),
+        Err(e) => {
+            info!("⚠️  Nelze zjistit dobu nečinnosti: {}", e.cause);
+            None
+        }
+    }
+}
+
+/// Zda nečinnost začínající v daný čas spadá do obědového okna
+pub fn is_lunch_time(idle_start: DateTime<Local>) -> bool {
+    let start = NaiveTime::from_hms_opt(LUNCH_WINDOW_START.0, LUNCH_WINDOW_START.1, 0).unwrap_or_default();
+    let end = NaiveTime::from_hms_opt(LUNCH_WINDOW_END.0, LUNCH_WINDOW_END.1, 0).unwrap_or_default();
+    let time = idle_start.time();
+
+    time >= start && time <= end
+}
+
+/// Nejdelší úsek bez vstupu od posledního známého vstupu.
+/// Zahrnuje jak probíhající nečinnost, tak mezeru, kdy počítač spal
+/// (po probuzení je `idle` malé, ale předchozí vstup je dávno).
+pub fn longest_inactivity(last_input_at: DateTime<Local>, now: DateTime<Local>, idle: Duration) -> Duration {
+    let idle = chrono::Duration::from_std(idle).unwrap_or_default();
+    let latest_input = now - idle;
+    let gap = (latest_input - last_input_at).max(idle);
+
+    gap.to_std().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_is_lunch_time() {
+        let at = |h, m| Local.with_ymd_and_hms(2025, 3, 12, h, m, 0).unwrap();
+
+        assert!(is_lunch_time(at(12, 5)));
+        assert!(is_lunch_time(at(11, 0)));
+        assert!(!is_lunch_time(at(10, 59)));
+        assert!(!is_lunch_time(at(16, 0)));
+    }
+
+    #[test]
+    fn test_idle_action() {
+        let min = |m: u64| Duration::from_secs(m * 60);
+
+        assert_eq!(idle_action(false, min(3), min(10)), IdleAction::Stay);
+        assert_eq!(idle_action(false, min(10), min(10)), IdleAction::Pause);
+        assert_eq!(idle_action(false, min(60), Duration::ZERO), IdleAction::Stay);
+        assert_eq!(idle_action(true, min(5), min(10)), IdleAction::Stay);
+        assert_eq!(idle_action(true, Duration::from_secs(2), min(10)), IdleAction::Resume);
+    }
+
+    #[test]
+    fn test_longest_inactivity() {
+        let at = |d, h| Local.with_ymd_and_hms(2025, 3, d, h, 0, 0).unwrap();
+
+        // Stále nečinný od pátku 17:00
+        let span = longest_inactivity(at(14, 17), at(17, 8), Duration::from_secs(63 * 3600));
+        assert_eq!(span, Duration::from_secs(63 * 3600));
+
+        // Po probuzení ze spánku: idle malé, ale mezera mezi vstupy velká
+        let span = longest_inactivity(at(14, 17), at(17, 8), Duration::from_secs(30));
+        assert!(span > Duration::from_secs(62 * 3600));
+
+        // Běžná práce
+        let span = longest_inactivity(at(17, 7), at(17, 8), Duration::from_secs(5));
+        assert!(span < Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,198 @@
+use serde::de::DeserializeOwned;
+
+/// Odstraní markdown code blok (```json ... ```) kolem odpovědi
+fn strip_code_fence(raw: &str) -> &str {
+    let trimmed = raw.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+
+    // Přeskoč jazyk za otevíracími backticky (```json)
+    let rest = rest.split_once('\n').map(|(_, body)| body).unwrap_or(rest);
+    rest.trim_end().strip_suffix("```").unwrap_or(rest).trim()
+}
+
+/// Odstraní čárku před uzavírací závorkou
+fn remove_trailing_comma(out: &mut String) {
+    let trimmed_len = out.trim_end().len();
+    if out[..trimmed_len].ends_with(',') {
+        out.truncate(trimmed_len - 1);
+    }
+}
+
+/// Doplní useknutý literál (tru -> true, nul -> null)
+fn complete_literal(out: &mut String) {
+    let run_len = out
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .count();
+    if run_len == 0 {
+        return;
+    }
+
+    let run = out[out.len() - run_len..].to_string();
+    for literal in ["true", "false", "null"] {
+        if literal.starts_with(&run) {
+            out.truncate(out.len() - run_len);
+            out.push_str(literal);
+            return;
+        }
+    }
+}
+
+/// Opraví typické chyby v JSON od AI modelu:
+/// markdown blok a text kolem, jednoduché uvozovky, čárky před závorkou,
+/// useknutý konec (neuzavřený string, chybějící hodnota, neuzavřené závorky).
+pub fn repair_json(raw: &str) -> String {
+    let text = strip_code_fence(raw);
+    let Some(start) = text.find(['{', '[']) else {
+        return text.to_string();
+    };
+    let text = &text[start..];
+
+    let mut out = String::with_capacity(text.len() + 8);
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string: Option<char> = None;
+    let mut escaped = false;
+    let mut last_string_is_key = false;
+
+    for c in text.chars() {
+        if let Some(quote) = in_string {
+            if escaped {
+                escaped = false;
+                if c == '\'' {
+                    // \' není platný JSON escape
+                    out.pop();
+                }
+                out.push(c);
+                continue;
+            }
+
+            match c {
+                '\\' => {
+                    out.push(c);
+                    escaped = true;
+                }
+                c if c == quote => {
+                    out.push('"');
+                    in_string = None;
+                }
+                '"' => out.push_str("\\\""),
+                '\n' => out.push_str("\\n"),
+                _ => out.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' | '\'' => {
+                let previous = out.trim_end().chars().last();
+                last_string_is_key =
+                    stack.last() == Some(&'}') && matches!(previous, Some('{') | Some(','));
+                in_string = Some(c);
+                out.push('"');
+            }
+            '{' => {
+                stack.push('}');
+                out.push(c);
+            }
+            '[' => {
+                stack.push(']');
+                out.push(c);
+            }
+            '}' | ']' => {
+                remove_trailing_comma(&mut out);
+                if stack.last() == Some(&c) {
+                    stack.pop();
+                }
+                out.push(c);
+                if stack.is_empty() {
+                    // Text za kořenovým objektem ignorujeme
+                    break;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    if stack.is_empty() {
+        return out;
+    }
+
+    // Useknutá odpověď - uzavřít string, doplnit hodnotu a závorky
+    if in_string.is_some() {
+        if escaped {
+            out.pop();
+        }
+        out.push('"');
+    }
+
+    let trimmed_len = out.trim_end().len();
+    out.truncate(trimmed_len);
+    complete_literal(&mut out);
+
+    if out.ends_with(',') {
+        out.pop();
+    } else if out.ends_with(':') {
+        out.push_str(" null");
+    } else if out.ends_with('"') && last_string_is_key {
+        out.push_str(": null");
+    }
+
+    while let Some(closing) = stack.pop() {
+        remove_trailing_comma(&mut out);
+        out.push(closing);
+    }
+
+    out
+}
+
+/// Tolerantní parse - nejdřív striktně, pak po opravě
+pub fn parse_lenient<T: DeserializeOwned>(raw: &str) -> Result<T, String> {
+    let stripped = strip_code_fence(raw);
+    if let Ok(value) = serde_json::from_str(stripped) {
+        return Ok(value);
+    }
+
+    let repaired = repair_json(raw);
+    serde_json::from_str(&repaired).map_err(|e| format!("{} (po opravě: {})", e, repaired))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn parse(raw: &str) -> Value {
+        parse_lenient(raw).unwrap()
+    }
+
+    #[test]
+    fn test_trailing_commas_and_fence() {
+        let raw = "```json\n{\"task_id\": 1, \"tags\": [\"a\", \"b\",],}\n```";
+        assert_eq!(parse(raw), json!({"task_id": 1, "tags": ["a", "b"]}));
+
+        let raw = "Tady je odpověď: {\"task_id\": null} Doufám, že to pomůže.";
+        assert_eq!(parse(raw), json!({"task_id": null}));
+    }
+
+    #[test]
+    fn test_single_quotes() {
+        let raw = "{'reasoning': 'Uživatel píše \"kód\"', 'it\\'s': true}";
+        assert_eq!(parse(raw), json!({"reasoning": "Uživatel píše \"kód\"", "it's": true}));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let raw = "{\"task_id\": 12, \"confidence\": 80, \"activity_description\": \"Editace kó";
+        assert_eq!(
+            parse(raw),
+            json!({"task_id": 12, "confidence": 80, "activity_description": "Editace kó"})
+        );
+
+        assert_eq!(parse("{\"task_id\": 12, \"reasoning\""), json!({"task_id": 12, "reasoning": null}));
+        assert_eq!(parse("{\"task_id\": nu"), json!({"task_id": null}));
+        assert_eq!(parse("{\"tags\": [\"a\", "), json!({"tags": ["a"]}));
+    }
+}
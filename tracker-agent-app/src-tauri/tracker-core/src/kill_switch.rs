@@ -0,0 +1,48 @@
+use crate::storage;
+use std::path::PathBuf;
+
+/// Proměnná prostředí, která tracking zakáže (hodnota 1/true/yes)
+pub const DISABLE_ENV: &str = "TRACKER_AGENT_DISABLED";
+
+/// Soubor v datovém adresáři aplikace, jehož existence tracking zakáže
+pub const DISABLE_FLAG_FILE: &str = "disabled.flag";
+
+pub fn flag_path() -> PathBuf {
+    storage::app_data_dir().join(DISABLE_FLAG_FILE)
+}
+
+fn env_enabled(value: Option<&str>) -> bool {
+    value.is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// Důvod zákazu z hodnoty proměnné a existence souboru
+fn evaluate(env_value: Option<&str>, flag_exists: bool) -> Option<String> {
+    if env_enabled(env_value) {
+        Some(format!("{}={}", DISABLE_ENV, env_value.unwrap_or_default().trim()))
+    } else if flag_exists {
+        Some(format!("soubor {:?}", flag_path()))
+    } else {
+        None
+    }
+}
+
+/// Nouzové vypnutí - Some(důvod), pokud je snímání obrazovky zakázané
+pub fn disabled_reason() -> Option<String> {
+    let env_value = std::env::var(DISABLE_ENV).ok();
+    evaluate(env_value.as_deref(), flag_path().exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate(Some("1"), false).as_deref(), Some("TRACKER_AGENT_DISABLED=1"));
+        assert!(evaluate(Some(" TRUE "), false).is_some());
+        assert!(evaluate(Some("0"), false).is_none());
+        assert!(evaluate(Some(""), false).is_none());
+        assert!(evaluate(None, false).is_none());
+        assert!(evaluate(None, true).unwrap().contains(DISABLE_FLAG_FILE));
+    }
+}
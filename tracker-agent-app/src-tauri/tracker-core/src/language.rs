@@ -0,0 +1,107 @@
+use crate::text_matcher::fold_diacritic;
+use serde::Serialize;
+
+/// Pod touto jistotou se jazyk nepoužije (krátké nebo smíšené texty)
+const MIN_CONFIDENCE: f64 = 0.5;
+
+/// Jazyk OCR textu - určuje pravidla normalizace slov pro matching
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextLanguage {
+    Czech,
+    English,
+    /// Nerozpoznáno nebo jiný jazyk - slova se porovnávají beze změny
+    Other,
+}
+
+impl TextLanguage {
+    /// Jazyky s vlastními pravidly normalizace
+    pub const NORMALIZED: [TextLanguage; 2] = [TextLanguage::Czech, TextLanguage::English];
+
+    /// Název do AI promptu
+    pub fn label(self) -> &'static str {
+        match self {
+            TextLanguage::Czech => "čeština",
+            TextLanguage::English => "angličtina",
+            TextLanguage::Other => "jiný",
+        }
+    }
+}
+
+/// Převládající jazyk textu, Other = nerozpoznáno nebo nejisté
+pub fn detect(text: &str) -> TextLanguage {
+    let Some(info) = whatlang::detect(text) else {
+        return TextLanguage::Other;
+    };
+    if info.confidence() < MIN_CONFIDENCE {
+        return TextLanguage::Other;
+    }
+    match info.lang() {
+        // OCR bez diakritiky se často plete se slovenštinou
+        whatlang::Lang::Ces | whatlang::Lang::Slk => TextLanguage::Czech,
+        whatlang::Lang::Eng => TextLanguage::English,
+        _ => TextLanguage::Other,
+    }
+}
+
+/// Koncovky českých pádů a čísel (bez diakritiky), delší mají přednost
+const CZECH_SUFFIXES: [&str; 24] = [
+    "ovi", "ich", "ach", "ami", "emi", "ymi", "imi", "eho", "emu", "ych", "ymu", "ove", "ym", "im", "em", "om", "ou",
+    "um", "a", "e", "i", "o", "u", "y",
+];
+
+/// Kmen slova - čeština bez diakritiky a koncovek ("Pohody" = "pohoda"),
+/// angličtina bez množného čísla. Slovo už je normalizované (malá písmena).
+pub fn normalize_word(word: &str, language: TextLanguage) -> String {
+    match language {
+        TextLanguage::Czech => {
+            let folded: String = word.chars().map(fold_diacritic).collect();
+            CZECH_SUFFIXES
+                .iter()
+                .find_map(|suffix| folded.strip_suffix(suffix).filter(|stem| stem.chars().count() >= 3))
+                .map(str::to_string)
+                .unwrap_or(folded)
+        }
+        TextLanguage::English => {
+            if let Some(stem) = word.strip_suffix("ies").filter(|s| s.len() >= 2) {
+                format!("{}y", stem)
+            } else if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+                word[..word.len() - 1].to_string()
+            } else {
+                word.to_string()
+            }
+        }
+        TextLanguage::Other => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_normalize() {
+        assert_eq!(
+            detect("Dobrý den, posílám v příloze fakturu za minulý měsíc a prosím o kontrolu."),
+            TextLanguage::Czech
+        );
+        assert_eq!(
+            detect("Please review the pull request before the release tomorrow morning."),
+            TextLanguage::English
+        );
+        assert_eq!(detect("404"), TextLanguage::Other);
+
+        let czech = |w: &str| normalize_word(w, TextLanguage::Czech);
+        assert_eq!(czech("pohody"), czech("pohoda"));
+        assert_eq!(czech("přihlášení"), czech("prihlaseni"));
+        assert_eq!(czech("faktury"), "faktur");
+        // Krátká slova zůstávají celá
+        assert_eq!(czech("api"), "api");
+
+        let english = |w: &str| normalize_word(w, TextLanguage::English);
+        assert_eq!(english("invoices"), "invoice");
+        assert_eq!(english("categories"), "category");
+        assert_eq!(english("access"), "access");
+        assert_eq!(normalize_word("pohody", TextLanguage::Other), "pohody");
+    }
+}
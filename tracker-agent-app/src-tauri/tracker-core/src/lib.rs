@@ -0,0 +1,73 @@
+//! Jádro trackeru bez závislosti na Tauri - snímání obrazovky, matching tasků,
+//! zápisy do služeb pro čas a lokální data. UI dostává eventy přes [`events::EventSink`].
+
+pub mod freelo;
+pub mod screenshot;
+pub mod tracker;
+pub mod ocr;
+pub mod text_matcher;
+pub mod ai_matcher;
+pub mod storage;
+pub mod rule_pack;
+pub mod window_info;
+pub mod idle;
+pub mod network;
+pub mod profiles;
+pub mod verification;
+pub mod offline;
+pub mod evidence;
+pub mod json_repair;
+pub mod matcher_stats;
+pub mod window_policy;
+pub mod resume;
+pub mod notifications;
+pub mod history;
+pub mod settings;
+pub mod debug_artifacts;
+pub mod tessdata;
+pub mod review;
+pub mod resources;
+pub mod config_check;
+pub mod note_templates;
+pub mod kill_switch;
+pub mod errors;
+pub mod redaction;
+pub mod secrets;
+pub mod summary;
+pub mod categories;
+pub mod backend;
+pub mod toggl;
+pub mod clockify;
+pub mod activity_context;
+pub mod context_providers;
+pub mod rate_limit;
+pub mod degradation;
+pub mod demo;
+pub mod embedding_matcher;
+pub mod language;
+pub mod evaluation;
+pub mod schedule;
+pub mod comparison;
+pub mod state_machine;
+pub mod prompt_template;
+pub mod budget;
+pub mod ai_usage;
+pub mod adaptive_interval;
+pub mod browser;
+pub mod connection_check;
+pub mod task_rules;
+pub mod activity_comment;
+pub mod recovery;
+pub mod events;
+pub mod config_files;
+pub mod hotkey_bindings;
+pub mod headless;
+pub mod timeline;
+pub mod export;
+pub mod task_budget;
+pub mod corrections;
+#[cfg(target_os = "macos")]
+pub mod ocr_vision;
+#[cfg(target_os = "windows")]
+pub mod ocr_windows;
+pub mod profile_crypto;
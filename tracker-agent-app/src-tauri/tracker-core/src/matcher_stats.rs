@@ -0,0 +1,116 @@
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use crate::text_matcher::MatchSource;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Soubor se statistikami v adresáři profilu
+const STATS_FILE: &str = "matcher_stats.json";
+
+/// Kolik posledních výsledků na matcher držíme (klouzavé okno)
+const ROLLING_WINDOW: usize = 200;
+
+/// Minimum vzorků, než se podle přesnosti rozhoduje o přednosti matcheru
+const MIN_SAMPLES_FOR_PRIORITY: usize = 20;
+
+/// Přesnost jednoho matcheru pro UI
+#[derive(Debug, Clone, Serialize)]
+pub struct MatcherAccuracy {
+    pub source: MatchSource,
+    pub samples: usize,
+    pub correct: usize,
+    pub accuracy: Option<f32>,
+}
+
+/// Klouzavá přesnost matcherů - ground truth jsou ruční opravy uživatele.
+/// Segment, který uživatel ručně přepnul na jiný task, je chyba matcheru,
+/// segment, který doběhl bez zásahu, je správně.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatcherStats {
+    outcomes: HashMap<MatchSource, VecDeque<bool>>,
+}
+
+impl MatcherStats {
+    pub fn load(profile: &str) -> Self {
+        profile_crypto::load_json(profile, &profile_dir(profile).join(STATS_FILE)).unwrap_or_default()
+    }
+
+    pub fn save(&self, profile: &str) -> Result<(), String> {
+        profile_crypto::save_json(profile, &profile_dir(profile).join(STATS_FILE), self)
+    }
+
+    pub fn record(&mut self, source: MatchSource, correct: bool) {
+        let outcomes = self.outcomes.entry(source).or_default();
+        outcomes.push_back(correct);
+        while outcomes.len() > ROLLING_WINDOW {
+            outcomes.pop_front();
+        }
+    }
+
+    fn accuracy_of(&self, source: MatchSource) -> Option<(f32, usize)> {
+        let outcomes = self.outcomes.get(&source)?;
+        if outcomes.is_empty() {
+            return None;
+        }
+        let correct = outcomes.iter().filter(|c| **c).count();
+        Some((correct as f32 / outcomes.len() as f32, outcomes.len()))
+    }
+
+    pub fn summary(&self) -> Vec<MatcherAccuracy> {
+        MatchSource::ALL
+            .iter()
+            .map(|&source| {
+                let outcomes = self.outcomes.get(&source);
+                MatcherAccuracy {
+                    source,
+                    samples: outcomes.map_or(0, |o| o.len()),
+                    correct: outcomes.map_or(0, |o| o.iter().filter(|c| **c).count()),
+                    accuracy: self.accuracy_of(source).map(|(accuracy, _)| accuracy),
+                }
+            })
+            .collect()
+    }
+
+    /// Který ze dvou matcherů má přednost při neshodě - ten s vyšší
+    /// naměřenou přesností, dokud není dost dat, vyhrává `default`
+    pub fn preferred(&self, default: MatchSource, other: MatchSource) -> MatchSource {
+        match (self.accuracy_of(default), self.accuracy_of(other)) {
+            (Some((default_acc, default_n)), Some((other_acc, other_n)))
+                if default_n >= MIN_SAMPLES_FOR_PRIORITY
+                    && other_n >= MIN_SAMPLES_FOR_PRIORITY
+                    && other_acc > default_acc =>
+            {
+                other
+            }
+            _ => default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_accuracy_and_preference() {
+        let mut stats = MatcherStats::default();
+        assert_eq!(stats.preferred(MatchSource::Ai, MatchSource::Text), MatchSource::Ai);
+
+        for i in 0..30 {
+            stats.record(MatchSource::Ai, i % 2 == 0);
+            stats.record(MatchSource::Text, i % 5 != 0);
+        }
+        assert_eq!(stats.preferred(MatchSource::Ai, MatchSource::Text), MatchSource::Text);
+
+        let ai = stats.summary().into_iter().find(|s| s.source == MatchSource::Ai).unwrap();
+        assert_eq!(ai.samples, 30);
+        assert_eq!(ai.accuracy, Some(0.5));
+
+        for _ in 0..ROLLING_WINDOW {
+            stats.record(MatchSource::Ai, true);
+        }
+        let ai = stats.summary().into_iter().find(|s| s.source == MatchSource::Ai).unwrap();
+        assert_eq!(ai.samples, ROLLING_WINDOW);
+        assert_eq!(ai.accuracy, Some(1.0));
+    }
+}
@@ -0,0 +1,205 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Síťový kontext - aktuální Wi-Fi a stav VPN
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NetworkContext {
+    pub ssid: Option<String>,
+    pub vpn_active: bool,
+}
+
+/// Co dělat, když pravidlo odpovídá
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkAction {
+    Track,
+    Pause,
+}
+
+/// Pravidlo pro síťovou bránu před tickem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRule {
+    /// SSID Wi-Fi sítě, None = libovolná
+    #[serde(default)]
+    pub ssid: Option<String>,
+    /// Some(true) = jen s VPN, Some(false) = jen bez VPN, None = nezáleží
+    #[serde(default)]
+    pub vpn: Option<bool>,
+    /// Časové okno "HH:MM-HH:MM" (může přecházet přes půlnoc), None = vždy
+    #[serde(default)]
+    pub hours: Option<String>,
+    pub action: NetworkAction,
+}
+
+impl NetworkRule {
+    fn matches(&self, ctx: &NetworkContext, now: NaiveTime) -> bool {
+        if let Some(ref ssid) = self.ssid {
+            if ctx.ssid.as_deref() != Some(ssid.as_str()) {
+                return false;
+            }
+        }
+        if let Some(vpn) = self.vpn {
+            if ctx.vpn_active != vpn {
+                return false;
+            }
+        }
+        match self.hours {
+            Some(ref hours) => in_time_window(hours, now).unwrap_or(false),
+            None => true,
+        }
+    }
+}
+
+/// Zda je čas v okně "HH:MM-HH:MM" (okno může přecházet přes půlnoc)
+fn in_time_window(window: &str, now: NaiveTime) -> Option<bool> {
+    let (from, to) = window.split_once('-')?;
+    let from = NaiveTime::parse_from_str(from.trim(), "%H:%M").ok()?;
+    let to = NaiveTime::parse_from_str(to.trim(), "%H:%M").ok()?;
+
+    Some(if from <= to {
+        now >= from && now < to
+    } else {
+        now >= from || now < to
+    })
+}
+
+/// Validace pravidel z nastavení
+pub fn validate_rules(rules: &[NetworkRule]) -> Result<(), String> {
+    for rule in rules {
+        if let Some(ref hours) = rule.hours {
+            if in_time_window(hours, NaiveTime::MIN).is_none() {
+                return Err(format!("Neplatné časové okno '{}' (očekáváno HH:MM-HH:MM)", hours));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Vyhodnotí pravidla - první odpovídající rozhoduje. Pokud žádné neodpovídá,
+/// sleduje se, ledaže existuje nějaké Track pravidlo (pak funguje jako allowlist).
+pub fn evaluate(rules: &[NetworkRule], ctx: &NetworkContext, now: NaiveTime) -> NetworkAction {
+    if let Some(rule) = rules.iter().find(|r| r.matches(ctx, now)) {
+        return rule.action;
+    }
+
+    if rules.iter().any(|r| r.action == NetworkAction::Track) {
+        NetworkAction::Pause
+    } else {
+        NetworkAction::Track
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// SSID aktuální Wi-Fi sítě
+fn current_ssid() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    let ssid = command_output("networksetup", &["-getairportnetwork", "en0"])
+        .and_then(|out| out.split_once(": ").map(|(_, ssid)| ssid.trim().to_string()));
+
+    #[cfg(target_os = "linux")]
+    let ssid = command_output("iwgetid", &["-r"]).map(|out| out.trim().to_string());
+
+    #[cfg(target_os = "windows")]
+    let ssid = command_output("netsh", &["wlan", "show", "interfaces"]).and_then(|out| {
+        out.lines()
+            .map(|l| l.trim())
+            .find(|l| l.starts_with("SSID") && !l.starts_with("SSID BSSID"))
+            .and_then(|l| l.split_once(':'))
+            .map(|(_, ssid)| ssid.trim().to_string())
+    });
+
+    ssid.filter(|s| !s.is_empty())
+}
+
+/// Zda je připojená VPN (podle VPN rozhraní nebo stavu připojení)
+fn vpn_active() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        command_output("scutil", &["--nc", "list"])
+            .map(|out| out.lines().any(|l| l.contains("(Connected)")))
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_dir("/sys/class/net")
+            .map(|entries| {
+                entries.filter_map(|e| e.ok()).any(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    ["tun", "tap", "wg", "ppp"].iter().any(|p| name.starts_with(p))
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        command_output("powershell", &["-NoProfile", "-Command", "(Get-VpnConnection | Where-Object ConnectionStatus -eq 'Connected').Count"])
+            .and_then(|out| out.trim().parse::<u32>().ok())
+            .is_some_and(|count| count > 0)
+    }
+}
+
+/// Zjistí aktuální síťový kontext
+pub fn current_context() -> NetworkContext {
+    NetworkContext {
+        ssid: current_ssid(),
+        vpn_active: vpn_active(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_in_time_window() {
+        assert_eq!(in_time_window("09:00-17:30", time(12, 0)), Some(true));
+        assert_eq!(in_time_window("09:00-17:30", time(18, 0)), Some(false));
+        assert_eq!(in_time_window("18:00-08:00", time(23, 0)), Some(true));
+        assert_eq!(in_time_window("18:00-08:00", time(7, 59)), Some(true));
+        assert_eq!(in_time_window("nonsense", time(7, 59)), None);
+    }
+
+    #[test]
+    fn test_evaluate_home_after_hours() {
+        let rules = vec![NetworkRule {
+            ssid: Some("HomeWiFi".to_string()),
+            vpn: None,
+            hours: Some("18:00-08:00".to_string()),
+            action: NetworkAction::Pause,
+        }];
+        let home = NetworkContext { ssid: Some("HomeWiFi".to_string()), vpn_active: false };
+
+        assert_eq!(evaluate(&rules, &home, time(20, 0)), NetworkAction::Pause);
+        assert_eq!(evaluate(&rules, &home, time(10, 0)), NetworkAction::Track);
+    }
+
+    #[test]
+    fn test_evaluate_allowlist() {
+        let rules = vec![
+            NetworkRule { ssid: Some("Office".to_string()), vpn: None, hours: None, action: NetworkAction::Track },
+            NetworkRule { ssid: None, vpn: Some(true), hours: None, action: NetworkAction::Track },
+        ];
+
+        let office = NetworkContext { ssid: Some("Office".to_string()), vpn_active: false };
+        let cafe_vpn = NetworkContext { ssid: Some("Cafe".to_string()), vpn_active: true };
+        let cafe = NetworkContext { ssid: Some("Cafe".to_string()), vpn_active: false };
+
+        assert_eq!(evaluate(&rules, &office, time(10, 0)), NetworkAction::Track);
+        assert_eq!(evaluate(&rules, &cafe_vpn, time(10, 0)), NetworkAction::Track);
+        assert_eq!(evaluate(&rules, &cafe, time(10, 0)), NetworkAction::Pause);
+    }
+}
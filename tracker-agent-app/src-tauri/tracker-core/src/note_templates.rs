@@ -0,0 +1,113 @@
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use crate::text_matcher::activity_key;
+use chrono::Local;
+use std::collections::{BTreeMap, HashSet};
+
+/// Soubor se šablonami poznámek (task_id → šablona) v adresáři profilu
+const TEMPLATES_FILE: &str = "note_templates.json";
+
+/// Freelo omezuje délku poznámky, šablona ji nesmí vyčerpat sama
+const MAX_TEMPLATE_CHARS: usize = 200;
+
+/// Zástupné symboly, které šablona smí obsahovat
+const PLACEHOLDERS: [&str; 4] = ["activity", "task", "project", "date"];
+
+/// Hodnoty dosazované do šablony
+pub struct NoteContext<'a> {
+    pub activity: &'a str,
+    pub task: &'a str,
+    pub project: &'a str,
+}
+
+/// Ověří šablonu - jen známé {symboly} a rozumná délka
+pub fn validate(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Šablona poznámky je prázdná".to_string());
+    }
+    if template.chars().count() > MAX_TEMPLATE_CHARS {
+        return Err(format!("Šablona poznámky může mít nejvýše {} znaků", MAX_TEMPLATE_CHARS));
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err("Šablona obsahuje neuzavřenou '{'".to_string());
+        };
+        let name = &rest[start + 1..start + len];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "Neznámý symbol {{{}}} (povoleno: {})",
+                name,
+                PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(", ")
+            ));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+/// Nový popis aktivity je výstižnější - víc slov a navazuje na původní (sdílí aspoň polovinu jeho slov)
+pub fn is_richer(current: &str, candidate: &str) -> bool {
+    let words = |text: &str| -> HashSet<String> { activity_key(text).split_whitespace().map(str::to_string).collect() };
+    let current = words(current);
+    let candidate = words(candidate);
+    candidate.len() > current.len() && current.intersection(&candidate).count() * 2 >= current.len()
+}
+
+/// Dosadí hodnoty do šablony
+pub fn render(template: &str, context: &NoteContext) -> String {
+    template
+        .replace("{activity}", context.activity)
+        .replace("{task}", context.task)
+        .replace("{project}", context.project)
+        .replace("{date}", &Local::now().format("%d.%m.%Y").to_string())
+        .trim()
+        .to_string()
+}
+
+pub fn load(profile: &str) -> BTreeMap<i32, String> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(TEMPLATES_FILE)).unwrap_or_default()
+}
+
+/// Nastaví šablonu tasku, None (nebo prázdný text) ji smaže
+pub fn set(profile: &str, task_id: i32, template: Option<&str>) -> Result<BTreeMap<i32, String>, String> {
+    let mut templates = load(profile);
+    match template.filter(|t| !t.trim().is_empty()) {
+        Some(template) => {
+            validate(template)?;
+            templates.insert(task_id, template.to_string());
+        }
+        None => {
+            templates.remove(&task_id);
+        }
+    }
+    profile_crypto::save_json(profile, &profile_dir(profile).join(TEMPLATES_FILE), &templates)?;
+    Ok(templates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_and_render() {
+        assert!(validate("Sprint 14 — {activity}").is_ok());
+        assert!(validate("{project}: {task} ({activity})").is_ok());
+        assert!(validate("Sprint {sprint}").is_err());
+        assert!(validate("Sprint {activity").is_err());
+        assert!(validate("   ").is_err());
+
+        let context = NoteContext {
+            activity: "Editace kódu",
+            task: "Login",
+            project: "Web",
+        };
+        assert_eq!(render("Sprint 14 — {activity}", &context), "Sprint 14 — Editace kódu");
+        assert_eq!(render("{project}/{task}: {activity}", &context), "Web/Login: Editace kódu");
+
+        assert!(is_richer("VS Code - tracker", "VS Code - tracker.rs: oprava restartu segmentu"));
+        assert!(!is_richer("VS Code - tracker.rs: oprava restartu", "VS Code - tracker"));
+        assert!(!is_richer("VS Code - tracker", "Slack - kanál vývoj a další zprávy"));
+    }
+}
@@ -0,0 +1,355 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+/// Výchozí klíčové slovo v názvu události kalendáře, která je focus blok
+pub const DEFAULT_FOCUS_KEYWORD: &str = "focus";
+
+/// Kolik odložených oznámení držíme pro pozdější kontrolu
+const MAX_DEFERRED: usize = 500;
+
+/// Úroveň, která se doručí vždy, i během focus bloku
+const CRITICAL_LEVEL: &str = "error";
+
+/// Blok soustředěné práce, kdy se nekritická oznámení jen zaznamenají
+#[derive(Debug, Clone)]
+pub struct FocusBlock {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub title: String,
+}
+
+/// Oznámení odložené během focus bloku
+#[derive(Debug, Clone, Serialize)]
+pub struct DeferredNotification {
+    pub at: String,
+    pub level: String,
+    pub message: String,
+}
+
+/// Stav pro UI
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusStatus {
+    pub active: bool,
+    pub until: Option<String>,
+    pub title: Option<String>,
+    pub deferred: usize,
+}
+
+/// Změna focus režimu od posledního dotazu
+#[derive(Debug, Clone, PartialEq)]
+pub enum FocusTransition {
+    Started,
+    Ended { deferred: usize },
+}
+
+/// Události, o kterých může přijít systémové oznámení
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesktopEvent {
+    /// Tracking začal na novém tasku
+    TaskStarted,
+    /// Návrh matcheru pod prahem confidence - sleduje se obecná práce
+    GeneralWork,
+    /// Freelo API selhalo (projde i během focus bloku)
+    FreeloError,
+    /// Pauza kvůli nečinnosti a návrat
+    IdlePause,
+    /// Task se blíží rozpočtu hodin nebo ho vyčerpal
+    TaskBudget,
+}
+
+/// Zapnutí systémových oznámení po událostech (chybějící = výchozí)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopNotifications {
+    pub task_started: bool,
+    pub general_work: bool,
+    pub freelo_error: bool,
+    pub idle_pause: bool,
+    pub task_budget: bool,
+}
+
+impl Default for DesktopNotifications {
+    fn default() -> Self {
+        Self {
+            task_started: true,
+            // Při nejisté práci by chodilo často
+            general_work: false,
+            freelo_error: true,
+            idle_pause: true,
+            task_budget: true,
+        }
+    }
+}
+
+impl DesktopNotifications {
+    pub fn is_enabled(&self, event: DesktopEvent) -> bool {
+        match event {
+            DesktopEvent::TaskStarted => self.task_started,
+            DesktopEvent::GeneralWork => self.general_work,
+            DesktopEvent::FreeloError => self.freelo_error,
+            DesktopEvent::IdlePause => self.idle_pause,
+            DesktopEvent::TaskBudget => self.task_budget,
+        }
+    }
+}
+
+/// Politika doručování oznámení - ruční focus blok nebo blok z kalendáře
+#[derive(Debug, Default)]
+pub struct NotificationPolicy {
+    manual: Option<FocusBlock>,
+    scheduled: Vec<FocusBlock>,
+    deferred: Vec<DeferredNotification>,
+    was_focused: bool,
+    desktop: DesktopNotifications,
+}
+
+impl NotificationPolicy {
+    fn current_block(&self, now: DateTime<Local>) -> Option<&FocusBlock> {
+        self.manual
+            .iter()
+            .chain(self.scheduled.iter())
+            .find(|b| b.start <= now && now < b.end)
+    }
+
+    /// Zda oznámení doručit hned, jinak se uloží k pozdější kontrole
+    pub fn should_deliver(&mut self, level: &str, message: &str, now: DateTime<Local>) -> bool {
+        if level == CRITICAL_LEVEL || self.current_block(now).is_none() {
+            return true;
+        }
+
+        if self.deferred.len() >= MAX_DEFERRED {
+            self.deferred.remove(0);
+        }
+        self.deferred.push(DeferredNotification {
+            at: now.format("%H:%M:%S").to_string(),
+            level: level.to_string(),
+            message: message.to_string(),
+        });
+        false
+    }
+
+    /// Zda poslat systémové oznámení - jen zapnuté události, během focus bloku jen chyby
+    pub fn allows_desktop(&self, event: DesktopEvent, now: DateTime<Local>) -> bool {
+        self.desktop.is_enabled(event) && (event == DesktopEvent::FreeloError || self.current_block(now).is_none())
+    }
+
+    pub fn set_desktop(&mut self, desktop: DesktopNotifications) {
+        self.desktop = desktop;
+    }
+
+    pub fn start_manual(&mut self, now: DateTime<Local>, minutes: i64) -> FocusBlock {
+        let block = FocusBlock {
+            start: now,
+            end: now + chrono::Duration::minutes(minutes),
+            title: "Ruční focus blok".to_string(),
+        };
+        self.manual = Some(block.clone());
+        block
+    }
+
+    /// Ukončí ruční blok i právě probíhající blok z kalendáře
+    pub fn end_now(&mut self, now: DateTime<Local>) {
+        self.manual = None;
+        for block in self.scheduled.iter_mut().filter(|b| b.start <= now && now < b.end) {
+            block.end = now;
+        }
+    }
+
+    pub fn set_scheduled(&mut self, blocks: Vec<FocusBlock>) {
+        self.scheduled = blocks;
+    }
+
+    pub fn status(&self, now: DateTime<Local>) -> FocusStatus {
+        let block = self.current_block(now);
+        FocusStatus {
+            active: block.is_some(),
+            until: block.map(|b| b.end.format("%H:%M").to_string()),
+            title: block.map(|b| b.title.clone()),
+            deferred: self.deferred.len(),
+        }
+    }
+
+    pub fn poll_transition(&mut self, now: DateTime<Local>) -> Option<FocusTransition> {
+        let focused = self.current_block(now).is_some();
+        if focused == self.was_focused {
+            return None;
+        }
+        self.was_focused = focused;
+
+        Some(if focused {
+            FocusTransition::Started
+        } else {
+            FocusTransition::Ended { deferred: self.deferred.len() }
+        })
+    }
+
+    pub fn take_review(&mut self) -> Vec<DeferredNotification> {
+        std::mem::take(&mut self.deferred)
+    }
+}
+
+/// Politika je sdílená pro celý proces - `emit_log` je volané z míst bez přístupu k trackeru
+pub static POLICY: LazyLock<Mutex<NotificationPolicy>> = LazyLock::new(Default::default);
+
+/// Zkratka pro `emit_log` - zda oznámení poslat do UI
+pub fn should_deliver(level: &str, message: &str) -> bool {
+    POLICY
+        .lock()
+        .map(|mut policy| policy.should_deliver(level, message, Local::now()))
+        .unwrap_or(true)
+}
+
+/// Zkratka pro systémová oznámení z trackeru
+pub fn desktop_allowed(event: DesktopEvent) -> bool {
+    POLICY
+        .lock()
+        .map(|policy| policy.allows_desktop(event, Local::now()))
+        .unwrap_or(false)
+}
+
+pub fn set_desktop(desktop: DesktopNotifications) {
+    if let Ok(mut policy) = POLICY.lock() {
+        policy.set_desktop(desktop);
+    }
+}
+
+/// Rozpracovaná událost při čtení iCalendar
+#[derive(Default)]
+struct IcsEvent {
+    start: Option<DateTime<Local>>,
+    end: Option<DateTime<Local>>,
+    summary: String,
+}
+
+/// Čas z iCalendar (20250312T090000, 20250312T080000Z), celodenní události vynechá
+fn parse_ics_time(value: &str) -> Option<DateTime<Local>> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive).with_timezone(&Local));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// Focus bloky z iCalendar exportu - události, jejichž název obsahuje klíčové slovo.
+/// Časy s TZID se berou jako lokální.
+pub fn parse_ics_focus_blocks(ics: &str, keyword: &str) -> Vec<FocusBlock> {
+    // Pokračovací řádky (začínají mezerou) patří k předchozímu řádku
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    let keyword = keyword.to_lowercase();
+    let mut blocks = Vec::new();
+    let mut event: Option<IcsEvent> = None;
+
+    for line in &lines {
+        match line.as_str() {
+            "BEGIN:VEVENT" => event = Some(IcsEvent::default()),
+            "END:VEVENT" => {
+                let Some(IcsEvent { start: Some(start), end: Some(end), summary }) = event.take() else {
+                    continue;
+                };
+                if start < end && summary.to_lowercase().contains(&keyword) {
+                    blocks.push(FocusBlock { start, end, title: summary });
+                }
+            }
+            _ => {
+                let (Some(current), Some((name, value))) = (event.as_mut(), line.split_once(':')) else {
+                    continue;
+                };
+                match name.split(';').next().unwrap_or_default() {
+                    "DTSTART" => current.start = parse_ics_time(value),
+                    "DTEND" => current.end = parse_ics_time(value),
+                    "SUMMARY" => current.summary = value.to_string(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    blocks.sort_by_key(|b| b.start);
+    blocks
+}
+
+pub fn load_calendar(path: &Path, keyword: &str) -> Result<Vec<FocusBlock>, String> {
+    let ics = std::fs::read_to_string(path).map_err(|e| format!("Nelze načíst kalendář {:?}: {}", path, e))?;
+    Ok(parse_ics_focus_blocks(&ics, keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deferral_during_focus() {
+        let now = Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap();
+        let mut policy = NotificationPolicy::default();
+
+        assert!(policy.should_deliver("info", "před blokem", now));
+        assert_eq!(policy.poll_transition(now), None);
+
+        policy.start_manual(now, 60);
+        assert_eq!(policy.poll_transition(now), Some(FocusTransition::Started));
+        assert!(!policy.should_deliver("info", "task změněn", now));
+        assert!(!policy.should_deliver("warning", "AI selhalo", now));
+        assert!(policy.should_deliver("error", "OCR chyba", now));
+
+        let later = now + chrono::Duration::minutes(61);
+        assert!(!policy.status(later).active);
+        assert_eq!(policy.poll_transition(later), Some(FocusTransition::Ended { deferred: 2 }));
+        assert_eq!(policy.take_review().len(), 2);
+        assert_eq!(policy.status(later).deferred, 0);
+    }
+
+    #[test]
+    fn test_desktop_notifications() {
+        let now = Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap();
+        let mut policy = NotificationPolicy::default();
+        assert!(policy.allows_desktop(DesktopEvent::TaskStarted, now));
+        assert!(!policy.allows_desktop(DesktopEvent::GeneralWork, now));
+
+        // Během focus bloku projdou jen chyby Freelo
+        policy.start_manual(now, 30);
+        assert!(!policy.allows_desktop(DesktopEvent::IdlePause, now));
+        assert!(policy.allows_desktop(DesktopEvent::FreeloError, now));
+
+        let toggles: DesktopNotifications = serde_json::from_str(r#"{"freelo_error": false}"#).unwrap();
+        assert!(toggles.task_started && !toggles.freelo_error);
+        policy.set_desktop(toggles);
+        assert!(!policy.allows_desktop(DesktopEvent::FreeloError, now));
+    }
+
+    #[test]
+    fn test_parse_ics_focus_blocks() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;TZID=Europe/Prague:20250312T090000\r\n\
+            DTEND;TZID=Europe/Prague:20250312T110000\r\n\
+            SUMMARY:Focus: refaktoring\r\n  API\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20250312T130000\r\n\
+            DTEND:20250312T140000\r\n\
+            SUMMARY:Porada\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20250313\r\n\
+            DTEND;VALUE=DATE:20250314\r\n\
+            SUMMARY:Focus den\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let blocks = parse_ics_focus_blocks(ics, "FOCUS");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].title, "Focus: refaktoring API");
+        assert_eq!(blocks[0].start.format("%H:%M").to_string(), "09:00");
+        assert_eq!(blocks[0].end.format("%H:%M").to_string(), "11:00");
+    }
+}
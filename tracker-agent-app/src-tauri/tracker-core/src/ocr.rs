@@ -0,0 +1,1041 @@
+use chrono::{DateTime, Local};
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use tesseract::{OcrEngineMode, Tesseract};
+use tracing::{debug, info, instrument, warn, Span};
+use std::path::{Path, PathBuf};
+use crate::debug_artifacts::{self, get_debug_dir};
+use crate::profile_crypto;
+use crate::redaction;
+use crate::screenshot::ImageArea;
+use crate::tessdata;
+
+/// Zkontroluje zda je Tesseract nainstalovaný
+fn check_tesseract_installed() -> bool {
+    std::process::Command::new("tesseract")
+        .arg("--version")
+        .output()
+        .is_ok()
+}
+
+/// Pokusí se automaticky nainstalovat Tesseract
+fn auto_install_tesseract() -> Result<(), String> {
+    info!("⚠️  Tesseract není nainstalovaný, pokouším se o automatickou instalaci...");
+
+    #[cfg(target_os = "macos")]
+    {
+        info!("🍎 Detekován macOS, instaluji přes Homebrew...");
+        let output = std::process::Command::new("brew")
+            .args(&["install", "tesseract", "tesseract-lang"])
+            .output()
+            .map_err(|e| format!("Chyba při spuštění brew: {}. Nainstalujte Homebrew z https://brew.sh", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Instalace selhala: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        info!("✅ Tesseract úspěšně nainstalován!");
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        info!("🐧 Detekován Linux, instaluji přes apt-get...");
+        let output = std::process::Command::new("sudo")
+            .args(&["apt-get", "update"])
+            .output()
+            .map_err(|e| format!("Chyba při aktualizaci apt: {}", e))?;
+
+        if !output.status.success() {
+            return Err("Aktualizace apt selhala".to_string());
+        }
+
+        let output = std::process::Command::new("sudo")
+            .args(&["apt-get", "install", "-y", "tesseract-ocr", "tesseract-ocr-eng", "libtesseract-dev", "libleptonica-dev"])
+            .output()
+            .map_err(|e| format!("Chyba při instalaci tesseract: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Instalace selhala: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        info!("✅ Tesseract úspěšně nainstalován!");
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Err("Automatická instalace na Windows není podporována. Prosím nainstalujte Tesseract manuálně z https://github.com/UB-Mannheim/tesseract/wiki".to_string())
+    }
+}
+
+/// Výchozí jazyky pro OCR (formát Tesseractu, více jazyků přes "+", např. "ces+eng")
+pub const DEFAULT_OCR_LANGUAGES: &str = "eng";
+
+/// Rozdělí jazyky z nastavení ("ces+eng") na kódy Tesseractu
+pub fn parse_languages(spec: &str) -> Result<Vec<String>, String> {
+    let mut languages: Vec<String> = Vec::new();
+    for code in spec.split('+').map(str::trim) {
+        tessdata::validate_code(code)?;
+        if languages.iter().any(|l| l == code) {
+            return Err(format!("OCR jazyk '{}' je uvedený dvakrát", code));
+        }
+        languages.push(code.to_string());
+    }
+    Ok(languages)
+}
+
+/// Jazyky, pro které chybí trénovací data (v tessdata adresáři s nejvíce z nich)
+pub fn missing_languages(languages: &[&str]) -> (Option<PathBuf>, Vec<String>) {
+    let tessdata_dir = find_tessdata_dir_with(languages).or_else(find_tessdata_dir);
+    let available = tessdata_dir.as_deref().map(languages_in).unwrap_or_default();
+    let missing = languages
+        .iter()
+        .filter(|lang| !available.iter().any(|l| l == *lang))
+        .map(|lang| lang.to_string())
+        .collect();
+    (tessdata_dir, missing)
+}
+
+/// Stav OCR subsystému pro `get_health`
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrHealth {
+    /// Backend, který OCR opravdu použije (Auto už rozhodnuté podle platformy)
+    pub engine: OcrEngineKind,
+    pub tesseract_installed: bool,
+    pub tessdata_dir: Option<String>,
+    pub available_languages: Vec<String>,
+    pub missing_languages: Vec<String>,
+    pub remediation: Option<String>,
+}
+
+/// Kandidátní umístění tessdata adresáře (v pořadí priority)
+fn tessdata_candidates() -> Vec<PathBuf> {
+    // Jazyky stažené aplikací mají přednost před systémovými
+    let mut candidates = vec![tessdata::managed_dir()];
+
+    if let Ok(prefix) = std::env::var("TESSDATA_PREFIX") {
+        let prefix = PathBuf::from(prefix);
+        candidates.push(prefix.join("tessdata"));
+        candidates.push(prefix);
+    }
+
+    for path in [
+        "/opt/homebrew/share/tessdata",
+        "/usr/local/share/tessdata",
+        "/usr/share/tesseract-ocr/5/tessdata",
+        "/usr/share/tesseract-ocr/4.00/tessdata",
+        "/usr/share/tessdata",
+        "C:\\Program Files\\Tesseract-OCR\\tessdata",
+        "C:\\Program Files (x86)\\Tesseract-OCR\\tessdata",
+    ] {
+        candidates.push(PathBuf::from(path));
+    }
+
+    candidates
+}
+
+/// Seznam jazyků (`*.traineddata`) dostupných v adresáři
+pub fn languages_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut languages: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            if path.extension().is_some_and(|ext| ext == "traineddata") {
+                path.file_stem().map(|s| s.to_string_lossy().to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    languages.sort();
+    languages
+}
+
+/// Najde první tessdata adresář, který obsahuje nějaká trénovací data
+pub fn find_tessdata_dir() -> Option<PathBuf> {
+    tessdata_candidates()
+        .into_iter()
+        .find(|dir| !languages_in(dir).is_empty())
+}
+
+/// Najde tessdata adresář, který obsahuje všechny požadované jazyky
+pub fn find_tessdata_dir_with(languages: &[&str]) -> Option<PathBuf> {
+    tessdata_candidates().into_iter().find(|dir| {
+        let available = languages_in(dir);
+        languages.iter().all(|lang| available.iter().any(|l| l == lang))
+    })
+}
+
+/// Návod k nápravě pro chybějící jazyky
+fn remediation_for(tessdata_dir: Option<&Path>, missing: &[String]) -> String {
+    let target = tessdata_dir
+        .map(|d| format!("{:?}", d))
+        .unwrap_or_else(|| "tessdata adresáře a nastavte TESSDATA_PREFIX".to_string());
+
+    let files: Vec<String> = missing
+        .iter()
+        .map(|lang| format!("https://github.com/tesseract-ocr/tessdata_fast/raw/main/{}.traineddata", lang))
+        .collect();
+
+    format!(
+        "Stáhněte jazyk v nastavení OCR jazyků, nebo ručně {} do {}",
+        files.join(", "),
+        target
+    )
+}
+
+/// Zkontroluje zvolený OCR backend - u Tesseractu i tessdata a dostupnost požadovaných jazyků
+pub fn check_ocr_health(engine: OcrEngineKind, required_languages: &[&str]) -> OcrHealth {
+    let engine = engine.resolve();
+    let tesseract_installed = check_tesseract_installed();
+    let (tessdata_dir, missing_languages) = missing_languages(required_languages);
+    let available_languages = tessdata_dir.as_deref().map(languages_in).unwrap_or_default();
+
+    OcrHealth {
+        engine,
+        tesseract_installed,
+        tessdata_dir: tessdata_dir.map(|d| d.to_string_lossy().to_string()),
+        available_languages,
+        // Nativní OCR trénovací data Tesseractu nepotřebuje
+        missing_languages: if engine == OcrEngineKind::Tesseract { missing_languages } else { Vec::new() },
+        remediation: create_engine(engine).and_then(|e| e.check(required_languages)).err(),
+    }
+}
+
+/// Kódy jazyků Tesseractu převedené na BCP 47 pro nativní OCR systému
+const NATIVE_LANGUAGES: [(&str, &str); 17] = [
+    ("eng", "en-US"),
+    ("ces", "cs-CZ"),
+    ("slk", "sk-SK"),
+    ("deu", "de-DE"),
+    ("pol", "pl-PL"),
+    ("fra", "fr-FR"),
+    ("spa", "es-ES"),
+    ("ita", "it-IT"),
+    ("por", "pt-BR"),
+    ("nld", "nl-NL"),
+    ("hun", "hu-HU"),
+    ("rus", "ru-RU"),
+    ("ukr", "uk-UA"),
+    ("chi_sim", "zh-Hans"),
+    ("chi_tra", "zh-Hant"),
+    ("jpn", "ja-JP"),
+    ("kor", "ko-KR"),
+];
+
+/// Jazyk z nastavení ("ces") jako tag pro nativní OCR, None = nativní OCR ho nezná
+pub fn native_language(code: &str) -> Option<&'static str> {
+    NATIVE_LANGUAGES.iter().find(|(tesseract, _)| *tesseract == code).map(|(_, tag)| *tag)
+}
+
+/// Výchozí PSM (sparse text) a kandidáti pro automatický výběr
+pub const DEFAULT_PSM: u32 = 11;
+const AUTO_PSM_CANDIDATES: [u32; 2] = [11, 6];
+
+/// Kolik ticků se v auto režimu porovnávají oba PSM, než se jeden vybere
+const AUTO_PSM_CALIBRATION_TICKS: u32 = 3;
+
+/// Minimální confidence slova (0-100), aby se počítalo jako "jisté"
+pub(crate) const HIGH_CONFIDENCE_WORD: f32 = 80.0;
+
+/// Od jaké šířky/výšky se snímek dělí na dlaždice (4K a víc)
+const AUTO_TILE_THRESHOLD: u32 = 2560;
+
+/// Výchozí velikost dlaždice v px
+pub const DEFAULT_TILE_SIZE: u32 = 1280;
+
+/// Překryv dlaždic - řádek textu rozříznutý hranou je celý v sousední dlaždici
+const TILE_OVERLAP: u32 = 48;
+
+/// Nastavení OCR pro jeden běh (PSM, OEM a dlaždice platí jen pro Tesseract)
+#[derive(Debug, Clone)]
+pub struct OcrTuning {
+    /// Backend z nastavení
+    pub engine: OcrEngineKind,
+    /// PSM hodnoty k vyzkoušení (vybere se ta s nejvíce jistými slovy)
+    pub psm_candidates: Vec<u32>,
+    /// OCR engine mode, None = výchozí dle tessdata
+    pub oem: Option<u32>,
+    /// Velikost dlaždice pro velké snímky, None = bez dělení
+    pub tile_size: Option<u32>,
+    /// Zvětšení před OCR (malé písmo na high-DPI), 1.0 = beze změny
+    pub scale: f32,
+    /// Jazyky Tesseractu ("ces+eng")
+    pub languages: String,
+}
+
+impl Default for OcrTuning {
+    fn default() -> Self {
+        Self {
+            engine: OcrEngineKind::default(),
+            psm_candidates: vec![DEFAULT_PSM],
+            oem: None,
+            tile_size: Some(DEFAULT_TILE_SIZE),
+            scale: 1.0,
+            languages: DEFAULT_OCR_LANGUAGES.to_string(),
+        }
+    }
+}
+
+/// Výřez snímku pro OCR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TileRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl TileRect {
+    /// Dlaždice pokrývá celý snímek - není co ořezávat
+    pub fn is_full(&self, img: &DynamicImage) -> bool {
+        self.x == 0 && self.y == 0 && self.width == img.width() && self.height == img.height()
+    }
+}
+
+/// Rozdělení snímku na dlaždice s překryvem - menší snímky zůstávají celé
+fn tile_rects(width: u32, height: u32, tile_size: Option<u32>) -> Vec<TileRect> {
+    let Some(tile_size) = tile_size.filter(|&s| s > 0) else {
+        return vec![TileRect { x: 0, y: 0, width, height }];
+    };
+    if width <= AUTO_TILE_THRESHOLD && height <= AUTO_TILE_THRESHOLD {
+        return vec![TileRect { x: 0, y: 0, width, height }];
+    }
+    split_tiles(width, height, tile_size)
+}
+
+/// Dlaždice, z nichž žádná nepřesáhne `max_side` px (Windows OCR má limit rozměru obrázku)
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn tiles_within(width: u32, height: u32, max_side: u32) -> Vec<TileRect> {
+    if width <= max_side && height <= max_side {
+        return vec![TileRect { x: 0, y: 0, width, height }];
+    }
+    // Krajní dlaždice mají překryv z jedné strany, vnitřní z obou
+    split_tiles(width, height, max_side.saturating_sub(2 * TILE_OVERLAP).max(1))
+}
+
+/// Dělení po osách na díly nejvýše `tile_size` px, sousední díly se překrývají
+fn split_tiles(width: u32, height: u32, tile_size: u32) -> Vec<TileRect> {
+    let axis = |length: u32| -> Vec<(u32, u32)> {
+        let count = length.div_ceil(tile_size).max(1);
+        let step = length.div_ceil(count);
+        (0..count)
+            .map(|i| {
+                let start = (i * step).saturating_sub(if i > 0 { TILE_OVERLAP } else { 0 });
+                let end = ((i + 1) * step + TILE_OVERLAP).min(length);
+                (start, end - start)
+            })
+            .collect()
+    };
+
+    let mut tiles = Vec::new();
+    for &(y, h) in &axis(height) {
+        for &(x, w) in &axis(width) {
+            tiles.push(TileRect { x, y, width: w, height: h });
+        }
+    }
+    tiles
+}
+
+/// Slovo (u Apple Vision řádek) v souřadnicích celého snímku
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrWord {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Confidence 0-100 jako v TSV Tesseractu
+    pub conf: f32,
+    pub text: String,
+}
+
+impl OcrWord {
+    fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    /// Plocha průniku s jiným slovem
+    fn intersection(&self, other: &OcrWord) -> f32 {
+        let w = (self.left + self.width).min(other.left + other.width) - self.left.max(other.left);
+        let h = (self.top + self.height).min(other.top + other.height) - self.top.max(other.top);
+        w.max(0.0) * h.max(0.0)
+    }
+}
+
+/// Slova z TSV jedné dlaždice přepočtená na souřadnice snímku
+fn tsv_words(tsv: &str, tile: TileRect, scale: f32) -> Vec<OcrWord> {
+    tsv.lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split('\t').collect();
+            if cols.len() < 12 || cols[0] != "5" || cols[11].trim().is_empty() {
+                return None;
+            }
+            let num = |i: usize| cols[i].parse::<f32>().ok();
+            Some(OcrWord {
+                left: tile.x as f32 + num(6)? / scale,
+                top: tile.y as f32 + num(7)? / scale,
+                width: num(8)? / scale,
+                height: num(9)? / scale,
+                conf: num(10)?,
+                text: cols[11].trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Spojí slova ze všech dlaždic: duplicity z překryvu (i slovo useknuté hranou)
+/// vyhraje větší box, pak řádky shora dolů a slova zleva doprava
+pub(crate) fn merge_words(mut words: Vec<OcrWord>) -> (String, usize) {
+    words.sort_by(|a, b| b.area().total_cmp(&a.area()).then(b.conf.total_cmp(&a.conf)));
+    let mut kept: Vec<OcrWord> = Vec::new();
+    for word in words {
+        let duplicate = kept
+            .iter()
+            .any(|k| k.intersection(&word) >= 0.5 * word.area().min(k.area()));
+        if !duplicate {
+            kept.push(word);
+        }
+    }
+    let confident = kept.iter().filter(|w| w.conf >= HIGH_CONFIDENCE_WORD).count();
+
+    let text = text_lines(kept)
+        .into_iter()
+        .map(|line| line.into_iter().map(|w| w.text).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    (text, confident)
+}
+
+/// Řádky shora dolů, slova v řádku zleva doprava
+fn text_lines(mut words: Vec<OcrWord>) -> Vec<Vec<OcrWord>> {
+    words.sort_by(|a, b| (a.top + a.height / 2.0).total_cmp(&(b.top + b.height / 2.0)));
+    let mut lines: Vec<Vec<OcrWord>> = Vec::new();
+    for word in words {
+        let center = word.top + word.height / 2.0;
+        match lines.last_mut() {
+            // Stejný řádek = střed slova leží v rozsahu prvního slova řádku
+            Some(line) if center >= line[0].top && center <= line[0].top + line[0].height => line.push(word),
+            _ => lines.push(vec![word]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| a.left.total_cmp(&b.left));
+    }
+    lines
+}
+
+/// Boxy slov s citlivými údaji (e-maily, hesla, účty, karty, klíče) v pixelech snímku.
+/// Údaje přes více slov (IBAN s mezerami) se hledají v celém řádku.
+pub fn sensitive_areas(words: &[OcrWord]) -> Vec<ImageArea> {
+    let mut areas = Vec::new();
+    for line in text_lines(words.to_vec()) {
+        let mut text = String::new();
+        let mut ranges = Vec::with_capacity(line.len());
+        for word in &line {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            ranges.push(text.len()..text.len() + word.text.len());
+            text.push_str(&word.text);
+        }
+
+        let spans = redaction::sensitive_spans(&text);
+        for (word, range) in line.iter().zip(ranges) {
+            if spans.iter().any(|span| span.start < range.end && range.start < span.end) {
+                areas.push(ImageArea {
+                    x: word.left.max(0.0).floor() as u32,
+                    y: word.top.max(0.0).floor() as u32,
+                    width: word.width.max(0.0).ceil() as u32 + 1,
+                    height: word.height.max(0.0).ceil() as u32 + 1,
+                });
+            }
+        }
+    }
+    areas
+}
+
+/// Kolik znaků posledního OCR textu vrátit, když UI limit nezadá
+pub const DEFAULT_OCR_PREVIEW_CHARS: usize = 2000;
+
+/// Strop vráceného textu - celá obrazovka by zahltila UI i log podpory
+pub const MAX_OCR_PREVIEW_CHARS: usize = 20_000;
+
+/// Text z OCR posledního ticku pro ladění (bez ukládání debug artefaktů)
+#[derive(Debug, Clone, Serialize)]
+pub struct OcrTextPreview {
+    pub at: DateTime<Local>,
+    pub text: String,
+    pub total_chars: usize,
+    pub truncated: bool,
+}
+
+impl OcrTextPreview {
+    /// Nejvýše `max_chars` znaků (omezeno na `MAX_OCR_PREVIEW_CHARS`)
+    pub fn new(text: &str, at: DateTime<Local>, max_chars: usize) -> Self {
+        let max_chars = max_chars.min(MAX_OCR_PREVIEW_CHARS);
+        let total_chars = text.chars().count();
+        Self {
+            at,
+            text: text.chars().take(max_chars).collect(),
+            total_chars,
+            truncated: total_chars > max_chars,
+        }
+    }
+}
+
+/// Výsledek OCR včetně skóre jednotlivých PSM
+#[derive(Debug, Clone)]
+pub struct OcrOutput {
+    pub text: String,
+    /// Použitý PSM, None = nativní OCR (PSM nemá)
+    pub psm: Option<u32>,
+    pub confident_words: usize,
+    /// (psm, počet jistých slov) pro každý vyzkoušený PSM, u nativního OCR prázdné
+    pub psm_scores: Vec<(u32, usize)>,
+    /// Rozpoznaná slova s polohou (pro zakrytí citlivých údajů ve snímku)
+    pub words: Vec<OcrWord>,
+}
+
+impl OcrOutput {
+    /// Výsledek nativního OCR - bez PSM a jeho skóre
+    pub fn native(text: String, confident_words: usize, words: Vec<OcrWord>) -> Self {
+        Self {
+            text,
+            psm: None,
+            confident_words,
+            psm_scores: Vec::new(),
+            words,
+        }
+    }
+}
+
+/// Automatický výběr PSM - prvních pár ticků zkouší oba kandidáty,
+/// pak zůstane u toho, který dává víc jistých slov
+#[derive(Debug, Clone, Default)]
+pub struct PsmAutoSelector {
+    scores: [usize; 2],
+    ticks: u32,
+    chosen: Option<u32>,
+}
+
+impl PsmAutoSelector {
+    /// PSM hodnoty, které se mají v tomto ticku vyzkoušet
+    pub fn psm_candidates(&self) -> Vec<u32> {
+        match self.chosen {
+            Some(psm) => vec![psm],
+            None => AUTO_PSM_CANDIDATES.to_vec(),
+        }
+    }
+
+    /// Zaznamená skóre z ticku, vrací Some(psm) ve chvíli, kdy se PSM vybere
+    pub fn record(&mut self, psm_scores: &[(u32, usize)]) -> Option<u32> {
+        if self.chosen.is_some() {
+            return None;
+        }
+
+        for (psm, words) in psm_scores {
+            if let Some(i) = AUTO_PSM_CANDIDATES.iter().position(|c| c == psm) {
+                self.scores[i] += words;
+            }
+        }
+        self.ticks += 1;
+
+        if self.ticks >= AUTO_PSM_CALIBRATION_TICKS {
+            let best = if self.scores[1] > self.scores[0] { 1 } else { 0 };
+            self.chosen = Some(AUTO_PSM_CANDIDATES[best]);
+        }
+
+        self.chosen
+    }
+}
+
+/// Validace PSM/OEM hodnot z nastavení
+pub fn validate_tuning(psm: Option<u32>, oem: Option<u32>) -> Result<(), String> {
+    if let Some(psm) = psm {
+        if psm > 13 {
+            return Err(format!("Neplatný PSM {} (povoleno 0-13)", psm));
+        }
+    }
+    if let Some(oem) = oem {
+        if oem > 3 {
+            return Err(format!("Neplatný OEM {} (povoleno 0-3)", oem));
+        }
+    }
+    Ok(())
+}
+
+/// Validace dlaždic a zvětšení z nastavení (0 = bez dlaždic)
+pub fn validate_scaling(tile_size: Option<u32>, scale: Option<f32>) -> Result<(), String> {
+    if let Some(size) = tile_size.filter(|&s| s != 0) {
+        if size < 480 {
+            return Err(format!("Velikost OCR dlaždice musí být aspoň 480 px (zadáno {})", size));
+        }
+    }
+    if let Some(scale) = scale {
+        if !(1.0..=3.0).contains(&scale) {
+            return Err(format!("Zvětšení pro OCR musí být 1.0-3.0 (zadáno {})", scale));
+        }
+    }
+    Ok(())
+}
+
+fn engine_mode(oem: u32) -> OcrEngineMode {
+    match oem {
+        0 => OcrEngineMode::TesseractOnly,
+        1 => OcrEngineMode::LstmOnly,
+        2 => OcrEngineMode::TesseractLstmCombined,
+        _ => OcrEngineMode::Default,
+    }
+}
+
+/// Spočítá slova s vysokou confidence z Tesseract TSV výstupu
+fn count_confident_words(tsv: &str) -> usize {
+    tsv.lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split('\t').collect();
+            // level 5 = slovo; sloupce: level ... conf(10) text(11)
+            if cols.len() < 12 || cols[0] != "5" {
+                return None;
+            }
+            let conf: f32 = cols[10].parse().ok()?;
+            (conf >= HIGH_CONFIDENCE_WORD && !cols[11].trim().is_empty()).then_some(())
+        })
+        .count()
+}
+
+/// Surový výstup jednoho běhu Tesseractu
+struct TesseractPass {
+    text: String,
+    tsv: String,
+}
+
+/// Provede OCR na obrázku pomocí Tesseract
+fn perform_ocr(img_buffer: &[u8], psm: u32, tuning: &OcrTuning) -> Result<TesseractPass, String> {
+    // Zkontroluj zda je Tesseract nainstalovaný
+    if !check_tesseract_installed() {
+        // Pokus o automatickou instalaci
+        auto_install_tesseract()?;
+
+        // Znovu zkontroluj
+        if !check_tesseract_installed() {
+            return Err("Tesseract se nepodařilo nainstalovat. Prosím nainstalujte ho manuálně.".to_string());
+        }
+    }
+
+    // Najdi tessdata a ověř jazyky, ať nedostaneme kryptickou chybu z inicializace
+    let languages: Vec<&str> = tuning.languages.split('+').collect();
+    let (tessdata_dir, missing) = missing_languages(&languages);
+    if !missing.is_empty() {
+        return Err(format!(
+            "Chybí trénovací data pro jazyk '{}'. {}",
+            missing.join("+"),
+            remediation_for(tessdata_dir.as_deref(), &missing)
+        ));
+    }
+    let datapath = tessdata_dir.as_ref().map(|d| d.to_string_lossy().to_string());
+
+    let tesseract = match tuning.oem {
+        Some(oem) => Tesseract::new_with_oem(datapath.as_deref(), Some(&tuning.languages), engine_mode(oem)),
+        None => Tesseract::new(datapath.as_deref(), Some(&tuning.languages)),
+    };
+
+    let mut tesseract = tesseract
+        .map_err(|e| format!("Chyba při inicializaci Tesseract ({:?}): {}", datapath, e))?
+        .set_variable("tessedit_pageseg_mode", &psm.to_string())
+        .map_err(|e| format!("Chyba při nastavení PSM: {}", e))?
+        .set_image_from_mem(img_buffer)
+        .map_err(|e| format!("Chyba při načítání obrazu: {}", e))?;
+
+    let tsv = tesseract
+        .get_tsv_text(0)
+        .map_err(|e| format!("OCR selhal: {}", e))?;
+    let text = tesseract
+        .get_text()
+        .map_err(|e| format!("OCR selhal: {}", e))?;
+
+    Ok(TesseractPass { text, tsv })
+}
+
+/// PNG buffer pro OCR (volitelně zvětšený)
+pub(crate) fn encode_for_ocr(img: &DynamicImage, scale: f32) -> Result<Vec<u8>, String> {
+    let scaled;
+    let img = if (scale - 1.0).abs() > f32::EPSILON {
+        let width = (img.width() as f32 * scale).round() as u32;
+        let height = (img.height() as f32 * scale).round() as u32;
+        scaled = img.resize_exact(width, height, image::imageops::FilterType::CatmullRom);
+        &scaled
+    } else {
+        img
+    };
+
+    let mut buffer = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Png)
+        .map_err(|e| format!("Chyba při konverzi obrazu: {}", e))?;
+    Ok(buffer)
+}
+
+/// OCR jednoho PSM přes všechny dlaždice
+fn ocr_tiles(tiles: &[(TileRect, Vec<u8>)], psm: u32, tuning: &OcrTuning) -> Result<OcrOutput, String> {
+    let (text, confident_words, words) = match tiles {
+        // Jediná nezvětšená dlaždice = celý snímek, text bere Tesseract rovnou
+        [(rect, buffer)] if (tuning.scale - 1.0).abs() <= f32::EPSILON => {
+            let pass = perform_ocr(buffer, psm, tuning)?;
+            let confident = count_confident_words(&pass.tsv);
+            (pass.text, confident, tsv_words(&pass.tsv, *rect, 1.0))
+        }
+        _ => {
+            let mut words = Vec::new();
+            for (rect, buffer) in tiles {
+                let pass = perform_ocr(buffer, psm, tuning)?;
+                words.extend(tsv_words(&pass.tsv, *rect, tuning.scale));
+            }
+            let (text, confident) = merge_words(words.clone());
+            (text, confident, words)
+        }
+    };
+
+    Ok(OcrOutput {
+        text,
+        psm: Some(psm),
+        confident_words,
+        psm_scores: vec![(psm, confident_words)],
+        words,
+    })
+}
+
+/// OCR backend z nastavení - Auto vybere nativní OCR platformy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrEngineKind {
+    #[default]
+    Auto,
+    Tesseract,
+    AppleVision,
+    WindowsOcr,
+}
+
+impl OcrEngineKind {
+    /// Konkrétní backend (Auto: macOS Apple Vision, Windows Windows.Media.Ocr, jinde Tesseract)
+    pub fn resolve(self) -> Self {
+        match self {
+            OcrEngineKind::Auto if cfg!(target_os = "macos") => OcrEngineKind::AppleVision,
+            OcrEngineKind::Auto if cfg!(target_os = "windows") => OcrEngineKind::WindowsOcr,
+            OcrEngineKind::Auto => OcrEngineKind::Tesseract,
+            kind => kind,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OcrEngineKind::Auto => "automaticky",
+            OcrEngineKind::Tesseract => "Tesseract",
+            OcrEngineKind::AppleVision => "Apple Vision",
+            OcrEngineKind::WindowsOcr => "Windows OCR",
+        }
+    }
+}
+
+/// Rozpoznání textu z obrázku obrazovky
+pub trait OcrEngine {
+    /// Poběží backend s jazyky z nastavení? Err = návod k nápravě
+    fn check(&self, languages: &[&str]) -> Result<(), String>;
+
+    fn recognize(&self, img: &DynamicImage, tuning: &OcrTuning) -> Result<OcrOutput, String>;
+}
+
+/// Backend pro nastavení, Err = na této platformě není
+pub fn create_engine(kind: OcrEngineKind) -> Result<Box<dyn OcrEngine>, String> {
+    match kind.resolve() {
+        #[cfg(target_os = "macos")]
+        OcrEngineKind::AppleVision => Ok(Box::new(crate::ocr_vision::AppleVisionEngine)),
+        #[cfg(target_os = "windows")]
+        OcrEngineKind::WindowsOcr => Ok(Box::new(crate::ocr_windows::WindowsOcrEngine)),
+        OcrEngineKind::Tesseract => Ok(Box::new(TesseractEngine)),
+        kind => Err(format!("OCR backend {} na této platformě není k dispozici", kind.label())),
+    }
+}
+
+/// Tesseract přes libtesseract - dlaždice velkých snímků a výběr PSM
+pub struct TesseractEngine;
+
+impl OcrEngine for TesseractEngine {
+    fn check(&self, languages: &[&str]) -> Result<(), String> {
+        if !check_tesseract_installed() {
+            return Err("Tesseract není nainstalovaný (macOS: brew install tesseract, Linux: apt-get install tesseract-ocr)".to_string());
+        }
+        let (tessdata_dir, missing) = missing_languages(languages);
+        if !missing.is_empty() {
+            return Err(remediation_for(tessdata_dir.as_deref(), &missing));
+        }
+        Ok(())
+    }
+
+    fn recognize(&self, img: &DynamicImage, tuning: &OcrTuning) -> Result<OcrOutput, String> {
+        // Velké snímky (4K) po dlaždicích - Tesseract na celé ploše ztrácí drobné písmo
+        let rects = tile_rects(img.width(), img.height(), tuning.tile_size);
+        let mut tiles = Vec::with_capacity(rects.len());
+        for rect in rects {
+            let tile = if rect.is_full(img) {
+                encode_for_ocr(img, tuning.scale)?
+            } else {
+                encode_for_ocr(&img.crop_imm(rect.x, rect.y, rect.width, rect.height), tuning.scale)?
+            };
+            tiles.push((rect, tile));
+        }
+        Span::current().record("tiles", tiles.len());
+
+        // OCR pomocí Tesseract (s automatickou instalací) - pro každý PSM kandidát,
+        // vyhrává ten s nejvíce jistými slovy
+        let mut best: Option<OcrOutput> = None;
+        let mut psm_scores = Vec::new();
+
+        for &psm in &tuning.psm_candidates {
+            let output = ocr_tiles(&tiles, psm, tuning)
+                .map_err(|e| format!("OCR selhal: {}", e))?;
+
+            debug!(psm, confident_words = output.confident_words, "PSM kandidát");
+            psm_scores.push((psm, output.confident_words));
+
+            if best.as_ref().is_none_or(|b| output.confident_words > b.confident_words) {
+                best = Some(output);
+            }
+        }
+
+        let mut output = best.ok_or_else(|| "Není zadán žádný PSM".to_string())?;
+        output.psm_scores = psm_scores;
+        Ok(output)
+    }
+}
+
+/// Extrakce textu z obrázku backendem z nastavení
+pub fn extract_text_from_image(
+    img: DynamicImage,
+    debug_profile: Option<&str>,
+    debug_window: Option<ImageArea>,
+    tuning: &OcrTuning,
+) -> Result<OcrOutput, String> {
+    // Debug: Uložení původního screenshotu (šifrovaně klíčem profilu) a polohy okna
+    if let Some(profile) = debug_profile {
+        let debug_dir = get_debug_dir(profile);
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let path = debug_dir.join(format!("{}_0_original.png", timestamp));
+        if let Err(e) = encode_for_ocr(&img, 1.0).and_then(|png| profile_crypto::write(profile, &path, &png)) {
+            warn!(error = %e, "nepodařilo se uložit original");
+        } else {
+            debug!(path = ?path, "debug: uložen original");
+        }
+        if let Some(area) = debug_window {
+            if let Err(e) = debug_artifacts::save_window_area(profile, &path, &area) {
+                warn!(error = %e, "nepodařilo se uložit polohu okna");
+            }
+        }
+    }
+
+    let output = create_engine(tuning.engine)?.recognize(&img, tuning)?;
+    let text = &output.text;
+
+    if let Some(psm) = output.psm {
+        Span::current().record("psm", psm);
+    }
+    Span::current()
+        .record("engine", tracing::field::debug(tuning.engine.resolve()))
+        .record("confident_words", output.confident_words)
+        .record("chars", text.len());
+
+    // Debug: Výpis extrahovaného textu
+    if let Some(profile) = debug_profile {
+        // Bezpečné oříznutí na 500 znaků (respektuje UTF-8 boundaries)
+        let preview = if text.chars().count() > 500 {
+            let truncated: String = text.chars().take(500).collect();
+            format!("{}...", truncated)
+        } else {
+            text.to_string()
+        };
+        debug!(preview = %preview, "OCR text (prvních 500 znaků)");
+
+        // Uložení textu do souboru
+        let debug_dir = get_debug_dir(profile);
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let path = debug_dir.join(format!("{}_4_ocr_text.txt", timestamp));
+        if let Err(e) = profile_crypto::write(profile, &path, text.as_bytes()) {
+            warn!(error = %e, "nepodařilo se uložit OCR text");
+        } else {
+            debug!(path = ?path, "debug: uložen OCR text");
+        }
+    }
+
+    Ok(output)
+}
+
+/// Extrakce textu ze screenshotu (base64)
+/// debug_profile: pokud Some, ukládá mezikroky do debug adresáře profilu
+#[instrument(
+    name = "ocr",
+    skip_all,
+    fields(debug = debug_profile.is_some(), bytes, width, height, engine, tiles, psm, confident_words, chars)
+)]
+pub fn extract_text_from_screenshot(
+    screenshot_base64: &str,
+    debug_profile: Option<&str>,
+    debug_window: Option<ImageArea>,
+    tuning: &OcrTuning,
+) -> Result<OcrOutput, String> {
+    use base64::Engine;
+
+    // Dekódování base64
+    let image_data = base64::engine::general_purpose::STANDARD
+        .decode(screenshot_base64)
+        .map_err(|e| format!("Chyba při dekódování base64: {}", e))?;
+
+    Span::current().record("bytes", image_data.len());
+
+    // Načtení obrazu
+    let img = image::load_from_memory(&image_data)
+        .map_err(|e| format!("Chyba při načítání obrazu: {}", e))?;
+
+    Span::current()
+        .record("width", img.width())
+        .record("height", img.height());
+
+    // OCR
+    extract_text_from_image(img, debug_profile, debug_window, tuning)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preprocessing() {
+        // Vytvoř testovací obrázek
+        let img = DynamicImage::new_rgb8(100, 100);
+        let processed = preprocess_image(img, false); // false = bez debug ukládání
+
+        assert_eq!(processed.width(), 100);
+        assert_eq!(processed.height(), 100);
+    }
+
+    #[test]
+    fn test_ocr_text_preview() {
+        let preview = OcrTextPreview::new("Přehled úkolů", Local::now(), 7);
+        assert_eq!(preview.text, "Přehled");
+        assert_eq!(preview.total_chars, 13);
+        assert!(preview.truncated);
+
+        let long = "a".repeat(MAX_OCR_PREVIEW_CHARS + 10);
+        let preview = OcrTextPreview::new(&long, Local::now(), usize::MAX);
+        assert_eq!(preview.text.len(), MAX_OCR_PREVIEW_CHARS);
+        assert!(!OcrTextPreview::new("abc", Local::now(), 3).truncated);
+    }
+
+    #[test]
+    fn test_count_confident_words() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   5\t1\t1\t1\t1\t1\t0\t0\t10\t10\t95.5\tHello\n\
+                   5\t1\t1\t1\t1\t2\t0\t0\t10\t10\t40.0\tWrld\n\
+                   4\t1\t1\t1\t1\t0\t0\t0\t10\t10\t-1\t\n\
+                   5\t1\t1\t1\t1\t3\t0\t0\t10\t10\t88\tfoo\n";
+        assert_eq!(count_confident_words(tsv), 2);
+    }
+
+    #[test]
+    fn test_parse_languages() {
+        assert_eq!(parse_languages("ces+eng").unwrap(), vec!["ces", "eng"]);
+        assert_eq!(parse_languages(" chi_sim ").unwrap(), vec!["chi_sim"]);
+        assert!(parse_languages("ces+").is_err());
+        assert!(parse_languages("ces+ces").is_err());
+        assert!(parse_languages("../eng").is_err());
+    }
+
+    #[test]
+    fn test_engine_selection() {
+        assert_eq!(OcrEngineKind::Tesseract.resolve(), OcrEngineKind::Tesseract);
+        assert_ne!(OcrEngineKind::Auto.resolve(), OcrEngineKind::Auto);
+        assert!(create_engine(OcrEngineKind::Auto).is_ok());
+        assert_eq!(native_language("ces"), Some("cs-CZ"));
+        assert_eq!(native_language("chi_sim"), Some("zh-Hans"));
+        assert_eq!(native_language("lat"), None);
+
+        // Windows OCR má strop rozměru obrázku - 4K se dělí, žádná dlaždice ho nepřesáhne
+        assert_eq!(tiles_within(1920, 1080, 2600).len(), 1);
+        let tiles = tiles_within(3840, 2160, 2600);
+        assert_eq!(tiles.len(), 2);
+        assert!(tiles.iter().all(|t| t.width <= 2600 && t.height <= 2600));
+        assert_eq!(tiles[1].x + tiles[1].width, 3840);
+    }
+
+    #[test]
+    fn test_psm_auto_selector_picks_better_psm() {
+        let mut selector = PsmAutoSelector::default();
+        assert_eq!(selector.psm_candidates(), vec![11, 6]);
+
+        assert_eq!(selector.record(&[(11, 10), (6, 30)]), None);
+        assert_eq!(selector.record(&[(11, 12), (6, 25)]), None);
+        assert_eq!(selector.record(&[(11, 8), (6, 20)]), Some(6));
+        assert_eq!(selector.psm_candidates(), vec![6]);
+    }
+
+    #[test]
+    fn test_tiling_and_merge() {
+        assert_eq!(tile_rects(1920, 1080, Some(DEFAULT_TILE_SIZE)).len(), 1);
+        assert_eq!(tile_rects(3840, 2160, None).len(), 1);
+
+        let tiles = tile_rects(3840, 2160, Some(DEFAULT_TILE_SIZE));
+        assert_eq!(tiles.len(), 6);
+        assert_eq!(tiles[0], TileRect { x: 0, y: 0, width: 1280 + TILE_OVERLAP, height: 1080 + TILE_OVERLAP });
+        assert_eq!(tiles[1].x, 1280 - TILE_OVERLAP);
+        assert_eq!(tiles[5].x + tiles[5].width, 3840);
+        assert_eq!(tiles[5].y + tiles[5].height, 2160);
+
+        // Dlaždice vpravo: "Export" ve zvětšení 2x, useknuté "Expo" a duplicita z levé dlaždice
+        let right = TileRect { x: 1232, y: 0, width: 1328, height: 1128 };
+        let right_tsv = "5\t1\t1\t1\t1\t1\t0\t40\t120\t40\t91\tExport\n\
+                         5\t1\t1\t1\t1\t2\t140\t40\t140\t40\t88\tfaktur\n\
+                         5\t1\t1\t1\t2\t1\t20\t200\t100\t40\t30\tPDF\n";
+        let left = TileRect { x: 0, y: 0, width: 1328, height: 1128 };
+        let left_tsv = "5\t1\t1\t1\t1\t1\t2464\t40\t40\t36\t60\tExpo\n\
+                        5\t1\t1\t1\t1\t2\t10\t40\t80\t40\t95\tFaktury\n\
+                        4\t1\t1\t1\t1\t0\t0\t0\t10\t10\t-1\t\n";
+
+        let mut words = tsv_words(right_tsv, right, 2.0);
+        assert_eq!(words[0].left, 1232.0);
+        words.extend(tsv_words(left_tsv, left, 2.0));
+        let (text, confident) = merge_words(words);
+        assert_eq!(text, "Faktury Export faktur\nPDF");
+        assert_eq!(confident, 3);
+    }
+
+    #[test]
+    fn test_sensitive_areas() {
+        let word = |left: f32, top: f32, text: &str| OcrWord {
+            left,
+            top,
+            width: 40.0,
+            height: 10.0,
+            conf: 90.0,
+            text: text.to_string(),
+        };
+        // Karta přes čtyři slova, e-mail na druhém řádku, zbytek zůstane
+        let words = vec![
+            word(200.0, 20.0, "jan.novak@firma.cz"),
+            word(0.0, 0.0, "Karta"),
+            word(50.0, 1.0, "4111"),
+            word(100.0, 0.0, "1111"),
+            word(150.0, 0.0, "1111"),
+            word(200.0, 0.0, "1111"),
+            word(0.0, 20.0, "Od:"),
+        ];
+        let areas = sensitive_areas(&words);
+        let lefts: Vec<u32> = areas.iter().map(|a| a.x).collect();
+        assert_eq!(lefts, vec![50, 100, 150, 200, 200]);
+        assert_eq!(areas[4], ImageArea { x: 200, y: 20, width: 41, height: 11 });
+        assert!(sensitive_areas(&[word(0.0, 0.0, "Faktury")]).is_empty());
+    }
+}
+
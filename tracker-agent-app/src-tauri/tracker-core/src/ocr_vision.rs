@@ -0,0 +1,104 @@
+use crate::ocr::{encode_for_ocr, merge_words, native_language, OcrEngine, OcrOutput, OcrTuning, OcrWord, HIGH_CONFIDENCE_WORD};
+use image::DynamicImage;
+use objc2::rc::{autoreleasepool, Retained};
+use objc2::AnyThread;
+use objc2_foundation::{NSArray, NSData, NSDictionary, NSError, NSString};
+use objc2_vision::{VNImageRequestHandler, VNRecognizeTextRequest, VNRequest, VNRequestTextRecognitionLevel};
+use tracing::debug;
+
+/// Vestavěné OCR macOS (Vision framework) - bez Tesseractu a stahování jazyků
+pub struct AppleVisionEngine;
+
+fn error_text(error: &NSError) -> String {
+    error.localizedDescription().to_string()
+}
+
+/// Jazyky z nastavení jako tagy Vision (neznámé se vynechají, prázdné = výchozí angličtina)
+fn vision_languages(languages: &str) -> Vec<&'static str> {
+    languages.split('+').filter_map(native_language).collect()
+}
+
+fn text_request(languages: &[&str]) -> Retained<VNRecognizeTextRequest> {
+    let request = VNRecognizeTextRequest::new();
+    request.setRecognitionLevel(VNRequestTextRecognitionLevel::Accurate);
+    request.setUsesLanguageCorrection(true);
+    if !languages.is_empty() {
+        let languages: Vec<Retained<NSString>> = languages.iter().map(|l| NSString::from_str(l)).collect();
+        request.setRecognitionLanguages(&NSArray::from_retained_slice(&languages));
+    }
+    request
+}
+
+impl OcrEngine for AppleVisionEngine {
+    fn check(&self, languages: &[&str]) -> Result<(), String> {
+        let tags = vision_languages(&languages.join("+"));
+        let unknown: Vec<&str> = languages.iter().copied().filter(|l| native_language(l).is_none()).collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Apple Vision nezná OCR jazyk {} - použijte OCR backend Tesseract",
+                unknown.join("+")
+            ));
+        }
+
+        autoreleasepool(|_| {
+            let supported = unsafe { text_request(&[]).supportedRecognitionLanguagesAndReturnError() }
+                .map_err(|e| format!("Apple Vision není dostupné: {}", error_text(&e)))?;
+            let supported: Vec<String> = supported.iter().map(|l| l.to_string()).collect();
+            let missing: Vec<&str> = tags
+                .into_iter()
+                .filter(|tag| !supported.iter().any(|s| s == tag))
+                .collect();
+            if missing.is_empty() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Tato verze macOS neumí rozpoznat jazyk {} - aktualizujte systém nebo použijte OCR backend Tesseract",
+                    missing.join(", ")
+                ))
+            }
+        })
+    }
+
+    fn recognize(&self, img: &DynamicImage, tuning: &OcrTuning) -> Result<OcrOutput, String> {
+        let png = encode_for_ocr(img, 1.0)?;
+        let (width, height) = (img.width() as f32, img.height() as f32);
+
+        let words = autoreleasepool(|_| -> Result<Vec<OcrWord>, String> {
+            let request = text_request(&vision_languages(&tuning.languages));
+            let data = NSData::with_bytes(&png);
+            let handler = VNImageRequestHandler::initWithData_options(VNImageRequestHandler::alloc(), &data, &NSDictionary::new());
+            let requests: &VNRequest = &request;
+            handler
+                .performRequests_error(&NSArray::from_slice(&[requests]))
+                .map_err(|e| format!("Apple Vision OCR selhalo: {}", error_text(&e)))?;
+
+            // Pozorování = řádek textu, box normalizovaný s počátkem vlevo dole
+            let mut words = Vec::new();
+            for observation in request.results().unwrap_or_default().iter() {
+                let Some(candidate) = observation.topCandidates(1).firstObject() else {
+                    continue;
+                };
+                let bounds = unsafe { observation.boundingBox() };
+                words.push(OcrWord {
+                    left: bounds.origin.x as f32 * width,
+                    top: (1.0 - (bounds.origin.y + bounds.size.height) as f32) * height,
+                    width: bounds.size.width as f32 * width,
+                    height: bounds.size.height as f32 * height,
+                    conf: candidate.confidence() * 100.0,
+                    text: candidate.string().to_string(),
+                });
+            }
+            Ok(words)
+        })?;
+
+        // Confidence má celý řádek - jistá jsou všechna jeho slova
+        let confident_words = words
+            .iter()
+            .filter(|w| w.conf >= HIGH_CONFIDENCE_WORD)
+            .map(|w| w.text.split_whitespace().count())
+            .sum();
+        debug!(lines = words.len(), confident_words, "Apple Vision OCR");
+        let (text, _) = merge_words(words.clone());
+        Ok(OcrOutput::native(text, confident_words, words))
+    }
+}
@@ -0,0 +1,100 @@
+use crate::ocr::{merge_words, native_language, tiles_within, OcrEngine, OcrOutput, OcrTuning, OcrWord};
+use image::DynamicImage;
+use tracing::debug;
+use windows::core::HSTRING;
+use windows::Globalization::Language;
+use windows::Graphics::Imaging::{BitmapPixelFormat, SoftwareBitmap};
+use windows::Media::Ocr::OcrEngine as MediaOcrEngine;
+use windows::Storage::Streams::DataWriter;
+use windows::Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED};
+
+/// Vestavěné OCR Windows 10+ (Windows.Media.Ocr) - jazyky podle jazykových balíčků systému
+pub struct WindowsOcrEngine;
+
+/// Windows OCR confidence nevrací - každé rozpoznané slovo se počítá jako jisté
+const WORD_CONFIDENCE: f32 = 100.0;
+
+/// Jazyk systému pro kód z nastavení - zkusí celý tag (cs-CZ) i samotný jazyk (cs)
+fn supported_language(code: &str) -> Option<Language> {
+    let tag = native_language(code)?;
+    [tag, tag.split('-').next().unwrap_or(tag)].into_iter().find_map(|tag| {
+        let language = Language::CreateLanguage(&HSTRING::from(tag)).ok()?;
+        MediaOcrEngine::IsLanguageSupported(&language).ok()?.then_some(language)
+    })
+}
+
+/// OCR engine pro první podporovaný jazyk z nastavení, jinak podle jazyků uživatelského profilu
+fn create_engine(languages: &str) -> Result<MediaOcrEngine, String> {
+    let engine = match languages.split('+').find_map(supported_language) {
+        Some(language) => MediaOcrEngine::TryCreateFromLanguage(&language),
+        None => MediaOcrEngine::TryCreateFromUserProfileLanguages(),
+    };
+    engine.map_err(|e| format!("Windows nemá OCR pro žádný nainstalovaný jazyk ({})", e))
+}
+
+/// Obrázek jako BGRA bitmapa pro WinRT
+fn software_bitmap(img: &DynamicImage) -> windows::core::Result<SoftwareBitmap> {
+    let mut pixels = img.to_rgba8().into_raw();
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let writer = DataWriter::new()?;
+    writer.WriteBytes(&pixels)?;
+    SoftwareBitmap::CreateCopyFromBuffer(&writer.DetachBuffer()?, BitmapPixelFormat::Bgra8, img.width() as i32, img.height() as i32)
+}
+
+impl OcrEngine for WindowsOcrEngine {
+    fn check(&self, languages: &[&str]) -> Result<(), String> {
+        let missing: Vec<&str> = languages.iter().copied().filter(|l| supported_language(l).is_none()).collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Windows nemá OCR pro jazyk {} - přidejte jazyk v Nastavení → Čas a jazyk → Jazyk (s funkcí rozpoznávání textu), nebo použijte OCR backend Tesseract",
+            missing.join("+")
+        ))
+    }
+
+    fn recognize(&self, img: &DynamicImage, tuning: &OcrTuning) -> Result<OcrOutput, String> {
+        // WinRT na vlákně z poolu - chyba znamená, že vlákno už inicializované je
+        let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+        let engine = create_engine(&tuning.languages)?;
+        let max_side = MediaOcrEngine::MaxImageDimension().map_err(|e| format!("Windows OCR není dostupné: {}", e))?;
+
+        // Větší snímky než limit Windows OCR (4K) po dlaždicích
+        let mut words = Vec::new();
+        for rect in tiles_within(img.width(), img.height(), max_side) {
+            let tile = if rect.is_full(img) {
+                software_bitmap(img)
+            } else {
+                software_bitmap(&img.crop_imm(rect.x, rect.y, rect.width, rect.height))
+            }
+            .map_err(|e| format!("Chyba při konverzi obrazu: {}", e))?;
+
+            let result = engine
+                .RecognizeAsync(&tile)
+                .and_then(|operation| operation.get())
+                .map_err(|e| format!("Windows OCR selhalo: {}", e))?;
+            let lines = result.Lines().map_err(|e| format!("Windows OCR selhalo: {}", e))?;
+            for line in &lines {
+                for word in &line.Words().map_err(|e| format!("Windows OCR selhalo: {}", e))? {
+                    let (Ok(bounds), Ok(text)) = (word.BoundingRect(), word.Text()) else {
+                        continue;
+                    };
+                    words.push(OcrWord {
+                        left: rect.x as f32 + bounds.X,
+                        top: rect.y as f32 + bounds.Y,
+                        width: bounds.Width,
+                        height: bounds.Height,
+                        conf: WORD_CONFIDENCE,
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+
+        debug!(words = words.len(), "Windows OCR");
+        let (text, confident_words) = merge_words(words.clone());
+        Ok(OcrOutput::native(text, confident_words, words))
+    }
+}
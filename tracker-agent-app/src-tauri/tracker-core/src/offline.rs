@@ -0,0 +1,215 @@
+use crate::freelo::WriteRouting;
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Soubor s frontou neodeslaných segmentů v adresáři profilu
+const OUTBOX_FILE: &str = "outbox.json";
+
+/// Backoff mezi pokusy o spojení s Freelo (zdvojuje se až do maxima)
+const BACKOFF_INITIAL: Duration = Duration::from_secs(30);
+const BACKOFF_MAX: Duration = Duration::from_secs(15 * 60);
+
+/// Dostupnost Freelo - kdy spadlo a kdy to zkusit znovu
+#[derive(Debug, Default)]
+pub struct FreeloConnectivity {
+    offline_since: Option<DateTime<Local>>,
+    failures: u32,
+    next_attempt: Option<Instant>,
+}
+
+impl FreeloConnectivity {
+    pub fn is_offline(&self) -> bool {
+        self.offline_since.is_some()
+    }
+
+    pub fn offline_since(&self) -> Option<DateTime<Local>> {
+        self.offline_since
+    }
+
+    /// Zda už uplynul backoff a má se zkusit další dotaz na Freelo
+    pub fn should_attempt(&self, now: Instant) -> bool {
+        self.next_attempt.is_none_or(|at| now >= at)
+    }
+
+    /// Zaznamená nedostupnost, vrací true pokud Freelo právě přešlo do offline
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        self.failures += 1;
+        self.next_attempt = Some(now + backoff(self.failures));
+
+        if self.offline_since.is_none() {
+            self.offline_since = Some(Local::now());
+            return true;
+        }
+        false
+    }
+
+    /// Zaznamená úspěšný dotaz, vrací začátek výpadku pokud Freelo bylo offline
+    pub fn record_success(&mut self) -> Option<DateTime<Local>> {
+        self.failures = 0;
+        self.next_attempt = None;
+        self.offline_since.take()
+    }
+}
+
+fn backoff(failures: u32) -> Duration {
+    let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+    BACKOFF_INITIAL.saturating_mul(factor).min(BACKOFF_MAX)
+}
+
+/// Zda chyba znamená nedostupnost Freelo (síť, výpadek serveru), ne chybu požadavku
+pub fn is_connectivity_error(error: &str) -> bool {
+    error.starts_with("HTTP chyba")
+        || [" 500", " 502", " 503", " 504"].iter().any(|code| error.contains(code))
+}
+
+/// Segment odpracovaný během výpadku Freelo, čeká na odeslání
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// UUID Freelo timeru, který se nepodařilo zastavit (None = segment jen lokálně)
+    pub uuid: Option<String>,
+    pub task_id: Option<i32>,
+    pub note: String,
+    pub started_at: DateTime<Local>,
+    pub stopped_at: DateTime<Local>,
+    /// ID segmentu v lokální historii (pro propojení s vytvořeným work reportem)
+    #[serde(default)]
+    pub segment_id: Option<String>,
+}
+
+impl OutboxEntry {
+    pub fn minutes(&self) -> i64 {
+        (self.stopped_at - self.started_at).num_seconds().max(0) / 60
+    }
+}
+
+/// Stav pro UI - "degradováno: Freelo offline od HH:MM", sandbox/dry-run režim zápisů
+#[derive(Debug, Clone, Serialize)]
+pub struct FreeloStatus {
+    pub online: bool,
+    pub offline_since: Option<String>,
+    pub queued: usize,
+    pub write_routing: WriteRouting,
+    /// Účet sandbox workspace, když do něj jdou zápisy
+    pub sandbox_email: Option<String>,
+}
+
+/// Srovná frontu podle času a odstraní překryvy, aby se čas nezapsal dvakrát
+/// (např. segment zahájený ručně během jiného lokálního segmentu).
+/// Timery, které ve Freelo opravdu běžely (s UUID), se nemění - zkracuje se lokální segment.
+/// Vrací upravenou frontu a počet upravených nebo vyřazených segmentů.
+pub fn reconcile_outbox(mut entries: Vec<OutboxEntry>) -> (Vec<OutboxEntry>, usize) {
+    entries.sort_by_key(|e| e.started_at);
+    let mut reconciled: Vec<OutboxEntry> = Vec::with_capacity(entries.len());
+    let mut adjusted = 0;
+
+    for mut entry in entries {
+        if let Some(previous) = reconciled.last_mut() {
+            if entry.started_at < previous.stopped_at {
+                match (previous.uuid.is_some(), entry.uuid.is_some()) {
+                    // Předchozí lokální segment končí tam, kde začíná běžící timer
+                    (false, true) => {
+                        previous.stopped_at = entry.started_at;
+                        adjusted += 1;
+                    }
+                    // Lokální segment začíná až po konci předchozího
+                    (_, false) => {
+                        entry.started_at = previous.stopped_at;
+                        adjusted += 1;
+                    }
+                    // Dva skutečné timery - necháme na ověření záznamů
+                    (true, true) => {}
+                }
+            }
+        }
+
+        // Segment úplně překrytý jiným nemá co zapsat
+        if entry.uuid.is_none() && entry.stopped_at <= entry.started_at {
+            continue;
+        }
+        if reconciled
+            .last()
+            .is_some_and(|p| p.uuid.is_none() && p.stopped_at <= p.started_at)
+        {
+            reconciled.pop();
+        }
+        reconciled.push(entry);
+    }
+
+    (reconciled, adjusted)
+}
+
+pub fn load_outbox(profile: &str) -> Vec<OutboxEntry> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(OUTBOX_FILE)).unwrap_or_default()
+}
+
+pub fn save_outbox(profile: &str, entries: &[OutboxEntry]) -> Result<(), String> {
+    profile_crypto::save_json(profile, &profile_dir(profile).join(OUTBOX_FILE), &entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_to_max() {
+        assert_eq!(backoff(1), Duration::from_secs(30));
+        assert_eq!(backoff(2), Duration::from_secs(60));
+        assert_eq!(backoff(4), Duration::from_secs(240));
+        assert_eq!(backoff(20), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn test_connectivity_transitions() {
+        let mut status = FreeloConnectivity::default();
+        let now = Instant::now();
+
+        assert!(status.should_attempt(now));
+        assert!(status.record_failure(now));
+        assert!(!status.record_failure(now));
+        assert!(status.is_offline());
+        assert!(!status.should_attempt(now + Duration::from_secs(30)));
+        assert!(status.should_attempt(now + Duration::from_secs(60)));
+
+        assert!(status.record_success().is_some());
+        assert!(!status.is_offline());
+        assert!(status.record_success().is_none());
+    }
+
+    #[test]
+    fn test_reconcile_outbox() {
+        use chrono::TimeZone;
+        let at = |h, m| Local.with_ymd_and_hms(2025, 3, 12, h, m, 0).unwrap();
+        let entry = |uuid: Option<&str>, task, from: DateTime<Local>, to: DateTime<Local>| OutboxEntry {
+            uuid: uuid.map(str::to_string),
+            task_id: Some(task),
+            note: String::new(),
+            started_at: from,
+            stopped_at: to,
+            segment_id: None,
+        };
+
+        let entries = vec![
+            entry(None, 2, at(9, 30), at(10, 15)),
+            entry(None, 1, at(9, 0), at(9, 45)),
+            entry(None, 3, at(9, 50), at(10, 0)),
+            entry(Some("abc"), 4, at(10, 10), at(11, 0)),
+        ];
+        let (reconciled, adjusted) = reconcile_outbox(entries);
+
+        let spans: Vec<(i32, i64)> = reconciled.iter().map(|e| (e.task_id.unwrap(), e.minutes())).collect();
+        // Task 3 je celý uvnitř task 2 a vypadne, task 2 končí začátkem běžícího timeru
+        assert_eq!(spans, vec![(1, 45), (2, 25), (4, 50)]);
+        assert_eq!(adjusted, 3);
+        assert_eq!(reconciled[1].started_at, at(9, 45));
+    }
+
+    #[test]
+    fn test_is_connectivity_error() {
+        assert!(is_connectivity_error("HTTP chyba: connection refused"));
+        assert!(is_connectivity_error("Freelo API error 503 Service Unavailable: "));
+        assert!(!is_connectivity_error("Freelo start tracking error 422 Unprocessable Entity: bad task"));
+    }
+}
@@ -0,0 +1,248 @@
+use crate::evidence::{open, seal};
+use crate::{secrets, storage};
+use aes_gcm::aead::{KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key};
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Začátek zašifrovaného souboru - soubory bez něj jsou ze starších verzí (nešifrované)
+const SEALED_MAGIC: &[u8] = b"TAENC1";
+
+/// Záložní klíče, když systém nemá klíčenku (mimo adresáře profilů, export je nepřibalí)
+const FALLBACK_KEY_DIR: &str = "profile_keys";
+
+/// Klíče načtené v tomto běhu - klíčenka se neptá při každém zápisu
+static KEYS: Mutex<Option<HashMap<String, Key<Aes256Gcm>>>> = Mutex::new(None);
+
+/// Kde leží klíče dat profilů
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyStore {
+    /// Klíčenka systému, bez ní soubor jen pro vlastníka
+    #[default]
+    Keychain,
+    /// Jen v paměti procesu (testy) - po skončení procesu klíče zaniknou
+    Memory,
+}
+
+static KEY_STORE: Mutex<KeyStore> = Mutex::new(KeyStore::Keychain);
+
+/// Klíče úložiště `KeyStore::Memory` (base64)
+static MEMORY_KEYS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Přepne úložiště klíčů pro celý proces
+pub fn set_key_store(store: KeyStore) {
+    if let Ok(mut current) = KEY_STORE.lock() {
+        *current = store;
+    }
+}
+
+fn key_store() -> KeyStore {
+    KEY_STORE.lock().map(|store| *store).unwrap_or_default()
+}
+
+fn memory_key(profile: &str, encoded: Option<&str>) -> Option<String> {
+    let mut keys = MEMORY_KEYS.lock().ok()?;
+    let keys = keys.get_or_insert_with(HashMap::new);
+    match encoded {
+        Some(encoded) => keys.insert(profile.to_string(), encoded.to_string()),
+        None => keys.remove(profile),
+    }
+}
+
+fn cached(profile: &str) -> Option<Key<Aes256Gcm>> {
+    KEYS.lock().ok()?.as_ref()?.get(profile).copied()
+}
+
+fn remember(profile: &str, key: Key<Aes256Gcm>) {
+    if let Ok(mut keys) = KEYS.lock() {
+        keys.get_or_insert_with(HashMap::new).insert(profile.to_string(), key);
+    }
+}
+
+fn fallback_key_path(profile: &str) -> PathBuf {
+    storage::data_file(FALLBACK_KEY_DIR).join(format!("{}.key", profile))
+}
+
+fn decode_key(encoded: &str) -> Result<Key<Aes256Gcm>, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| "Poškozený klíč dat profilu".to_string())?;
+    if bytes.len() != 32 {
+        return Err("Poškozený klíč dat profilu".to_string());
+    }
+    Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
+}
+
+fn store_fallback_key(profile: &str, encoded: &str) -> Result<(), String> {
+    let path = fallback_key_path(profile);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Nelze vytvořit {:?}: {}", parent, e))?;
+    }
+    std::fs::write(&path, encoded).map_err(|e| format!("Nelze uložit klíč {:?}: {}", path, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Nelze nastavit práva klíče {:?}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+/// Klíč dat profilu z klíčenky, při prvním použití ho vygeneruje. Bez klíčenky
+/// (např. Linux bez Secret Service) leží v souboru jen pro vlastníka.
+/// Chyba klíčenky (zamčená, odepřený přístup) není chybějící klíč - nový klíč by
+/// přepsal původní a data profilu by už nešla přečíst.
+pub fn profile_key(profile: &str) -> Result<Key<Aes256Gcm>, String> {
+    if let Some(key) = cached(profile) {
+        return Ok(key);
+    }
+
+    let stored = match key_store() {
+        KeyStore::Memory => MEMORY_KEYS.lock().ok().and_then(|keys| keys.as_ref()?.get(profile).cloned()),
+        KeyStore::Keychain => {
+            let fallback = std::fs::read_to_string(fallback_key_path(profile)).ok();
+            match secrets::load_profile_data_key(profile) {
+                Ok(Some(encoded)) => Some(encoded),
+                Ok(None) => fallback,
+                Err(e) => Some(fallback.ok_or(e)?),
+            }
+        }
+    };
+    let key = match stored {
+        Some(encoded) => decode_key(&encoded)?,
+        None => {
+            let key = Aes256Gcm::generate_key(OsRng);
+            let encoded = base64::engine::general_purpose::STANDARD.encode(key.as_slice());
+            if key_store() == KeyStore::Memory {
+                memory_key(profile, Some(&encoded));
+            } else if let Err(e) = secrets::store_profile_data_key(profile, Some(&encoded)) {
+                tracing::warn!(error = %e, "klíčenka nedostupná, klíč dat profilu zůstává v souboru");
+                store_fallback_key(profile, &encoded)?;
+            }
+            key
+        }
+    };
+
+    remember(profile, key);
+    Ok(key)
+}
+
+/// Smaže klíč profilu - kopie jeho dat, které někde zůstaly, už nepůjde dešifrovat
+pub fn delete_profile_key(profile: &str) -> Result<(), String> {
+    if let Ok(mut keys) = KEYS.lock() {
+        if let Some(keys) = keys.as_mut() {
+            keys.remove(profile);
+        }
+    }
+    if key_store() == KeyStore::Memory {
+        memory_key(profile, None);
+        return Ok(());
+    }
+
+    let path = fallback_key_path(profile);
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("Nelze smazat klíč {:?}: {}", path, e))?;
+    }
+    secrets::store_profile_data_key(profile, None)
+}
+
+fn encrypt(key: &Key<Aes256Gcm>, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut data = SEALED_MAGIC.to_vec();
+    data.extend(seal(key, plaintext)?);
+    Ok(data)
+}
+
+/// Dešifruje obsah souboru - soubor bez hlavičky je ze starší verze a vrátí se beze změny
+fn decrypt(key: &Key<Aes256Gcm>, data: Vec<u8>) -> Result<Vec<u8>, String> {
+    match data.strip_prefix(SEALED_MAGIC) {
+        Some(sealed) => open(key, sealed),
+        None => Ok(data),
+    }
+}
+
+/// Přečte a dešifruje soubor profilu
+pub fn read(profile: &str, path: &Path) -> Result<Vec<u8>, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Nelze číst {:?}: {}", path, e))?;
+    decrypt(&profile_key(profile)?, data).map_err(|e| format!("{:?}: {}", path, e))
+}
+
+/// Zašifruje a zapíše soubor profilu (přes dočasný soubor, aby nevznikl poloviční zápis)
+pub fn write(profile: &str, path: &Path, plaintext: &[u8]) -> Result<(), String> {
+    let data = encrypt(&profile_key(profile)?, plaintext)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Chyba při vytváření adresáře {:?}: {}", parent, e))?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, data)
+        .map_err(|e| format!("Chyba při zápisu {:?}: {}", tmp_path, e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Chyba při přejmenování {:?}: {}", path, e))
+}
+
+/// Načte zašifrovaný JSON profilu, při chybě nebo neexistenci vrací None
+pub fn load_json<T: DeserializeOwned>(profile: &str, path: &Path) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+    let content = match read(profile, path) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::warn!("⚠️  Nepodařilo se načíst {:?}: {}", path, e);
+            return None;
+        }
+    };
+    match serde_json::from_slice(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("⚠️  Nepodařilo se načíst {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Uloží hodnotu jako zašifrovaný JSON profilu
+pub fn save_json<T: Serialize>(profile: &str, path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Chyba při serializaci: {}", e))?;
+    write(profile, path, &content)
+}
+
+/// Klíč databáze profilu pro SQLCipher (`PRAGMA key`) - syrový klíč bez odvozování z hesla
+pub fn sqlite_key(profile: &str) -> Result<String, String> {
+    let hex: String = profile_key(profile)?.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!("x'{}'", hex))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_and_legacy_files() {
+        let key = Aes256Gcm::generate_key(OsRng);
+
+        let sealed = encrypt(&key, br#"{"a": 1}"#).unwrap();
+        assert!(sealed.starts_with(SEALED_MAGIC));
+        assert_eq!(decrypt(&key, sealed.clone()).unwrap(), br#"{"a": 1}"#);
+
+        // Jiný klíč (smazaný profil) data neotevře
+        let other_key = Aes256Gcm::generate_key(OsRng);
+        assert!(decrypt(&other_key, sealed).is_err());
+
+        // Nešifrovaný soubor ze starší verze se přečte a příštím zápisem zašifruje
+        let legacy = br#"{"a": 1}"#.to_vec();
+        assert_eq!(decrypt(&key, legacy.clone()).unwrap(), legacy);
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(key.as_slice());
+        assert_eq!(decode_key(&encoded).unwrap(), key);
+        assert!(decode_key("krátký").is_err());
+    }
+}
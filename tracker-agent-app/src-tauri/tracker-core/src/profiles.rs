@@ -0,0 +1,200 @@
+use crate::debug_artifacts::get_debug_dir;
+use crate::{history, profile_crypto, storage};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Profil používaný, pokud uživatel žádný nezvolí
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Přehled dat jednoho profilu (klienta)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub data_dir: String,
+    pub debug_dir: String,
+    pub size_bytes: u64,
+}
+
+/// Ověří název profilu - používá se jako název adresáře, takže jen bezpečné znaky
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("Název profilu musí mít 1-64 znaků".to_string());
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Neplatný název profilu '{}' (povoleno a-z, 0-9, - a _)", name));
+    }
+    Ok(())
+}
+
+/// Kořen adresářů profilů mimo datový adresář aplikace (testy), None = výchozí
+static PROFILES_ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Přesměruje adresáře všech profilů (platí pro celý proces)
+pub fn set_profiles_root(root: Option<PathBuf>) {
+    if let Ok(mut current) = PROFILES_ROOT.lock() {
+        *current = root;
+    }
+}
+
+fn profiles_root() -> PathBuf {
+    PROFILES_ROOT
+        .lock()
+        .ok()
+        .and_then(|root| root.clone())
+        .unwrap_or_else(|| storage::app_data_dir().join("profiles"))
+}
+
+/// Datový adresář profilu (historie, exporty...) - vytvoří ho pokud neexistuje
+pub fn profile_dir(profile: &str) -> PathBuf {
+    let path = profiles_root().join(profile);
+    if !path.exists() {
+        std::fs::create_dir_all(&path).ok();
+    }
+    path
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| {
+            let path = e.path();
+            if path.is_dir() {
+                dir_size(&path)
+            } else {
+                e.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Zkopíruje adresář profilu, soubory zašifrované klíčem profilu dešifruje
+fn copy_dir(profile: &str, from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| format!("Nelze vytvořit {:?}: {}", to, e))?;
+
+    let entries = std::fs::read_dir(from).map_err(|e| format!("Nelze číst {:?}: {}", from, e))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if source.is_dir() {
+            copy_dir(profile, &source, &target)?;
+        } else {
+            let content = profile_crypto::read(profile, &source)?;
+            std::fs::write(&target, content)
+                .map_err(|e| format!("Nelze zkopírovat {:?}: {}", source, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Seznam profilů, které mají nějaká data
+pub fn list_profiles() -> Vec<ProfileInfo> {
+    let mut names: Vec<String> = std::fs::read_dir(profiles_root())
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if !names.iter().any(|n| n == DEFAULT_PROFILE) {
+        names.push(DEFAULT_PROFILE.to_string());
+    }
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let data_dir = profiles_root().join(&name);
+            let debug_dir = get_debug_dir(&name);
+            ProfileInfo {
+                size_bytes: dir_size(&data_dir) + dir_size(&debug_dir),
+                data_dir: data_dir.to_string_lossy().to_string(),
+                debug_dir: debug_dir.to_string_lossy().to_string(),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Zkopíruje všechna data profilu (data + debug artefakty) do cílového adresáře
+pub fn export_profile(profile: &str, destination: &Path) -> Result<PathBuf, String> {
+    validate_profile_name(profile)?;
+
+    let target = destination.join(format!(
+        "{}-{}",
+        profile,
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+
+    copy_dir(profile, &profile_dir(profile), &target.join("data"))?;
+    copy_dir(profile, &get_debug_dir(profile), &target.join("debug_screenshots"))?;
+    // Log rozhodnutí je databáze SQLCipher - dešifruje se exportem celé databáze
+    if profile_dir(profile).join(history::DECISIONS_DB).exists() {
+        history::export_decisions(profile, &target.join("data").join(history::DECISIONS_DB))?;
+    }
+
+    Ok(target)
+}
+
+/// Smaže všechna data profilu (data + debug artefakty) i jeho šifrovací klíč,
+/// ostatních profilů se nedotkne
+pub fn delete_profile(profile: &str) -> Result<(), String> {
+    validate_profile_name(profile)?;
+
+    for dir in [profiles_root().join(profile), get_debug_dir(profile)] {
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| format!("Nelze smazat {:?}: {}", dir, e))?;
+        }
+    }
+
+    profile_crypto::delete_profile_key(profile)
+}
+
+/// Profil pro testy - data v dočasném adresáři, klíč jen v paměti (mimo klíčenku).
+/// Smaže se při dropu, tedy i když test selže.
+#[cfg(test)]
+pub(crate) struct TestProfile {
+    pub name: String,
+}
+
+#[cfg(test)]
+impl TestProfile {
+    pub(crate) fn new(name: &str) -> Self {
+        static ISOLATE: std::sync::Once = std::sync::Once::new();
+        ISOLATE.call_once(|| {
+            set_profiles_root(Some(std::env::temp_dir().join(format!("tracker-profiles-{}", std::process::id()))));
+            profile_crypto::set_key_store(profile_crypto::KeyStore::Memory);
+        });
+        Self { name: format!("test-{}", name) }
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestProfile {
+    fn drop(&mut self) {
+        if let Err(e) = delete_profile(&self.name) {
+            eprintln!("Testovací profil {} nelze smazat: {}", self.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("acme-corp_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("a b").is_err());
+    }
+}
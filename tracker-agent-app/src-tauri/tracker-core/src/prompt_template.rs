@@ -0,0 +1,123 @@
+use crate::config_files::SyntaxError;
+use regex::{Captures, Regex};
+use std::sync::{LazyLock, RwLock};
+
+/// Vlastní šablona promptu pro AI matching v datovém adresáři aplikace
+pub const PROMPT_TEMPLATE_FILE: &str = "match_prompt.txt";
+
+/// Proměnné šablony - vše ostatní ve složených závorkách (např. JSON) zůstává beze změny
+const PLACEHOLDERS: [&str; 7] = [
+    "window",
+    "ocr_text",
+    "language_hint",
+    "recent_activity",
+    "examples",
+    "tasks",
+    "answer_format",
+];
+
+/// Bez nich AI nemá z čeho vybírat nebo odpoví v jiném formátu
+const REQUIRED: [&str; 3] = ["ocr_text", "tasks", "answer_format"];
+
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([a-z_]+)\}").unwrap());
+
+/// Platná šablona ze souboru, None = vestavěný prompt
+static ACTIVE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Hodnoty proměnných pro jeden request
+pub struct PromptValues<'a> {
+    pub window: &'a str,
+    pub ocr_text: &'a str,
+    pub language_hint: &'a str,
+    pub recent_activity: &'a str,
+    /// Příklady z ručních oprav přiřazení (prázdné = žádné)
+    pub examples: &'a str,
+    pub tasks: &'a str,
+    pub answer_format: &'a str,
+}
+
+/// Ověří šablonu - neznámá proměnná s řádkem a sloupcem, chybějící povinná bez pozice
+pub fn validate(template: &str) -> Result<(), SyntaxError> {
+    for (i, line) in template.lines().enumerate() {
+        for caps in PLACEHOLDER.captures_iter(line) {
+            let name = &caps[1];
+            if !PLACEHOLDERS.contains(&name) {
+                let column = line[..caps.get(0).map_or(0, |m| m.start())].chars().count() + 1;
+                return Err(SyntaxError {
+                    line: Some(i + 1),
+                    column: Some(column),
+                    message: format!("Neznámá proměnná {{{}}} (povolené: {})", name, PLACEHOLDERS.join(", ")),
+                });
+            }
+        }
+    }
+    for name in REQUIRED {
+        if !template.contains(&format!("{{{}}}", name)) {
+            return Err(SyntaxError {
+                line: None,
+                column: None,
+                message: format!("Šablona musí obsahovat proměnnou {{{}}}", name),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Dosadí hodnoty v jednom průchodu (dosazený text se už dál nenahrazuje)
+pub fn render(template: &str, values: &PromptValues) -> String {
+    PLACEHOLDER
+        .replace_all(template, |caps: &Captures| match &caps[1] {
+            "window" => values.window.to_string(),
+            "ocr_text" => values.ocr_text.to_string(),
+            "language_hint" => values.language_hint.to_string(),
+            "recent_activity" => values.recent_activity.to_string(),
+            "examples" => values.examples.to_string(),
+            "tasks" => values.tasks.to_string(),
+            "answer_format" => values.answer_format.to_string(),
+            _ => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Nastaví platnou šablonu (None = zpět na vestavěný prompt)
+pub fn set_active(template: Option<String>) {
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = template;
+    }
+}
+
+pub fn active() -> Option<String> {
+    ACTIVE.read().ok().and_then(|t| t.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_template() {
+        let template = "Okno: {window}\nText: {ocr_text}\nTasky:\n{tasks}\n{answer_format}\nPříklad: {\"task_id\": 1}";
+        assert!(validate(template).is_ok());
+
+        let values = PromptValues {
+            window: "VS Code | main.rs",
+            ocr_text: "fn main {tasks}",
+            language_hint: "",
+            recent_activity: "",
+            examples: "",
+            tasks: "- 1: Login",
+            answer_format: "JSON",
+        };
+        let prompt = render(template, &values);
+        // Text z obrazovky s proměnnou se znovu nenahrazuje
+        assert!(prompt.contains("Text: fn main {tasks}\n"));
+        assert!(prompt.contains("Tasky:\n- 1: Login\n"));
+        assert!(prompt.ends_with("{\"task_id\": 1}"));
+
+        let unknown = validate("{ocr_text} {tasks}\n  čtení {answer_fromat}").unwrap_err();
+        assert_eq!((unknown.line, unknown.column), (Some(2), Some(9)));
+        let missing = validate("{ocr_text} {answer_format}").unwrap_err();
+        assert_eq!(missing.line, None);
+        assert!(missing.message.contains("{tasks}"));
+    }
+}
@@ -0,0 +1,142 @@
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Kolikrát se request zkusí znovu po 429/503
+const MAX_RETRIES: u32 = 3;
+
+/// Základ exponenciálního backoffu
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Strop jedné pauzy (i když server v Retry-After chce víc)
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Token bucket sdílený všemi klienty jedné služby (klient se vytváří každý tick znovu)
+pub struct RateLimiter {
+    state: Mutex<BucketState>,
+    capacity: f64,
+    per_second: f64,
+}
+
+struct BucketState {
+    tokens: f64,
+    updated: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub const fn new(capacity: u32, per_second: u32) -> Self {
+        Self {
+            state: Mutex::new(BucketState {
+                tokens: capacity as f64,
+                updated: None,
+            }),
+            capacity: capacity as f64,
+            per_second: per_second as f64,
+        }
+    }
+
+    /// Rezervuje token a vrátí, jak dlouho se má před requestem počkat
+    fn reserve(&self, now: Instant) -> Duration {
+        let Ok(mut state) = self.state.lock() else {
+            return Duration::ZERO;
+        };
+        let elapsed = state.updated.map(|t| now.duration_since(t).as_secs_f64()).unwrap_or_default();
+        state.tokens = (state.tokens + elapsed * self.per_second).min(self.capacity);
+        state.updated = Some(now);
+
+        // Záporný stav = dluh za requesty, které už na token čekají
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.per_second)
+        }
+    }
+
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Pauza před dalším pokusem - exponenciální s náhodným rozptylem, Retry-After má přednost
+fn retry_delay(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    if let Some(after) = retry_after {
+        return after.min(MAX_DELAY);
+    }
+    let exponential = BASE_DELAY.saturating_mul(1 << attempt.min(16)).min(MAX_DELAY);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or_default();
+    let jitter = exponential.mul_f64((nanos % 1000) as f64 / 1000.0) / 2;
+    exponential / 2 + jitter
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds: u64 = response.headers().get("Retry-After")?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Odeslání přes rate limiter s opakováním po 429/503. Chyby spojení se neopakují -
+/// ty řeší offline režim.
+// Implementace jsou jen v tomto crate, Send futures hlídá až tokio::spawn v trackeru
+#[allow(async_fn_in_trait)]
+pub trait SendWithRetry {
+    async fn send_with_retry(self, limiter: &RateLimiter) -> Result<Response, String>;
+}
+
+impl SendWithRetry for RequestBuilder {
+    async fn send_with_retry(self, limiter: &RateLimiter) -> Result<Response, String> {
+        let mut attempt = 0;
+        loop {
+            // Request s tělem, které nejde naklonovat (stream), se pošle jen jednou
+            let Some(request) = self.try_clone() else {
+                limiter.acquire().await;
+                return self.send().await.map_err(|e| format!("HTTP chyba: {}", e));
+            };
+            limiter.acquire().await;
+            let response = request.send().await.map_err(|e| format!("HTTP chyba: {}", e))?;
+
+            let status = response.status();
+            let retryable = status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE;
+            if !retryable || attempt >= MAX_RETRIES {
+                return Ok(response);
+            }
+
+            let delay = retry_delay(attempt, retry_after(&response));
+            warn!(status = status.as_u16(), attempt = attempt + 1, delay_ms = delay.as_millis() as u64, "API přetížené, opakuji request");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_and_backoff() {
+        let limiter = RateLimiter::new(2, 1);
+        let start = Instant::now();
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_secs(1));
+        assert_eq!(limiter.reserve(start), Duration::from_secs(2));
+        // Po 5 s je dluh splacený a bucket zase plný jen do kapacity
+        let later = start + Duration::from_secs(5);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert!(limiter.reserve(later) > Duration::ZERO);
+
+        for attempt in 0..4 {
+            let exponential = BASE_DELAY * (1 << attempt);
+            let delay = retry_delay(attempt, None);
+            assert!(delay >= exponential / 2 && delay <= exponential, "{:?}", delay);
+        }
+        assert_eq!(retry_delay(10, None).max(MAX_DELAY), MAX_DELAY);
+        assert_eq!(retry_delay(0, Some(Duration::from_secs(7))), Duration::from_secs(7));
+        assert_eq!(retry_delay(0, Some(Duration::from_secs(600))), MAX_DELAY);
+    }
+}
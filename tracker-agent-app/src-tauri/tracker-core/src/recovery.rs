@@ -0,0 +1,107 @@
+use crate::freelo::ActiveTracking;
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Soubor s běžícím timerem v adresáři profilu - po pádu aplikace zůstane na disku
+const RUNNING_TIMER_FILE: &str = "running_timer.json";
+
+/// Timer spuštěný ve službě pro čas, který ještě nebyl zastaven
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunningTimer {
+    pub uuid: String,
+    /// Klíč trackingu (ID tasku nebo kategorie)
+    pub task_id: String,
+    pub note: String,
+    pub application: String,
+    pub activity: String,
+    pub started_at: DateTime<Local>,
+    /// Služba, ve které timer běží ("Freelo", "Toggl", ...)
+    pub backend: String,
+}
+
+/// Co udělat s timerem, který po pádu zůstal běžet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// Zastavit timer a zapsat segment do historie
+    Stop,
+    /// Převzít timer jako aktuální segment a pokračovat v trackingu
+    Adopt,
+    /// Zapomenout záznam (timer se ukončí ručně ve službě)
+    Dismiss,
+}
+
+impl RunningTimer {
+    pub fn from_tracking(tracking: &ActiveTracking, backend: &str) -> Self {
+        Self {
+            uuid: tracking.uuid.clone(),
+            task_id: tracking.task_id.clone(),
+            note: tracking.note.clone(),
+            application: tracking.last_application.clone(),
+            activity: tracking.note_activity.clone(),
+            started_at: DateTime::<Local>::from(tracking.start_time),
+            backend: backend.to_string(),
+        }
+    }
+
+    /// Segment navazující na původní začátek timeru
+    pub fn to_tracking(&self) -> ActiveTracking {
+        let mut tracking = ActiveTracking::new(
+            self.task_id.clone(),
+            self.uuid.clone(),
+            self.application.clone(),
+            self.activity.clone(),
+            self.note.clone(),
+        );
+        tracking.start_time = self.started_at.into();
+        tracking
+    }
+}
+
+pub fn load(profile: &str) -> Option<RunningTimer> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(RUNNING_TIMER_FILE))
+}
+
+pub fn save(profile: &str, timer: &RunningTimer) -> Result<(), String> {
+    profile_crypto::save_json(profile, &profile_dir(profile).join(RUNNING_TIMER_FILE), timer)
+}
+
+pub fn clear(profile: &str) -> Result<(), String> {
+    let path = profile_dir(profile).join(RUNNING_TIMER_FILE);
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Nelze smazat {:?}: {}", path, e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_timer_roundtrip() {
+        let mut tracking = ActiveTracking::new(
+            "4242".to_string(),
+            "uuid-1".to_string(),
+            "IntelliJ IDEA".to_string(),
+            "Refaktoring objednávek".to_string(),
+            "Refaktoring objednávek".to_string(),
+        );
+        let started = Local::now() - chrono::Duration::hours(3);
+        tracking.start_time = started.into();
+
+        let timer = RunningTimer::from_tracking(&tracking, "Freelo");
+        let json = serde_json::to_string(&timer).unwrap();
+        assert_eq!(serde_json::from_str::<RunningTimer>(&json).unwrap(), timer);
+
+        let adopted = timer.to_tracking();
+        assert_eq!((adopted.task_id.as_str(), adopted.uuid.as_str()), ("4242", "uuid-1"));
+        assert_eq!(DateTime::<Local>::from(adopted.start_time), started);
+        assert_eq!(adopted.note, "Refaktoring objednávek");
+
+        let action: RecoveryAction = serde_json::from_str("\"adopt\"").unwrap();
+        assert_eq!(action, RecoveryAction::Adopt);
+    }
+}
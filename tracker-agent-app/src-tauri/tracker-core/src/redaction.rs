@@ -0,0 +1,213 @@
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Minimální délka tokenu, u kterého má smysl měřit entropii (API klíče, tokeny)
+const MIN_SECRET_CHARS: usize = 24;
+
+/// Náhodné klíče mají entropii kolem 4-6 bitů na znak, běžná slova/ID výrazně méně
+const MIN_SECRET_ENTROPY: f64 = 3.5;
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap());
+
+/// Hodnota za "heslo:" / "password=" apod.
+static PASSWORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(heslo|password|passwd|pwd|pin)(\s*[:=]\s*)\S+").unwrap());
+
+static IBAN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b").unwrap());
+
+/// Číslo účtu v českém formátu (předčíslí-číslo/kód banky)
+static BANK_ACCOUNT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:\d{1,6}-)?\d{6,10}/\d{4}\b").unwrap());
+
+static CARD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap());
+
+/// Klíče se známým prefixem (OpenRouter/OpenAI, GitHub, Slack, AWS)
+static KNOWN_KEY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:sk-[A-Za-z0-9_-]{16,}|gh[pousr]_[A-Za-z0-9]{20,}|xox[abprs]-[A-Za-z0-9-]{10,}|AKIA[0-9A-Z]{16})\b")
+        .unwrap()
+});
+
+static TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z0-9_+-]{24,}").unwrap());
+
+/// Výsledek čištění - text bez citlivých údajů a počty náhrad podle typu
+#[derive(Debug, Default)]
+pub struct Redacted {
+    pub text: String,
+    pub counts: BTreeMap<&'static str, usize>,
+}
+
+impl Redacted {
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Např. "2× email, 1× karta" pro log (bez samotných hodnot)
+    pub fn summary(&self) -> String {
+        self.counts
+            .iter()
+            .map(|(kind, count)| format!("{}× {}", count, kind))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn luhn_valid(digits: &str) -> bool {
+    let digits: Vec<u32> = digits.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Kontrolní součet IBAN (mod 97)
+fn iban_valid(iban: &str) -> bool {
+    let compact: String = iban.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.len() < 15 || compact.len() > 34 {
+        return false;
+    }
+    let rearranged = format!("{}{}", &compact[4..], &compact[..4]);
+    let mut remainder: u32 = 0;
+    for c in rearranged.chars() {
+        let Some(value) = c.to_digit(36) else {
+            return false;
+        };
+        let base = if value >= 10 { 100 } else { 10 };
+        remainder = (remainder * base + value) % 97;
+    }
+    remainder == 1
+}
+
+/// Shannonova entropie v bitech na znak
+fn entropy(token: &str) -> f64 {
+    let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+    for c in token.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = token.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Token vypadá jako náhodný klíč - mix písmen a číslic s vysokou entropií
+fn looks_like_secret(token: &str) -> bool {
+    token.len() >= MIN_SECRET_CHARS
+        && token.chars().any(|c| c.is_ascii_digit())
+        && token.chars().any(|c| c.is_ascii_lowercase())
+        && token.chars().any(|c| c.is_ascii_uppercase())
+        && entropy(token) >= MIN_SECRET_ENTROPY
+}
+
+fn replace(
+    text: &str,
+    regex: &Regex,
+    kind: &'static str,
+    counts: &mut BTreeMap<&'static str, usize>,
+    accept: impl Fn(&Captures) -> Option<String>,
+) -> String {
+    regex
+        .replace_all(text, |caps: &Captures| match accept(caps) {
+            Some(replacement) => {
+                *counts.entry(kind).or_default() += 1;
+                replacement
+            }
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Odstraní z textu e-maily, hesla, IBAN/čísla účtů, platební karty a API klíče
+pub fn redact(text: &str) -> Redacted {
+    let mut counts = BTreeMap::new();
+    let mut text = replace(text, &PASSWORD, "heslo", &mut counts, |c| {
+        Some(format!("{}{}[HESLO]", &c[1], &c[2]))
+    });
+    text = replace(&text, &EMAIL, "email", &mut counts, |_| Some("[EMAIL]".to_string()));
+    text = replace(&text, &KNOWN_KEY, "klíč", &mut counts, |_| Some("[KLÍČ]".to_string()));
+    text = replace(&text, &IBAN, "iban", &mut counts, |c| {
+        iban_valid(&c[0]).then(|| "[IBAN]".to_string())
+    });
+    text = replace(&text, &BANK_ACCOUNT, "účet", &mut counts, |_| Some("[ÚČET]".to_string()));
+    text = replace(&text, &CARD, "karta", &mut counts, |c| {
+        luhn_valid(&c[0]).then(|| "[KARTA]".to_string())
+    });
+    text = replace(&text, &TOKEN, "klíč", &mut counts, |c| {
+        looks_like_secret(&c[0]).then(|| "[KLÍČ]".to_string())
+    });
+    Redacted { text, counts }
+}
+
+/// Regex citlivého údaje a kontrola nalezené shody (checksum, entropie)
+type Detector<'a> = (&'a Regex, fn(&str) -> bool);
+
+/// Kde v textu citlivé údaje jsou (bajtové rozsahy) - stejné detektory jako `redact`,
+/// pro zakrytí slov ve screenshotu
+pub fn sensitive_spans(text: &str) -> Vec<Range<usize>> {
+    let detectors: [Detector; 7] = [
+        (&PASSWORD, |_| true),
+        (&EMAIL, |_| true),
+        (&KNOWN_KEY, |_| true),
+        (&IBAN, iban_valid),
+        (&BANK_ACCOUNT, |_| true),
+        (&CARD, luhn_valid),
+        (&TOKEN, looks_like_secret),
+    ];
+    let mut spans: Vec<Range<usize>> = detectors
+        .iter()
+        .flat_map(|(regex, valid)| regex.find_iter(text).filter(|m| valid(m.as_str())).map(|m| m.range()))
+        .collect();
+    spans.sort_by_key(|span| span.start);
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let text = "Od: jan.novak@firma.cz\n\
+                    IBAN CZ65 0800 0000 1920 0014 5399, účet 19-2000145399/0800\n\
+                    Karta 4111 1111 1111 1111, objednávka 1234 5678 9012 3456\n\
+                    heslo: Tajne123 a OPENROUTER=sk-or-v1-abcdef0123456789abcdef\n\
+                    token aZ3kQ9pL2mX7vB4nR8tY1wE6uI5oP0sD, commit 4f2a9c1e\n\
+                    Editace src/tracker.rs v VS Code";
+        let redacted = redact(text);
+
+        assert!(redacted.text.contains("Od: [EMAIL]"));
+        assert!(redacted.text.contains("IBAN [IBAN], účet [ÚČET]"));
+        assert!(redacted.text.contains("Karta [KARTA], objednávka 1234 5678 9012 3456"));
+        assert!(redacted.text.contains("heslo: [HESLO]"));
+        assert!(redacted.text.contains("OPENROUTER=[KLÍČ]"));
+        assert!(redacted.text.contains("token [KLÍČ], commit 4f2a9c1e"));
+        assert!(redacted.text.contains("Editace src/tracker.rs v VS Code"));
+        assert_eq!(redacted.total(), 7);
+        assert_eq!(redacted.counts["klíč"], 2);
+    }
+
+    #[test]
+    fn test_sensitive_spans() {
+        let text = "Od: jan.novak@firma.cz, karta 4111 1111 1111 1111, objednávka 1234 5678 9012 3456";
+        let spans: Vec<&str> = sensitive_spans(text).into_iter().map(|span| &text[span]).collect();
+        assert_eq!(spans, vec!["jan.novak@firma.cz", "4111 1111 1111 1111"]);
+        assert!(sensitive_spans("Editace src/tracker.rs v VS Code").is_empty());
+    }
+}
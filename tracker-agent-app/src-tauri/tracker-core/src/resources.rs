@@ -0,0 +1,130 @@
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Max. vláken Tesseractu (OpenMP) - víc jader OCR nepotřebuje
+pub const OCR_THREAD_LIMIT: usize = 2;
+
+/// Kolik OCR úloh smí běžet současně (spawn_blocking)
+pub const MAX_CONCURRENT_OCR: usize = 1;
+
+/// Výchozí strop paměti agenta v MB (0 = bez limitu)
+pub const DEFAULT_MEMORY_LIMIT_MB: u64 = 500;
+
+/// OCR delší než tento podíl intervalu znamená, že agent zatěžuje CPU
+const OCR_BUDGET_FRACTION: f64 = 0.5;
+
+/// Pod tímto podílem limitu (paměť i OCR) se degradace postupně vrací
+const RELAX_FRACTION: f64 = 0.8;
+
+/// Stupně degradace: (měřítko snímku, násobek intervalu)
+const LEVELS: [(f32, u64); 3] = [(1.0, 1), (0.75, 2), (0.5, 4)];
+
+static OCR_SLOTS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_OCR);
+
+/// Omezí vlákna Tesseractu - musí proběhnout před prvním OCR (OpenMP čte env při startu)
+pub fn limit_ocr_threads() {
+    if std::env::var_os("OMP_THREAD_LIMIT").is_none() {
+        std::env::set_var("OMP_THREAD_LIMIT", OCR_THREAD_LIMIT.to_string());
+    }
+}
+
+/// Volný slot pro OCR, None = limit souběžných OCR je vyčerpaný
+pub fn try_ocr_slot() -> Option<SemaphorePermit<'static>> {
+    OCR_SLOTS.try_acquire().ok()
+}
+
+/// Paměť (RSS) vlastního procesu v MB, None = na platformě nezjistíme
+pub fn current_rss_mb() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb / 1024)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+            .output()
+            .ok()?;
+        let kb: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(kb / 1024)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Stav omezování zátěže (sdílený mezi tickem a smyčkou)
+#[derive(Debug, Default)]
+pub struct ResourceGovernor {
+    /// Stupeň degradace, 0 = plná kvalita
+    pub level: usize,
+    /// Délka posledního OCR
+    pub last_ocr_seconds: f64,
+}
+
+/// Naměřená zátěž agenta za poslední tick
+#[derive(Debug, Clone, Copy)]
+pub struct Load {
+    pub rss_mb: Option<u64>,
+    pub ocr_seconds: f64,
+}
+
+/// Stupeň degradace podle zátěže - při překročení limitu o stupeň výš,
+/// při výrazně nižší zátěži o stupeň zpět
+pub fn next_level(level: usize, load: Load, memory_limit_mb: u64, interval_seconds: u64) -> usize {
+    let memory = load
+        .rss_mb
+        .filter(|_| memory_limit_mb > 0)
+        .map(|rss| rss as f64 / memory_limit_mb as f64)
+        .unwrap_or(0.0);
+    let cpu = load.ocr_seconds / (interval_seconds.max(1) as f64 * OCR_BUDGET_FRACTION);
+
+    if memory > 1.0 || cpu > 1.0 {
+        (level + 1).min(LEVELS.len() - 1)
+    } else if memory < RELAX_FRACTION && cpu < RELAX_FRACTION {
+        level.saturating_sub(1)
+    } else {
+        level
+    }
+}
+
+/// Měřítko snímku pro stupeň degradace
+pub fn capture_scale(level: usize) -> f32 {
+    LEVELS[level.min(LEVELS.len() - 1)].0
+}
+
+/// Interval ticku pro stupeň degradace
+pub fn effective_interval(level: usize, interval_seconds: u64) -> u64 {
+    interval_seconds * LEVELS[level.min(LEVELS.len() - 1)].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_level() {
+        let load = |rss_mb: u64, ocr_seconds: f64| Load {
+            rss_mb: Some(rss_mb),
+            ocr_seconds,
+        };
+
+        // Paměť přes limit, pak i CPU - nahoru až po poslední stupeň
+        assert_eq!(next_level(0, load(600, 1.0), 500, 10), 1);
+        assert_eq!(next_level(1, load(300, 6.0), 500, 10), 2);
+        assert_eq!(next_level(2, load(600, 6.0), 500, 10), 2);
+
+        // Mezi 80 % a 100 % se stupeň drží, pod 80 % klesá
+        assert_eq!(next_level(2, load(450, 1.0), 500, 10), 2);
+        assert_eq!(next_level(2, load(200, 1.0), 500, 10), 1);
+
+        // Limit 0 = paměť se nehlídá
+        assert_eq!(next_level(0, load(5000, 1.0), 0, 10), 0);
+
+        assert_eq!(capture_scale(2), 0.5);
+        assert_eq!(effective_interval(1, 10), 20);
+    }
+}
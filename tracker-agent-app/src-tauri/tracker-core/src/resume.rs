@@ -0,0 +1,152 @@
+use crate::window_info::WindowInfo;
+use chrono::{DateTime, Duration, Local};
+
+/// Výchozí prodleva po probuzení, kdy se nic neanalyzuje
+pub const DEFAULT_RESUME_WARMUP_SECONDS: u64 = 30;
+
+/// Mezera mezi ticky delší než N intervalů (a alespoň minuta) = počítač spal
+const RESUME_GAP_INTERVALS: i64 = 3;
+const RESUME_MIN_GAP_SECONDS: i64 = 60;
+
+/// Na kolika prvních analyzovaných ticích po probuzení hledat zamykací obrazovku
+const LOCK_SCREEN_CHECK_TICKS: u32 = 3;
+
+/// Zamykací obrazovka má málo textu - delší OCR už je běžná práce
+const LOCK_SCREEN_MAX_CHARS: usize = 400;
+
+/// Procesy zamykací obrazovky / spořiče (macOS, Windows, Linux)
+const LOCK_SCREEN_APPS: &[&str] = &[
+    "loginwindow",
+    "screensaverengine",
+    "lockapp",
+    "logonui",
+    "gnome-screensaver",
+    "xscreensaver",
+    "i3lock",
+    "swaylock",
+];
+
+/// Typické texty zamykací obrazovky
+const LOCK_SCREEN_MARKERS: &[&str] = &[
+    "enter password",
+    "zadejte heslo",
+    "touch id",
+    "switch user",
+    "přepnout uživatele",
+    "screen locked",
+    "uzamčeno",
+    "zamčeno",
+    "slide to unlock",
+];
+
+/// Co dělat s tickem vzhledem k probuzení ze spánku
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResumeGate {
+    /// Normální tick
+    Run,
+    /// Právě detekované probuzení (jak dlouho počítač spal)
+    JustResumed(Duration),
+    /// Běží prodleva po probuzení
+    WarmingUp(Duration),
+}
+
+/// Sleduje mezery mezi ticky (wall clock) a prodlevu po probuzení
+#[derive(Debug, Default)]
+pub struct ResumeGuard {
+    last_tick_at: Option<DateTime<Local>>,
+    resumed_at: Option<DateTime<Local>>,
+    /// Analyzované ticky od probuzení (pro detekci zamykací obrazovky)
+    ticks_since_resume: u32,
+}
+
+impl ResumeGuard {
+    pub fn on_tick(&mut self, now: DateTime<Local>, interval_seconds: u64, warmup_seconds: u64) -> ResumeGate {
+        let previous = self.last_tick_at.replace(now);
+        let max_gap = Duration::seconds((interval_seconds as i64 * RESUME_GAP_INTERVALS).max(RESUME_MIN_GAP_SECONDS));
+
+        if let Some(previous) = previous {
+            let gap = now - previous;
+            if gap > max_gap {
+                self.resumed_at = Some(now);
+                self.ticks_since_resume = 0;
+                if warmup_seconds > 0 {
+                    return ResumeGate::JustResumed(gap);
+                }
+            }
+        }
+
+        if let Some(resumed_at) = self.resumed_at {
+            let remaining = resumed_at + Duration::seconds(warmup_seconds as i64) - now;
+            if remaining > Duration::zero() {
+                return ResumeGate::WarmingUp(remaining);
+            }
+        }
+
+        ResumeGate::Run
+    }
+
+    /// Zda je tick mezi prvními po probuzení, kde hrozí zamykací obrazovka
+    pub fn check_lock_screen(&mut self) -> bool {
+        if self.resumed_at.is_none() {
+            return false;
+        }
+        self.ticks_since_resume += 1;
+        if self.ticks_since_resume > LOCK_SCREEN_CHECK_TICKS {
+            self.resumed_at = None;
+            return false;
+        }
+        true
+    }
+}
+
+/// Zda snímek vypadá jako zamykací obrazovka (podle aktivního procesu nebo textu)
+pub fn looks_like_lock_screen(ocr_text: Option<&str>, window: Option<&WindowInfo>) -> bool {
+    if let Some(window) = window {
+        let app_name = window.app_name.to_lowercase();
+        if LOCK_SCREEN_APPS.iter().any(|app| app_name.contains(app)) {
+            return true;
+        }
+    }
+
+    let Some(text) = ocr_text else {
+        return false;
+    };
+    let text = text.to_lowercase();
+    text.chars().count() <= LOCK_SCREEN_MAX_CHARS && LOCK_SCREEN_MARKERS.iter().any(|m| text.contains(m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_resume_detection_and_warmup() {
+        let start = Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap();
+        let at = |secs| start + Duration::seconds(secs);
+        let mut guard = ResumeGuard::default();
+
+        assert_eq!(guard.on_tick(at(0), 10, 30), ResumeGate::Run);
+        assert_eq!(guard.on_tick(at(10), 10, 30), ResumeGate::Run);
+        assert!(!guard.check_lock_screen());
+
+        // Noc ve spánku
+        assert_eq!(guard.on_tick(at(3600), 10, 30), ResumeGate::JustResumed(Duration::seconds(3590)));
+        assert_eq!(guard.on_tick(at(3610), 10, 30), ResumeGate::WarmingUp(Duration::seconds(20)));
+        assert_eq!(guard.on_tick(at(3630), 10, 30), ResumeGate::Run);
+
+        // Zamykací obrazovku hlídáme jen na prvních ticích
+        assert!((0..LOCK_SCREEN_CHECK_TICKS).all(|_| guard.check_lock_screen()));
+        assert!(!guard.check_lock_screen());
+    }
+
+    #[test]
+    fn test_looks_like_lock_screen() {
+        let window = |app: &str| WindowInfo { app_name: app.to_string(), title: String::new(), rect: None };
+
+        assert!(looks_like_lock_screen(None, Some(&window("loginwindow"))));
+        assert!(looks_like_lock_screen(Some("Jan Novák\nEnter Password"), Some(&window("Finder"))));
+        assert!(!looks_like_lock_screen(Some("fn main() { println!(\"hello\"); }"), Some(&window("Code"))));
+        assert!(!looks_like_lock_screen(Some(&"zadejte heslo ".repeat(50)), None));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::ai_matcher::AiSuggestion;
+use crate::freelo::FreeloTask;
+use crate::history::HistorySegment;
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use serde::{Deserialize, Serialize};
+
+/// Soubor s frontou segmentů ke kontrole v adresáři profilu
+const REVIEW_FILE: &str = "review_queue.json";
+
+/// Pod touto confidence matcheru jde ukončený segment ke kontrole
+pub const REVIEW_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+/// Kolik návrhů tasků nabídnout
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Navržený task pro opravu segmentu jedním kliknutím
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSuggestion {
+    pub task_id: i32,
+    pub task_name: String,
+    pub project_name: String,
+    /// Jednořádkové zdůvodnění od AI
+    pub reason: String,
+}
+
+/// Segment s nízkou confidence čekající na potvrzení nebo opravu tasku
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub segment: HistorySegment,
+    pub confidence: f32,
+    pub suggestions: Vec<TaskSuggestion>,
+}
+
+/// Ruční volby (bez matcheru) a jistá rozhodnutí se nekontrolují
+pub fn needs_review(confidence: Option<f32>, minutes: i64) -> bool {
+    minutes >= 1 && confidence.is_some_and(|c| c < REVIEW_CONFIDENCE_THRESHOLD)
+}
+
+/// Převede návrhy AI na tasky z cache - neznámá a opakovaná ID zahodí
+pub fn resolve_suggestions(suggestions: &[AiSuggestion], tasks: &[FreeloTask]) -> Vec<TaskSuggestion> {
+    let mut resolved: Vec<TaskSuggestion> = Vec::new();
+    for suggestion in suggestions {
+        let Some(task) = tasks.iter().find(|t| t.id == suggestion.task_id) else {
+            continue;
+        };
+        if resolved.iter().any(|s| s.task_id == task.id) {
+            continue;
+        }
+        resolved.push(TaskSuggestion {
+            task_id: task.id,
+            task_name: task.name.clone(),
+            project_name: task.project_name.clone(),
+            reason: suggestion.reason.lines().next().unwrap_or_default().trim().to_string(),
+        });
+        if resolved.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    resolved
+}
+
+pub fn load(profile: &str) -> Vec<ReviewItem> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(REVIEW_FILE)).unwrap_or_default()
+}
+
+fn save(profile: &str, items: &[ReviewItem]) -> Result<(), String> {
+    profile_crypto::save_json(profile, &profile_dir(profile).join(REVIEW_FILE), &items)
+}
+
+pub fn push(profile: &str, item: ReviewItem) -> Result<(), String> {
+    let mut items = load(profile);
+    items.retain(|i| i.segment.id != item.segment.id);
+    items.push(item);
+    save(profile, &items)
+}
+
+/// Odebere segment z fronty, vrací ho pokud tam byl
+pub fn take(profile: &str, segment_id: &str) -> Result<Option<ReviewItem>, String> {
+    let mut items = load(profile);
+    let Some(position) = items.iter().position(|i| i.segment.id == segment_id) else {
+        return Ok(None);
+    };
+    let item = items.remove(position);
+    save(profile, &items)?;
+    Ok(Some(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_suggestions() {
+        let task = |id: i32, name: &str| FreeloTask {
+            id,
+            name: name.to_string(),
+            project_id: 1,
+            project_name: "Web".to_string(),
+            estimate_minutes: None,
+        };
+        let tasks = vec![task(1, "Frontend"), task(2, "Backend"), task(3, "Deploy"), task(4, "Docs")];
+        let suggest = |task_id: i32, reason: &str| AiSuggestion {
+            task_id,
+            reason: reason.to_string(),
+        };
+
+        let resolved = resolve_suggestions(
+            &[
+                suggest(2, "API v editoru\nDalší řádek"),
+                suggest(99, "neexistuje"),
+                suggest(2, "duplicita"),
+                suggest(1, "CSS"),
+                suggest(4, "README"),
+                suggest(3, "CI"),
+            ],
+            &tasks,
+        );
+        let ids: Vec<i32> = resolved.iter().map(|s| s.task_id).collect();
+        assert_eq!(ids, vec![2, 1, 4]);
+        assert_eq!(resolved[0].reason, "API v editoru");
+        assert_eq!(resolved[0].task_name, "Backend");
+
+        assert!(needs_review(Some(0.4), 5));
+        assert!(!needs_review(Some(0.9), 5));
+        assert!(!needs_review(None, 5));
+        assert!(!needs_review(Some(0.4), 0));
+    }
+}
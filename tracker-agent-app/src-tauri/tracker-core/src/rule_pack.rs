@@ -0,0 +1,198 @@
+use crate::config_files::SyntaxError;
+use crate::corrections::LearnedAssociations;
+use crate::profile_crypto;
+use crate::profiles::profile_dir;
+use crate::storage;
+use crate::task_rules::{self, TaskRule};
+use crate::text_matcher::{default_app_signatures, AppSignature};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Aktuální verze formátu rule packu
+pub const RULE_PACK_VERSION: u32 = 1;
+
+/// Soubor s aktivním rule packem v datovém adresáři aplikace (signatury aplikací jsou společné)
+const RULE_PACK_FILE: &str = "rule_pack.json";
+
+/// Pravidla tasků v adresáři profilu - tasky má každý klient jiné
+const TASK_RULES_FILE: &str = "task_rules.json";
+
+/// Sdílitelný balíček pravidel pro matcher (signatury aplikací, pravidla tasků, naučené asociace)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RulePack {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    #[serde(default)]
+    pub app_signatures: Vec<AppSignature>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub task_rules: Vec<TaskRule>,
+    /// Opravy a váhy slov profilu - jen v exportu, uložené jsou u profilu (corrections)
+    #[serde(default, skip_serializing_if = "LearnedAssociations::is_empty")]
+    pub learned: LearnedAssociations,
+}
+
+fn task_rules_path(profile: &str) -> PathBuf {
+    profile_dir(profile).join(TASK_RULES_FILE)
+}
+
+impl Default for RulePack {
+    fn default() -> Self {
+        Self {
+            version: RULE_PACK_VERSION,
+            exported_at: None,
+            app_signatures: default_app_signatures(),
+            task_rules: Vec::new(),
+            learned: LearnedAssociations::default(),
+        }
+    }
+}
+
+impl RulePack {
+    /// Společný rule pack (signatury aplikací), případně výchozí - bez pravidel tasků profilu
+    pub fn load_shared() -> Self {
+        storage::load_json(&Self::path()).unwrap_or_default()
+    }
+
+    /// Rule pack profilu - společné signatury a pravidla tasků profilu.
+    /// Pravidla ze starší verze (ve společném souboru) převezme profil, který se načte první.
+    pub fn load(profile: &str) -> Self {
+        let mut pack = Self::load_shared();
+        match profile_crypto::load_json(profile, &task_rules_path(profile)) {
+            Some(rules) => pack.task_rules = rules,
+            None if !pack.task_rules.is_empty() => {
+                if let Err(e) = pack.save(profile) {
+                    tracing::warn!(error = %e, "nelze převést pravidla tasků k profilu");
+                }
+            }
+            None => {}
+        }
+        pack
+    }
+
+    /// Uloží signatury do společného souboru a pravidla tasků k profilu
+    pub fn save(&self, profile: &str) -> Result<(), String> {
+        let shared = Self {
+            task_rules: Vec::new(),
+            learned: LearnedAssociations::default(),
+            ..self.clone()
+        };
+        storage::save_json(&Self::path(), &shared)?;
+        profile_crypto::save_json(profile, &task_rules_path(profile), &self.task_rules)
+    }
+
+    /// Exportuje rule pack do zadaného souboru
+    pub fn export_to(&self, path: &Path) -> Result<(), String> {
+        let mut pack = self.clone();
+        pack.exported_at = Some(chrono::Local::now().to_rfc3339());
+        storage::save_json(path, &pack)
+    }
+
+    /// Načte rule pack ze zadaného souboru (např. od kolegy)
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Nelze přečíst {:?}: {}", path, e))?;
+        Self::parse(&content).map_err(|e| e.to_string())
+    }
+
+    /// Rozparsuje a ověří rule pack (chyba JSON s řádkem a sloupcem)
+    pub fn parse(content: &str) -> Result<Self, SyntaxError> {
+        let pack: RulePack = serde_json::from_str(content).map_err(|e| SyntaxError {
+            line: Some(e.line()),
+            column: Some(e.column()),
+            message: format!("Neplatný rule pack: {}", e),
+        })?;
+
+        if pack.version > RULE_PACK_VERSION {
+            return Err(SyntaxError {
+                line: None,
+                column: None,
+                message: format!(
+                    "Rule pack má novější verzi ({}) než podporuje aplikace ({})",
+                    pack.version, RULE_PACK_VERSION
+                ),
+            });
+        }
+
+        for rule in &pack.task_rules {
+            task_rules::validate(rule).map_err(|message| SyntaxError { line: None, column: None, message })?;
+        }
+
+        Ok(pack)
+    }
+
+    /// Cesta k aktivnímu rule packu (hlídá ji file_watch)
+    pub fn path() -> PathBuf {
+        storage::data_file(RULE_PACK_FILE)
+    }
+
+    /// Sloučí importovaný pack do aktuálního, vrací počet přidaných/aktualizovaných položek.
+    /// Signatury a pravidla se stejným názvem se přepíší, nové se přidají na začátek (mají přednost).
+    pub fn merge(&mut self, other: RulePack) -> usize {
+        merge_named(&mut self.app_signatures, other.app_signatures, |s| &s.name)
+            + merge_named(&mut self.task_rules, other.task_rules, |r| &r.name)
+    }
+}
+
+fn merge_named<T: PartialEq>(current: &mut Vec<T>, imported: Vec<T>, name: impl Fn(&T) -> &str) -> usize {
+    let mut changed = 0;
+
+    for item in imported.into_iter().rev() {
+        match current.iter_mut().find(|existing| name(existing) == name(&item)) {
+            Some(existing) => {
+                if *existing != item {
+                    *existing = item;
+                    changed += 1;
+                }
+            }
+            None => {
+                current.insert(0, item);
+                changed += 1;
+            }
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_adds_and_updates_signatures() {
+        let mut pack = RulePack::default();
+        let base_len = pack.app_signatures.len();
+
+        let imported = RulePack {
+            version: RULE_PACK_VERSION,
+            exported_at: None,
+            app_signatures: vec![
+                AppSignature { name: "IntelliJ IDEA".to_string(), keywords: vec!["intellij".to_string()] },
+                AppSignature { name: "Slack".to_string(), keywords: vec!["slack".to_string(), "huddle".to_string()] },
+            ],
+            task_rules: Vec::new(),
+            learned: LearnedAssociations::default(),
+        };
+
+        assert_eq!(pack.merge(imported.clone()), 2);
+        assert_eq!(pack.app_signatures.len(), base_len + 1);
+        assert_eq!(pack.app_signatures[0].name, "IntelliJ IDEA");
+
+        // Opakovaný import nic nemění
+        assert_eq!(pack.merge(imported), 0);
+    }
+
+    #[test]
+    fn test_parse_reports_position() {
+        let pack = RulePack::parse(r#"{"version": 1, "app_signatures": []}"#).unwrap();
+        assert!(pack.app_signatures.is_empty());
+
+        let broken = RulePack::parse("{\n  \"version\": 1,\n  \"app_signatures\": [}\n").unwrap_err();
+        assert_eq!(broken.line, Some(3));
+        assert!(broken.column.is_some());
+
+        let newer = RulePack::parse(r#"{"version": 99}"#).unwrap_err();
+        assert!(newer.message.contains("novější verzi"));
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Klíče dnů v nastavení, pořadí podle `Weekday::num_days_from_monday`
+const DAY_KEYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Pracovní doba - mimo ni agent nesnímá obrazovku a nenechá běžet timer.
+/// Okna "HH:MM-HH:MM" po dnech ("mon": ["09:00-12:00", "12:30-17:30"]), chybějící den = volno.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkSchedule {
+    pub days: BTreeMap<String, Vec<String>>,
+}
+
+fn parse_window(window: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (from, to) = window.split_once('-')?;
+    let from = NaiveTime::parse_from_str(from.trim(), "%H:%M").ok()?;
+    let to = NaiveTime::parse_from_str(to.trim(), "%H:%M").ok()?;
+    // Přes půlnoc nejde - noční část patří do oken dalšího dne
+    (from < to).then_some((from, to))
+}
+
+fn day_key(day: Weekday) -> &'static str {
+    DAY_KEYS[day.num_days_from_monday() as usize]
+}
+
+impl WorkSchedule {
+    fn windows(&self, day: Weekday) -> impl Iterator<Item = (NaiveTime, NaiveTime)> + '_ {
+        self.days
+            .get(day_key(day))
+            .into_iter()
+            .flatten()
+            .filter_map(|w| parse_window(w))
+    }
+
+    /// Je v tuto chvíli pracovní doba?
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        let time = now.time();
+        self.windows(now.weekday()).any(|(from, to)| time >= from && time < to)
+    }
+
+    /// Nejbližší začátek pracovní doby po `now` (do týdne), None = rozvrh je prázdný
+    pub fn next_start(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..=7).find_map(|offset| {
+            let date = now.date() + Duration::days(offset);
+            self.windows(date.weekday())
+                .map(|(from, _)| date.and_time(from))
+                .filter(|start| *start > now)
+                .min()
+        })
+    }
+}
+
+/// Validace rozvrhu z nastavení
+pub fn validate(schedule: &WorkSchedule) -> Result<(), String> {
+    for (day, windows) in &schedule.days {
+        if !DAY_KEYS.contains(&day.as_str()) {
+            return Err(format!("Neznámý den '{}' v pracovní době (mon-sun)", day));
+        }
+        for window in windows {
+            if parse_window(window).is_none() {
+                return Err(format!("Neplatné okno pracovní doby '{}' (očekáváno HH:MM-HH:MM, konec po začátku)", window));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_schedule_windows() {
+        let weekdays = ["mon", "tue", "wed", "thu", "fri"];
+        let schedule = WorkSchedule {
+            days: weekdays
+                .iter()
+                .map(|d| (d.to_string(), vec!["09:00-12:00".to_string(), "12:30-17:30".to_string()]))
+                .collect(),
+        };
+        assert!(validate(&schedule).is_ok());
+
+        // 14. 3. 2025 je pátek
+        let at = |day: u32, h: u32, m: u32| NaiveDate::from_ymd_opt(2025, 3, day).unwrap().and_hms_opt(h, m, 0).unwrap();
+        assert!(schedule.is_active(at(14, 9, 0)));
+        assert!(!schedule.is_active(at(14, 12, 15)));
+        assert!(!schedule.is_active(at(14, 17, 30)));
+        assert!(!schedule.is_active(at(15, 10, 0)));
+
+        assert_eq!(schedule.next_start(at(14, 12, 15)), Some(at(14, 12, 30)));
+        // Z pátečního večera až na pondělí ráno
+        assert_eq!(schedule.next_start(at(14, 18, 0)), Some(at(17, 9, 0)));
+        assert_eq!(WorkSchedule::default().next_start(at(14, 18, 0)), None);
+
+        let invalid = |day: &str, window: &str| WorkSchedule {
+            days: BTreeMap::from([(day.to_string(), vec![window.to_string()])]),
+        };
+        assert!(validate(&invalid("mon", "22:00-06:00")).is_err());
+        assert!(validate(&invalid("pondeli", "09:00-17:00")).is_err());
+    }
+}
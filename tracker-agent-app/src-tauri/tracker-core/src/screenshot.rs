@@ -0,0 +1,394 @@
+use base64::{engine::general_purpose, Engine as _};
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use tracing::{debug, instrument, warn, Span};
+use xcap::Monitor;
+
+/// Obdélník na ploše ve fyzických pixelech
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRect {
+    pub fn overlaps(&self, other: &ScreenRect) -> bool {
+        let right = |r: &ScreenRect| r.x as i64 + r.width as i64;
+        let bottom = |r: &ScreenRect| r.y as i64 + r.height as i64;
+
+        (self.x as i64) < right(other)
+            && (other.x as i64) < right(self)
+            && (self.y as i64) < bottom(other)
+            && (other.y as i64) < bottom(self)
+    }
+
+    fn intersection_area(&self, other: &ScreenRect) -> u64 {
+        let left = self.x.max(other.x) as i64;
+        let top = self.y.max(other.y) as i64;
+        let right = (self.x as i64 + self.width as i64).min(other.x as i64 + other.width as i64);
+        let bottom = (self.y as i64 + self.height as i64).min(other.y as i64 + other.height as i64);
+        ((right - left).max(0) * (bottom - top).max(0)) as u64
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && (x as i64) < self.x as i64 + self.width as i64
+            && (y as i64) < self.y as i64 + self.height as i64
+    }
+}
+
+/// Obdélník v pixelech snímku (po zmenšení)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageArea {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Který monitor snímat při více displejích
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorSelection {
+    /// Vždy primární monitor
+    Primary,
+    /// Monitor, na kterém uživatel právě pracuje (aktivní okno, kurzor)
+    #[default]
+    Active,
+}
+
+/// Levné signály, kde uživatel pracuje - zjišťují se před snímkem
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CaptureHint {
+    /// Poloha aktivního okna
+    pub window: Option<ScreenRect>,
+    /// Poloha kurzoru myši
+    pub cursor: Option<(i32, i32)>,
+}
+
+/// Snímek obrazovky (JPEG v base64) a monitor, ze kterého pochází
+pub struct Capture {
+    pub image: String,
+    pub monitor: String,
+    /// Percepční hash pro porovnání s minulým snímkem
+    pub hash: ScreenHash,
+    /// Aktivní okno ve snímku - debug artefakty z něj nechají čitelný jen titulek
+    pub window_area: Option<ImageArea>,
+}
+
+/// Strana mřížky percepčního hashe - 16×16 = 256 bitů (8×8 by změnu textu nezachytilo)
+const HASH_SIDE: u32 = 16;
+
+/// Rozdílový percepční hash (dHash) snímku - každý bit říká, jestli je sousední
+/// políčko ve zmenšeném šedém snímku světlejší
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenHash([u64; 4]);
+
+impl ScreenHash {
+    pub fn of(img: &image::DynamicImage) -> Self {
+        let small = img
+            .resize_exact(HASH_SIDE + 1, HASH_SIDE, image::imageops::FilterType::Triangle)
+            .to_luma8();
+        let mut bits = [0u64; 4];
+        for y in 0..HASH_SIDE {
+            for x in 0..HASH_SIDE {
+                if small.get_pixel(x + 1, y)[0] > small.get_pixel(x, y)[0] {
+                    let bit = (y * HASH_SIDE + x) as usize;
+                    bits[bit / 64] |= 1 << (bit % 64);
+                }
+            }
+        }
+        Self(bits)
+    }
+
+    /// Počet rozdílných bitů (0 = stejná obrazovka, 256 = úplně jiná)
+    pub fn distance(&self, other: &ScreenHash) -> u32 {
+        self.0.iter().zip(other.0.iter()).map(|(a, b)| (a ^ b).count_ones()).sum()
+    }
+}
+
+/// Index monitoru ke snímání: aktivní okno (největší překryv) → kurzor → primární → první
+fn select_monitor(monitors: &[(ScreenRect, bool)], selection: MonitorSelection, hint: &CaptureHint) -> Option<usize> {
+    let primary = || monitors.iter().position(|(_, primary)| *primary);
+    if monitors.len() < 2 || selection == MonitorSelection::Primary {
+        return primary().or((!monitors.is_empty()).then_some(0));
+    }
+
+    let by_window = hint.window.and_then(|window| {
+        monitors
+            .iter()
+            .enumerate()
+            .map(|(i, (rect, _))| (i, rect.intersection_area(&window)))
+            .filter(|(_, area)| *area > 0)
+            .max_by_key(|(_, area)| *area)
+            .map(|(i, _)| i)
+    });
+    let by_cursor = || {
+        let (x, y) = hint.cursor?;
+        monitors.iter().position(|(rect, _)| rect.contains(x, y))
+    };
+
+    by_window.or_else(by_cursor).or_else(primary).or(Some(0))
+}
+
+fn monitor_rect(monitor: &Monitor) -> Result<ScreenRect, String> {
+    let err = |e: xcap::XCapError| format!("Nelze zjistit rozměry monitoru: {}", e);
+
+    Ok(ScreenRect {
+        x: monitor.x().map_err(err)?,
+        y: monitor.y().map_err(err)?,
+        width: monitor.width().map_err(err)?,
+        height: monitor.height().map_err(err)?,
+    })
+}
+
+/// Monitor, který se snímá - jen jeden, OCR a AI běží jen nad ním
+fn captured_monitor(selection: MonitorSelection, hint: &CaptureHint) -> Result<Monitor, String> {
+    let mut monitors = Monitor::all().map_err(|e| {
+        let err_msg = format!("Failed to get monitors: {}. DŮLEŽITÉ: Aplikace potřebuje Screen Recording permission!", e);
+        warn!(error = %err_msg, "seznam monitorů nedostupný");
+        err_msg
+    })?;
+
+    let layout: Vec<(ScreenRect, bool)> = monitors
+        .iter()
+        .map(|m| {
+            let rect = monitor_rect(m).unwrap_or(ScreenRect { x: 0, y: 0, width: 0, height: 0 });
+            (rect, m.is_primary().unwrap_or(false))
+        })
+        .collect();
+
+    let index = select_monitor(&layout, selection, hint).ok_or_else(|| {
+        let err_msg = "No monitors found".to_string();
+        warn!(error = %err_msg, "žádný monitor");
+        err_msg
+    })?;
+    debug!(index, count = layout.len(), ?hint, "vybrán monitor ke snímání");
+
+    Ok(monitors.swap_remove(index))
+}
+
+/// Plocha snímaného monitoru
+pub fn captured_monitor_bounds(selection: MonitorSelection, hint: &CaptureHint) -> Result<ScreenRect, String> {
+    monitor_rect(&captured_monitor(selection, hint)?)
+}
+
+/// Část snímku (x, y, šířka, výška v pixelech obrázku), kterou na monitoru zakrývá okno.
+/// Obrázek může mít jiné rozlišení než plocha monitoru (HiDPI).
+fn masked_area(monitor: &ScreenRect, window: &ScreenRect, image_width: u32, image_height: u32) -> Option<(u32, u32, u32, u32)> {
+    if monitor.width == 0 || monitor.height == 0 || !monitor.overlaps(window) {
+        return None;
+    }
+    let scale_x = image_width as f64 / monitor.width as f64;
+    let scale_y = image_height as f64 / monitor.height as f64;
+    let left = (window.x.max(monitor.x) - monitor.x) as f64;
+    let top = (window.y.max(monitor.y) - monitor.y) as f64;
+    let right = ((window.x as i64 + window.width as i64).min(monitor.x as i64 + monitor.width as i64) - monitor.x as i64) as f64;
+    let bottom = ((window.y as i64 + window.height as i64).min(monitor.y as i64 + monitor.height as i64) - monitor.y as i64) as f64;
+
+    let x = ((left * scale_x).floor() as u32).min(image_width);
+    let y = ((top * scale_y).floor() as u32).min(image_height);
+    let width = ((right * scale_x).ceil() as u32).min(image_width) - x;
+    let height = ((bottom * scale_y).ceil() as u32).min(image_height) - y;
+    (width > 0 && height > 0).then_some((x, y, width, height))
+}
+
+/// Zachytí monitor, na kterém uživatel pracuje, `scale` < 1.0 snímek zmenší (šetří paměť i CPU při OCR).
+/// Plocha okna `exclude` se ve snímku začerní.
+#[instrument(name = "capture", skip_all, fields(monitor, width, height, bytes))]
+pub fn capture_and_encode(
+    scale: f32,
+    selection: MonitorSelection,
+    hint: &CaptureHint,
+    exclude: Option<ScreenRect>,
+) -> Result<Capture, String> {
+    let monitor = captured_monitor(selection, hint)?;
+
+    let monitor_name = monitor.name().unwrap_or_else(|_| "Unknown".to_string());
+    Span::current().record("monitor", monitor_name.as_str());
+
+    // Capture screenshot
+    let mut image = monitor.capture_image().map_err(|e| {
+        let err_msg = format!("Failed to capture monitor: {}", e);
+        warn!(error = %err_msg, "zachycení monitoru selhalo");
+        err_msg
+    })?;
+
+    Span::current()
+        .record("width", image.width())
+        .record("height", image.height());
+
+    let bounds = monitor_rect(&monitor).ok();
+    if let Some(window) = exclude {
+        let area = bounds.and_then(|rect| masked_area(&rect, &window, image.width(), image.height()));
+        if let Some((x, y, width, height)) = area {
+            for py in y..y + height {
+                for px in x..x + width {
+                    image.put_pixel(px, py, image::Rgba([0, 0, 0, 255]));
+                }
+            }
+            debug!(x, y, width, height, "okno trackeru ve snímku začerněno");
+        }
+    }
+
+    // xcap vrací RgbaImage, konvertujeme na DynamicImage
+    let mut img = image::DynamicImage::ImageRgba8(image);
+    if scale < 1.0 {
+        let width = ((img.width() as f32 * scale) as u32).max(1);
+        let height = ((img.height() as f32 * scale) as u32).max(1);
+        img = img.resize_exact(width, height, image::imageops::FilterType::Triangle);
+    }
+    let hash = ScreenHash::of(&img);
+    let window_area = bounds
+        .zip(hint.window)
+        .and_then(|(rect, window)| masked_area(&rect, &window, img.width(), img.height()))
+        .map(|(x, y, width, height)| ImageArea { x, y, width, height });
+
+    // Encode to JPEG
+    let mut buffer = Cursor::new(Vec::new());
+    img.write_to(&mut buffer, ImageFormat::Jpeg)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    // Base64 encode
+    let base64_string = general_purpose::STANDARD.encode(buffer.into_inner());
+
+    Span::current().record("bytes", base64_string.len());
+    debug!("screenshot zakódován");
+
+    Ok(Capture {
+        image: base64_string,
+        monitor: monitor_name,
+        hash,
+        window_area,
+    })
+}
+
+/// Velikost bloku pixelizace zakrytých oblastí
+const PIXELATE_BLOCK: u32 = 16;
+
+/// Pixelizuje oblasti snímku (base64 JPEG jako z `capture_and_encode`) - např. citlivé
+/// údaje před odesláním do AI. Vrací nový base64 JPEG.
+pub fn pixelate_areas(screenshot_base64: &str, areas: &[ImageArea]) -> Result<String, String> {
+    let data = general_purpose::STANDARD
+        .decode(screenshot_base64)
+        .map_err(|e| format!("Chyba při dekódování base64: {}", e))?;
+    let mut img = image::load_from_memory(&data)
+        .map_err(|e| format!("Chyba při načítání obrazu: {}", e))?
+        .to_rgba8();
+
+    for area in areas {
+        let x = area.x.min(img.width());
+        let y = area.y.min(img.height());
+        let width = area.width.min(img.width() - x);
+        let height = area.height.min(img.height() - y);
+        if width == 0 || height == 0 {
+            continue;
+        }
+        let crop = image::imageops::crop_imm(&img, x, y, width, height).to_image();
+        let small = image::imageops::resize(
+            &crop,
+            (width / PIXELATE_BLOCK).max(1),
+            (height / PIXELATE_BLOCK).max(1),
+            image::imageops::FilterType::Triangle,
+        );
+        let blocks = image::imageops::resize(&small, width, height, image::imageops::FilterType::Nearest);
+        image::imageops::replace(&mut img, &blocks, x as i64, y as i64);
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(img)
+        .to_rgb8()
+        .write_to(&mut buffer, ImageFormat::Jpeg)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(general_purpose::STANDARD.encode(buffer.into_inner()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_monitor() {
+        let left = ScreenRect { x: 0, y: 0, width: 1920, height: 1080 };
+        let right = ScreenRect { x: 1920, y: 0, width: 2560, height: 1440 };
+        let monitors = [(left, true), (right, false)];
+        let hint = |window: Option<ScreenRect>, cursor: Option<(i32, i32)>| CaptureHint { window, cursor };
+
+        // Okno přes oba monitory - vyhrává větší překryv
+        let window = ScreenRect { x: 1800, y: 100, width: 1000, height: 800 };
+        assert_eq!(select_monitor(&monitors, MonitorSelection::Active, &hint(Some(window), None)), Some(1));
+
+        // Bez okna rozhodne kurzor, bez signálů primární
+        assert_eq!(select_monitor(&monitors, MonitorSelection::Active, &hint(None, Some((2000, 50)))), Some(1));
+        assert_eq!(select_monitor(&monitors, MonitorSelection::Active, &hint(None, Some((-50, 50)))), Some(0));
+        assert_eq!(select_monitor(&monitors, MonitorSelection::Primary, &hint(Some(window), None)), Some(0));
+
+        assert_eq!(select_monitor(&[], MonitorSelection::Active, &hint(None, None)), None);
+        assert_eq!(select_monitor(&[(right, false)], MonitorSelection::Primary, &hint(None, None)), Some(0));
+    }
+
+    #[test]
+    fn test_masked_area() {
+        let monitor = ScreenRect { x: 1920, y: 0, width: 1280, height: 720 };
+        let window = ScreenRect { x: 2920, y: 600, width: 800, height: 600 };
+        // Okno přesahuje monitor vpravo dole, obrázek má dvojnásobné rozlišení (HiDPI)
+        assert_eq!(masked_area(&monitor, &window, 2560, 1440), Some((2000, 1200, 560, 240)));
+        assert_eq!(masked_area(&monitor, &window, 1280, 720), Some((1000, 600, 280, 120)));
+
+        let elsewhere = ScreenRect { x: 0, y: 0, width: 800, height: 600 };
+        assert_eq!(masked_area(&monitor, &elsewhere, 1280, 720), None);
+    }
+
+    #[test]
+    fn test_screen_hash_distance() {
+        // Svislé pruhy jako řádky textu, druhý snímek s jedním změněným pruhem
+        let stripes = |changed: bool| {
+            image::DynamicImage::ImageLuma8(image::GrayImage::from_fn(340, 160, |x, y| {
+                let dark = (x / 20) % 2 == 0 || (changed && x >= 160 && x < 200 && y < 80);
+                image::Luma([if dark { 30 } else { 220 }])
+            }))
+        };
+        let original = ScreenHash::of(&stripes(false));
+        assert_eq!(original.distance(&ScreenHash::of(&stripes(false))), 0);
+
+        // Zmenšený snímek (degradace kvality) má stejný hash
+        let smaller = stripes(false).resize_exact(170, 80, image::imageops::FilterType::Triangle);
+        assert!(original.distance(&ScreenHash::of(&smaller)) <= 2);
+
+        let changed = ScreenHash::of(&stripes(true));
+        assert!(original.distance(&changed) > 6, "{}", original.distance(&changed));
+
+        let inverted = ScreenHash(original.0.map(|b| !b));
+        assert_eq!(original.distance(&inverted), 256);
+    }
+
+    #[test]
+    fn test_pixelate_areas() {
+        // Svislé pruhy po 8 px - pixelizace je slije do šedé, okolí zůstane kontrastní
+        let stripes = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(128, 64, |x, _| {
+            if (x / 8) % 2 == 0 {
+                image::Rgb([0, 0, 0])
+            } else {
+                image::Rgb([255, 255, 255])
+            }
+        }));
+        let mut buffer = Cursor::new(Vec::new());
+        stripes.write_to(&mut buffer, ImageFormat::Jpeg).unwrap();
+        let encoded = general_purpose::STANDARD.encode(buffer.into_inner());
+
+        let area = ImageArea { x: 32, y: 16, width: 64, height: 32 };
+        let redacted = pixelate_areas(&encoded, &[area, ImageArea { x: 120, y: 60, width: 50, height: 50 }]).unwrap();
+        let img = image::load_from_memory(&general_purpose::STANDARD.decode(redacted).unwrap())
+            .unwrap()
+            .to_luma8();
+        assert_eq!(img.dimensions(), (128, 64));
+        let gray = img.get_pixel(36, 24)[0];
+        assert!(gray > 70 && gray < 190, "oblast není rozmazaná: {}", gray);
+        assert!(img.get_pixel(4, 4)[0] < 60);
+        assert!(img.get_pixel(12, 4)[0] > 200);
+    }
+}
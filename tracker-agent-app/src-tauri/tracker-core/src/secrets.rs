@@ -0,0 +1,123 @@
+use crate::settings::Settings;
+use crate::storage::APP_IDENTIFIER;
+use keyring::Entry;
+
+/// Názvy položek v klíčence systému (služba = identifikátor aplikace)
+const FREELO_KEY: &str = "freelo_api_key";
+const OPENROUTER_KEY: &str = "openrouter_api_key";
+const SANDBOX_FREELO_KEY: &str = "sandbox_freelo_api_key";
+const TOGGL_TOKEN: &str = "toggl_api_token";
+const CLOCKIFY_KEY: &str = "clockify_api_key";
+const PROFILE_DATA_KEY: &str = "profile_data_key";
+
+/// Název položky - klíče uložené u profilu mají předponu profilu ("klient-a/freelo_api_key")
+fn scoped(name: &str, scope: Option<&str>) -> String {
+    match scope {
+        Some(profile) => format!("{}/{}", profile, name),
+        None => name.to_string(),
+    }
+}
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(APP_IDENTIFIER, name).map_err(|e| format!("Klíčenka nedostupná: {}", e))
+}
+
+/// Uloží tajemství do klíčenky, prázdná hodnota ho smaže
+fn store(name: &str, value: Option<&str>) -> Result<(), String> {
+    let entry = entry(name)?;
+    match value.filter(|v| !v.is_empty()) {
+        Some(value) => entry
+            .set_password(value)
+            .map_err(|e| format!("Nelze uložit {} do klíčenky: {}", name, e)),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Nelze smazat {} z klíčenky: {}", name, e)),
+        },
+    }
+}
+
+fn load(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Nelze načíst {} z klíčenky: {}", name, e)),
+    }
+}
+
+/// Vyjme API klíče z nastavení (v souboru zůstanou prázdné)
+fn take_secrets(settings: &mut Settings) -> [(&'static str, Option<String>); 5] {
+    [
+        (FREELO_KEY, Some(std::mem::take(&mut settings.freelo_key)).filter(|k| !k.is_empty())),
+        (OPENROUTER_KEY, settings.openrouter_key.take()),
+        (SANDBOX_FREELO_KEY, settings.sandbox_freelo_key.take()),
+        (TOGGL_TOKEN, settings.toggl_api_token.take()),
+        (CLOCKIFY_KEY, settings.clockify_api_key.take()),
+    ]
+}
+
+/// Přesune API klíče do klíčenky - vrací nastavení bez klíčů pro zápis na disk.
+/// `scope` = profil pro uložené nastavení profilu, None = aktivní nastavení.
+pub fn move_to_keychain(settings: &Settings, scope: Option<&str>) -> Result<Settings, String> {
+    let mut on_disk = settings.clone();
+    for (name, value) in take_secrets(&mut on_disk) {
+        store(&scoped(name, scope), value.as_deref())?;
+    }
+    Ok(on_disk)
+}
+
+/// Doplní chybějící API klíče z klíčenky (klíče ze starších verzí v souboru mají přednost)
+pub fn fill_from_keychain(settings: &mut Settings, scope: Option<&str>) -> Result<(), String> {
+    let load = |name: &str| load(&scoped(name, scope));
+    if settings.freelo_key.is_empty() {
+        settings.freelo_key = load(FREELO_KEY)?.unwrap_or_default();
+    }
+    if settings.openrouter_key.is_none() {
+        settings.openrouter_key = load(OPENROUTER_KEY)?;
+    }
+    if settings.sandbox_freelo_key.is_none() {
+        settings.sandbox_freelo_key = load(SANDBOX_FREELO_KEY)?;
+    }
+    if settings.toggl_api_token.is_none() {
+        settings.toggl_api_token = load(TOGGL_TOKEN)?;
+    }
+    if settings.clockify_api_key.is_none() {
+        settings.clockify_api_key = load(CLOCKIFY_KEY)?;
+    }
+    Ok(())
+}
+
+/// Šifrovací klíč dat profilu (base64), None = profil ho ještě nemá
+pub fn load_profile_data_key(profile: &str) -> Result<Option<String>, String> {
+    load(&scoped(PROFILE_DATA_KEY, Some(profile)))
+}
+
+/// Uloží klíč dat profilu, None ho smaže
+pub fn store_profile_data_key(profile: &str, key: Option<&str>) -> Result<(), String> {
+    store(&scoped(PROFILE_DATA_KEY, Some(profile)), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_secrets() {
+        let mut settings: Settings = serde_json::from_str(
+            r#"{"interval": 10, "freelo_email": "a@b.cz", "freelo_key": "fk", "openrouter_key": "sk-or"}"#,
+        )
+        .unwrap();
+
+        let secrets = take_secrets(&mut settings);
+        assert_eq!(secrets[0], (FREELO_KEY, Some("fk".to_string())));
+        assert_eq!(secrets[1], (OPENROUTER_KEY, Some("sk-or".to_string())));
+        assert_eq!(secrets[2], (SANDBOX_FREELO_KEY, None));
+        assert_eq!(secrets[3], (TOGGL_TOKEN, None));
+
+        let on_disk = serde_json::to_string(&settings).unwrap();
+        assert!(!on_disk.contains("fk") && !on_disk.contains("sk-or"));
+        assert_eq!(settings.freelo_email, "a@b.cz");
+
+        assert_eq!(scoped(FREELO_KEY, None), "freelo_api_key");
+        assert_eq!(scoped(FREELO_KEY, Some("klient-a")), "klient-a/freelo_api_key");
+    }
+}
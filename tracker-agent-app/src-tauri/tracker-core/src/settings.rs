@@ -0,0 +1,447 @@
+use crate::ai_matcher::{self, AiParams, LlmProvider, LlmProviderKind};
+use crate::backend::{BackendConfig, BackendKind};
+use crate::budget::TickBudget;
+use crate::secrets;
+use crate::storage::{self, APP_IDENTIFIER};
+use crate::freelo::FreeloCredentials;
+use crate::tracker::{self, AnalysisMode, TrackerConfig};
+use crate::{
+    adaptive_interval, ai_usage, budget, categories, context_providers, debug_artifacts, evidence, history, hotkey_bindings, idle,
+    network, notifications, ocr, profile_crypto, profiles, resources, resume, schedule, task_budget,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Soubor s nastavením v konfiguračním adresáři platformy
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Nastavení z UI - ukládá se na disk, aby se po restartu nemuselo zadávat znovu
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Settings {
+    pub interval: u64,
+    pub freelo_email: String,
+    pub freelo_key: String,
+    /// Služba pro sledování času, None = Freelo
+    pub backend: Option<BackendKind>,
+    pub toggl_api_token: Option<String>,
+    /// None = výchozí workspace Toggl účtu
+    pub toggl_workspace_id: Option<i64>,
+    pub clockify_api_key: Option<String>,
+    /// None = aktivní workspace Clockify uživatele
+    pub clockify_workspace_id: Option<String>,
+    pub openrouter_key: Option<String>,
+    /// Kam posílat AI requesty, None = OpenRouter
+    pub llm_provider: Option<LlmProviderKind>,
+    /// Adresa lokální Ollamy (None = http://localhost:11434)
+    pub ollama_url: Option<String>,
+    /// Model v Ollamě (None = llama3.2-vision)
+    pub ollama_model: Option<String>,
+    /// OCR backend (None = automaticky podle platformy)
+    pub ocr_engine: Option<ocr::OcrEngineKind>,
+    /// Pokročilé: Tesseract PSM (None = auto)
+    pub ocr_psm: Option<u32>,
+    /// Pokročilé: Tesseract OEM (None = výchozí)
+    pub ocr_oem: Option<u32>,
+    /// Pokročilé: velikost OCR dlaždice pro snímky nad 2560 px (None = 1280, 0 = bez dlaždic)
+    pub ocr_tile_size: Option<u32>,
+    /// Pokročilé: zvětšení snímku před OCR, 1.0-3.0 (None = 1.0)
+    pub ocr_scale: Option<f32>,
+    /// Jazyky OCR ve formátu Tesseractu, např. "ces+eng" (None = eng)
+    pub ocr_languages: Option<String>,
+    /// Ochranná doba po ručním zásahu v sekundách
+    pub manual_grace_seconds: Option<u64>,
+    /// Max. doba segmentu bez aktivity uživatele v minutách
+    pub max_inactive_minutes: Option<u64>,
+    /// Podezřele dlouhý segment bez aktivity automaticky ukončit
+    #[serde(default)]
+    pub spike_auto_stop: bool,
+    /// Po skončení bloku přidat k Freelo tasku komentář se souhrnem práce
+    #[serde(default)]
+    pub post_activity_comments: bool,
+    /// Síťová pravidla (Wi-Fi SSID / VPN) pro automatické sledování
+    #[serde(default)]
+    pub network_rules: Vec<crate::network::NetworkRule>,
+    /// Profil (klient) pro oddělení dat, None = výchozí
+    pub profile: Option<String>,
+    /// Důkazní screenshot jednou za N minut (opt-in), None = vypnuto
+    pub evidence_interval_minutes: Option<u64>,
+    /// Doba uchování důkazních screenshotů ve dnech
+    pub evidence_retention_days: Option<u64>,
+    /// Pokročilé: parametry AI requestu, None = výchozí
+    pub ai_model: Option<String>,
+    pub ai_temperature: Option<f32>,
+    pub ai_max_tokens: Option<u32>,
+    pub ai_top_p: Option<f32>,
+    /// Při neshodě AI a textového matcheru rozhoduje naměřená přesnost
+    #[serde(default)]
+    pub matcher_auto_priority: bool,
+    /// Skrývat citlivé údaje před odesláním do AI, None = zapnuto
+    pub redact_ai_input: Option<bool>,
+    /// Režim soukromí - aplikace si nenechává text z obrazovky pro ladění
+    #[serde(default)]
+    pub privacy_mode: bool,
+    /// Analýza obrazovky (OCR / AI nad screenshotem / hybrid), None = OCR
+    pub analysis_mode: Option<AnalysisMode>,
+    /// Kdy skrývat okno před snímkem, None = vždy
+    pub hide_policy: Option<crate::window_policy::HidePolicy>,
+    /// Který monitor snímat, None = aktivní
+    pub monitor_selection: Option<crate::screenshot::MonitorSelection>,
+    /// Prodleva po probuzení ze spánku v sekundách (0 = vypnuto)
+    pub resume_warmup_seconds: Option<u64>,
+    /// Cesta k iCalendar souboru s focus bloky
+    pub focus_calendar_path: Option<String>,
+    /// Klíčové slovo v názvu události, None = "focus"
+    pub focus_keyword: Option<String>,
+    /// Testovací Freelo workspace pro zápisy (sandbox)
+    pub sandbox_freelo_email: Option<String>,
+    pub sandbox_freelo_key: Option<String>,
+    /// Pauza trackingu po N minutách nečinnosti (0 = vypnuto)
+    pub idle_threshold_minutes: Option<u64>,
+    /// Strop paměti agenta v MB (0 = bez limitu)
+    pub memory_limit_mb: Option<u64>,
+    /// Obnova seznamu tasků z Freelo po N minutách (0 = jen při startu)
+    pub task_refresh_minutes: Option<u64>,
+    /// Projekty Freelo, do kterých se smí matchovat (prázdné = všechny)
+    #[serde(default)]
+    pub selected_project_ids: Vec<i32>,
+    /// Kolik měsíců držet jednotlivé ticky, starší se sloučí do denních souhrnů (None = 12, 0 = navždy)
+    pub history_retention_months: Option<u32>,
+    /// Globální klávesové zkratky (chybějící = výchozí)
+    #[serde(default)]
+    pub hotkeys: hotkey_bindings::HotkeyBindings,
+    /// Pracovní doba, mimo ni se nesnímá a timer se zastaví (None = pořád)
+    pub schedule: Option<schedule::WorkSchedule>,
+    /// Pokročilé: minimální confidence matcheru (0-1)
+    pub min_confidence: Option<f32>,
+    /// Pokročilé: počet nestabilních ticků před restartem segmentu
+    pub unstable_ticks_needed: Option<u32>,
+    /// Pokročilé: minimální délka segmentu před restartem v sekundách
+    pub restart_cooldown_seconds: Option<u64>,
+    /// Adresář pro debug artefakty (None = datový adresář aplikace)
+    pub debug_dir: Option<String>,
+    /// Před voláním LLM zkusit jistý textový match a sémantickou shodu přes embeddingy
+    #[serde(default)]
+    pub embedding_matching: bool,
+    /// Pokročilé: rozdíl percepčních hashů obrazovky (bity z 256), pod kterým se OCR přeskočí
+    /// (None = 6, 0 = OCR vždy)
+    pub screen_diff_threshold: Option<u32>,
+    /// Kategorie práce mimo tasky (porady, administrativa...)
+    #[serde(default)]
+    pub work_categories: Vec<categories::WorkCategory>,
+    /// Zapnutí zdrojů kontextu podle ID ("git": true), chybějící mají výchozí stav
+    #[serde(default)]
+    pub context_providers: BTreeMap<String, bool>,
+    /// Tvrdý limit délky ticku v sekundách, None = bez limitu
+    pub tick_max_seconds: Option<f64>,
+    /// Tvrdý limit ceny AI za tick v USD, None = bez limitu
+    pub tick_max_cost_usd: Option<f64>,
+    /// Denní rozpočet AI v USD, po vyčerpání jen textový matching (None = bez limitu)
+    pub ai_daily_budget_usd: Option<f64>,
+    /// Adaptivní interval - po změně kontextu kratší, při stabilní práci delší
+    #[serde(default)]
+    pub adaptive_interval: bool,
+    /// Strop adaptivního intervalu v sekundách (None = 60)
+    pub max_interval: Option<u64>,
+    /// Systémová oznámení po událostech (chybějící = výchozí)
+    #[serde(default)]
+    pub desktop_notifications: notifications::DesktopNotifications,
+    /// Rozpočty hodin podle tasků
+    #[serde(default)]
+    pub task_budgets: Vec<task_budget::TaskBudget>,
+    /// Tasky bez vlastního rozpočtu hlídat podle odhadu z Freelo
+    #[serde(default)]
+    pub budget_from_estimates: bool,
+    /// Na task s vyčerpaným rozpočtem už čas nepřiřazovat (tracking se zastaví)
+    #[serde(default)]
+    pub stop_over_budget: bool,
+}
+
+impl Settings {
+    /// Limity jednoho ticku
+    pub fn tick_budget(&self) -> TickBudget {
+        TickBudget {
+            max_seconds: self.tick_max_seconds,
+            max_cost_usd: self.tick_max_cost_usd,
+        }
+    }
+
+    /// Parametry AI requestu, nezadané hodnoty = výchozí
+    pub fn ai_params(&self) -> AiParams {
+        let defaults = AiParams::default();
+        AiParams {
+            model: self.ai_model.clone().filter(|m| !m.trim().is_empty()).unwrap_or(defaults.model),
+            temperature: self.ai_temperature.unwrap_or(defaults.temperature),
+            max_tokens: self.ai_max_tokens.unwrap_or(defaults.max_tokens),
+            top_p: self.ai_top_p,
+        }
+    }
+
+    /// Přihlašovací údaje zvolené služby pro sledování času
+    pub fn backend_config(&self) -> Result<BackendConfig, String> {
+        let required = |value: &Option<String>, name: &str| {
+            value
+                .clone()
+                .filter(|v| !v.trim().is_empty())
+                .ok_or_else(|| format!("Chybí {}", name))
+        };
+        Ok(match self.backend.unwrap_or_default() {
+            BackendKind::Freelo => BackendConfig::Freelo,
+            BackendKind::Toggl => BackendConfig::Toggl {
+                api_token: required(&self.toggl_api_token, "Toggl API token")?,
+                workspace_id: self.toggl_workspace_id,
+            },
+            BackendKind::Clockify => BackendConfig::Clockify {
+                api_key: required(&self.clockify_api_key, "Clockify API klíč")?,
+                workspace_id: self.clockify_workspace_id.clone().filter(|w| !w.trim().is_empty()),
+            },
+        })
+    }
+
+    /// AI služba pro matching, None = bez AI (OpenRouter bez klíče)
+    pub fn llm(&self) -> Option<LlmProvider> {
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        match self.llm_provider.unwrap_or_default() {
+            LlmProviderKind::OpenRouter => non_empty(&self.openrouter_key).map(|api_key| LlmProvider::OpenRouter { api_key }),
+            LlmProviderKind::Ollama => Some(LlmProvider::Ollama {
+                endpoint: non_empty(&self.ollama_url).unwrap_or_else(|| ai_matcher::DEFAULT_OLLAMA_URL.to_string()),
+                model: non_empty(&self.ollama_model).unwrap_or_else(|| ai_matcher::DEFAULT_OLLAMA_MODEL.to_string()),
+            }),
+        }
+    }
+
+    /// Kódy jazyků OCR (prázdné nastavení = výchozí)
+    pub fn ocr_language_codes(&self) -> Result<Vec<String>, String> {
+        let spec = self
+            .ocr_languages
+            .as_deref()
+            .filter(|l| !l.trim().is_empty())
+            .unwrap_or(ocr::DEFAULT_OCR_LANGUAGES);
+        ocr::parse_languages(spec)
+    }
+
+    /// Kontrola hodnot před uložením (i při kontrole konfigurace z CLI)
+    pub fn validate(&self) -> Result<(), String> {
+        ocr::create_engine(self.ocr_engine.unwrap_or_default())?;
+        ocr::validate_tuning(self.ocr_psm, self.ocr_oem)?;
+        ocr::validate_scaling(self.ocr_tile_size, self.ocr_scale)?;
+        self.ocr_language_codes()?;
+        if let Some(dir) = self.debug_dir.as_deref().filter(|d| !d.trim().is_empty()) {
+            debug_artifacts::validate_debug_dir(dir)?;
+        }
+        network::validate_rules(&self.network_rules)?;
+        if let Some(ref profile) = self.profile {
+            profiles::validate_profile_name(profile)?;
+        }
+        ai_matcher::validate_params(&self.ai_params())?;
+        categories::validate(&self.work_categories)?;
+        context_providers::validate(&self.context_providers)?;
+        if let Some(ref schedule) = self.schedule {
+            schedule::validate(schedule)?;
+        }
+        budget::validate(&self.tick_budget())?;
+        ai_usage::validate_daily_budget(self.ai_daily_budget_usd)?;
+        adaptive_interval::validate(self.interval, self.max_interval)?;
+        task_budget::validate(&self.task_budgets)?;
+        if let Some(url) = self.ollama_url.as_deref().filter(|u| !u.trim().is_empty()) {
+            ai_matcher::validate_ollama_url(url)?;
+        }
+        if self.analysis_mode.is_some_and(|m| m != AnalysisMode::OcrText) && self.llm().is_none() {
+            return Err("Analýza screenshotu přes AI vyžaduje OpenRouter API key nebo Ollamu".to_string());
+        }
+        self.backend_config()?;
+        if let Some(min_confidence) = self.min_confidence {
+            if !(0.0..=1.0).contains(&min_confidence) {
+                return Err(format!("Minimální confidence musí být 0.0-1.0 (zadáno {})", min_confidence));
+            }
+        }
+        if let Some(ticks) = self.unstable_ticks_needed {
+            if !(1..=20).contains(&ticks) {
+                return Err(format!("Počet nestabilních ticků musí být 1-20 (zadáno {})", ticks));
+            }
+        }
+        if let Some(threshold) = self.screen_diff_threshold {
+            if threshold > 64 {
+                return Err(format!("Práh změny obrazovky musí být 0-64 (zadáno {})", threshold));
+            }
+        }
+        if self.evidence_interval_minutes == Some(0) {
+            return Err("Interval důkazních screenshotů musí být alespoň 1 minuta".to_string());
+        }
+        if self.evidence_retention_days == Some(0) {
+            return Err("Doba uchování důkazních screenshotů musí být alespoň 1 den".to_string());
+        }
+        Ok(())
+    }
+
+    /// Konfigurace trackeru z uloženého nastavení
+    pub fn tracker_config(&self) -> Result<TrackerConfig, String> {
+        let profile = self
+            .profile
+            .clone()
+            .unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string());
+
+        Ok(TrackerConfig {
+            interval_seconds: self.interval,
+            freelo_email: self.freelo_email.clone(),
+            freelo_api_key: self.freelo_key.clone(),
+            backend: self.backend_config()?,
+            llm: self.llm(),
+            ocr_engine: self.ocr_engine.unwrap_or_default(),
+            ocr_psm: self.ocr_psm,
+            ocr_oem: self.ocr_oem,
+            ocr_tile_size: match self.ocr_tile_size {
+                Some(0) => None,
+                size => Some(size.unwrap_or(ocr::DEFAULT_TILE_SIZE)),
+            },
+            ocr_scale: self.ocr_scale.unwrap_or(1.0),
+            ocr_languages: self.ocr_language_codes()?.join("+"),
+            manual_grace_seconds: self
+                .manual_grace_seconds
+                .unwrap_or(tracker::DEFAULT_MANUAL_GRACE_SECONDS),
+            max_inactive_minutes: self
+                .max_inactive_minutes
+                .unwrap_or(tracker::DEFAULT_MAX_INACTIVE_MINUTES),
+            spike_auto_stop: self.spike_auto_stop,
+            network_rules: self.network_rules.clone(),
+            profile,
+            evidence_interval_minutes: self.evidence_interval_minutes,
+            evidence_retention_days: self
+                .evidence_retention_days
+                .unwrap_or(evidence::DEFAULT_EVIDENCE_RETENTION_DAYS),
+            ai_params: self.ai_params(),
+            matcher_auto_priority: self.matcher_auto_priority,
+            redact_ai_input: self.redact_ai_input.unwrap_or(true),
+            privacy_mode: self.privacy_mode,
+            analysis_mode: self.analysis_mode.unwrap_or_default(),
+            hide_policy: self.hide_policy.unwrap_or_default(),
+            monitor_selection: self.monitor_selection.unwrap_or_default(),
+            resume_warmup_seconds: self
+                .resume_warmup_seconds
+                .unwrap_or(resume::DEFAULT_RESUME_WARMUP_SECONDS),
+            focus_calendar: self
+                .focus_calendar_path
+                .as_ref()
+                .filter(|p| !p.is_empty())
+                .map(std::path::PathBuf::from),
+            focus_keyword: self
+                .focus_keyword
+                .clone()
+                .filter(|k| !k.trim().is_empty())
+                .unwrap_or_else(|| notifications::DEFAULT_FOCUS_KEYWORD.to_string()),
+            sandbox_credentials: match (&self.sandbox_freelo_email, &self.sandbox_freelo_key) {
+                (Some(email), Some(api_key)) if !email.is_empty() && !api_key.is_empty() => {
+                    Some(FreeloCredentials {
+                        email: email.clone(),
+                        api_key: api_key.clone(),
+                    })
+                }
+                _ => None,
+            },
+            idle_threshold_minutes: self
+                .idle_threshold_minutes
+                .unwrap_or(idle::DEFAULT_IDLE_THRESHOLD_MINUTES),
+            memory_limit_mb: self
+                .memory_limit_mb
+                .unwrap_or(resources::DEFAULT_MEMORY_LIMIT_MB),
+            task_refresh_minutes: self
+                .task_refresh_minutes
+                .unwrap_or(tracker::DEFAULT_TASK_REFRESH_MINUTES),
+            selected_project_ids: self.selected_project_ids.clone(),
+            schedule: self.schedule.clone(),
+            post_activity_comments: self.post_activity_comments,
+            history_retention_months: self
+                .history_retention_months
+                .unwrap_or(history::DEFAULT_HISTORY_RETENTION_MONTHS),
+            min_confidence: self.min_confidence.unwrap_or(tracker::DEFAULT_MIN_CONFIDENCE),
+            unstable_ticks_needed: self
+                .unstable_ticks_needed
+                .unwrap_or(tracker::DEFAULT_UNSTABLE_TICKS_NEEDED),
+            restart_cooldown_seconds: self
+                .restart_cooldown_seconds
+                .unwrap_or(tracker::DEFAULT_RESTART_COOLDOWN_SECONDS),
+            embedding_matching: self.embedding_matching,
+            screen_diff_threshold: self
+                .screen_diff_threshold
+                .unwrap_or(tracker::DEFAULT_SCREEN_DIFF_THRESHOLD),
+            work_categories: self.work_categories.clone(),
+            context_providers: context_providers::ProviderToggles::new(self.context_providers.clone()),
+            tick_budget: self.tick_budget(),
+            ai_daily_budget_usd: self.ai_daily_budget_usd,
+            adaptive_interval: self.adaptive_interval,
+            max_interval_seconds: self
+                .max_interval
+                .unwrap_or(adaptive_interval::DEFAULT_MAX_INTERVAL_SECONDS),
+            desktop_notifications: self.desktop_notifications,
+            task_budgets: self.task_budgets.clone(),
+            budget_from_estimates: self.budget_from_estimates,
+            stop_over_budget: self.stop_over_budget,
+        })
+    }
+}
+
+/// Cesta k souboru s nastavením (~/.config/<app>/settings.json, %APPDATA%\<app>\…)
+pub fn settings_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_IDENTIFIER)
+        .join(SETTINGS_FILE)
+}
+
+/// Nastavení uložené u profilu při přepnutí na jiný profil
+fn profile_settings_path(profile: &str) -> PathBuf {
+    profiles::profile_dir(profile).join(SETTINGS_FILE)
+}
+
+/// Načte uložené nastavení (API klíče z klíčenky), None pokud ještě nebylo uloženo
+pub fn load() -> Option<Settings> {
+    load_from(&settings_path(), None)
+}
+
+/// Nastavení profilu z posledního přepnutí pryč z něj, None = profil ho ještě nemá
+pub fn load_profile(profile: &str) -> Option<Settings> {
+    load_from(&profile_settings_path(profile), Some(profile))
+}
+
+fn load_from(path: &Path, scope: Option<&str>) -> Option<Settings> {
+    // Nastavení profilu je šifrované klíčem profilu
+    let mut settings: Settings = match scope {
+        Some(profile) => profile_crypto::load_json(profile, path)?,
+        None => storage::load_json(path)?,
+    };
+    if let Err(e) = secrets::fill_from_keychain(&mut settings, scope) {
+        tracing::warn!(error = %e, "API klíče z klíčenky nenačteny");
+    }
+    Some(settings)
+}
+
+/// Uloží nastavení - API klíče do klíčenky systému. Bez klíčenky (např. Linux
+/// bez Secret Service) zůstanou v souboru, proto je soubor jen pro vlastníka.
+pub fn save(settings: &Settings) -> Result<(), String> {
+    save_to(&settings_path(), settings, None)
+}
+
+/// Odloží nastavení k profilu (přihlašovací údaje klienta pro příští přepnutí zpět)
+pub fn save_profile(profile: &str, settings: &Settings) -> Result<(), String> {
+    save_to(&profile_settings_path(profile), settings, Some(profile))
+}
+
+fn save_to(path: &Path, settings: &Settings, scope: Option<&str>) -> Result<(), String> {
+    let on_disk = secrets::move_to_keychain(settings, scope).unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "klíčenka nedostupná, API klíče zůstávají v souboru");
+        settings.clone()
+    });
+    match scope {
+        Some(profile) => profile_crypto::save_json(profile, path, &on_disk)?,
+        None => storage::save_json(path, &on_disk)?,
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Nelze nastavit práva {:?}: {}", path, e))?;
+    }
+
+    Ok(())
+}
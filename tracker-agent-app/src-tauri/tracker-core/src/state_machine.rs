@@ -0,0 +1,471 @@
+use crate::text_matcher::{activity_key, MatchResult};
+use std::time::Duration;
+
+/// Klíč segmentu obecné práce (bez tasku a kategorie)
+pub const GENERAL_WORK_KEY: &str = "general_work";
+
+/// Běžící segment, jak ho vidí rozhodování (bez Freelo stavu)
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentState<'a> {
+    pub key: &'a str,
+    pub application: &'a str,
+    pub activity: &'a str,
+    pub unstable_count: u32,
+    /// Jak dlouho segment běží
+    pub age: Duration,
+}
+
+/// Výsledek ticku převedený na klíč segmentu
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// ID tasku, klíč kategorie nebo `GENERAL_WORK_KEY`
+    pub key: String,
+    /// Matcher našel task nad prahem confidence
+    pub matched_task: bool,
+    pub application: String,
+    pub activity: String,
+}
+
+/// Co se oproti běžícímu segmentu změnilo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContextChange {
+    pub application: bool,
+    pub activity: bool,
+    pub task: bool,
+}
+
+impl ContextChange {
+    pub fn any(self) -> bool {
+        self.application || self.activity || self.task
+    }
+}
+
+/// Proč se tracking zastavuje
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// Uživatel je pryč od počítače
+    Idle,
+    /// Mimo nastavenou pracovní dobu
+    OutsideSchedule,
+    /// Síť, ve které se podle pravidel netrackuje
+    Network,
+    /// Nečinnost v době oběda
+    LunchBreak,
+    /// Segment běží dlouho bez vstupu (zapomenutý timer, se `spike_auto_stop`)
+    InactivitySpike,
+    /// Pozorovaný task má vyčerpaný rozpočet (se `stop_over_budget`)
+    OverBudget,
+}
+
+/// Brány ticku, které mají přednost před hysterezí
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickGates {
+    pub idle: bool,
+    pub outside_schedule: bool,
+    pub network_paused: bool,
+    pub lunch_break: bool,
+    pub inactivity_spike: bool,
+    /// Týká se pozorovaného tasku, ne běžícího segmentu
+    pub over_budget: bool,
+}
+
+/// Rozhodnutí pro běžící segment - Freelo zápisy provádí tracker podle něj
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackingAction {
+    /// Nic neběží - spustit nový segment
+    Start,
+    /// Ukončit běžící segment a nový nespouštět
+    Stop { reason: StopReason },
+    /// Segment pokračuje; změněný kontext se převezme, počítadlo nestability je `unstable_count`
+    Continue { change: ContextChange, unstable_count: u32 },
+    /// Návrh jiného tasku, ale kontext ještě není stabilní - segment zatím běží dál
+    Wait { change: ContextChange, unstable_count: u32 },
+    /// Kontext je stabilně jiný - ukončit segment a spustit nový
+    Restart { change: ContextChange },
+    /// Ochranná doba po ručním zásahu - `overridden` = automatika chtěla jiný task
+    Hold { overridden: bool },
+}
+
+/// Hystereze přepínání segmentů - čisté rozhodování bez vedlejších efektů
+#[derive(Debug, Clone, Copy)]
+pub struct TrackingStateMachine {
+    /// Pod touto confidence se task z matcheru nepoužije
+    pub min_confidence: f32,
+    /// Kolik ticků v řadě se musí kontext lišit
+    pub unstable_ticks_needed: u32,
+    /// Mladší segment se nerestartuje
+    pub restart_cooldown: Duration,
+}
+
+impl TrackingStateMachine {
+    /// Klíč segmentu z výsledku matcheru - kategorie práce má přednost
+    pub fn observe(&self, match_result: &MatchResult, category_key: Option<String>) -> Observation {
+        let task_id = match_result.task_id.filter(|_| match_result.confidence > self.min_confidence);
+        Observation {
+            matched_task: task_id.is_some(),
+            key: category_key
+                .or_else(|| task_id.map(|id| id.to_string()))
+                .unwrap_or_else(|| GENERAL_WORK_KEY.to_string()),
+            application: match_result.detected_application.clone(),
+            activity: match_result.activity_description.clone(),
+        }
+    }
+
+    /// Změna kontextu proti segmentu (aktivita se porovnává bez drobných rozdílů v textu)
+    pub fn change(segment: &SegmentState, observation: &Observation) -> ContextChange {
+        ContextChange {
+            application: segment.application != observation.application,
+            activity: activity_key(segment.activity) != activity_key(&observation.activity),
+            task: segment.key != observation.key,
+        }
+    }
+
+    /// Nový restart je nutný, ale segment je na něj moc čerstvý
+    pub fn cooling_down(&self, segment: &SegmentState, unstable_count: u32) -> bool {
+        unstable_count >= self.needed() && segment.age < self.restart_cooldown
+    }
+
+    fn needed(&self) -> u32 {
+        self.unstable_ticks_needed.max(1)
+    }
+
+    /// Brány, které platí bez ohledu na pozorování (tracker je kontroluje ještě před snímkem)
+    pub fn gate(gates: TickGates) -> Option<TrackingAction> {
+        let reason = if gates.outside_schedule {
+            StopReason::OutsideSchedule
+        } else if gates.network_paused {
+            StopReason::Network
+        } else if gates.lunch_break {
+            StopReason::LunchBreak
+        } else if gates.idle {
+            StopReason::Idle
+        } else if gates.inactivity_spike {
+            StopReason::InactivitySpike
+        } else {
+            return None;
+        };
+        Some(TrackingAction::Stop { reason })
+    }
+
+    pub fn decide(
+        &self,
+        segment: Option<&SegmentState>,
+        observation: &Observation,
+        gates: TickGates,
+        in_grace_period: bool,
+    ) -> TrackingAction {
+        if let Some(action) = Self::gate(gates) {
+            return action;
+        }
+        // Rozpočet se týká jen tasku, kategorie ani obecná práce ho nemají
+        let over_budget = gates.over_budget && observation.matched_task;
+        let over_budget_stop = TrackingAction::Stop { reason: StopReason::OverBudget };
+
+        let Some(segment) = segment else {
+            return if over_budget { over_budget_stop } else { TrackingAction::Start };
+        };
+        let change = Self::change(segment, observation);
+
+        // Po ručním zásahu automatika nepřepíná ani nerestartuje (ani kvůli rozpočtu)
+        if in_grace_period {
+            return TrackingAction::Hold { overridden: change.task };
+        }
+
+        if over_budget && !change.task {
+            return over_budget_stop;
+        }
+
+        if !change.any() {
+            return TrackingAction::Continue { change, unstable_count: 0 };
+        }
+
+        let unstable_count = segment.unstable_count + 1;
+        if unstable_count >= self.needed() && !self.cooling_down(segment, unstable_count) {
+            // Na task s vyčerpaným rozpočtem se nepřepíná, jen se skončí
+            if over_budget {
+                over_budget_stop
+            } else {
+                TrackingAction::Restart { change }
+            }
+        } else if change.task {
+            TrackingAction::Wait { change, unstable_count }
+        } else {
+            TrackingAction::Continue { change, unstable_count }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_matcher::MatchSource;
+
+    const MACHINE: TrackingStateMachine = TrackingStateMachine {
+        min_confidence: 0.5,
+        unstable_ticks_needed: 2,
+        restart_cooldown: Duration::from_secs(60),
+    };
+
+    fn observation(key: &str, application: &str, activity: &str) -> Observation {
+        Observation {
+            key: key.to_string(),
+            matched_task: key != GENERAL_WORK_KEY,
+            application: application.to_string(),
+            activity: activity.to_string(),
+        }
+    }
+
+    /// Běžící segment jako v trackeru - akce se na něj aplikují stejně jako na ActiveTracking
+    struct Running {
+        key: String,
+        application: String,
+        activity: String,
+        unstable_count: u32,
+        age: Duration,
+    }
+
+    impl Running {
+        fn new(obs: &Observation) -> Self {
+            Running {
+                key: obs.key.clone(),
+                application: obs.application.clone(),
+                activity: obs.activity.clone(),
+                unstable_count: 0,
+                age: Duration::from_secs(600),
+            }
+        }
+
+        fn state(&self) -> SegmentState<'_> {
+            SegmentState {
+                key: &self.key,
+                application: &self.application,
+                activity: &self.activity,
+                unstable_count: self.unstable_count,
+                age: self.age,
+            }
+        }
+    }
+
+    /// Projde sekvenci ticků a vrátí rozhodnutí
+    fn run(machine: &TrackingStateMachine, ticks: &[Observation]) -> Vec<TrackingAction> {
+        let ticks: Vec<_> = ticks.iter().map(|obs| (obs.clone(), TickGates::default())).collect();
+        run_gated(machine, &ticks)
+    }
+
+    fn run_gated(machine: &TrackingStateMachine, ticks: &[(Observation, TickGates)]) -> Vec<TrackingAction> {
+        let mut running: Option<Running> = None;
+        ticks
+            .iter()
+            .map(|(obs, gates)| {
+                let action = machine.decide(running.as_ref().map(|r| r.state()).as_ref(), obs, *gates, false);
+                match action {
+                    TrackingAction::Start | TrackingAction::Restart { .. } => running = Some(Running::new(obs)),
+                    TrackingAction::Stop { .. } => running = None,
+                    TrackingAction::Continue { change, unstable_count } => {
+                        let r = running.as_mut().unwrap();
+                        r.unstable_count = unstable_count;
+                        if change.any() {
+                            r.application = obs.application.clone();
+                            r.activity = obs.activity.clone();
+                        }
+                    }
+                    TrackingAction::Wait { unstable_count, .. } => running.as_mut().unwrap().unstable_count = unstable_count,
+                    TrackingAction::Hold { .. } => {}
+                }
+                action
+            })
+            .collect()
+    }
+
+    fn kind(action: &TrackingAction) -> &'static str {
+        match action {
+            TrackingAction::Start => "start",
+            TrackingAction::Stop { .. } => "stop",
+            TrackingAction::Continue { .. } => "continue",
+            TrackingAction::Wait { .. } => "wait",
+            TrackingAction::Restart { .. } => "restart",
+            TrackingAction::Hold { .. } => "hold",
+        }
+    }
+
+    fn kinds(machine: &TrackingStateMachine, ticks: &[Observation]) -> Vec<&'static str> {
+        run(machine, ticks).iter().map(kind).collect()
+    }
+
+    #[test]
+    fn test_observe_threshold_and_category() {
+        let result = MatchResult {
+            task_id: Some(42),
+            task_name: Some("Login".to_string()),
+            confidence: 0.4,
+            detected_application: "VS Code".to_string(),
+            matched_keywords: Vec::new(),
+            activity_description: "Píše kód".to_string(),
+            source: MatchSource::Text,
+        };
+        let weak = MACHINE.observe(&result, None);
+        assert_eq!(weak.key, GENERAL_WORK_KEY);
+        assert!(!weak.matched_task);
+
+        let strong = MACHINE.observe(&MatchResult { confidence: 0.9, ..result.clone() }, None);
+        assert_eq!(strong.key, "42");
+        assert!(strong.matched_task);
+
+        let category = MACHINE.observe(&MatchResult { confidence: 0.9, ..result }, Some("category:meeting".to_string()));
+        assert_eq!(category.key, "category:meeting");
+    }
+
+    #[test]
+    fn test_stable_context_continues() {
+        let tick = observation("1", "VS Code", "Píše kód");
+        let actions = run(&MACHINE, &[tick.clone(), tick.clone(), tick]);
+        assert_eq!(actions[0], TrackingAction::Start);
+        assert_eq!(actions[1], TrackingAction::Continue { change: ContextChange::default(), unstable_count: 0 });
+        assert_eq!(actions[2], actions[1]);
+    }
+
+    #[test]
+    fn test_single_noisy_tick_is_ignored() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("2", "Slack", "Čte zprávy");
+        assert_eq!(kinds(&MACHINE, &[a.clone(), b, a.clone(), a]), vec!["start", "wait", "continue", "continue"]);
+    }
+
+    #[test]
+    fn test_task_switch_after_needed_ticks() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("2", "Slack", "Čte zprávy");
+        let actions = run(&MACHINE, &[a, b.clone(), b.clone(), b]);
+        assert_eq!(actions.iter().map(kind).collect::<Vec<_>>(), vec!["start", "wait", "restart", "continue"]);
+        assert_eq!(
+            actions[2],
+            TrackingAction::Restart {
+                change: ContextChange { application: true, activity: true, task: true }
+            }
+        );
+    }
+
+    #[test]
+    fn test_task_switch_in_same_application() {
+        // Jiný task se stejnou aplikací i aktivitou se dřív nikdy nepřepnul
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("2", "VS Code", "Píše kód");
+        assert_eq!(kinds(&MACHINE, &[a, b.clone(), b]), vec!["start", "wait", "restart"]);
+    }
+
+    #[test]
+    fn test_same_task_drifting_context() {
+        let ticks = [
+            observation("1", "VS Code", "Píše kód"),
+            observation("1", "Terminal", "Spouští testy"),
+            observation("1", "Firefox", "Čte dokumentaci"),
+        ];
+        let actions = run(&MACHINE, &ticks);
+        assert_eq!(
+            actions[1],
+            TrackingAction::Continue {
+                change: ContextChange { application: true, activity: true, task: false },
+                unstable_count: 1
+            }
+        );
+        // Druhá změna v řadě = stabilně jiný kontext i u stejného tasku
+        assert_eq!(kind(&actions[2]), "restart");
+    }
+
+    #[test]
+    fn test_context_settles_resets_counter() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("1", "Terminal", "Spouští testy");
+        let actions = run(&MACHINE, &[a, b.clone(), b]);
+        assert_eq!(actions[2], TrackingAction::Continue { change: ContextChange::default(), unstable_count: 0 });
+    }
+
+    #[test]
+    fn test_cooldown_postpones_restart() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("2", "Slack", "Čte zprávy");
+        let mut running = Running::new(&a);
+        running.age = Duration::from_secs(30);
+        running.unstable_count = 1;
+        assert!(MACHINE.cooling_down(&running.state(), 2));
+        assert_eq!(
+            MACHINE.decide(Some(&running.state()), &b, TickGates::default(), false),
+            TrackingAction::Wait {
+                change: ContextChange { application: true, activity: true, task: true },
+                unstable_count: 2
+            }
+        );
+        running.age = Duration::from_secs(60);
+        assert_eq!(kind(&MACHINE.decide(Some(&running.state()), &b, TickGates::default(), false)), "restart");
+    }
+
+    #[test]
+    fn test_single_tick_threshold() {
+        let machine = TrackingStateMachine { unstable_ticks_needed: 0, ..MACHINE };
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("2", "Slack", "Čte zprávy");
+        assert_eq!(kinds(&machine, &[a, b]), vec!["start", "restart"]);
+    }
+
+    #[test]
+    fn test_grace_period_holds_segment() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let running = Running::new(&a);
+        let b = observation("2", "Slack", "Čte zprávy");
+        assert_eq!(MACHINE.decide(Some(&running.state()), &b, TickGates::default(), true), TrackingAction::Hold { overridden: true });
+        assert_eq!(MACHINE.decide(Some(&running.state()), &a, TickGates::default(), true), TrackingAction::Hold { overridden: false });
+        // Bez běžícího segmentu se startuje i v ochranné době
+        assert_eq!(MACHINE.decide(None, &b, TickGates::default(), true), TrackingAction::Start);
+    }
+
+    #[test]
+    fn test_idle_and_schedule_stop_segment() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let idle = TickGates { idle: true, ..Default::default() };
+        let actions = run_gated(&MACHINE, &[(a.clone(), TickGates::default()), (a.clone(), idle), (a.clone(), idle), (a.clone(), TickGates::default())]);
+        assert_eq!(actions.iter().map(kind).collect::<Vec<_>>(), vec!["start", "stop", "stop", "start"]);
+        assert_eq!(actions[1], TrackingAction::Stop { reason: StopReason::Idle });
+
+        // Mimo pracovní dobu se zastavuje i v ochranné době po ručním zásahu
+        let outside = TickGates { outside_schedule: true, idle: true, ..Default::default() };
+        let running = Running::new(&a);
+        assert_eq!(
+            MACHINE.decide(Some(&running.state()), &a, outside, true),
+            TrackingAction::Stop { reason: StopReason::OutsideSchedule }
+        );
+        assert_eq!(TrackingStateMachine::gate(TickGates::default()), None);
+
+        // Oběd je zvláštní případ nečinnosti, síť zastavuje i bez nečinnosti
+        let lunch = TickGates { lunch_break: true, idle: true, ..Default::default() };
+        assert_eq!(TrackingStateMachine::gate(lunch), Some(TrackingAction::Stop { reason: StopReason::LunchBreak }));
+        let network = TickGates { network_paused: true, inactivity_spike: true, ..Default::default() };
+        assert_eq!(TrackingStateMachine::gate(network), Some(TrackingAction::Stop { reason: StopReason::Network }));
+        let spike = TickGates { inactivity_spike: true, ..Default::default() };
+        assert_eq!(
+            MACHINE.decide(Some(&running.state()), &a, spike, true),
+            TrackingAction::Stop { reason: StopReason::InactivitySpike }
+        );
+    }
+
+    #[test]
+    fn test_over_budget_task_is_not_tracked() {
+        let a = observation("1", "VS Code", "Píše kód");
+        let b = observation("2", "Slack", "Čte zprávy");
+        let over = TickGates { over_budget: true, ..Default::default() };
+        let stop = TrackingAction::Stop { reason: StopReason::OverBudget };
+
+        // Běžící task s vyčerpaným rozpočtem skončí a znovu se nespustí
+        let actions = run_gated(&MACHINE, &[(a.clone(), TickGates::default()), (a.clone(), over), (a.clone(), over)]);
+        assert_eq!(actions[1..], [stop, stop]);
+
+        // Na jiný task s vyčerpaným rozpočtem se nepřepne - až stabilní kontext segment ukončí
+        let actions = run_gated(&MACHINE, &[(a, TickGates::default()), (b.clone(), over), (b.clone(), over)]);
+        assert_eq!(actions.iter().map(kind).collect::<Vec<_>>(), vec!["start", "wait", "stop"]);
+
+        // Obecná práce rozpočet nemá, ruční volba v ochranné době má přednost
+        let general = observation(GENERAL_WORK_KEY, "Slack", "Čte zprávy");
+        assert_eq!(MACHINE.decide(None, &general, over, false), TrackingAction::Start);
+        let running = Running::new(&b);
+        assert_eq!(MACHINE.decide(Some(&running.state()), &b, over, true), TrackingAction::Hold { overridden: false });
+    }
+}
@@ -0,0 +1,54 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Identifikátor aplikace (stejný jako v tauri.conf.json), aby data ležela
+/// ve stejném adresáři jako Tauri `app_data_dir`
+pub const APP_IDENTIFIER: &str = "com.janrubes.tracker-agent-app";
+
+/// Adresář pro perzistentní data aplikace (vytvoří ho pokud neexistuje)
+pub fn app_data_dir() -> PathBuf {
+    let path = dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_IDENTIFIER);
+
+    if !path.exists() {
+        std::fs::create_dir_all(&path).ok();
+    }
+
+    path
+}
+
+/// Cesta k souboru v datovém adresáři aplikace
+pub fn data_file(name: &str) -> PathBuf {
+    app_data_dir().join(name)
+}
+
+/// Načte JSON soubor, při chybě nebo neexistenci vrací None
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("⚠️  Nepodařilo se načíst {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Uloží hodnotu jako JSON (přes dočasný soubor, aby nevznikl poloviční zápis)
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Chyba při serializaci: {}", e))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Chyba při vytváření adresáře {:?}: {}", parent, e))?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, content)
+        .map_err(|e| format!("Chyba při zápisu {:?}: {}", tmp_path, e))?;
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Chyba při přejmenování {:?}: {}", path, e))
+}
@@ -0,0 +1,430 @@
+use crate::freelo::FreeloTask;
+use crate::history::{HistorySegment, TickAction, TickRecord};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Mezera mezi segmenty kratší než tohle není "nesledovaný čas" (přepnutí, restart)
+pub const MIN_GAP_MINUTES: i64 = 5;
+
+/// Tick se započítá nejvýše jako dva intervaly (mezi ticky mohla být pauza/spánek)
+pub const MAX_TICK_SPAN_INTERVALS: i64 = 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTotal {
+    /// None = obecná práce bez tasku
+    pub task_id: Option<i32>,
+    pub task_name: String,
+    pub project_name: Option<String>,
+    pub minutes: i64,
+    pub segments: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplicationTotal {
+    pub application: String,
+    pub minutes: i64,
+}
+
+/// Nesledovaný úsek mezi dvěma segmenty
+#[derive(Debug, Clone, Serialize)]
+pub struct Gap {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    pub minutes: i64,
+}
+
+/// Souhrn dne z lokální historie (dokončené segmenty + log rozhodnutí)
+#[derive(Debug, Clone, Serialize)]
+pub struct DailySummary {
+    pub date: NaiveDate,
+    pub total_minutes: i64,
+    pub first_start: Option<DateTime<Local>>,
+    pub last_stop: Option<DateTime<Local>>,
+    /// Od nejdelšího
+    pub tasks: Vec<TaskTotal>,
+    /// Od nejdelší (podle ticků, ne segmentů)
+    pub applications: Vec<ApplicationTotal>,
+    /// Kolikrát se mezi po sobě jdoucími segmenty změnil task
+    pub context_switches: usize,
+    pub gaps: Vec<Gap>,
+    pub untracked_minutes: i64,
+}
+
+fn task_totals(segments: &[HistorySegment], tasks: &[FreeloTask]) -> Vec<TaskTotal> {
+    let mut totals: BTreeMap<Option<i32>, TaskTotal> = BTreeMap::new();
+    for segment in segments {
+        let total = totals.entry(segment.task_id).or_insert_with(|| {
+            let task = segment.task_id.and_then(|id| tasks.iter().find(|t| t.id == id));
+            TaskTotal {
+                task_id: segment.task_id,
+                task_name: match (segment.task_id, task) {
+                    (_, Some(task)) => task.name.clone(),
+                    (Some(id), None) => format!("Task #{}", id),
+                    (None, None) => "Obecná práce".to_string(),
+                },
+                project_name: task.map(|t| t.project_name.clone()),
+                minutes: 0,
+                segments: 0,
+            }
+        });
+        total.minutes += segment.minutes();
+        total.segments += 1;
+    }
+
+    let mut totals: Vec<TaskTotal> = totals.into_values().collect();
+    totals.sort_by_key(|t| std::cmp::Reverse(t.minutes));
+    totals
+}
+
+/// Čas v aplikacích podle ticků - každý tick trvá do dalšího (max. 2 intervaly)
+fn application_totals(ticks: &[TickRecord], interval_seconds: u64) -> Vec<ApplicationTotal> {
+    let mut ticks: Vec<&TickRecord> = ticks
+        .iter()
+        .filter(|t| t.action != TickAction::Skipped && t.application.is_some())
+        .collect();
+    ticks.sort_by_key(|t| t.at);
+
+    let max_span = interval_seconds as i64 * MAX_TICK_SPAN_INTERVALS;
+    let mut seconds: BTreeMap<&str, i64> = BTreeMap::new();
+    for (i, tick) in ticks.iter().enumerate() {
+        let span = ticks
+            .get(i + 1)
+            .map(|next| (next.at - tick.at).num_seconds())
+            .unwrap_or(interval_seconds as i64)
+            .clamp(0, max_span);
+        *seconds.entry(tick.application.as_deref().unwrap_or_default()).or_default() += span;
+    }
+
+    let mut totals: Vec<ApplicationTotal> = seconds
+        .into_iter()
+        .map(|(application, seconds)| ApplicationTotal {
+            application: application.to_string(),
+            minutes: seconds / 60,
+        })
+        .collect();
+    totals.sort_by_key(|t| std::cmp::Reverse(t.minutes));
+    totals
+}
+
+/// Souhrn dne - `segments` a `ticks` můžou obsahovat i jiné dny, vyberou se jen z `date`
+pub fn daily_summary(
+    date: NaiveDate,
+    segments: &[HistorySegment],
+    ticks: &[TickRecord],
+    tasks: &[FreeloTask],
+    interval_seconds: u64,
+) -> DailySummary {
+    let mut day: Vec<HistorySegment> = segments
+        .iter()
+        .filter(|s| s.started_at.date_naive() == date)
+        .cloned()
+        .collect();
+    day.sort_by_key(|s| s.started_at);
+
+    let day_ticks: Vec<TickRecord> = ticks.iter().filter(|t| t.at.date_naive() == date).cloned().collect();
+
+    let context_switches = day.windows(2).filter(|pair| pair[0].task_id != pair[1].task_id).count();
+
+    let mut gaps = Vec::new();
+    let mut covered_until: Option<DateTime<Local>> = None;
+    for segment in &day {
+        if let Some(until) = covered_until {
+            let minutes = (segment.started_at - until).num_minutes();
+            if minutes >= MIN_GAP_MINUTES {
+                gaps.push(Gap {
+                    from: until,
+                    to: segment.started_at,
+                    minutes,
+                });
+            }
+        }
+        covered_until = Some(covered_until.map_or(segment.stopped_at, |u| u.max(segment.stopped_at)));
+    }
+
+    DailySummary {
+        date,
+        total_minutes: day.iter().map(|s| s.minutes()).sum(),
+        first_start: day.first().map(|s| s.started_at),
+        last_stop: covered_until,
+        tasks: task_totals(&day, tasks),
+        applications: application_totals(&day_ticks, interval_seconds),
+        context_switches,
+        untracked_minutes: gaps.iter().map(|g| g.minutes).sum(),
+        gaps,
+    }
+}
+
+/// Kratší úseky se ve vysvětlení mezery neuvádějí
+const MIN_CAUSE_SECONDS: i64 = 60;
+
+/// Jeden důvod, proč v části mezery neběžel záznam
+#[derive(Debug, Clone, Serialize)]
+pub struct GapCause {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    pub minutes: i64,
+    /// Kód důvodu - důvod přeskočení ticku (`idle`, `lock_screen`...), `tracker_stopped`, `error`...
+    pub cause: String,
+    pub description: String,
+    /// Kolik ticků úsek pokrývá (0 = tracker neběžel)
+    pub ticks: usize,
+}
+
+/// Vysvětlení nesledovaného času v rozsahu
+#[derive(Debug, Clone, Serialize)]
+pub struct GapExplanation {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    pub tracked_minutes: i64,
+    pub untracked_minutes: i64,
+    /// Chronologicky
+    pub causes: Vec<GapCause>,
+    /// Minuty podle důvodu
+    pub totals: BTreeMap<String, i64>,
+}
+
+fn cause_description(cause: &str) -> String {
+    match cause {
+        "tracker_stopped" => "Tracker neběžel (aplikace vypnutá, tracking zastavený nebo počítač uspaný)".to_string(),
+        "idle" => "Nečinnost uživatele".to_string(),
+        "lock_screen" => "Zamčená obrazovka".to_string(),
+        "lunch_break" => "Pauza na oběd".to_string(),
+        "paused" => "Tracking ručně pozastavený".to_string(),
+        "network_rules" => "Pozastaveno síťovými pravidly (Wi-Fi/VPN)".to_string(),
+        "kill_switch" => "Nouzové vypnutí".to_string(),
+        "resume_warmup" => "Probuzení ze spánku".to_string(),
+        "capture_failed" => "Screenshot selhal".to_string(),
+        "ocr_busy" => "OCR nestíhalo, ticky se přeskakovaly".to_string(),
+        "no_screen_context" => "Bez OCR i aktivního okna".to_string(),
+        "degraded" => "Screenshot ani aktivní okno nefungují, analýza pozastavená".to_string(),
+        "error" => "Chyba při zpracování ticku".to_string(),
+        "waiting" => "Kontext se měnil, čekalo se na stabilizaci".to_string(),
+        "over_budget" => "Task měl vyčerpaný rozpočet, čas se mu nepřiřazoval".to_string(),
+        "not_recorded" => "Tracking běžel, ale segment není v historii (dry-run, sandbox nebo stále běží)".to_string(),
+        other => format!("Tick přeskočen ({})", other),
+    }
+}
+
+/// Důvod podle rozhodnutí v ticku
+fn tick_cause(tick: &TickRecord) -> String {
+    match tick.action {
+        TickAction::Skipped => tick.reason.clone().unwrap_or_else(|| "skipped".to_string()),
+        TickAction::Stopped => tick.reason.clone().unwrap_or_else(|| "stopped".to_string()),
+        TickAction::Failed => "error".to_string(),
+        TickAction::Waiting => "waiting".to_string(),
+        TickAction::Continued | TickAction::Started | TickAction::Switched | TickAction::HeldByManual => {
+            "not_recorded".to_string()
+        }
+    }
+}
+
+/// Proč v rozsahu [from, to) nebyl sledovaný čas - z uložených segmentů a logu rozhodnutí.
+/// Tick pokrývá čas do dalšího ticku (max. 2 intervaly), nepokrytý čas = tracker neběžel.
+pub fn explain_gap(
+    from: DateTime<Local>,
+    to: DateTime<Local>,
+    segments: &[HistorySegment],
+    ticks: &[TickRecord],
+    interval_seconds: u64,
+) -> GapExplanation {
+    // Sledované úseky oříznuté na rozsah a sloučené
+    let mut covered: Vec<(DateTime<Local>, DateTime<Local>)> = segments
+        .iter()
+        .map(|s| (s.started_at.max(from), s.stopped_at.min(to)))
+        .filter(|(start, stop)| start < stop)
+        .collect();
+    covered.sort();
+    let mut merged: Vec<(DateTime<Local>, DateTime<Local>)> = Vec::new();
+    for (start, stop) in covered {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(stop),
+            _ => merged.push((start, stop)),
+        }
+    }
+
+    // Nesledované úseky
+    let mut untracked = Vec::new();
+    let mut cursor = from;
+    for &(start, stop) in &merged {
+        if start > cursor {
+            untracked.push((cursor, start));
+        }
+        cursor = cursor.max(stop);
+    }
+    if cursor < to {
+        untracked.push((cursor, to));
+    }
+
+    // Co tracker dělal - každý tick pokrývá čas do dalšího
+    let mut sorted: Vec<&TickRecord> = ticks.iter().collect();
+    sorted.sort_by_key(|t| t.at);
+    let max_span = chrono::Duration::seconds(interval_seconds as i64 * MAX_TICK_SPAN_INTERVALS);
+    let spans: Vec<(DateTime<Local>, DateTime<Local>, String)> = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, tick)| {
+            let end = sorted.get(i + 1).map_or(tick.at + max_span, |next| next.at.min(tick.at + max_span));
+            (tick.at, end, tick_cause(tick))
+        })
+        .collect();
+
+    let mut causes: Vec<GapCause> = Vec::new();
+    let mut push = |start: DateTime<Local>, stop: DateTime<Local>, cause: String, ticks: usize| {
+        if stop <= start {
+            return;
+        }
+        match causes.last_mut() {
+            Some(last) if last.cause == cause && last.to == start => {
+                last.to = stop;
+                last.ticks += ticks;
+            }
+            _ => causes.push(GapCause {
+                from: start,
+                to: stop,
+                minutes: 0,
+                description: cause_description(&cause),
+                cause,
+                ticks,
+            }),
+        }
+    };
+    for (gap_start, gap_stop) in untracked {
+        let mut cursor = gap_start;
+        for (start, end, cause) in spans.iter().filter(|(start, end, _)| *end > gap_start && *start < gap_stop) {
+            let start = (*start).max(gap_start);
+            push(cursor, start, "tracker_stopped".to_string(), 0);
+            let end = (*end).min(gap_stop);
+            push(start.max(cursor), end, cause.clone(), 1);
+            cursor = cursor.max(end);
+        }
+        push(cursor, gap_stop, "tracker_stopped".to_string(), 0);
+    }
+
+    causes.retain(|c| (c.to - c.from).num_seconds() >= MIN_CAUSE_SECONDS);
+    let mut totals: BTreeMap<String, i64> = BTreeMap::new();
+    for cause in &mut causes {
+        cause.minutes = (cause.to - cause.from).num_minutes();
+        *totals.entry(cause.cause.clone()).or_default() += cause.minutes;
+    }
+
+    let tracked_minutes = merged.iter().map(|(start, stop)| (*stop - *start).num_minutes()).sum();
+    GapExplanation {
+        from,
+        to,
+        tracked_minutes,
+        untracked_minutes: (to - from).num_minutes() - tracked_minutes,
+        causes,
+        totals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_daily_summary() {
+        let at = |h: u32, m: u32| Local.with_ymd_and_hms(2025, 3, 10, h, m, 0).unwrap();
+        let segment = |task_id: Option<i32>, from: DateTime<Local>, to: DateTime<Local>| HistorySegment {
+            id: format!("{}", from.timestamp()),
+            task_id,
+            note: String::new(),
+            started_at: from,
+            stopped_at: to,
+            source: None,
+            freelo_report_id: None,
+        };
+        let segments = vec![
+            segment(Some(1), at(9, 0), at(10, 0)),
+            segment(Some(2), at(10, 2), at(10, 30)),
+            segment(None, at(11, 0), at(11, 15)),
+            segment(Some(1), at(11, 15), at(12, 0)),
+            segment(Some(1), at(9, 0) + chrono::Duration::days(1), at(10, 0) + chrono::Duration::days(1)),
+        ];
+        let tasks = vec![FreeloTask {
+            id: 1,
+            name: "Login".to_string(),
+            project_id: 7,
+            project_name: "Web".to_string(),
+            estimate_minutes: None,
+        }];
+        let tick = |time: DateTime<Local>, action: TickAction, application: &str| TickRecord {
+            application: Some(application.to_string()),
+            action,
+            at: time,
+            ..TickRecord::skipped("")
+        };
+        let ticks = vec![
+            tick(at(9, 0), TickAction::Started, "VS Code"),
+            tick(at(9, 1), TickAction::Continued, "VS Code"),
+            tick(at(9, 2), TickAction::Continued, "Slack"),
+            tick(at(9, 3), TickAction::Skipped, "Slack"),
+            tick(at(9, 30), TickAction::Continued, "Slack"),
+        ];
+
+        let summary = daily_summary(at(0, 0).date_naive(), &segments, &ticks, &tasks, 60);
+        assert_eq!(summary.total_minutes, 60 + 28 + 15 + 45);
+        assert_eq!(summary.tasks[0].task_name, "Login");
+        assert_eq!(summary.tasks[0].minutes, 105);
+        assert_eq!(summary.tasks[0].segments, 2);
+        assert_eq!(summary.tasks[1].task_name, "Task #2");
+        assert_eq!(summary.tasks[2].task_name, "Obecná práce");
+        assert_eq!(summary.context_switches, 3);
+
+        // 10:00-10:02 je pod limitem, 10:30-11:00 je mezera
+        assert_eq!(summary.gaps.len(), 1);
+        assert_eq!(summary.untracked_minutes, 30);
+        assert_eq!(summary.last_stop, Some(at(12, 0)));
+
+        // VS Code 2 min, Slack 2 min (9:02 → max. 2 intervaly) + 1 min (poslední tick)
+        let apps: Vec<(&str, i64)> = summary.applications.iter().map(|a| (a.application.as_str(), a.minutes)).collect();
+        assert_eq!(apps, vec![("Slack", 3), ("VS Code", 2)]);
+    }
+
+    #[test]
+    fn test_explain_gap() {
+        let at = |h: u32, m: u32| Local.with_ymd_and_hms(2025, 3, 10, h, m, 0).unwrap();
+        let segments = vec![HistorySegment {
+            id: "a".to_string(),
+            task_id: Some(1),
+            note: String::new(),
+            started_at: at(8, 0),
+            stopped_at: at(9, 10),
+            source: None,
+            freelo_report_id: None,
+        }];
+        let tick = |time: DateTime<Local>, action: TickAction, reason: &str| TickRecord {
+            at: time,
+            action,
+            ..TickRecord::skipped(reason)
+        };
+        let mut ticks = vec![tick(at(9, 9), TickAction::Continued, "")];
+        // 9:20-9:30 nečinnost, 9:30-9:40 zamčeno, pak nic až do 10:00
+        ticks.extend((0..10).map(|m| tick(at(9, 20 + m), TickAction::Skipped, "idle")));
+        ticks.extend((0..10).map(|m| tick(at(9, 30 + m), TickAction::Skipped, "lock_screen")));
+
+        let explanation = explain_gap(at(9, 0), at(10, 0), &segments, &ticks, 60);
+        assert_eq!(explanation.tracked_minutes, 10);
+        assert_eq!(explanation.untracked_minutes, 50);
+
+        let causes: Vec<(&str, i64, usize)> = explanation
+            .causes
+            .iter()
+            .map(|c| (c.cause.as_str(), c.minutes, c.ticks))
+            .collect();
+        assert_eq!(
+            causes,
+            vec![
+                // Tick z 9:09 pokrývá jen 2 intervaly (do 9:11)
+                ("not_recorded", 1, 1),
+                ("tracker_stopped", 9, 0),
+                ("idle", 10, 10),
+                ("lock_screen", 11, 10),
+                ("tracker_stopped", 19, 0),
+            ]
+        );
+        assert_eq!(explanation.totals.get("tracker_stopped"), Some(&28));
+    }
+}
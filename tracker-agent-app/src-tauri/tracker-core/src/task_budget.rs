@@ -0,0 +1,186 @@
+use crate::freelo::FreeloTask;
+use crate::history::HistorySegment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Od kolika procent rozpočtu tracker varuje, že se task blíží limitu
+pub const WARNING_RATIO: f64 = 0.9;
+
+/// Rozpočet hodin tasku zadaný v nastavení (má přednost před odhadem z Freelo)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskBudget {
+    pub task_id: i32,
+    pub hours: f64,
+}
+
+/// Jak je task na tom s rozpočtem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLevel {
+    Within,
+    Warning,
+    Exceeded,
+}
+
+/// Stav rozpočtu tasku pro UI
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    pub task_id: i32,
+    pub task_name: String,
+    pub budget_minutes: i64,
+    pub tracked_minutes: i64,
+    pub level: BudgetLevel,
+}
+
+/// Co tracker o rozpočtech drží mezi ticky
+#[derive(Debug, Default)]
+pub struct BudgetWatch {
+    /// Odpracované minuty z historie, None = načíst znovu (po ukončení segmentu)
+    pub tracked: Option<HashMap<i32, i64>>,
+    /// Nejhorší stav tasku, o kterém už uživatel ví
+    alerted: HashMap<i32, BudgetLevel>,
+}
+
+impl BudgetWatch {
+    /// Upozornit jen při zhoršení stavu, ne každý tick
+    pub fn should_alert(&mut self, task_id: i32, level: BudgetLevel) -> bool {
+        let previous = self.alerted.get(&task_id).copied().unwrap_or(BudgetLevel::Within);
+        if level <= previous {
+            return false;
+        }
+        self.alerted.insert(task_id, level);
+        true
+    }
+}
+
+/// Validace rozpočtů před uložením
+pub fn validate(budgets: &[TaskBudget]) -> Result<(), String> {
+    for (i, budget) in budgets.iter().enumerate() {
+        if !budget.hours.is_finite() || budget.hours <= 0.0 {
+            return Err(format!("Rozpočet tasku {} musí být kladný počet hodin (zadáno {})", budget.task_id, budget.hours));
+        }
+        if budgets[..i].iter().any(|b| b.task_id == budget.task_id) {
+            return Err(format!("Task {} má rozpočet zadaný vícekrát", budget.task_id));
+        }
+    }
+    Ok(())
+}
+
+/// Rozpočet tasku v minutách - z nastavení, jinak (je-li zapnuto) odhad z Freelo.
+/// None = task rozpočet nemá.
+pub fn budget_minutes(task_id: i32, budgets: &[TaskBudget], tasks: &[FreeloTask], use_estimates: bool) -> Option<i64> {
+    if let Some(budget) = budgets.iter().find(|b| b.task_id == task_id) {
+        return Some((budget.hours * 60.0).round() as i64);
+    }
+    if !use_estimates {
+        return None;
+    }
+    tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .and_then(|t| t.estimate_minutes)
+        .filter(|minutes| *minutes > 0)
+}
+
+pub fn level(tracked_minutes: i64, budget_minutes: i64) -> BudgetLevel {
+    if tracked_minutes >= budget_minutes {
+        BudgetLevel::Exceeded
+    } else if tracked_minutes as f64 >= budget_minutes as f64 * WARNING_RATIO {
+        BudgetLevel::Warning
+    } else {
+        BudgetLevel::Within
+    }
+}
+
+/// Odpracované minuty podle tasků z celé lokální historie
+pub fn tracked_minutes(segments: &[HistorySegment]) -> HashMap<i32, i64> {
+    let mut minutes: HashMap<i32, i64> = HashMap::new();
+    for segment in segments {
+        if let Some(task_id) = segment.task_id {
+            *minutes.entry(task_id).or_default() += segment.minutes();
+        }
+    }
+    minutes
+}
+
+/// Stav všech tasků s rozpočtem, od nejvíc vyčerpaného
+pub fn statuses(
+    budgets: &[TaskBudget],
+    tasks: &[FreeloTask],
+    use_estimates: bool,
+    tracked: &HashMap<i32, i64>,
+) -> Vec<BudgetStatus> {
+    let mut task_ids: Vec<i32> = budgets.iter().map(|b| b.task_id).collect();
+    if use_estimates {
+        task_ids.extend(tasks.iter().filter(|t| !task_ids.contains(&t.id)).map(|t| t.id).collect::<Vec<_>>());
+    }
+
+    let mut statuses: Vec<BudgetStatus> = task_ids
+        .into_iter()
+        .filter_map(|task_id| {
+            let budget_minutes = budget_minutes(task_id, budgets, tasks, use_estimates)?;
+            let tracked_minutes = tracked.get(&task_id).copied().unwrap_or(0);
+            Some(BudgetStatus {
+                task_id,
+                task_name: tasks
+                    .iter()
+                    .find(|t| t.id == task_id)
+                    .map(|t| t.name.clone())
+                    .unwrap_or_else(|| format!("Task #{}", task_id)),
+                budget_minutes,
+                tracked_minutes,
+                level: level(tracked_minutes, budget_minutes),
+            })
+        })
+        .collect();
+    statuses.sort_by(|a, b| {
+        let ratio = |s: &BudgetStatus| s.tracked_minutes as f64 / s.budget_minutes as f64;
+        ratio(b).total_cmp(&ratio(a))
+    });
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i32, estimate_minutes: Option<i64>) -> FreeloTask {
+        FreeloTask {
+            id,
+            name: format!("Task {}", id),
+            project_id: 1,
+            project_name: "Projekt".to_string(),
+            estimate_minutes,
+        }
+    }
+
+    #[test]
+    fn test_budget_sources_and_levels() {
+        let budgets = vec![TaskBudget { task_id: 1, hours: 2.5 }];
+        let tasks = vec![task(1, Some(600)), task(2, Some(60)), task(3, None)];
+
+        // Nastavení má přednost před odhadem z Freelo
+        assert_eq!(budget_minutes(1, &budgets, &tasks, true), Some(150));
+        assert_eq!(budget_minutes(2, &budgets, &tasks, true), Some(60));
+        assert_eq!(budget_minutes(2, &budgets, &tasks, false), None);
+        assert_eq!(budget_minutes(3, &budgets, &tasks, true), None);
+
+        assert_eq!(level(100, 150), BudgetLevel::Within);
+        assert_eq!(level(135, 150), BudgetLevel::Warning);
+        assert_eq!(level(150, 150), BudgetLevel::Exceeded);
+
+        let tracked = HashMap::from([(1, 30), (2, 90)]);
+        let statuses = statuses(&budgets, &tasks, true, &tracked);
+        let summary: Vec<_> = statuses.iter().map(|s| (s.task_id, s.level)).collect();
+        assert_eq!(summary, vec![(2, BudgetLevel::Exceeded), (1, BudgetLevel::Within)]);
+
+        let mut watch = BudgetWatch::default();
+        assert!(!watch.should_alert(1, BudgetLevel::Within));
+        assert!(watch.should_alert(1, BudgetLevel::Warning));
+        assert!(!watch.should_alert(1, BudgetLevel::Warning));
+        assert!(watch.should_alert(1, BudgetLevel::Exceeded));
+
+        assert!(validate(&[TaskBudget { task_id: 1, hours: 0.0 }]).is_err());
+        assert!(validate(&[budgets[0].clone(), budgets[0].clone()]).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::freelo::FreeloTask;
+use crate::text_matcher::fold_diacritic;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Uživatelské pravidlo "aplikace + titulek + slova → task" - vyhodnocuje se před
+/// textovým i AI matchingem, první platné pravidlo vyhrává
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskRule {
+    pub name: String,
+    pub task_id: i32,
+    /// Část názvu aplikace aktivního okna (bez ohledu na velikost písmen), None = jakákoli
+    #[serde(default)]
+    pub application: Option<String>,
+    /// Regulární výraz na titulek okna (bez ohledu na velikost písmen)
+    #[serde(default)]
+    pub title_pattern: Option<String>,
+    /// Všechna slova musí být v OCR textu nebo titulku okna
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Co o obrazovce víme v okamžiku vyhodnocení pravidel
+pub struct RuleInput<'a> {
+    pub application: &'a str,
+    pub title: &'a str,
+    /// OCR text s doplňujícím kontextem, None = OCR neběželo
+    pub text: Option<&'a str>,
+}
+
+/// Porovnání bez velikosti písmen a diakritiky (OCR ji často zkomolí)
+fn fold(text: &str) -> String {
+    text.to_lowercase().chars().map(fold_diacritic).collect()
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Validace pravidla před uložením
+pub fn validate(rule: &TaskRule) -> Result<(), String> {
+    if rule.name.trim().is_empty() {
+        return Err("Pravidlo musí mít název".to_string());
+    }
+    if let Some(pattern) = non_empty(&rule.title_pattern) {
+        RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| format!("Pravidlo '{}': neplatný regex titulku: {}", rule.name, e))?;
+    }
+    if non_empty(&rule.application).is_none()
+        && non_empty(&rule.title_pattern).is_none()
+        && rule.keywords.iter().all(|k| k.trim().is_empty())
+    {
+        return Err(format!("Pravidlo '{}' nemá žádnou podmínku (aplikace, titulek nebo slova)", rule.name));
+    }
+    Ok(())
+}
+
+impl TaskRule {
+    /// Platí všechny vyplněné podmínky
+    pub fn matches(&self, input: &RuleInput) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if let Some(application) = non_empty(&self.application) {
+            if !fold(input.application).contains(&fold(application)) {
+                return false;
+            }
+        }
+        if let Some(pattern) = non_empty(&self.title_pattern) {
+            let Ok(regex) = RegexBuilder::new(pattern).case_insensitive(true).build() else {
+                return false;
+            };
+            if !regex.is_match(input.title) {
+                return false;
+            }
+        }
+        let haystack = fold(&format!("{}\n{}", input.title, input.text.unwrap_or_default()));
+        self.keywords
+            .iter()
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+            .all(|k| haystack.contains(&fold(k)))
+    }
+}
+
+/// První pravidlo, které sedí a jehož task je mezi aktivními (u uzavřeného tasku se pokračuje dalším)
+pub fn find_rule<'a>(rules: &'a [TaskRule], input: &RuleInput, tasks: &'a [FreeloTask]) -> Option<(&'a TaskRule, &'a FreeloTask)> {
+    rules
+        .iter()
+        .filter(|rule| rule.matches(input))
+        .find_map(|rule| tasks.iter().find(|t| t.id == rule.task_id).map(|task| (rule, task)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_rule() {
+        let rule = |name: &str, task_id, application: Option<&str>, title: Option<&str>, keywords: &[&str]| TaskRule {
+            name: name.to_string(),
+            task_id,
+            application: application.map(str::to_string),
+            title_pattern: title.map(str::to_string),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            enabled: true,
+        };
+        let rules = vec![
+            rule("Uzavřený task", 1, Some("intellij"), None, &[]),
+            rule("foo-api", 4242, Some("IntelliJ"), Some(r"foo-api\b"), &[]),
+            rule("Faktury", 7, None, None, &["faktura", "splatnost"]),
+        ];
+        let tasks: Vec<FreeloTask> = [4242, 7]
+            .into_iter()
+            .map(|id| FreeloTask {
+                id,
+                name: format!("Task {}", id),
+                project_id: 1,
+                project_name: "Web".to_string(),
+                estimate_minutes: None,
+            })
+            .collect();
+
+        let ide = RuleInput { application: "IntelliJ IDEA", title: "foo-api – OrderService.kt", text: None };
+        let (fired, task) = find_rule(&rules, &ide, &tasks).unwrap();
+        assert_eq!((fired.name.as_str(), task.id), ("foo-api", 4242));
+
+        let other_repo = RuleInput { application: "IntelliJ IDEA", title: "foo-apiary – Main.kt", text: None };
+        assert!(find_rule(&rules, &other_repo, &tasks).is_none());
+
+        // Slova bez ohledu na diakritiku, musí sedět všechna
+        let invoice = RuleInput { application: "Firefox", title: "Fakturoid", text: Some("Faktúra 2024-001, SPLATNOST 14 dní") };
+        assert_eq!(find_rule(&rules, &invoice, &tasks).unwrap().1.id, 7);
+        let partial = RuleInput { application: "Firefox", title: "Fakturoid", text: Some("Faktura 2024-001") };
+        assert!(find_rule(&rules, &partial, &tasks).is_none());
+
+        let mut disabled = rules[1].clone();
+        disabled.enabled = false;
+        assert!(!disabled.matches(&ide));
+
+        assert!(validate(&rule("Prázdné", 1, None, None, &[" "])).is_err());
+        assert!(validate(&rule("Regex", 1, None, Some("foo("), &[])).is_err());
+        assert!(validate(&rules[1]).is_ok());
+    }
+}
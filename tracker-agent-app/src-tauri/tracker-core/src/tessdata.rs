@@ -0,0 +1,202 @@
+use crate::ocr;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::path::PathBuf;
+
+/// Trénovací data bereme z tessdata_fast (malé a rychlé modely) v pevné verzi
+const TESSDATA_API: &str = "https://api.github.com/repos/tesseract-ocr/tessdata_fast/contents";
+const TESSDATA_REF: &str = "4.1.0";
+
+/// Progress se posílá po každém takovém kusu (soubory mají jednotky MB)
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+/// Jazyky nabízené v nastavení (kód Tesseractu, název)
+pub const KNOWN_LANGUAGES: [(&str, &str); 8] = [
+    ("eng", "Angličtina"),
+    ("ces", "Čeština"),
+    ("slk", "Slovenština"),
+    ("deu", "Němčina"),
+    ("pol", "Polština"),
+    ("fra", "Francouzština"),
+    ("spa", "Španělština"),
+    ("ita", "Italština"),
+];
+
+/// Jazyk pro UI - zda je dostupný a zda ho spravuje aplikace (lze smazat)
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguagePack {
+    pub code: String,
+    pub name: String,
+    pub installed: bool,
+    pub managed: bool,
+}
+
+/// Průběh stahování pro event `ocr-language-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub language: String,
+    pub downloaded: u64,
+    pub total: u64,
+    pub done: bool,
+}
+
+/// Metadata souboru z GitHub API (git blob SHA-1 a velikost)
+#[derive(Debug, Deserialize)]
+struct GithubFile {
+    sha: String,
+    size: u64,
+    download_url: Option<String>,
+}
+
+/// Adresář s jazyky staženými aplikací - nezávislý na systémových balíčcích
+pub fn managed_dir() -> PathBuf {
+    storage::app_data_dir().join("tessdata")
+}
+
+/// Kód jazyka jde do cesty k souboru - jen malá písmena a podtržítko
+pub fn validate_code(language: &str) -> Result<(), String> {
+    let valid = !language.is_empty()
+        && language.len() <= 16
+        && language.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+    if !valid {
+        return Err(format!("Neplatný kód jazyka '{}'", language));
+    }
+    Ok(())
+}
+
+/// Nabízené jazyky a vše, co je už nainstalované (i mimo katalog)
+pub fn list_languages() -> Vec<LanguagePack> {
+    let managed = ocr::languages_in(&managed_dir());
+    let system = ocr::find_tessdata_dir()
+        .map(|dir| ocr::languages_in(&dir))
+        .unwrap_or_default();
+
+    let mut packs: Vec<LanguagePack> = KNOWN_LANGUAGES
+        .iter()
+        .map(|(code, name)| LanguagePack {
+            code: code.to_string(),
+            name: name.to_string(),
+            installed: managed.iter().chain(system.iter()).any(|l| l == code),
+            managed: managed.iter().any(|l| l == code),
+        })
+        .collect();
+
+    for code in managed.iter().chain(system.iter()) {
+        if !packs.iter().any(|p| &p.code == code) {
+            packs.push(LanguagePack {
+                code: code.clone(),
+                name: code.clone(),
+                installed: true,
+                managed: managed.contains(code),
+            });
+        }
+    }
+
+    packs
+}
+
+/// SHA-1 git blobu - stejný otisk, jaký GitHub uvádí u souboru
+fn git_blob_sha1(data: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", data.len()).as_bytes());
+    hasher.update(data);
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Stáhne `<jazyk>.traineddata` do spravovaného adresáře a ověří velikost a otisk
+/// proti metadatům z GitHubu. Soubor se přejmenuje na místo až po ověření.
+pub async fn download(language: &str, on_progress: impl Fn(DownloadProgress)) -> Result<PathBuf, String> {
+    validate_code(language)?;
+    let client = reqwest::Client::new();
+    let file_name = format!("{}.traineddata", language);
+
+    let meta: GithubFile = client
+        .get(format!("{}/{}?ref={}", TESSDATA_API, file_name, TESSDATA_REF))
+        .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+        .header("Accept", "application/vnd.github.object")
+        .send()
+        .await
+        .map_err(|e| format!("HTTP chyba: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Jazyk '{}' není k dispozici: {}", language, e))?
+        .json()
+        .await
+        .map_err(|e| format!("JSON parse error: {}", e))?;
+    let url = meta
+        .download_url
+        .ok_or_else(|| format!("GitHub neuvádí odkaz ke stažení pro '{}'", file_name))?;
+
+    let mut response = client
+        .get(&url)
+        .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+        .send()
+        .await
+        .map_err(|e| format!("HTTP chyba: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Stažení '{}' selhalo: {}", file_name, e))?;
+
+    let mut data: Vec<u8> = Vec::with_capacity(meta.size as usize);
+    let mut reported = 0;
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("HTTP chyba: {}", e))? {
+        data.extend_from_slice(&chunk);
+        if data.len() as u64 - reported >= PROGRESS_STEP_BYTES {
+            reported = data.len() as u64;
+            on_progress(DownloadProgress {
+                language: language.to_string(),
+                downloaded: reported,
+                total: meta.size,
+                done: false,
+            });
+        }
+    }
+
+    if data.len() as u64 != meta.size {
+        return Err(format!("'{}': neúplné stažení ({} z {} B)", file_name, data.len(), meta.size));
+    }
+    let checksum = git_blob_sha1(&data);
+    if checksum != meta.sha {
+        return Err(format!("'{}': nesouhlasí kontrolní součet ({} ≠ {})", file_name, checksum, meta.sha));
+    }
+
+    let dir = managed_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Nelze vytvořit {:?}: {}", dir, e))?;
+    let target = dir.join(&file_name);
+    let partial = dir.join(format!("{}.part", file_name));
+    std::fs::write(&partial, &data).map_err(|e| format!("Nelze zapsat {:?}: {}", partial, e))?;
+    std::fs::rename(&partial, &target).map_err(|e| format!("Nelze přesunout {:?}: {}", target, e))?;
+
+    on_progress(DownloadProgress {
+        language: language.to_string(),
+        downloaded: meta.size,
+        total: meta.size,
+        done: true,
+    });
+    Ok(target)
+}
+
+/// Smaže jazyk stažený aplikací (systémové balíčky nechává být)
+pub fn remove(language: &str) -> Result<(), String> {
+    validate_code(language)?;
+    let path = managed_dir().join(format!("{}.traineddata", language));
+    if !path.exists() {
+        return Err(format!("Jazyk '{}' nebyl stažen aplikací", language));
+    }
+    std::fs::remove_file(&path).map_err(|e| format!("Nelze smazat {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_blob_sha1_and_codes() {
+        // `printf 'hello\n' | git hash-object --stdin`
+        assert_eq!(git_blob_sha1(b"hello\n"), "ce013625030ba8dba906f756967f9e9ca394464a");
+
+        assert!(validate_code("ces").is_ok());
+        assert!(validate_code("chi_sim").is_ok());
+        assert!(validate_code("../eng").is_err());
+        assert!(validate_code("").is_err());
+    }
+}
@@ -0,0 +1,626 @@
+use crate::freelo::FreeloTask;
+use crate::language::{self, TextLanguage};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, instrument, Span};
+
+/// Který matcher výsledek vyprodukoval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSource {
+    Text,
+    Ai,
+    /// Jen titulek aktivního okna (bez OCR)
+    Window,
+    /// AI nad screenshotem (bez OCR)
+    Vision,
+    /// Sémantická podobnost embeddingů tasků a OCR textu
+    Embedding,
+    /// Uživatelské pravidlo z rule packu
+    Rule,
+}
+
+impl MatchSource {
+    pub const ALL: [MatchSource; 6] = [
+        MatchSource::Text,
+        MatchSource::Ai,
+        MatchSource::Window,
+        MatchSource::Vision,
+        MatchSource::Embedding,
+        MatchSource::Rule,
+    ];
+}
+
+/// Výsledek textového matchingu
+#[derive(Debug, Clone)]
+pub struct MatchResult {
+    pub task_id: Option<i32>,
+    pub task_name: Option<String>,
+    pub confidence: f32,
+    pub detected_application: String,
+    pub matched_keywords: Vec<String>,
+    pub activity_description: String, // Popis co uživatel dělá
+    pub source: MatchSource,
+}
+
+/// Normalizace textu pro porovnávání
+fn normalize_text(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Písmeno bez diakritiky (čeština, slovenština, němčina, polština)
+pub fn fold_diacritic(c: char) -> char {
+    match c {
+        'á' | 'ä' | 'ą' => 'a',
+        'č' | 'ć' => 'c',
+        'ď' => 'd',
+        'é' | 'ě' | 'ę' => 'e',
+        'í' => 'i',
+        'ĺ' | 'ľ' | 'ł' => 'l',
+        'ň' | 'ń' => 'n',
+        'ó' | 'ô' | 'ö' => 'o',
+        'ŕ' | 'ř' => 'r',
+        'š' | 'ś' => 's',
+        'ť' => 't',
+        'ú' | 'ů' | 'ü' => 'u',
+        'ý' => 'y',
+        'ž' | 'ź' | 'ż' => 'z',
+        'ß' => 's',
+        _ => c,
+    }
+}
+
+/// Klíč popisu aktivity pro detekci změny - bez velikosti písmen, diakritiky
+/// a proměnlivých čísel (časy, data, počty), které OCR mezi ticky mění.
+/// Poznámka pro Freelo zůstává v původním znění.
+pub fn activity_key(description: &str) -> String {
+    description
+        .to_lowercase()
+        .chars()
+        .map(fold_diacritic)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .filter(|word| !word.chars().any(|c| c.is_ascii_digit()))
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Max. počet zvýraznění jednoho klíčového slova (opakující se slova v menu apod.)
+const MAX_HIGHLIGHTS_PER_KEYWORD: usize = 5;
+
+/// Znaky kontextu na každou stranu nálezu
+const HIGHLIGHT_CONTEXT_CHARS: usize = 30;
+
+/// Místo v OCR textu, kde matcher našel klíčové slovo (pro zvýraznění v UI)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KeywordHighlight {
+    pub keyword: String,
+    /// Rozsah v OCR textu v UTF-16 jednotkách (indexy JS řetězce)
+    pub start: usize,
+    pub end: usize,
+    /// Nález s okolím pro náhled
+    pub snippet: String,
+}
+
+/// Najde v původním OCR textu místa, kde matcher (nad normalizovaným textem) našel klíčová slova
+pub fn keyword_highlights(ocr_text: &str, keywords: &[String]) -> Vec<KeywordHighlight> {
+    // Normalizovaný text stejně jako `normalize_text`, ke každému znaku jeho pozice v originále
+    let original: Vec<char> = ocr_text.chars().collect();
+    let mut normalized: Vec<char> = Vec::new();
+    let mut positions: Vec<usize> = Vec::new();
+    for (i, c) in original.iter().enumerate() {
+        if c.is_whitespace() {
+            if normalized.last().is_some_and(|last| *last != ' ') {
+                normalized.push(' ');
+                positions.push(i);
+            }
+        } else if c.is_alphanumeric() {
+            normalized.push(c.to_lowercase().next().unwrap_or(*c));
+            positions.push(i);
+        }
+    }
+
+    let utf16_offset = |char_index: usize| -> usize { original[..char_index].iter().map(|c| c.len_utf16()).sum() };
+
+    let mut highlights = Vec::new();
+    for keyword in keywords {
+        let needle: Vec<char> = keyword.chars().collect();
+        if needle.is_empty() {
+            continue;
+        }
+        let mut from = 0;
+        let mut found = 0;
+        while found < MAX_HIGHLIGHTS_PER_KEYWORD && from + needle.len() <= normalized.len() {
+            let Some(offset) = normalized[from..].windows(needle.len()).position(|w| w == needle.as_slice()) else {
+                break;
+            };
+            let start = positions[from + offset];
+            let end = positions[from + offset + needle.len() - 1] + 1;
+            let context_start = start.saturating_sub(HIGHLIGHT_CONTEXT_CHARS);
+            let context_end = (end + HIGHLIGHT_CONTEXT_CHARS).min(original.len());
+            highlights.push(KeywordHighlight {
+                keyword: keyword.clone(),
+                start: utf16_offset(start),
+                end: utf16_offset(end),
+                snippet: original[context_start..context_end]
+                    .iter()
+                    .collect::<String>()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            });
+            from += offset + needle.len();
+            found += 1;
+        }
+    }
+    highlights.sort_by_key(|h| h.start);
+    highlights
+}
+
+/// Jaccard similarity dvou množin slov
+fn jaccard(words1: &HashSet<&str>, words2: &HashSet<&str>) -> f32 {
+    if words1.is_empty() && words2.is_empty() {
+        return 1.0;
+    }
+    
+    let intersection = words1.intersection(words2).count();
+    let union = words1.union(words2).count();
+    
+    if union == 0 {
+        return 0.0;
+    }
+    
+    intersection as f32 / union as f32
+}
+
+/// Task s předpočítanými normalizovanými slovy a TF-IDF vahami
+#[derive(Debug, Clone)]
+struct IndexedTask {
+    /// Pozice v `TaskIndex::tasks`
+    position: usize,
+    name_words: Vec<String>,
+    project_words: Vec<String>,
+    /// TF-IDF váha každého slova z názvu tasku (vzácná slova váží víc)
+    tfidf: HashMap<String, f32>,
+    /// Slova názvu a projektu normalizovaná podle jazyka (názvy ve stejném pořadí jako `name_words`)
+    forms: HashMap<TextLanguage, (Vec<String>, Vec<String>)>,
+}
+
+impl IndexedTask {
+    /// Slova názvu a projektu pro jazyk OCR textu
+    fn words(&self, language: TextLanguage) -> (&[String], &[String]) {
+        match self.forms.get(&language) {
+            Some((name, project)) => (name, project),
+            None => (&self.name_words, &self.project_words),
+        }
+    }
+}
+
+/// Předpočítaný index tasků - staví se jednou při načtení tasků, ne každý tick
+#[derive(Debug, Clone, Default)]
+pub struct TaskIndex {
+    tasks: Vec<FreeloTask>,
+    indexed: Vec<IndexedTask>,
+    /// Násobky vah klíčových slov naučené z oprav uživatele (task → slovo → násobek)
+    keyword_weights: HashMap<i32, HashMap<String, f32>>,
+}
+
+impl TaskIndex {
+    pub fn build(tasks: &[FreeloTask]) -> Self {
+        let tokenized: Vec<(Vec<String>, Vec<String>)> = tasks
+            .iter()
+            .map(|t| {
+                let words = |text: &str| normalize_text(text).split_whitespace().map(String::from).collect();
+                (words(&t.name), words(&t.project_name))
+            })
+            .collect();
+
+        // Document frequency slov z názvů tasků
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for (name_words, _) in &tokenized {
+            let unique: HashSet<&str> = name_words.iter().map(|w| w.as_str()).collect();
+            for word in unique {
+                *document_frequency.entry(word).or_default() += 1;
+            }
+        }
+
+        let task_count = tasks.len() as f32;
+        let idf = |word: &str| {
+            let df = document_frequency.get(word).copied().unwrap_or(0) as f32;
+            ((task_count + 1.0) / (df + 1.0)).ln() + 1.0
+        };
+
+        let indexed = tasks
+            .iter()
+            .zip(&tokenized)
+            .enumerate()
+            .map(|(position, (_, (name_words, project_words)))| {
+                let mut tfidf: HashMap<String, f32> = HashMap::new();
+                for word in name_words {
+                    *tfidf.entry(word.clone()).or_default() += 1.0 / name_words.len() as f32;
+                }
+                for (word, weight) in tfidf.iter_mut() {
+                    *weight *= idf(word);
+                }
+
+                let normalize = |words: &[String], language| -> Vec<String> {
+                    words.iter().map(|w| language::normalize_word(w, language)).collect()
+                };
+                let forms = TextLanguage::NORMALIZED
+                    .into_iter()
+                    .map(|language| (language, (normalize(name_words, language), normalize(project_words, language))))
+                    .collect();
+
+                IndexedTask {
+                    position,
+                    name_words: name_words.clone(),
+                    project_words: project_words.clone(),
+                    tfidf,
+                    forms,
+                }
+            })
+            .collect();
+
+        Self {
+            tasks: tasks.to_vec(),
+            indexed,
+            keyword_weights: HashMap::new(),
+        }
+    }
+
+    /// Index s vahami klíčových slov podle oprav uživatele
+    pub fn with_keyword_weights(mut self, weights: HashMap<i32, HashMap<String, f32>>) -> Self {
+        self.keyword_weights = weights;
+        self
+    }
+
+    fn keyword_weight(&self, task_id: i32, word: &str) -> f32 {
+        self.keyword_weights
+            .get(&task_id)
+            .and_then(|words| words.get(word))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    pub fn tasks(&self) -> &[FreeloTask] {
+        &self.tasks
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+/// Signatura aplikace - klíčová slova, podle kterých ji poznáme v OCR textu
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppSignature {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+impl AppSignature {
+    fn new(name: &str, keywords: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+}
+
+/// Vestavěné signatury známých aplikací (pořadí určuje prioritu)
+pub fn default_app_signatures() -> Vec<AppSignature> {
+    vec![
+        AppSignature::new("Visual Studio Code", &["visual studio code", "vscode"]),
+        AppSignature::new("Google Chrome", &["chrome", "google chrome"]),
+        AppSignature::new("Firefox", &["firefox"]),
+        AppSignature::new("Safari", &["safari"]),
+        AppSignature::new("Freelo", &["freelo"]),
+        AppSignature::new("Slack", &["slack"]),
+        AppSignature::new("Terminal", &["terminal", "iterm"]),
+    ]
+}
+
+/// Detekce aplikace z OCR textu podle vestavěných signatur
+#[cfg(test)]
+fn detect_application(ocr_text: &str) -> String {
+    detect_application_with(ocr_text, &default_app_signatures())
+}
+
+/// Detekce aplikace z OCR textu podle zadaných signatur
+fn detect_application_with(ocr_text: &str, signatures: &[AppSignature]) -> String {
+    let normalized = normalize_text(ocr_text);
+
+
+    // Detekce známých aplikací podle klíčových slov
+    for signature in signatures {
+        if signature
+            .keywords
+            .iter()
+            .any(|k| normalized.contains(&normalize_text(k)))
+        {
+            debug!(application = %signature.name, "aplikace detekována");
+            return signature.name.clone();
+        }
+    }
+
+    // Pokud nenajdeme specifickou aplikaci, vrátíme obecný název
+    debug!("aplikace nerozpoznána");
+    "Unknown Application".to_string()
+}
+
+/// Signatury, jejichž klíčová slova se v textu vyskytují (v pořadí priority - vyhrává první),
+/// i se slovy, která zabrala
+pub fn matching_signatures<'a>(ocr_text: &str, signatures: &'a [AppSignature]) -> Vec<(&'a AppSignature, Vec<String>)> {
+    let normalized = normalize_text(ocr_text);
+    signatures
+        .iter()
+        .filter_map(|signature| {
+            let hits: Vec<String> = signature
+                .keywords
+                .iter()
+                .filter(|k| normalized.contains(&normalize_text(k)))
+                .cloned()
+                .collect();
+            (!hits.is_empty()).then_some((signature, hits))
+        })
+        .collect()
+}
+
+/// Slova OCR textu v tvaru podle jazyka textu (čeština bez diakritiky a koncovek)
+fn ocr_forms(ocr_text: &str) -> (TextLanguage, Vec<String>) {
+    let language = language::detect(ocr_text);
+    let forms = normalize_text(ocr_text)
+        .split_whitespace()
+        .map(|w| language::normalize_word(w, language))
+        .collect();
+    (language, forms)
+}
+
+/// Slova z názvu tasku nalezená v OCR textu (hlásí se v původním tvaru)
+fn matched_keywords(indexed: &IndexedTask, name_forms: &[String], ocr_forms_text: &str) -> Vec<String> {
+    indexed
+        .name_words
+        .iter()
+        .zip(name_forms)
+        .filter(|(word, form)| word.len() > 3 && ocr_forms_text.contains(form.as_str()))
+        .map(|(word, _)| word.clone())
+        .collect()
+}
+
+/// Klíčová slova tasku, která jsou v OCR textu (pro učení z oprav)
+pub fn task_keywords(ocr_text: &str, index: &TaskIndex, task_id: i32) -> Vec<String> {
+    let Some(indexed) = index.indexed.iter().find(|i| index.tasks[i.position].id == task_id) else {
+        return Vec::new();
+    };
+    let (language, forms) = ocr_forms(ocr_text);
+    let (name_forms, _) = indexed.words(language);
+    matched_keywords(indexed, name_forms, &forms.join(" "))
+}
+
+/// Najde nejlepší matching task z OCR textu
+#[instrument(
+    name = "match",
+    skip_all,
+    fields(matcher = "text", chars = ocr_text.len(), tasks = index.len(), language, application, task_id, confidence)
+)]
+pub fn find_best_matching_task(
+    ocr_text: &str,
+    index: &TaskIndex,
+    signatures: &[AppSignature],
+) -> MatchResult {
+    // Slova se porovnávají podle pravidel jazyka textu (čeština bez diakritiky a koncovek)
+    let (language, ocr_forms) = ocr_forms(ocr_text);
+    Span::current().record("language", tracing::field::debug(language));
+    let ocr_words: HashSet<&str> = ocr_forms.iter().map(|w| w.as_str()).collect();
+    let ocr_forms_text = ocr_forms.join(" ");
+    
+    // Detekce aplikace
+    let detected_app = detect_application_with(ocr_text, signatures);
+    Span::current().record("application", detected_app.as_str());
+    
+    if index.is_empty() {
+        debug!("žádné tasky k dispozici");
+        return MatchResult {
+            task_id: None,
+            task_name: None,
+            confidence: 0.0,
+            detected_application: detected_app.clone(),
+            matched_keywords: vec![],
+            activity_description: format!("{} - práce mimo Freelo", detected_app),
+            source: MatchSource::Text,
+        };
+    }
+    
+    // Najdi nejlepší match
+    let mut best_match: Option<(&FreeloTask, f32, Vec<String>)> = None;
+
+    for indexed in &index.indexed {
+        let task = &index.tasks[indexed.position];
+
+        let (name_forms, project_forms) = indexed.words(language);
+
+        // Porovnej s názvem tasku
+        let name_words: HashSet<&str> = name_forms.iter().map(|w| w.as_str()).collect();
+        let name_similarity = jaccard(&ocr_words, &name_words);
+
+        // Porovnej s názvem projektu
+        let project_words: HashSet<&str> = project_forms.iter().map(|w| w.as_str()).collect();
+        let project_similarity = jaccard(&ocr_words, &project_words);
+
+        // Najdi konkrétní klíčová slova z tasku v OCR textu
+        let matched_keywords = matched_keywords(indexed, name_forms, &ocr_forms_text);
+
+        // Celková confidence = váhovaný průměr, klíčová slova vážená TF-IDF a opravami uživatele
+        let keyword_bonus = if !matched_keywords.is_empty() {
+            let total_weight: f32 = indexed.tfidf.values().sum();
+            let matched: HashSet<&str> = matched_keywords.iter().map(|w| w.as_str()).collect();
+            let matched_weight: f32 = matched
+                .iter()
+                .filter_map(|w| Some(indexed.tfidf.get(*w)? * index.keyword_weight(task.id, w)))
+                .sum();
+            0.3 * (matched_weight / total_weight)
+        } else {
+            0.0
+        };
+
+        let confidence = ((name_similarity * 0.5) + (project_similarity * 0.2) + keyword_bonus).min(1.0);
+
+        // Debug log pro každý task s confidence > 0.1
+        if confidence > 0.1 {
+            debug!(
+                task = %task.name,
+                name_similarity,
+                project_similarity,
+                keywords = matched_keywords.len(),
+                confidence,
+                "kandidát"
+            );
+        }
+
+        if let Some((_, best_confidence, _)) = best_match {
+            if confidence > best_confidence {
+                best_match = Some((task, confidence, matched_keywords));
+            }
+        } else {
+            best_match = Some((task, confidence, matched_keywords));
+        }
+    }
+    
+    // Vytvoř základní popis aktivity z detekované aplikace a OCR textu
+    let activity_desc = format!("{} - {}",
+        detected_app,
+        ocr_text.chars().take(50).collect::<String>().trim()
+    );
+
+    if let Some((task, confidence, keywords)) = best_match {
+        // Threshold pro přiřazení tasku
+        if confidence > 0.3 {
+            Span::current()
+                .record("task_id", task.id)
+                .record("confidence", confidence);
+            return MatchResult {
+                task_id: Some(task.id),
+                task_name: Some(task.name.clone()),
+                confidence,
+                detected_application: detected_app,
+                matched_keywords: keywords,
+                activity_description: activity_desc,
+                source: MatchSource::Text,
+            };
+        } else {
+            debug!(task = %task.name, confidence, "nejlepší match pod prahem, nepoužívám");
+        }
+    }
+
+    // Žádný dostatečně dobrý match
+    MatchResult {
+        task_id: None,
+        task_name: None,
+        confidence: 0.0,
+        detected_application: detected_app,
+        matched_keywords: vec![],
+        activity_description: activity_desc,
+        source: MatchSource::Text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("Hello World!"), "hello world");
+        assert_eq!(normalize_text("Test  123"), "test 123");
+    }
+    
+    #[test]
+    fn test_activity_key() {
+        assert_eq!(
+            activity_key("Úprava ŘÁDKŮ v Excelu (12:30, 3 položky)"),
+            "uprava radku v excelu polozky"
+        );
+        assert_eq!(
+            activity_key("Editace kódu v tracker-agent-app 14:05"),
+            activity_key("editace kodu v tracker agent app - 14:06")
+        );
+        assert_eq!(activity_key("Review PR #482 v GitHubu"), "review pr v githubu");
+    }
+
+    #[test]
+    fn test_jaccard() {
+        let words = |text: &'static str| text.split_whitespace().collect::<HashSet<&str>>();
+        assert_eq!(jaccard(&words("hello world"), &words("hello world")), 1.0);
+        assert_eq!(jaccard(&words("hello"), &words("world")), 0.0);
+        assert_eq!(jaccard(&words("hello world"), &words("hello")), 0.5);
+        assert_eq!(jaccard(&words(""), &words("")), 1.0);
+    }
+    
+    #[test]
+    fn test_detect_application() {
+        assert_eq!(detect_application("Visual Studio Code - file.rs"), "Visual Studio Code");
+        assert_eq!(detect_application("Google Chrome - Tab"), "Google Chrome");
+    }
+
+    #[test]
+    fn test_detect_application_custom_signature() {
+        let mut signatures = default_app_signatures();
+        signatures.insert(0, AppSignature::new("IntelliJ IDEA", &["intellij"]));
+
+        assert_eq!(detect_application_with("IntelliJ IDEA - foo-api", &signatures), "IntelliJ IDEA");
+        assert_eq!(detect_application_with("nothing here", &signatures), "Unknown Application");
+    }
+
+    #[test]
+    fn test_task_index_prefers_rare_keywords() {
+        let task = |id, name: &str| FreeloTask {
+            id,
+            name: name.to_string(),
+            project_id: 1,
+            project_name: "Web".to_string(),
+            estimate_minutes: None,
+        };
+        let index = TaskIndex::build(&[
+            task(1, "Oprava faktury export"),
+            task(2, "Oprava faktury platby"),
+            task(3, "Oprava přihlášení"),
+        ]);
+        assert_eq!(index.len(), 3);
+
+        let result = find_best_matching_task("faktury export do PDF", &index, &default_app_signatures());
+        assert_eq!(result.task_id, Some(1));
+        assert!(result.matched_keywords.contains(&"export".to_string()));
+
+        let empty = find_best_matching_task("cokoliv", &TaskIndex::default(), &default_app_signatures());
+        assert_eq!(empty.task_id, None);
+    }
+
+    #[test]
+    fn test_keyword_highlights() {
+        let ocr = "Řešení: Export-faktur\n\ndo  PDF (export)";
+        let keywords = vec!["exportfaktur".to_string(), "export".to_string(), "do pdf".to_string()];
+        let highlights = keyword_highlights(ocr, &keywords);
+        assert_eq!(highlights.len(), 4);
+
+        let utf16: Vec<u16> = ocr.encode_utf16().collect();
+        let text_at = |h: &KeywordHighlight| String::from_utf16(&utf16[h.start..h.end]).unwrap();
+        assert_eq!(text_at(&highlights[0]), "Export-faktur");
+        assert_eq!(text_at(&highlights[1]), "Export");
+        assert_eq!(text_at(&highlights[2]), "do  PDF");
+        assert_eq!(text_at(&highlights[3]), "export");
+        assert_eq!(highlights[3].snippet, "šení: Export-faktur do PDF (export)");
+
+        assert!(keyword_highlights(ocr, &["faktura".to_string()]).is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::events::Events;
+use crate::freelo::ActiveTracking;
+use crate::text_matcher::{MatchResult, MatchSource};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+/// Kanál strukturovaných eventů pro živou časovou osu v UI
+pub const TIMELINE_CHANNEL: &str = "timeline-event";
+
+/// Krok pipeline trackeru - UI je vykresluje místo parsování logu
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum TimelineEvent {
+    TickStarted {
+        at: DateTime<Local>,
+        tick: u64,
+    },
+    OcrCompleted {
+        at: DateTime<Local>,
+        chars: usize,
+        duration_ms: u64,
+    },
+    MatchCompleted {
+        at: DateTime<Local>,
+        application: String,
+        task_id: Option<i32>,
+        task_name: Option<String>,
+        confidence: f32,
+        source: MatchSource,
+        /// Obrazovka se nezměnila, platí minulé rozhodnutí
+        cached: bool,
+    },
+    TrackingStarted {
+        at: DateTime<Local>,
+        /// ID tasku nebo kategorie
+        task_id: String,
+        entry_id: String,
+        note: String,
+        /// Segment běží jen lokálně (offline, dry-run, kategorie bez Freelo)
+        local: bool,
+    },
+    TrackingStopped {
+        at: DateTime<Local>,
+        task_id: String,
+        entry_id: String,
+        started_at: DateTime<Local>,
+        minutes: i64,
+    },
+}
+
+impl TimelineEvent {
+    pub fn match_completed(result: &MatchResult, cached: bool) -> Self {
+        TimelineEvent::MatchCompleted {
+            at: Local::now(),
+            application: result.detected_application.clone(),
+            task_id: result.task_id,
+            task_name: result.task_name.clone(),
+            confidence: result.confidence,
+            source: result.source,
+            cached,
+        }
+    }
+
+    pub fn tracking_started(tracking: &ActiveTracking) -> Self {
+        TimelineEvent::TrackingStarted {
+            at: Local::now(),
+            task_id: tracking.task_id.clone(),
+            entry_id: tracking.uuid.clone(),
+            note: tracking.note.clone(),
+            local: tracking.local_only || tracking.offline,
+        }
+    }
+
+    pub fn tracking_stopped(tracking: &ActiveTracking) -> Self {
+        let started_at = DateTime::<Local>::from(tracking.start_time);
+        let at = Local::now();
+        TimelineEvent::TrackingStopped {
+            at,
+            task_id: tracking.task_id.clone(),
+            entry_id: tracking.uuid.clone(),
+            started_at,
+            minutes: (at - started_at).num_minutes(),
+        }
+    }
+
+    /// Odešle event na kanál časové osy
+    pub fn emit(self, app: &Events) {
+        let _ = app.emit(TIMELINE_CHANNEL, self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_event_shape() {
+        let result = MatchResult {
+            task_id: Some(42),
+            task_name: Some("Web".to_string()),
+            confidence: 0.8,
+            detected_application: "Firefox".to_string(),
+            matched_keywords: vec!["web".to_string()],
+            activity_description: "Úprava webu".to_string(),
+            source: MatchSource::Text,
+        };
+        let json = serde_json::to_value(TimelineEvent::match_completed(&result, true)).unwrap();
+        assert_eq!(json["type"], "MatchCompleted");
+        assert_eq!((json["task_id"].as_i64(), json["source"].as_str()), (Some(42), Some("text")));
+        assert_eq!(json["cached"], true);
+        assert!(json.get("matched_keywords").is_none());
+
+        let tick = serde_json::to_value(TimelineEvent::TickStarted { at: Local::now(), tick: 3 }).unwrap();
+        assert_eq!((tick["type"].as_str(), tick["tick"].as_u64()), (Some("TickStarted"), Some(3)));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::backend::{self, ExternalTask, TimeTrackingBackend};
+use crate::connection_check;
+use crate::freelo::{FreeloTask, SharedWriteRouting, WriteRouting};
+use chrono::{SecondsFormat, Utc};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::Deserialize;
+use tracing::{info, instrument, Span};
+
+const API_URL: &str = "https://api.track.toggl.com/api/v9";
+
+/// Toggl Track - tasky jsou projekty (tasky v Togglu jsou jen v placených plánech)
+#[derive(Clone)]
+pub struct TogglClient {
+    client: Client,
+    api_token: String,
+    workspace_id: Option<i64>,
+    routing: SharedWriteRouting,
+}
+
+#[derive(Deserialize)]
+struct Me {
+    default_workspace_id: i64,
+}
+
+#[derive(Deserialize)]
+struct Project {
+    id: i64,
+    workspace_id: i64,
+    name: String,
+    #[serde(default)]
+    active: bool,
+}
+
+#[derive(Deserialize)]
+struct TimeEntry {
+    id: i64,
+}
+
+impl TogglClient {
+    pub fn new(api_token: String, workspace_id: Option<i64>) -> Self {
+        Self {
+            client: Client::new(),
+            api_token,
+            workspace_id,
+            routing: SharedWriteRouting::default(),
+        }
+    }
+
+    /// Zápisy se řídí sdíleným přepínačem - sandbox je Freelo workspace, tady = dry-run
+    pub fn with_write_routing(mut self, routing: SharedWriteRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    pub fn write_routing(&self) -> WriteRouting {
+        self.routing.read().map(|r| *r).unwrap_or_default()
+    }
+
+    fn writes_enabled(&self, op: &str) -> bool {
+        let enabled = self.write_routing() == WriteRouting::Live;
+        if !enabled {
+            info!(op, "dry-run: zápis do Toggl neodeslán");
+        }
+        enabled
+    }
+
+    fn request(&self, builder: RequestBuilder) -> RequestBuilder {
+        builder
+            .basic_auth(&self.api_token, Some("api_token"))
+            .header("User-Agent", "TrackerAgent/1.0 (tracker@agent.io)")
+    }
+
+    async fn send(&self, builder: RequestBuilder, op: &str) -> Result<Response, String> {
+        let response = self
+            .request(builder)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP chyba: {}", e))?;
+        Span::current().record("status", response.status().as_u16());
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Toggl {} error {}: {}", op, status, text));
+        }
+        Ok(response)
+    }
+
+    /// Ověří API token dotazem na profil
+    #[instrument(name = "toggl", skip_all, fields(op = "check_connection", status))]
+    pub async fn check_connection(&self) -> Result<(), String> {
+        let response = self
+            .request(self.client.get(format!("{}/me", API_URL)))
+            .timeout(connection_check::CHECK_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("HTTP chyba: {}", e))?;
+        Span::current().record("status", response.status().as_u16());
+
+        connection_check::check_status(response.status())
+    }
+
+    async fn workspace_id(&self) -> Result<i64, String> {
+        if let Some(id) = self.workspace_id {
+            return Ok(id);
+        }
+        let me: Me = self
+            .send(self.client.get(format!("{}/me", API_URL)), "me")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        Ok(me.default_workspace_id)
+    }
+}
+
+impl TimeTrackingBackend for TogglClient {
+    #[instrument(name = "toggl", skip_all, fields(op = "list_tasks", status))]
+    async fn list_tasks(&self) -> Result<Vec<FreeloTask>, String> {
+        let projects: Vec<Project> = self
+            .send(self.client.get(format!("{}/me/projects", API_URL)), "projects")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+
+        Ok(projects
+            .into_iter()
+            .filter(|p| p.active && self.workspace_id.is_none_or(|ws| ws == p.workspace_id))
+            .map(|p| {
+                let id = backend::register(ExternalTask {
+                    workspace_id: p.workspace_id.to_string(),
+                    project_id: p.id.to_string(),
+                    task_id: None,
+                });
+                FreeloTask {
+                    id,
+                    name: p.name.clone(),
+                    project_id: id,
+                    project_name: p.name,
+                    estimate_minutes: None,
+                }
+            })
+            .collect())
+    }
+
+    #[instrument(name = "toggl", skip_all, fields(op = "start", status))]
+    async fn start(&self, task_id: Option<&str>, note: &str) -> Result<String, String> {
+        if !self.writes_enabled("start") {
+            return Ok(format!("dry-run-{}", chrono::Local::now().timestamp_millis()));
+        }
+        let target = match task_id {
+            Some(id) => Some(backend::resolve(id).ok_or_else(|| format!("Neznámý Toggl projekt {}", id))?),
+            None => None,
+        };
+        let workspace_id = match target {
+            Some(ref t) => t.workspace_id.parse().map_err(|_| format!("Neplatný workspace {}", t.workspace_id))?,
+            None => self.workspace_id().await?,
+        };
+
+        let mut body = serde_json::json!({
+            "created_with": "TrackerAgent",
+            "description": note,
+            "workspace_id": workspace_id,
+            "start": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "duration": -1,
+        });
+        if let Some(target) = target {
+            body["project_id"] = serde_json::json!(target.project_id.parse::<i64>().unwrap_or_default());
+        }
+
+        let url = format!("{}/workspaces/{}/time_entries", API_URL, workspace_id);
+        let entry: TimeEntry = self
+            .send(self.client.post(url).json(&body), "start tracking")
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("JSON parse error: {}", e))?;
+        Ok(backend::entry_id(&workspace_id.to_string(), &entry.id.to_string()))
+    }
+
+    #[instrument(name = "toggl", skip_all, fields(op = "stop", status))]
+    async fn stop(&self, entry_id: &str) -> Result<(), String> {
+        if !self.writes_enabled("stop") {
+            return Ok(());
+        }
+        let (workspace_id, id) = backend::split_entry_id(entry_id)?;
+        let url = format!("{}/workspaces/{}/time_entries/{}/stop", API_URL, workspace_id, id);
+        self.send(self.client.patch(url), "stop tracking").await?;
+        Ok(())
+    }
+
+    #[instrument(name = "toggl", skip_all, fields(op = "annotate", status))]
+    async fn annotate(&self, entry_id: &str, note: &str) -> Result<(), String> {
+        if !self.writes_enabled("annotate") {
+            return Ok(());
+        }
+        let (workspace_id, id) = backend::split_entry_id(entry_id)?;
+        let url = format!("{}/workspaces/{}/time_entries/{}", API_URL, workspace_id, id);
+        let body = serde_json::json!({ "description": note });
+        self.send(self.client.put(url).json(&body), "edit tracking").await?;
+        Ok(())
+    }
+}
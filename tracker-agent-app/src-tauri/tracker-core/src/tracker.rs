@@ -0,0 +1,4625 @@
+use crate::freelo::{
+    ActiveTracking, FreeloClient, FreeloCredentials, FreeloProject, FreeloTask, ProjectMeta, SharedWriteRouting,
+    Tasklist, WorkReport, WorklogEdit, WriteRouting,
+};
+use crate::screenshot::{capture_and_encode, pixelate_areas, CaptureHint, ImageArea, MonitorSelection, ScreenHash};
+use crate::ocr::{self, extract_text_from_screenshot, OcrEngineKind, OcrOutput, OcrTextPreview, OcrTuning, OcrWord, PsmAutoSelector};
+use crate::text_matcher::{self, find_best_matching_task, keyword_highlights, AppSignature, MatchResult, MatchSource, TaskIndex};
+use crate::matcher_stats::{MatcherAccuracy, MatcherStats};
+use crate::activity_comment;
+use crate::recovery::{self, RecoveryAction, RunningTimer};
+use crate::timeline::TimelineEvent;
+use crate::export::{self, ExportFormat, ExportRange};
+use crate::task_budget::{self, BudgetLevel, BudgetStatus, BudgetWatch, TaskBudget};
+use crate::activity_context::ActivityContext;
+use crate::context_providers::{self, ProviderRegistry, ProviderToggles, TickInput};
+use crate::ai_matcher::{self, analyze_screenshot, match_task_with_ai, suggest_tasks, AiParams, LlmProvider};
+use crate::rule_pack::RulePack;
+use crate::task_rules::{self, RuleInput, TaskRule};
+use crate::profiles::delete_profile;
+use crate::schedule::WorkSchedule;
+use crate::comparison::{self, ComparisonReport};
+use crate::config_files::{ConfigFile, ConfigFileEvent};
+use crate::budget::{self, BudgetPlan, Stage, StageTimings, TickBudget};
+use crate::ai_usage::{self, AiUsageReport};
+use crate::adaptive_interval::AdaptiveInterval;
+use crate::prompt_template;
+use crate::state_machine::{Observation, SegmentState, StopReason, TickGates, TrackingAction, TrackingStateMachine};
+use crate::window_info::{get_active_window, WindowInfo};
+use crate::window_policy::{self, HidePolicy};
+use crate::resume::{looks_like_lock_screen, ResumeGate, ResumeGuard};
+use crate::notifications::{self, DeferredNotification, DesktopEvent, DesktopNotifications, FocusStatus, FocusTransition};
+use crate::idle::{
+    idle_action, idle_duration, is_lunch_time, longest_inactivity, IdleAction, IdlePause, ACTIVITY_RESUMED_IDLE,
+    LUNCH_BREAK_MIN_IDLE,
+};
+use crate::network::{self, NetworkAction, NetworkRule};
+use crate::verification::{self, ExpectedEntry, PendingCorrection};
+use crate::debug_artifacts;
+use crate::kill_switch;
+use crate::backend::{Backend, BackendConfig, TimeTrackingBackend};
+use crate::toggl::TogglClient;
+use crate::clockify::ClockifyClient;
+use crate::categories::{self, WorkCategory};
+use crate::redaction;
+use crate::summary::{self, DailySummary, GapExplanation};
+use crate::errors::{self, ErrorCode, ErrorEvent, Severity};
+use crate::events::{Events, TrayState};
+use crate::note_templates::{self, NoteContext};
+use crate::resources::{self, Load, ResourceGovernor};
+use crate::demo::DemoBackend;
+use crate::embedding_matcher::EmbeddingMatcher;
+use crate::degradation::{Component, DegradationLadder, DegradationLevel, DegradationStatus, LevelChange};
+use crate::review::{self, ReviewItem};
+use crate::evidence;
+use crate::history::{self, BulkResult, HistorySegment, RetentionResult, SegmentEdit, TickAction, TickDay, TickRecord};
+use crate::evaluation::{self, EvaluationReport};
+use crate::corrections::{self, Correction, LearnedAssociations};
+use crate::offline::{
+    is_connectivity_error, load_outbox, reconcile_outbox, save_outbox, FreeloConnectivity, FreeloStatus, OutboxEntry,
+};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+use tokio::time::{interval, Duration};
+
+/// Výchozí minimální confidence, aby se task z matcheru použil
+pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.3;
+
+/// Výchozí počet nestabilních ticků v řadě, po kterém se segment restartuje
+pub const DEFAULT_UNSTABLE_TICKS_NEEDED: u32 = 2;
+
+/// Výchozí minimální délka segmentu před restartem (0 = bez omezení)
+pub const DEFAULT_RESTART_COOLDOWN_SECONDS: u64 = 0;
+
+/// Koeficient confidence pro matching bez OCR (jen podle titulku okna)
+const WINDOW_ONLY_CONFIDENCE_FACTOR: f32 = 0.7;
+
+/// Výchozí ochranná doba po ručním zásahu, kdy automatika nemění task
+pub const DEFAULT_MANUAL_GRACE_SECONDS: u64 = 120;
+
+/// Výchozí maximální doba segmentu bez jakékoliv aktivity uživatele
+pub const DEFAULT_MAX_INACTIVE_MINUTES: u64 = 240;
+
+/// Výchozí interval obnovy seznamu tasků z Freelo v minutách
+pub const DEFAULT_TASK_REFRESH_MINUTES: u64 = 15;
+
+/// Výchozí práh změny obrazovky (bity z 256), pod kterým se OCR přeskočí
+pub const DEFAULT_SCREEN_DIFF_THRESHOLD: u32 = 6;
+
+/// Po tolika tickech beze změny obrazovky se OCR spustí i tak (hodiny, malý text)
+const MAX_REUSED_TICKS: u32 = 10;
+
+/// Od této confidence stačí textový/embedding match a LLM se nevolá
+const TIER_CONFIDENCE: f32 = 0.6;
+
+/// Změna seznamu tasků po obnově (event `tasks-updated`)
+#[derive(Debug, Clone, Serialize)]
+pub struct TasksUpdate {
+    pub count: usize,
+    pub added: Vec<String>,
+    pub removed: usize,
+}
+
+/// Kdy agent znovu analyzuje obrazovku (None = tracking neběží)
+#[derive(Debug, Clone, Serialize)]
+pub struct TickEta {
+    pub next_at: Option<String>,
+    pub seconds_remaining: Option<i64>,
+}
+
+/// Stav trackingu pro UI - běh, pauza a stupeň degradace analýzy
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackingStatus {
+    pub running: bool,
+    pub paused: bool,
+    pub degradation: DegradationStatus,
+}
+
+/// Poslední analyzovaná obrazovka a rozhodnutí nad ní
+struct ScreenMemory {
+    hash: ScreenHash,
+    window_key: String,
+    result: MatchResult,
+    context_label: String,
+    reused_ticks: u32,
+}
+
+/// Text z OCR posledního ticku
+struct LastOcrText {
+    at: DateTime<Local>,
+    text: String,
+}
+
+/// Naměřené délky fází ticku a poslední omezení kvůli rozpočtu
+#[derive(Default)]
+struct BudgetState {
+    timings: StageTimings,
+    reasons: Vec<String>,
+    /// Dnešní útrata za AI (načte se z historie, pak se přičítá)
+    spent_today: Option<(NaiveDate, f64)>,
+}
+
+/// Payload eventu `budget-event` - rozpočet ticku vynutil nižší kvalitu analýzy (nebo byl překročen)
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetEvent {
+    pub plan: BudgetPlan,
+    /// Skutečná délka ticku, pokud překročila limit
+    pub overrun_seconds: Option<f64>,
+}
+
+/// Zámek na ručně zvolený task - matcher ho nesmí přepnout
+#[derive(Debug, Clone, Serialize)]
+pub struct ManualLock {
+    pub task_id: i32,
+    pub task_name: String,
+    /// None = do ručního zrušení
+    pub until: Option<DateTime<Local>>,
+}
+
+impl ManualLock {
+    fn expired(&self, now: DateTime<Local>) -> bool {
+        self.until.is_some_and(|until| now >= until)
+    }
+}
+
+/// Prodleva před ověřením zastaveného záznamu (Freelo ho nemusí mít hned uložený)
+const VERIFY_DELAY_SECONDS: u64 = 10;
+
+/// Jak se analyzuje obrazovka
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisMode {
+    /// Tesseract OCR + textový/AI matching textu
+    #[default]
+    OcrText,
+    /// Screenshot rovnou multimodálnímu modelu, bez OCR
+    AiVision,
+    /// OCR, a když nenajde jistý task (nebo selže), screenshot do AI
+    Hybrid,
+}
+
+#[derive(Clone)]
+pub struct TrackerConfig {
+    pub interval_seconds: u64,
+    pub freelo_email: String,
+    pub freelo_api_key: String,
+    /// Kam se zapisuje čas (Freelo / Toggl / Clockify)
+    pub backend: BackendConfig,
+    /// AI pro matching (OpenRouter nebo lokální Ollama), None = jen textový matching
+    pub llm: Option<LlmProvider>,
+    /// OCR backend (Auto = nativní OCR platformy)
+    pub ocr_engine: OcrEngineKind,
+    /// Tesseract PSM, None = automatický výběr
+    pub ocr_psm: Option<u32>,
+    /// Tesseract OEM, None = výchozí
+    pub ocr_oem: Option<u32>,
+    /// Dlaždice pro OCR velkých snímků, None = celý snímek najednou
+    pub ocr_tile_size: Option<u32>,
+    /// Zvětšení snímku před OCR
+    pub ocr_scale: f32,
+    /// Jazyky Tesseractu ("ces+eng")
+    pub ocr_languages: String,
+    /// Jak dlouho po ručním zásahu nesmí automatika přepnout task
+    pub manual_grace_seconds: u64,
+    /// Po kolika minutách bez vstupu je běžící segment podezřelý (zapomenutý timer)
+    pub max_inactive_minutes: u64,
+    /// Podezřelý segment automaticky ukončit (další aktivní tick začne nový)
+    pub spike_auto_stop: bool,
+    /// Síťová pravidla (Wi-Fi/VPN) vyhodnocovaná před každým tickem
+    pub network_rules: Vec<NetworkRule>,
+    /// Profil (klient), do jehož adresářů se ukládají data a debug artefakty
+    pub profile: String,
+    /// Důkazní screenshot jednou za N minut, None = vypnuto
+    pub evidence_interval_minutes: Option<u64>,
+    /// Po kolika dnech se důkazní screenshoty mažou
+    pub evidence_retention_days: u64,
+    /// Parametry AI requestu - berou se živě z aktuálního nastavení
+    pub ai_params: AiParams,
+    /// Při neshodě AI a textového matcheru rozhoduje naměřená přesnost
+    pub matcher_auto_priority: bool,
+    /// Před odesláním do AI odstranit z OCR textu a titulku okna citlivé údaje
+    pub redact_ai_input: bool,
+    /// Režim soukromí - poslední OCR text se nedrží v paměti ani nevrací do UI
+    pub privacy_mode: bool,
+    pub analysis_mode: AnalysisMode,
+    /// Kdy skrývat okno trackeru před snímkem
+    pub hide_policy: HidePolicy,
+    /// Který monitor snímat při více displejích
+    pub monitor_selection: MonitorSelection,
+    /// Prodleva po probuzení ze spánku, kdy se nic neanalyzuje
+    pub resume_warmup_seconds: u64,
+    /// iCalendar soubor s focus bloky, None = jen ruční focus bloky
+    pub focus_calendar: Option<PathBuf>,
+    /// Klíčové slovo v názvu události, která je focus blok
+    pub focus_keyword: String,
+    /// Testovací Freelo workspace pro zápisy při ladění pravidel
+    pub sandbox_credentials: Option<FreeloCredentials>,
+    /// Po kolika minutách bez vstupu se Freelo timer pozastaví (0 = vypnuto)
+    pub idle_threshold_minutes: u64,
+    /// Strop paměti agenta v MB, nad ním se snižuje kvalita snímků a prodlužuje interval (0 = bez limitu)
+    pub memory_limit_mb: u64,
+    /// Jak často znovu načíst tasky z Freelo (0 = jen při startu)
+    pub task_refresh_minutes: u64,
+    /// Projekty, do jejichž tasků se smí matchovat (prázdné = všechny)
+    pub selected_project_ids: Vec<i32>,
+    /// Pracovní doba (None = tracking kdykoliv)
+    pub schedule: Option<WorkSchedule>,
+    /// Po skončení bloku přidat k tasku komentář se souhrnem aktivit
+    pub post_activity_comments: bool,
+    /// Kolik měsíců držet jednotlivé ticky v logu rozhodnutí (0 = navždy)
+    pub history_retention_months: u32,
+    /// Pod touto confidence se návrh matcheru nepoužije (sleduje se obecná práce)
+    pub min_confidence: f32,
+    /// Kolik ticků v řadě se musí kontext lišit, než se segment restartuje (hystereze)
+    pub unstable_ticks_needed: u32,
+    /// Segment mladší než tato doba se kvůli změně kontextu nerestartuje
+    pub restart_cooldown_seconds: u64,
+    /// Rozdíl hashů obrazovky, pod kterým se OCR/AI přeskočí (0 = vypnuto)
+    pub screen_diff_threshold: u32,
+    /// Mezistupeň před LLM: jistý textový match, pak sémantická shoda embeddingů
+    pub embedding_matching: bool,
+    /// Kategorie práce mimo tasky - mají přednost před matcherem
+    pub work_categories: Vec<WorkCategory>,
+    /// Které zdroje kontextu (OCR, okno, kalendář, git, procesy) se používají
+    pub context_providers: ProviderToggles,
+    /// Tvrdé limity ticku (čas, cena AI) - při překročení se tick degraduje
+    pub tick_budget: TickBudget,
+    /// Denní rozpočet AI v USD - po vyčerpání se AI do půlnoci nevolá
+    pub ai_daily_budget_usd: Option<f64>,
+    /// Interval se přizpůsobuje stabilitě práce (mezi polovinou intervalu a stropem)
+    pub adaptive_interval: bool,
+    pub max_interval_seconds: u64,
+    /// Které události mají systémové oznámení
+    pub desktop_notifications: DesktopNotifications,
+    /// Rozpočty hodin podle tasků
+    pub task_budgets: Vec<TaskBudget>,
+    /// Tasky bez vlastního rozpočtu hlídat podle odhadu z Freelo
+    pub budget_from_estimates: bool,
+    /// Po vyčerpání rozpočtu se tracking tasku zastaví
+    pub stop_over_budget: bool,
+}
+
+/// Sdílený stav trackeru (všechna pole jsou Arc, klonování je levné)
+#[derive(Clone)]
+pub struct Tracker {
+    config: Arc<Mutex<Option<TrackerConfig>>>,
+    is_running: Arc<Mutex<bool>>,
+    active_tracking: Arc<Mutex<Option<ActiveTracking>>>,
+    freelo_tasks_cache: Arc<Mutex<Vec<FreeloTask>>>,
+    /// Kdy byly tasky naposledy načteny z Freelo
+    tasks_loaded_at: Arc<Mutex<Option<Instant>>>,
+    /// Předpočítaný index pro textový matching, přestaví se s cache tasků
+    task_index: Arc<Mutex<Arc<TaskIndex>>>,
+    rule_pack: Arc<Mutex<RulePack>>,
+    psm_selector: Arc<Mutex<PsmAutoSelector>>,
+    last_manual_action: Arc<Mutex<Option<Instant>>>,
+    /// Začátek právě probíhající pauzy na oběd
+    lunch_break: Arc<Mutex<Option<DateTime<Local>>>>,
+    /// Pauza kvůli nečinnosti uživatele
+    idle_pause: Arc<Mutex<Option<IdlePause>>>,
+    /// Ruční pauza z UI (smyčka běží, ticky se přeskakují)
+    user_pause: Arc<Mutex<Option<IdlePause>>>,
+    /// Barvy a avatary projektů z Freelo (podle project_id)
+    project_meta_cache: Arc<Mutex<HashMap<i32, ProjectMeta>>>,
+    /// Tracking je pozastavený síťovými pravidly
+    network_paused: Arc<Mutex<bool>>,
+    /// Tracking stojí mimo pracovní dobu
+    schedule_paused: Arc<Mutex<bool>>,
+    /// Dostupnost Freelo (výpadek, backoff)
+    freelo_status: Arc<Mutex<FreeloConnectivity>>,
+    /// Kdy byl uložen poslední důkazní screenshot
+    last_evidence_at: Arc<Mutex<Option<DateTime<Local>>>>,
+    /// Klouzavá přesnost matcherů podle ručních oprav
+    matcher_stats: Arc<Mutex<MatcherStats>>,
+    /// Detekce probuzení ze spánku (mezera mezi ticky)
+    resume_guard: Arc<Mutex<ResumeGuard>>,
+    /// Kam jdou zápisy do Freelo (ostrý workspace / sandbox / dry-run)
+    write_routing: SharedWriteRouting,
+    /// Plánovaný čas dalšího ticku
+    next_tick_at: Arc<Mutex<Option<DateTime<Local>>>>,
+    /// Ruční zámek tasku (všechny ticky jdou na zvolený task)
+    manual_lock: Arc<Mutex<Option<ManualLock>>>,
+    /// Degradace kvality/intervalu při vysoké zátěži
+    resource_governor: Arc<Mutex<ResourceGovernor>>,
+    /// Název naposledy snímaného monitoru (log při přechodu na jiný)
+    captured_monitor: Arc<Mutex<Option<String>>>,
+    /// Shrnutí poslední hodiny aktivity pro AI prompty
+    activity_context: Arc<Mutex<ActivityContext>>,
+    /// Stupeň degradace analýzy podle zdraví AI, OCR, okna a screenshotu
+    degradation: Arc<Mutex<DegradationLadder>>,
+    /// Poslední analyzovaná obrazovka - beze změny se OCR/AI přeskočí
+    last_screen: Arc<Mutex<Option<ScreenMemory>>>,
+    /// Text z OCR posledního ticku (mimo režim soukromí) pro ladění
+    last_ocr_text: Arc<Mutex<Option<LastOcrText>>>,
+    /// Délky fází ticku pro plánování podle rozpočtu
+    budget: Arc<Mutex<BudgetState>>,
+    /// Embeddingy názvů tasků pro sémantický matching
+    embedding_matcher: Arc<Mutex<EmbeddingMatcher>>,
+    /// Odpracovaný čas tasků s rozpočtem a už odeslaná upozornění
+    task_budget: Arc<Mutex<BudgetWatch>>,
+    /// Konfigurace před ukázkovým režimem (Some = ukázka běží), po zastavení se obnoví
+    config_before_demo: Arc<Mutex<Option<Option<TrackerConfig>>>>,
+    /// Běžící tracking smyčka - přepnutí profilu čeká na její doběhnutí
+    loop_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Probudí smyčku čekající na další tick (zastavení, okamžitá analýza)
+    wake_signal: Arc<Notify>,
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tracker {
+    pub fn new() -> Self {
+        Self {
+            config: Arc::new(Mutex::new(None)),
+            is_running: Arc::new(Mutex::new(false)),
+            active_tracking: Arc::new(Mutex::new(None)),
+            freelo_tasks_cache: Arc::new(Mutex::new(Vec::new())),
+            tasks_loaded_at: Arc::new(Mutex::new(None)),
+            task_index: Arc::new(Mutex::new(Arc::new(TaskIndex::default()))),
+            rule_pack: Arc::new(Mutex::new(RulePack::load_shared())),
+            psm_selector: Arc::new(Mutex::new(PsmAutoSelector::default())),
+            last_manual_action: Arc::new(Mutex::new(None)),
+            lunch_break: Arc::new(Mutex::new(None)),
+            idle_pause: Arc::new(Mutex::new(None)),
+            user_pause: Arc::new(Mutex::new(None)),
+            project_meta_cache: Arc::new(Mutex::new(HashMap::new())),
+            network_paused: Arc::new(Mutex::new(false)),
+            schedule_paused: Arc::new(Mutex::new(false)),
+            freelo_status: Arc::new(Mutex::new(FreeloConnectivity::default())),
+            last_evidence_at: Arc::new(Mutex::new(None)),
+            matcher_stats: Arc::new(Mutex::new(MatcherStats::default())),
+            resume_guard: Arc::new(Mutex::new(ResumeGuard::default())),
+            write_routing: SharedWriteRouting::default(),
+            next_tick_at: Arc::new(Mutex::new(None)),
+            manual_lock: Arc::new(Mutex::new(None)),
+            resource_governor: Arc::new(Mutex::new(ResourceGovernor::default())),
+            captured_monitor: Arc::new(Mutex::new(None)),
+            activity_context: Arc::new(Mutex::new(ActivityContext::default())),
+            degradation: Arc::new(Mutex::new(DegradationLadder::default())),
+            last_screen: Arc::new(Mutex::new(None)),
+            last_ocr_text: Arc::new(Mutex::new(None)),
+            budget: Arc::new(Mutex::new(BudgetState::default())),
+            embedding_matcher: Arc::new(Mutex::new(EmbeddingMatcher::default())),
+            task_budget: Arc::new(Mutex::new(BudgetWatch::default())),
+            config_before_demo: Arc::new(Mutex::new(None)),
+            loop_handle: Arc::new(Mutex::new(None)),
+            wake_signal: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Freelo klient se sdíleným přepínačem zápisů
+    fn freelo_client(&self, cfg: &TrackerConfig) -> FreeloClient {
+        FreeloClient::new(cfg.freelo_email.clone(), cfg.freelo_api_key.clone())
+            .with_write_routing(self.write_routing.clone(), cfg.sandbox_credentials.clone())
+    }
+
+    /// Služba pro sledování času podle nastavení (se sdíleným přepínačem zápisů)
+    fn backend(&self, cfg: &TrackerConfig) -> Backend {
+        match cfg.backend {
+            BackendConfig::Freelo => Backend::Freelo(self.freelo_client(cfg)),
+            BackendConfig::Toggl { ref api_token, workspace_id } => Backend::Toggl(
+                TogglClient::new(api_token.clone(), workspace_id).with_write_routing(self.write_routing.clone()),
+            ),
+            BackendConfig::Clockify { ref api_key, ref workspace_id } => Backend::Clockify(
+                ClockifyClient::new(api_key.clone(), workspace_id.clone()).with_write_routing(self.write_routing.clone()),
+            ),
+            BackendConfig::Demo => Backend::Demo(DemoBackend::default()),
+        }
+    }
+
+    pub async fn set_config(&self, config: TrackerConfig) {
+        // Během ukázky se nové nastavení odloží a platí po jejím zastavení
+        if let Some(before) = self.config_before_demo.lock().await.as_mut() {
+            *before = Some(config);
+            return;
+        }
+        let mut cfg = self.config.lock().await;
+        // Jiný výběr projektů - tasky se znovu načtou a vyfiltrují při příští obnově
+        if cfg.as_ref().is_some_and(|c| c.selected_project_ids != config.selected_project_ids) {
+            *self.tasks_loaded_at.lock().await = None;
+        }
+        // Pravidla tasků má každý profil svoje
+        if cfg.as_ref().is_none_or(|c| c.profile != config.profile) {
+            *self.rule_pack.lock().await = RulePack::load(&config.profile);
+        }
+        *cfg = Some(config);
+    }
+
+    /// Ukázkový režim - tracking s vestavěnými tasky a nahranými obrazovkami,
+    /// po zastavení platí zase uložené nastavení
+    pub async fn start_demo(&self, app: Events, config: TrackerConfig) -> Result<(), String> {
+        if *self.is_running.lock().await {
+            return Err("Tracker už běží".to_string());
+        }
+        let previous = self.config.lock().await.replace(config);
+        self.config_before_demo.lock().await.get_or_insert(previous);
+        if let Err(e) = self.start(app).await {
+            self.restore_after_demo().await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn restore_after_demo(&self) {
+        if let Some(previous) = self.config_before_demo.lock().await.take() {
+            *self.config.lock().await = previous;
+        }
+    }
+
+    pub async fn start(&self, app: Events) -> Result<(), String> {
+        if let Some(reason) = kill_switch::disabled_reason() {
+            tracing::warn!(%reason, "start trackingu odmítnut - nouzové vypnutí");
+            return Err(format!("Tracking je nouzově vypnutý ({})", reason));
+        }
+        Self::clear_error(&app, ErrorCode::KillSwitch);
+        Self::clear_error(&app, ErrorCode::ConfigMissing);
+
+        let mut is_running = self.is_running.lock().await;
+        if *is_running {
+            return Err("Tracker už běží".to_string());
+        }
+        *is_running = true;
+        drop(is_running);
+        app.set_tray_state(TrayState::Running);
+        // Nový běh začíná naplno, stupeň se ustálí během prvních ticků
+        *self.degradation.lock().await = DegradationLadder::default();
+        *self.last_screen.lock().await = None;
+        *self.task_budget.lock().await = BudgetWatch::default();
+
+        // Nový segment by přepsal záznam o timeru, který zůstal běžet po pádu
+        if let Some(orphan) = self.get_orphaned_tracking().await {
+            Self::emit_log(&app, "warning", &format!("Nalezen neukončený timer od {}, zastavuji ho", orphan.started_at.format("%H:%M")));
+            if let Err(e) = self.stop_orphaned_timer(&app, &orphan).await {
+                Self::emit_error(&app, errors::classify_freelo(&e), &format!("Neukončený timer nelze zastavit: {}", e));
+            }
+        }
+
+        // Spawn background task
+        let tracker = self.clone();
+        let handle = tokio::spawn(async move {
+            tracker.tracking_loop(app).await;
+        });
+        *self.loop_handle.lock().await = Some(handle);
+
+        Ok(())
+    }
+
+    pub async fn stop(&self, app: Events) -> Result<(), String> {
+        let mut is_running = self.is_running.lock().await;
+        if !*is_running {
+            return Err("Tracker neběží".to_string());
+        }
+        *is_running = false;
+        drop(is_running);
+        self.wake_signal.notify_waiters();
+        app.set_tray_state(TrayState::Stopped);
+
+        // Stop active tracking if any
+        let cfg = self.config.lock().await.clone();
+        if let Some(cfg) = cfg {
+            let backend = self.backend(&cfg);
+            self.stop_active_tracking(&app, &backend, Local::now()).await;
+        }
+        *self.lunch_break.lock().await = None;
+        *self.idle_pause.lock().await = None;
+        if self.user_pause.lock().await.take().is_some() {
+            let _ = app.emit("tracking-paused", false);
+        }
+        *self.manual_lock.lock().await = None;
+        let _ = app.emit("manual-lock", None::<ManualLock>);
+        *self.next_tick_at.lock().await = None;
+        let _ = app.emit("tick-schedule", self.get_next_tick_eta().await);
+        self.restore_after_demo().await;
+
+        Ok(())
+    }
+
+    /// Analýza obrazovky hned, bez čekání na tick (i když se obrazovka nezměnila)
+    pub async fn rescan_now(&self) -> Result<(), String> {
+        if !*self.is_running.lock().await {
+            return Err("Tracker neběží".to_string());
+        }
+        *self.last_screen.lock().await = None;
+        self.wake_signal.notify_waiters();
+        Ok(())
+    }
+
+    /// Počká, až zastavená smyčka dokončí rozběhnutý tick a skončí
+    async fn wait_for_loop_exit(&self) {
+        let Some(mut handle) = self.loop_handle.lock().await.take() else {
+            return;
+        };
+        loop {
+            tokio::select! {
+                _ = &mut handle => return,
+                // Smyčka mohla signál minout uprostřed ticku
+                _ = tokio::time::sleep(Duration::from_millis(200)) => self.wake_signal.notify_waiters(),
+            }
+        }
+    }
+
+    /// Přepne na jiný profil: zastaví tracking, dokončí zápisy starého profilu
+    /// (běžící timer, offline fronta) jeho přihlašovacími údaji, vymění konfiguraci
+    /// a cache a volitelně spustí tracking pod novým profilem. Vrací, zda tracking běží.
+    pub async fn switch_profile(&self, app: &Events, config: TrackerConfig, auto_start: Option<bool>) -> Result<bool, String> {
+        if self.config_before_demo.lock().await.is_some() {
+            return Err("Během ukázky nelze přepnout profil, nejdřív ji zastavte".to_string());
+        }
+        let was_running = *self.is_running.lock().await;
+        if was_running {
+            self.stop(app.clone()).await?;
+            self.wait_for_loop_exit().await;
+        }
+
+        let previous = self.config.lock().await.clone();
+        if let Some(previous) = previous {
+            let backend = self.backend(&previous);
+            // Timer mohl spustit tick, který běžel během zastavení
+            self.stop_active_tracking(app, &backend, Local::now()).await;
+            if self.freelo_status.lock().await.is_offline() {
+                Self::emit_log(
+                    app,
+                    "warning",
+                    &format!("📴 Offline fronta profilu '{}' se odešle při jeho příštím spuštění", previous.profile),
+                );
+            } else {
+                self.sync_outbox(app, &backend).await;
+            }
+        }
+
+        let profile = config.profile.clone();
+        *self.config.lock().await = Some(config);
+        self.reset_profile_state().await;
+        Self::emit_log(app, "success", &format!("👤 Přepnuto na profil '{}'", profile));
+
+        let start = auto_start.unwrap_or(was_running);
+        if start {
+            self.start(app.clone()).await?;
+        }
+        Ok(start)
+    }
+
+    /// Zahodí stav vázaný na předchozí profil (tasky, projekty, kontext, pravidla z disku)
+    /// a načte statistiky matcherů nového profilu
+    async fn reset_profile_state(&self) {
+        let profile = self.current_profile().await;
+        self.freelo_tasks_cache.lock().await.clear();
+        *self.tasks_loaded_at.lock().await = None;
+        *self.task_index.lock().await = Arc::new(TaskIndex::default());
+        self.project_meta_cache.lock().await.clear();
+        *self.rule_pack.lock().await = RulePack::load(&profile);
+        *self.freelo_status.lock().await = FreeloConnectivity::default();
+        *self.matcher_stats.lock().await = MatcherStats::load(&profile);
+        *self.task_budget.lock().await = BudgetWatch::default();
+        *self.embedding_matcher.lock().await = EmbeddingMatcher::load(&profile);
+        *self.activity_context.lock().await = ActivityContext::default();
+        *self.last_screen.lock().await = None;
+        *self.last_ocr_text.lock().await = None;
+        *self.last_evidence_at.lock().await = None;
+        *self.last_manual_action.lock().await = None;
+    }
+
+    /// Úklid při ukončení aplikace - zastaví smyčku a běžící timer (při výpadku jde segment do offline fronty)
+    pub async fn shutdown(&self, app: &Events) {
+        *self.is_running.lock().await = false;
+        let cfg = self.config.lock().await.clone();
+        if let Some(cfg) = cfg {
+            let backend = self.backend(&cfg);
+            self.stop_active_tracking(app, &backend, Local::now()).await;
+        }
+    }
+
+    /// Zastaví běžící Freelo tracking (pokud nějaký běží), segment končí v `stopped_at`
+    async fn stop_active_tracking(&self, app: &Events, backend: &Backend, stopped_at: DateTime<Local>) {
+        if let Some(active) = self.active_tracking.lock().await.take() {
+            self.record_matcher_outcome(&active, true).await;
+            if let Err(e) = self.finish_segment(app, backend, &active, stopped_at).await {
+                Self::emit_error(app, errors::classify_freelo(&e), &format!("Chyba při zastavení Freelo trackingu: {}", e));
+            } else {
+                Self::emit_log(app, "success", "Freelo tracking zastaven");
+            }
+        }
+    }
+
+    /// Brány před analýzou (pracovní doba, síť, oběd, nečinnost, zapomenutý timer) -
+    /// o zastavení rozhoduje stavový automat, segment končí v `stopped_at`
+    async fn apply_gates(&self, app: &Events, backend: &Backend, gates: TickGates, stopped_at: DateTime<Local>) {
+        if let Some(TrackingAction::Stop { .. }) = TrackingStateMachine::gate(gates) {
+            self.stop_active_tracking(app, backend, stopped_at).await;
+        }
+    }
+
+    /// Zahájí segment - spustí Freelo timer, při výpadku Freelo běží segment jen lokálně
+    async fn start_segment(
+        &self,
+        app: &Events,
+        backend: &Backend,
+        tracking_key: String,
+        application: String,
+        activity: String,
+        note: &str,
+    ) -> Result<ActiveTracking, String> {
+        // Dry-run - segment jen lokálně, Freelo timer se nespustí ani nezastaví
+        let dry_run = self.current_write_routing() == WriteRouting::DryRun;
+        if dry_run || categories::is_local(&tracking_key) {
+            let prefix = if dry_run { "dry-run" } else { "local" };
+            let uuid = format!("{}-{}", prefix, Local::now().timestamp_millis());
+            let mut tracking = ActiveTracking::new(tracking_key, uuid, application, activity, note.to_string());
+            tracking.local_only = true;
+            TimelineEvent::tracking_started(&tracking).emit(app);
+            return Ok(tracking);
+        }
+
+        if !self.freelo_status.lock().await.is_offline() {
+            // Obecná práce i kategorie bez tasku běží jako Freelo timer bez tasku
+            let task_id = tracking_key.parse::<i32>().is_ok().then_some(tracking_key.as_str());
+            match backend.start(task_id, note).await {
+                Ok(uuid) => {
+                    let tracking = ActiveTracking::new(tracking_key, uuid, application, activity, note.to_string());
+                    self.remember_running_timer(app, backend, &tracking).await;
+                    TimelineEvent::tracking_started(&tracking).emit(app);
+                    return Ok(tracking);
+                }
+                Err(e) if is_connectivity_error(&e) => self.mark_freelo_offline(app, &e).await,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let uuid = format!("offline-{}", Local::now().timestamp_millis());
+        let mut tracking = ActiveTracking::new(tracking_key, uuid, application, activity, note.to_string());
+        tracking.offline = true;
+        TimelineEvent::tracking_started(&tracking).emit(app);
+        Ok(tracking)
+    }
+
+    /// Ukončí segment - zastaví Freelo timer a ověří záznam. Lokální segmenty
+    /// (a timery, které kvůli výpadku nejde zastavit) jdou do offline fronty.
+    /// `stopped_at` dřív než teď (začátek nečinnosti) zkrátí segment i záznam ve Freelo.
+    async fn finish_segment(
+        &self,
+        app: &Events,
+        backend: &Backend,
+        tracking: &ActiveTracking,
+        stopped_at: DateTime<Local>,
+    ) -> Result<(), String> {
+        let profile = self.current_profile().await;
+        let stopped_at = stopped_at.max(DateTime::<Local>::from(tracking.start_time));
+        // Timer ve Freelo se zastaví až teď - delší záznam ověření zkrátí
+        let trimmed = Local::now() - stopped_at >= chrono::Duration::minutes(1);
+        TimelineEvent::tracking_stopped(tracking).emit(app);
+        // Segment přibude do historie - odpracovaný čas tasků se načte znovu
+        self.task_budget.lock().await.tracked = None;
+
+        if tracking.local_only {
+            self.record_history(app, &profile, tracking, stopped_at);
+            return Ok(());
+        }
+
+        if !tracking.offline {
+            match backend.stop(&tracking.uuid).await {
+                Ok(()) => {
+                    // Ověření čte ostrý workspace - sandbox/dry-run záznamy v něm nejsou
+                    if backend.write_routing() == WriteRouting::Live {
+                        self.record_history(app, &profile, tracking, stopped_at);
+                        if let Some(freelo) = backend.freelo() {
+                            let expected = Self::expected_entry(tracking, stopped_at);
+                            Self::spawn_entry_verification(app, freelo, &profile, expected, trimmed);
+                        }
+                    }
+                    let post_comments = self.config.lock().await.as_ref().is_some_and(|c| c.post_activity_comments);
+                    if let Some(freelo) = backend.freelo().filter(|_| post_comments) {
+                        Self::spawn_activity_comment(app, freelo, tracking);
+                    }
+                    Self::forget_running_timer(app, &profile);
+                    return Ok(());
+                }
+                Err(e) if is_connectivity_error(&e) => self.mark_freelo_offline(app, &e).await,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let entry = OutboxEntry {
+            uuid: (!tracking.offline).then(|| tracking.uuid.clone()),
+            task_id: tracking.task_id.parse().ok(),
+            note: tracking.full_note(),
+            started_at: DateTime::<Local>::from(tracking.start_time),
+            stopped_at,
+            segment_id: Some(tracking.uuid.clone()),
+        };
+        if backend.write_routing() == WriteRouting::Live {
+            self.record_history(app, &profile, tracking, stopped_at);
+        }
+        let mut outbox = load_outbox(&profile);
+        outbox.push(entry);
+        save_outbox(&profile, &outbox)?;
+        // Zastavení timeru teď hlídá offline fronta
+        Self::forget_running_timer(app, &profile);
+
+        Self::emit_log(app, "info", &format!("📥 Segment uložen do offline fronty ({} čeká na Freelo)", outbox.len()));
+        self.emit_freelo_status(app).await;
+        Ok(())
+    }
+
+    /// Uloží běžící timer na disk, aby šel po pádu aplikace dohledat
+    async fn remember_running_timer(&self, app: &Events, backend: &Backend, tracking: &ActiveTracking) {
+        let profile = self.current_profile().await;
+        if let Err(e) = recovery::save(&profile, &RunningTimer::from_tracking(tracking, backend.name())) {
+            Self::emit_error(app, ErrorCode::StorageFailed, &format!("Nelze uložit běžící timer: {}", e));
+        }
+    }
+
+    fn forget_running_timer(app: &Events, profile: &str) {
+        if let Err(e) = recovery::clear(profile) {
+            Self::emit_error(app, ErrorCode::StorageFailed, &format!("Nelze smazat běžící timer: {}", e));
+        }
+    }
+
+    /// Timer, který zůstal běžet po pádu aplikace (na disku, ale bez aktivního segmentu)
+    pub async fn get_orphaned_tracking(&self) -> Option<RunningTimer> {
+        if self.active_tracking.lock().await.is_some() {
+            return None;
+        }
+        recovery::load(&self.current_profile().await)
+    }
+
+    /// Vyřeší osiřelý timer - zastaví ho, převezme jako aktuální segment, nebo ho zapomene
+    pub async fn recover_tracking(&self, app: &Events, action: RecoveryAction) -> Result<(), String> {
+        let orphan = self.get_orphaned_tracking().await.ok_or("Žádný neukončený záznam")?;
+        match action {
+            RecoveryAction::Stop => self.stop_orphaned_timer(app, &orphan).await?,
+            RecoveryAction::Adopt => {
+                self.orphan_backend(&orphan).await?;
+                *self.active_tracking.lock().await = Some(orphan.to_tracking());
+                Self::emit_log(app, "success", &format!("▶️ Pokračuji v timeru od {}", orphan.started_at.format("%H:%M")));
+                if !*self.is_running.lock().await {
+                    self.start(app.clone()).await?;
+                }
+            }
+            RecoveryAction::Dismiss => {
+                recovery::clear(&self.current_profile().await)?;
+                Self::emit_log(app, "info", &format!("Neukončený záznam {} zapomenut", orphan.uuid));
+            }
+        }
+        Ok(())
+    }
+
+    /// Backend, ve kterém osiřelý timer běží (po změně služby ho nejde obsloužit)
+    async fn orphan_backend(&self, orphan: &RunningTimer) -> Result<Backend, String> {
+        let cfg = self.config.lock().await.clone().ok_or("Tracker není nakonfigurovaný")?;
+        let backend = self.backend(&cfg);
+        if backend.name() != orphan.backend {
+            return Err(format!(
+                "Záznam běží ve službě {}, aktuálně je nastavená {}",
+                orphan.backend,
+                backend.name()
+            ));
+        }
+        Ok(backend)
+    }
+
+    /// Zastaví osiřelý timer a zapíše segment do historie (při výpadku přes offline frontu)
+    async fn stop_orphaned_timer(&self, app: &Events, orphan: &RunningTimer) -> Result<(), String> {
+        let backend = self.orphan_backend(orphan).await?;
+        self.finish_segment(app, &backend, &orphan.to_tracking(), Local::now()).await?;
+        Self::emit_log(app, "success", &format!("⏹️ Neukončený timer od {} zastaven", orphan.started_at.format("%H:%M")));
+        Ok(())
+    }
+
+    /// Zapíše ukončený segment do lokální historie
+    fn record_history(&self, app: &Events, profile: &str, tracking: &ActiveTracking, stopped_at: DateTime<Local>) {
+        let segment = HistorySegment {
+            id: tracking.uuid.clone(),
+            task_id: tracking.task_id.parse().ok(),
+            note: tracking.full_note(),
+            started_at: DateTime::<Local>::from(tracking.start_time),
+            stopped_at,
+            source: tracking.source,
+            freelo_report_id: None,
+        };
+        if review::needs_review(tracking.confidence, segment.minutes()) {
+            self.spawn_review_suggestions(app, profile, segment.clone(), tracking.confidence.unwrap_or_default());
+        }
+        if let Err(e) = history::record(profile, segment) {
+            Self::emit_error(app, ErrorCode::StorageFailed, &format!("Nelze uložit segment do historie: {}", e));
+        }
+    }
+
+    /// Segment s nízkou confidence zařadí ke kontrole - AI (levný model, jen text)
+    /// navrhne nejpravděpodobnější tasky, aby oprava byla jedním kliknutím
+    fn spawn_review_suggestions(&self, app: &Events, profile: &str, segment: HistorySegment, confidence: f32) {
+        let tracker = self.clone();
+        let app = app.clone();
+        let profile = profile.to_string();
+
+        tokio::spawn(async move {
+            let (llm, redact) = tracker
+                .config
+                .lock()
+                .await
+                .as_ref()
+                .map(|c| (c.llm.clone(), c.redact_ai_input))
+                .unwrap_or((None, true));
+            let tasks = tracker.freelo_tasks_cache.lock().await.clone();
+
+            let mut suggestions = Vec::new();
+            if let Some(llm) = llm.filter(|_| !tasks.is_empty()) {
+                let note = if redact { redaction::redact(&segment.note).text } else { segment.note.clone() };
+                let context = format!(
+                    "Poznámka: {}\nDélka: {} min\nZvolený task: {}",
+                    note,
+                    segment.minutes(),
+                    segment.task_id.map(|id| id.to_string()).unwrap_or_else(|| "žádný".to_string())
+                );
+                match suggest_tasks(&context, &tasks, review::MAX_SUGGESTIONS, &llm).await {
+                    Ok(raw) => suggestions = review::resolve_suggestions(&raw, &tasks),
+                    Err(e) => Self::emit_log(&app, "warning", &format!("⚠️  Návrhy tasků selhaly: {}", e)),
+                }
+            }
+
+            let item = ReviewItem {
+                segment,
+                confidence,
+                suggestions,
+            };
+            if let Err(e) = review::push(&profile, item) {
+                Self::emit_error(&app, ErrorCode::StorageFailed, &format!("Nelze uložit segment ke kontrole: {}", e));
+                return;
+            }
+            Self::emit_log(
+                &app,
+                "info",
+                &format!("🧐 Segment s nízkou jistotou ({:.0}%) čeká na kontrolu", confidence * 100.0),
+            );
+            let _ = app.emit("review-queue-updated", review::load(&profile).len());
+        });
+    }
+
+    /// Segmenty čekající na kontrolu tasku
+    pub async fn get_review_queue(&self) -> Vec<ReviewItem> {
+        review::load(&self.current_profile().await)
+    }
+
+    /// Vyřeší segment z fronty - s `task_id` ho přeřadí (volitelně i ve Freelo),
+    /// bez něj potvrdí původní task
+    pub async fn resolve_review(
+        &self,
+        app: &Events,
+        segment_id: &str,
+        task_id: Option<i32>,
+        push_to_freelo: bool,
+    ) -> Result<(), String> {
+        let profile = self.current_profile().await;
+        let item = review::load(&profile)
+            .into_iter()
+            .find(|i| i.segment.id == segment_id)
+            .ok_or_else(|| format!("Segment {} není ve frontě ke kontrole", segment_id))?;
+
+        if let Some(task_id) = task_id.filter(|id| item.segment.task_id != Some(*id)) {
+            let edit = SegmentEdit {
+                task_id: Some(task_id),
+                ..Default::default()
+            };
+            let result = self
+                .edit_history_segments(app, &[segment_id.to_string()], &edit, push_to_freelo)
+                .await?;
+            if let Some(e) = result.errors.first() {
+                return Err(e.clone());
+            }
+        }
+
+        review::take(&profile, segment_id)?;
+        let _ = app.emit("review-queue-updated", review::load(&profile).len());
+        Ok(())
+    }
+
+    /// Lokální historie segmentů (volitelně jen v rozsahu dní)
+    pub async fn list_history_segments(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<HistorySegment> {
+        history::filter_by_date(history::load(&self.current_profile().await), from, to)
+    }
+
+    /// Log rozhodnutí trackeru (tick po ticku) za rozsah dní
+    pub async fn get_history(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Vec<TickRecord>, String> {
+        history::ticks_in_range(&self.current_profile().await, from, to)
+    }
+
+    /// Ruční oprava přiřazení ticku - uloží ji, upraví váhy slov textového matcheru
+    /// a od dalšího ticku ji AI dostává jako příklad
+    pub async fn submit_correction(&self, app: &Events, tick_id: i64, correct_task_id: i32) -> Result<(), String> {
+        let profile = self.current_profile().await;
+        let tick = history::tick_by_id(&profile, tick_id)?.ok_or_else(|| format!("Tick {} v historii není", tick_id))?;
+        if tick.task_id == Some(correct_task_id) {
+            return Err(format!("Tick {} už je přiřazený k tasku {}", tick_id, correct_task_id));
+        }
+        let task_index = self.task_index.lock().await.clone();
+        let task_name = task_index
+            .tasks()
+            .iter()
+            .find(|t| t.id == correct_task_id)
+            .map(|t| t.name.clone())
+            .ok_or_else(|| format!("Task {} není mezi načtenými tasky", correct_task_id))?;
+
+        // Text obrazovky z ladicích OCR souborů - bez nich se uloží jen oprava samotná
+        let privacy_mode = self.config.lock().await.as_ref().is_some_and(|c| c.privacy_mode);
+        let text = evaluation::sample_at(&profile, tick.at).map(|s| s.text).unwrap_or_default();
+        let correction = Correction {
+            tick_id,
+            at: Local::now(),
+            application: tick.application.clone(),
+            wrong_task_id: tick.task_id,
+            correct_task_id,
+            boosted: text_matcher::task_keywords(&text, &task_index, correct_task_id),
+            penalized: tick
+                .task_id
+                .map(|id| text_matcher::task_keywords(&text, &task_index, id))
+                .unwrap_or_default(),
+            excerpt: (!privacy_mode && !text.trim().is_empty()).then(|| corrections::excerpt(&redaction::redact(&text).text)),
+        };
+        corrections::record(&profile, correction)?;
+
+        let tasks = task_index.tasks().to_vec();
+        let weights = corrections::profile_weights(&profile);
+        *self.task_index.lock().await = Arc::new(TaskIndex::build(&tasks).with_keyword_weights(weights));
+
+        if let Some(source) = tick.source {
+            // Mimo běh trackingu nejsou statistiky v paměti načtené - uložily by se jen s touto opravou
+            let mut stats = self.matcher_stats.lock().await;
+            *stats = MatcherStats::load(&profile);
+            stats.record(source, false);
+            if let Err(e) = stats.save(&profile) {
+                tracing::warn!(error = %e, "nelze uložit statistiky matcherů");
+            }
+        }
+
+        Self::emit_log(app, "success", &format!("🎓 Oprava uložena: tick {} patří k \"{}\"", tick_id, task_name));
+        Ok(())
+    }
+
+    /// Souhrn dne - časy podle tasků a aplikací, přepnutí kontextu a nesledované mezery
+    pub async fn get_daily_summary(&self, date: NaiveDate) -> Result<DailySummary, String> {
+        let profile = self.current_profile().await;
+        // Bez konfigurace výchozí interval z UI
+        let interval_seconds = self.config.lock().await.as_ref().map(|c| c.interval_seconds).unwrap_or(10);
+        let ticks = history::ticks_in_range(&profile, Some(date), Some(date))?;
+        let tasks = self.freelo_tasks_cache.lock().await.clone();
+
+        Ok(summary::daily_summary(date, &history::load(&profile), &ticks, &tasks, interval_seconds))
+    }
+
+    /// Proč v rozsahu chybí sledovaný čas - z historie segmentů a logu rozhodnutí
+    pub async fn explain_gap(&self, from: DateTime<Local>, to: DateTime<Local>) -> Result<GapExplanation, String> {
+        if from >= to {
+            return Err("Začátek rozsahu musí být před koncem".to_string());
+        }
+        let profile = self.current_profile().await;
+        let interval_seconds = self.config.lock().await.as_ref().map(|c| c.interval_seconds).unwrap_or(10);
+        // Tick těsně před začátkem rozsahu pokrývá i jeho začátek
+        let lookback = chrono::Duration::seconds(interval_seconds as i64 * summary::MAX_TICK_SPAN_INTERVALS);
+        let ticks = history::ticks_between(&profile, from - lookback, to)?;
+
+        Ok(summary::explain_gap(from, to, &history::load(&profile), &ticks, interval_seconds))
+    }
+
+    /// Text z OCR posledního ticku (nejvýše `max_chars` znaků)
+    pub async fn get_last_ocr_text(&self, max_chars: usize) -> Result<OcrTextPreview, String> {
+        if self.config.lock().await.as_ref().is_some_and(|c| c.privacy_mode) {
+            *self.last_ocr_text.lock().await = None;
+            return Err("V režimu soukromí se text z obrazovky neuchovává".to_string());
+        }
+        match self.last_ocr_text.lock().await.as_ref() {
+            Some(last) => Ok(OcrTextPreview::new(&last.text, last.at, max_chars)),
+            None => Err("Zatím žádný OCR text - počkejte na další tick".to_string()),
+        }
+    }
+
+    /// Odchylky historie od očekávaných hodin z CSV (den, projekt, hodiny)
+    pub async fn compare_with_expected(&self, csv: &str, tolerance_minutes: i64) -> Result<ComparisonReport, String> {
+        let profile = self.current_profile().await;
+        let tasks = self.freelo_tasks_cache.lock().await.clone();
+        comparison::compare(csv, &history::load(&profile), &tasks, tolerance_minutes)
+    }
+
+    /// Výkaz z lokální historie za rozsah dní do CSV/XLSX. S `include_freelo` doplní
+    /// záznamy z Freelo, které v historii chybí. Vrací počet řádků výkazu.
+    pub async fn export_report(
+        &self,
+        app: &Events,
+        range: ExportRange,
+        format: ExportFormat,
+        include_freelo: bool,
+        path: &Path,
+    ) -> Result<usize, String> {
+        if range.from > range.to {
+            return Err("Začátek exportu musí být před koncem".to_string());
+        }
+        let profile = self.current_profile().await;
+        let mut segments = history::load(&profile);
+        if include_freelo {
+            let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+            let reports = self.freelo_client(&cfg).get_work_reports_range(range.from, range.to, None).await?;
+            history::import_reports(&mut segments, &reports);
+        }
+        let tasks = self.freelo_tasks_cache.lock().await.clone();
+
+        let rows = export::report_rows(range, &segments, &tasks);
+        export::save(&rows, format, path)?;
+        Self::emit_log(app, "success", &format!("📤 Výkaz {} - {} uložen do {:?} ({} řádků)", range.from, range.to, path, rows.len()));
+        Ok(rows.len())
+    }
+
+    /// Znovu spustí textový a (do stropu requestů) AI matcher nad uloženými OCR texty
+    /// a porovná je s konečnými rozhodnutími v historii (včetně ručních oprav)
+    pub async fn evaluate_matchers(
+        &self,
+        app: &Events,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        max_ai_calls: usize,
+    ) -> Result<EvaluationReport, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let task_index = self.task_index.lock().await.clone();
+        if task_index.is_empty() {
+            return Err("Tasky nejsou načtené - spusťte tracking nebo obnovte tasky".to_string());
+        }
+        let signatures = self.rule_pack.lock().await.app_signatures.clone();
+        let samples = evaluation::load_samples(&cfg.profile, from, to);
+        let segments = history::load(&cfg.profile);
+        Self::emit_log(app, "info", &format!("🧪 Vyhodnocuji matchery na {} uložených OCR textech...", samples.len()));
+
+        // Stejný práh jako v ticku - pod ním by tracker task nepoužil
+        let accepted = |task_id: Option<i32>, confidence: f32| task_id.filter(|_| confidence > cfg.min_confidence);
+        let mut report = EvaluationReport::default();
+        let mut ai_calls = 0;
+        for sample in &samples {
+            let decision = evaluation::final_decision(&segments, sample.at);
+            let text = find_best_matching_task(&sample.text, &task_index, &signatures);
+
+            // Placené AI jen na vzorcích, které jde vyhodnotit
+            let llm = cfg.llm.as_ref().filter(|_| decision.is_some() && ai_calls < max_ai_calls);
+            let ai = match llm {
+                Some(llm) => {
+                    ai_calls += 1;
+                    let ai_text = if cfg.redact_ai_input { redaction::redact(&sample.text).text } else { sample.text.clone() };
+                    match match_task_with_ai(&ai_text, None, task_index.tasks(), None, None, llm, &cfg.ai_params).await {
+                        Ok(result) => Some(accepted(result.task_id, result.confidence / 100.0)),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "AI při vyhodnocení selhalo");
+                            report.ai_errors += 1;
+                            None
+                        }
+                    }
+                }
+                None => None,
+            };
+            report.record(sample.at, decision, accepted(text.task_id, text.confidence), ai);
+        }
+        report.finish();
+
+        let percent = |ratio: Option<f32>| ratio.map(|r| format!("{:.0} %", r * 100.0)).unwrap_or_else(|| "-".to_string());
+        Self::emit_log(
+            app,
+            "info",
+            &format!(
+                "🧪 Přesnost: text {} ({} vzorků), AI {} ({} vzorků), shoda {}",
+                percent(report.text.accuracy),
+                report.text.evaluated,
+                percent(report.ai.accuracy),
+                report.ai.evaluated,
+                percent(report.agreement)
+            ),
+        );
+        Ok(report)
+    }
+
+    /// Smaže log rozhodnutí aktivního profilu, vrací počet smazaných ticků
+    pub async fn clear_history(&self) -> Result<usize, String> {
+        history::clear_ticks(&self.current_profile().await)
+    }
+
+    /// Vrátí log rozhodnutí smazaný v posledních dnech
+    pub async fn restore_history(&self) -> Result<usize, String> {
+        history::restore_ticks(&self.current_profile().await)
+    }
+
+    /// Denní souhrny ticků, které údržba sloučila
+    pub async fn get_history_days(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<Vec<TickDay>, String> {
+        history::tick_days_in_range(&self.current_profile().await, from, to)
+    }
+
+    /// Údržba logu rozhodnutí - staré ticky do denních souhrnů, smazané natrvalo, VACUUM
+    pub async fn compact_history(&self, app: &Events) -> Result<RetentionResult, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let result = tokio::task::spawn_blocking(move || {
+            history::enforce_retention(&cfg.profile, cfg.history_retention_months, Local::now())
+        })
+        .await
+        .map_err(|e| format!("Údržba historie task chyba: {}", e))??;
+
+        if !result.is_empty() {
+            Self::emit_log(
+                app,
+                "info",
+                &format!(
+                    "🗜️  Historie zmenšena: {} starých ticků sloučeno do denních souhrnů, {} smazaných odstraněno",
+                    result.aggregated_ticks, result.purged_ticks
+                ),
+            );
+        }
+        Ok(result)
+    }
+
+    /// Hromadná úprava segmentů v historii, volitelně i oprava ve Freelo
+    /// (zapíše se nový work report a smaže se starý)
+    pub async fn edit_history_segments(
+        &self,
+        app: &Events,
+        ids: &[String],
+        edit: &SegmentEdit,
+        push_to_freelo: bool,
+    ) -> Result<BulkResult, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        if push_to_freelo {
+            self.require_live_writes()?;
+        }
+        let freelo = self.freelo_client(&cfg);
+        let profile = cfg.profile.clone();
+
+        let mut segments = history::load(&profile);
+        let mut result = BulkResult::default();
+
+        for segment in segments.iter_mut().filter(|s| ids.contains(&s.id)) {
+            let edited = match segment.edited(edit) {
+                Ok(edited) => edited,
+                Err(e) => {
+                    result.errors.push(e);
+                    continue;
+                }
+            };
+
+            if push_to_freelo {
+                match Self::replace_work_report(&freelo, segment, &edited).await {
+                    Ok(report_id) => {
+                        *segment = HistorySegment { freelo_report_id: report_id, ..edited };
+                        result.pushed += 1;
+                    }
+                    Err(e) => {
+                        // Segment zůstane beze změny, ať odpovídá záznamu, který ve Freelo zůstal
+                        result.errors.push(format!("Segment {}: Freelo neopraveno: {}", segment.id, e));
+                        continue;
+                    }
+                }
+            } else {
+                *segment = edited;
+            }
+            result.changed += 1;
+        }
+
+        history::save(&profile, &segments)?;
+        Self::emit_bulk_result(app, "upraveno", &result);
+        Ok(result)
+    }
+
+    /// Opravy záznamů čtou ostrý workspace - v sandboxu by se upravovala cizí ID
+    /// a v dry-run by se historie odpojila od záznamů, které se nezměnily
+    fn require_live_writes(&self) -> Result<(), String> {
+        match self.current_write_routing() {
+            WriteRouting::Live => Ok(()),
+            WriteRouting::Sandbox | WriteRouting::DryRun => {
+                Err("Opravy záznamů ve Freelo jsou dostupné jen při zápisech do ostrého Freelo".to_string())
+            }
+        }
+    }
+
+    /// Záznamy práce ve Freelo za den (výchozí dnes) pro kontrolu a opravy
+    pub async fn list_worklog(&self, date: NaiveDate) -> Result<Vec<WorkReport>, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        self.require_live_writes()?;
+        match self.backend(&cfg).freelo() {
+            Some(freelo) => freelo.get_work_reports(date, None).await,
+            None => Err("Opravy záznamů práce jsou dostupné jen pro Freelo".to_string()),
+        }
+    }
+
+    /// Opraví záznam práce ve Freelo - jiný task znamená nový záznam a smazání původního.
+    /// Segment v lokální historii propojený se záznamem se upraví stejně.
+    pub async fn edit_worklog_entry(
+        &self,
+        app: &Events,
+        date: NaiveDate,
+        report_id: i32,
+        edit: &WorklogEdit,
+    ) -> Result<WorkReport, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let backend = self.backend(&cfg);
+        let freelo = backend.freelo().ok_or_else(|| "Opravy záznamů práce jsou dostupné jen pro Freelo".to_string())?;
+        self.require_live_writes()?;
+
+        let original = freelo
+            .get_work_reports(date, None)
+            .await?
+            .into_iter()
+            .find(|r| r.id == report_id)
+            .ok_or_else(|| format!("Záznam {} ze dne {} ve Freelo nenalezen", report_id, date))?;
+        let mut edited = original.edited(edit)?;
+        let report_date = original.date.unwrap_or(date);
+
+        // ID záznamu ve Freelo po opravě, None = Freelo nové ID nevrátilo
+        let linked_id = match edited.task_id.filter(|id| Some(*id) != original.task_id) {
+            Some(task_id) => {
+                // Nový záznam napřed - při chybě zůstane původní
+                let new_id = freelo.create_work_report(task_id, edited.minutes, &edited.note, report_date).await?;
+                if let Err(delete_error) = freelo.delete_work_report(report_id).await {
+                    // Původní záznam zůstal - nový se vrátí, aby čas nebyl ve Freelo dvakrát
+                    let Some(new_id) = new_id else {
+                        return Err(format!(
+                            "Původní záznam {} nelze smazat ({}) a nový záznam nemá ID - ve Freelo je čas dvakrát, smažte duplicitu ručně",
+                            report_id, delete_error
+                        ));
+                    };
+                    return match freelo.delete_work_report(new_id).await {
+                        Ok(()) => Err(format!(
+                            "Původní záznam {} nelze smazat ({}) - nový záznam byl vrácen, nic se nezměnilo",
+                            report_id, delete_error
+                        )),
+                        Err(cleanup_error) => {
+                            // Historie patří k opravenému záznamu, původní je duplicita
+                            edited.id = new_id;
+                            Self::relink_segment(&cfg.profile, report_id, &edited, Some(new_id))?;
+                            Err(format!(
+                                "Původní záznam {} nelze smazat ({}) ani vrátit nový záznam {} ({}) - ve Freelo je duplicita, smažte ručně záznam {}",
+                                report_id, delete_error, new_id, cleanup_error, report_id
+                            ))
+                        }
+                    };
+                }
+                if let Some(id) = new_id {
+                    edited.id = id;
+                }
+                new_id
+            }
+            None => {
+                freelo.update_work_report(report_id, edited.minutes, &edited.note, report_date).await?;
+                Some(report_id)
+            }
+        };
+
+        Self::relink_segment(&cfg.profile, report_id, &edited, linked_id)?;
+
+        Self::emit_log(
+            app,
+            "success",
+            &format!(
+                "🗂️  Záznam ve Freelo opraven: {} min, {}",
+                edited.minutes,
+                edited.task_name.as_deref().unwrap_or(&edited.note)
+            ),
+        );
+        Ok(edited)
+    }
+
+    /// Přenese opravu záznamu do propojeného segmentu historie (`linked_id` = nové ID záznamu)
+    fn relink_segment(profile: &str, report_id: i32, edited: &WorkReport, linked_id: Option<i32>) -> Result<(), String> {
+        let mut segments = history::load(profile);
+        if let Some(segment) = segments.iter_mut().find(|s| s.freelo_report_id == Some(report_id)) {
+            segment.task_id = edited.task_id;
+            segment.note = edited.note.clone();
+            segment.stopped_at = segment.started_at + chrono::Duration::minutes(edited.minutes);
+            segment.freelo_report_id = linked_id;
+            history::save(profile, &segments)?;
+        }
+        Ok(())
+    }
+
+    /// Smaže záznam práce ve Freelo i propojený segment v lokální historii
+    pub async fn delete_worklog_entry(&self, app: &Events, report_id: i32) -> Result<(), String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let backend = self.backend(&cfg);
+        let freelo = backend.freelo().ok_or_else(|| "Opravy záznamů práce jsou dostupné jen pro Freelo".to_string())?;
+        self.require_live_writes()?;
+        freelo.delete_work_report(report_id).await?;
+
+        let mut segments = history::load(&cfg.profile);
+        let before = segments.len();
+        segments.retain(|s| s.freelo_report_id != Some(report_id));
+        if segments.len() != before {
+            history::save(&cfg.profile, &segments)?;
+        }
+
+        Self::emit_log(app, "success", &format!("🗑️  Záznam {} ve Freelo smazán", report_id));
+        Ok(())
+    }
+
+    /// Zapíše nový work report podle upraveného segmentu a smaže původní.
+    /// Když původní nejde smazat, nový se vrátí - při chybě ve Freelo zůstane jen původní záznam.
+    async fn replace_work_report(
+        freelo: &FreeloClient,
+        original: &HistorySegment,
+        edited: &HistorySegment,
+    ) -> Result<Option<i32>, String> {
+        let report_id = original
+            .freelo_report_id
+            .ok_or_else(|| "záznam ve Freelo není známý (neověřený segment)".to_string())?;
+        let task_id = edited.task_id.ok_or_else(|| "segment nemá task".to_string())?;
+
+        let new_id = freelo
+            .create_work_report(task_id, edited.minutes(), &edited.note, edited.started_at.date_naive())
+            .await?;
+        let Err(delete_error) = freelo.delete_work_report(report_id).await else {
+            return Ok(new_id);
+        };
+        let Some(new_id) = new_id else {
+            return Err(format!(
+                "původní záznam {} nelze smazat ({}) a nový záznam nemá ID - ve Freelo je čas dvakrát, smažte duplicitu ručně",
+                report_id, delete_error
+            ));
+        };
+        match freelo.delete_work_report(new_id).await {
+            Ok(()) => Err(format!(
+                "původní záznam {} nelze smazat ({}) - nový záznam byl vrácen",
+                report_id, delete_error
+            )),
+            Err(cleanup_error) => Err(format!(
+                "původní záznam {} nelze smazat ({}) ani vrátit nový záznam {} ({}) - ve Freelo je duplicita, smažte ručně záznam {}",
+                report_id, delete_error, new_id, cleanup_error, new_id
+            )),
+        }
+    }
+
+    /// Hromadné smazání segmentů z historie, volitelně i jejich work reportů ve Freelo
+    pub async fn delete_history_segments(
+        &self,
+        app: &Events,
+        ids: &[String],
+        push_to_freelo: bool,
+    ) -> Result<BulkResult, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        if push_to_freelo {
+            self.require_live_writes()?;
+        }
+        let freelo = self.freelo_client(&cfg);
+        let profile = cfg.profile.clone();
+
+        let (deleted, kept): (Vec<HistorySegment>, Vec<HistorySegment>) =
+            history::load(&profile).into_iter().partition(|s| ids.contains(&s.id));
+        let mut result = BulkResult {
+            changed: deleted.len(),
+            ..Default::default()
+        };
+
+        if push_to_freelo {
+            for segment in &deleted {
+                let pushed = match segment.freelo_report_id {
+                    Some(report_id) => freelo.delete_work_report(report_id).await,
+                    None => Err("záznam ve Freelo není známý (neověřený segment)".to_string()),
+                };
+                match pushed {
+                    Ok(()) => result.pushed += 1,
+                    Err(e) => result.errors.push(format!("Segment {}: Freelo neopraveno: {}", segment.id, e)),
+                }
+            }
+        }
+
+        history::save(&profile, &kept)?;
+        Self::emit_bulk_result(app, "smazáno", &result);
+        Ok(result)
+    }
+
+    fn emit_bulk_result(app: &Events, action: &str, result: &BulkResult) {
+        Self::emit_log(
+            app,
+            if result.errors.is_empty() { "success" } else { "warning" },
+            &format!(
+                "🗂️  Historie: {} {} segmentů, opraveno ve Freelo {}",
+                action, result.changed, result.pushed
+            ),
+        );
+        for error in &result.errors {
+            Self::emit_log(app, "warning", &format!("   {}", error));
+        }
+    }
+
+    /// Doplní do lokální historie work reporty z Freelo za rozsah dní (už importované přeskočí)
+    pub async fn import_freelo_history(&self, app: &Events, from: NaiveDate, to: NaiveDate) -> Result<usize, String> {
+        if from > to {
+            return Err("Začátek importu musí být před koncem".to_string());
+        }
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let freelo = self.freelo_client(&cfg);
+
+        Self::emit_log(app, "info", &format!("📥 Importuji záznamy z Freelo ({} - {})...", from, to));
+        let reports = freelo.get_work_reports_range(from, to, None).await?;
+
+        let mut segments = history::load(&cfg.profile);
+        let imported = history::import_reports(&mut segments, &reports);
+        history::save(&cfg.profile, &segments)?;
+        history::mark_initial_import(&cfg.profile)?;
+
+        Self::emit_log(
+            app,
+            "success",
+            &format!("📥 Import z Freelo: {} nových segmentů ({} záznamů celkem)", imported, reports.len()),
+        );
+        Ok(imported)
+    }
+
+    /// Při prvním spuštění profilu stáhne historii z Freelo na pozadí
+    fn spawn_initial_import(&self, app: &Events, profile: &str) {
+        if history::initial_import_done(profile) {
+            return;
+        }
+
+        let tracker = self.clone();
+        let app = app.clone();
+        tokio::spawn(async move {
+            let to = Local::now().date_naive();
+            let from = to - chrono::Duration::days(history::INITIAL_IMPORT_DAYS);
+            if let Err(e) = tracker.import_freelo_history(&app, from, to).await {
+                Self::emit_log(&app, "warning", &format!("⚠️  Import historie z Freelo selhal: {}", e));
+            }
+        });
+    }
+
+    /// Doplní výstižnější poznámku do běžícího Freelo záznamu (lokální segmenty jen v paměti)
+    async fn refresh_note(
+        &self,
+        app: &Events,
+        backend: &Backend,
+        tracking: &mut ActiveTracking,
+        note: String,
+        activity: &str,
+    ) {
+        if note == tracking.note {
+            return;
+        }
+        let previous = std::mem::replace(&mut tracking.note, note);
+        if !tracking.offline && !tracking.local_only {
+            if let Err(e) = backend.annotate(&tracking.uuid, &tracking.full_note()).await {
+                tracking.note = previous;
+                Self::emit_log(app, "warning", &format!("⚠️  Poznámku běžícího záznamu nelze aktualizovat: {}", e));
+                return;
+            }
+        }
+        tracking.note_activity = activity.to_string();
+        Self::emit_log(app, "info", &format!("📝 Poznámka záznamu aktualizována: {}", tracking.note));
+    }
+
+    /// Poznámka pro segment kategorie podle její šablony, jinak podle tasku
+    async fn segment_note(&self, tracking_key: &str, category: Option<&WorkCategory>, activity: &str) -> String {
+        match category {
+            Some(category) => categories::note(category, activity),
+            None => self.note_for(tracking_key, activity).await,
+        }
+    }
+
+    /// Poznámka pro nový segment - podle šablony tasku, jinak popis aktivity
+    async fn note_for(&self, tracking_key: &str, activity: &str) -> String {
+        let Ok(task_id) = tracking_key.parse::<i32>() else {
+            return activity.to_string();
+        };
+        let profile = self.current_profile().await;
+        let Some(template) = note_templates::load(&profile).remove(&task_id) else {
+            return activity.to_string();
+        };
+
+        let cache = self.freelo_tasks_cache.lock().await;
+        let task = cache.iter().find(|t| t.id == task_id);
+        note_templates::render(
+            &template,
+            &NoteContext {
+                activity,
+                task: task.map(|t| t.name.as_str()).unwrap_or_default(),
+                project: task.map(|t| t.project_name.as_str()).unwrap_or_default(),
+            },
+        )
+    }
+
+    /// Šablony poznámek aktivního profilu (task_id → šablona)
+    pub async fn get_note_templates(&self) -> BTreeMap<i32, String> {
+        note_templates::load(&self.current_profile().await)
+    }
+
+    /// Nastaví (nebo s None smaže) šablonu poznámky tasku, platí od dalšího startu segmentu
+    pub async fn set_note_template(&self, task_id: i32, template: Option<&str>) -> Result<BTreeMap<i32, String>, String> {
+        note_templates::set(&self.current_profile().await, task_id, template)
+    }
+
+    /// Uloží načtené tasky a přestaví index pro textový matching, vrací změny oproti cache
+    async fn set_tasks(&self, mut tasks: Vec<FreeloTask>) -> TasksUpdate {
+        // Jen vybrané projekty - ostatní tasky nevidí textový matching ani AI prompt
+        if let Some(cfg) = self.config.lock().await.as_ref().filter(|c| !c.selected_project_ids.is_empty()) {
+            tasks.retain(|t| cfg.selected_project_ids.contains(&t.project_id));
+        }
+        // Váhy slov naučené z ručních oprav
+        let weights = corrections::profile_weights(&self.current_profile().await);
+        let mut cache = self.freelo_tasks_cache.lock().await;
+        let update = TasksUpdate {
+            count: tasks.len(),
+            added: tasks
+                .iter()
+                .filter(|t| !cache.iter().any(|c| c.id == t.id))
+                .map(|t| t.name.clone())
+                .collect(),
+            removed: cache.iter().filter(|c| !tasks.iter().any(|t| t.id == c.id)).count(),
+        };
+
+        *self.task_index.lock().await = Arc::new(TaskIndex::build(&tasks).with_keyword_weights(weights));
+        *cache = tasks;
+        *self.tasks_loaded_at.lock().await = Some(Instant::now());
+        update
+    }
+
+    /// Aktivní tasklisty projektu ve Freelo
+    pub async fn get_tasklists(&self, project_id: i32) -> Result<Vec<Tasklist>, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        match self.backend(&cfg).freelo() {
+            Some(freelo) => freelo.get_tasklists(project_id).await,
+            None => Err("Tasklisty jsou dostupné jen pro Freelo".to_string()),
+        }
+    }
+
+    /// Načtené tasky (po filtru projektů) pro ruční výběr
+    pub async fn get_tasks(&self) -> Vec<FreeloTask> {
+        self.freelo_tasks_cache.lock().await.clone()
+    }
+
+    /// Projekty ve Freelo pro výběr, do kterých se smí matchovat
+    pub async fn get_projects(&self) -> Result<Vec<FreeloProject>, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        match self.backend(&cfg).freelo() {
+            Some(freelo) => freelo.get_projects().await,
+            None => Err("Výběr projektů je dostupný jen pro Freelo".to_string()),
+        }
+    }
+
+    /// Znovu načte tasky z Freelo (ručně z UI nebo periodicky ze smyčky)
+    pub async fn refresh_tasks(&self, app: &Events) -> Result<TasksUpdate, String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let tasks = match self.backend(&cfg).list_tasks().await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                if is_connectivity_error(&e) {
+                    self.mark_freelo_offline(app, &e).await;
+                }
+                return Err(format!("Chyba při načítání tasků: {}", e));
+            }
+        };
+
+        let update = self.set_tasks(tasks).await;
+        Self::clear_error(app, ErrorCode::FreeloAuth);
+        if !update.added.is_empty() || update.removed > 0 {
+            Self::emit_log(
+                app,
+                "info",
+                &format!(
+                    "🔄 Tasky obnoveny: {} aktivních (+{} / -{}){}",
+                    update.count,
+                    update.added.len(),
+                    update.removed,
+                    if update.added.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" - nové: {}", update.added.join(", "))
+                    }
+                ),
+            );
+        }
+        let _ = app.emit("tasks-updated", &update);
+        Ok(update)
+    }
+
+    /// Periodická obnova tasků, aby se matchovaly i tasky založené během dne
+    async fn refresh_tasks_if_due(&self, app: &Events, cfg: &TrackerConfig) {
+        if cfg.task_refresh_minutes == 0 || self.freelo_status.lock().await.is_offline() {
+            return;
+        }
+        let due = self
+            .tasks_loaded_at
+            .lock()
+            .await
+            .is_none_or(|at| at.elapsed() >= Duration::from_secs(cfg.task_refresh_minutes * 60));
+        if !due {
+            return;
+        }
+
+        if let Err(e) = self.refresh_tasks(app).await {
+            // Další pokus až po dalším intervalu
+            *self.tasks_loaded_at.lock().await = Some(Instant::now());
+            Self::emit_log(app, "warning", &format!("⚠️  {}", e));
+        }
+    }
+
+    /// Zaznamená nedostupnost Freelo (další pokus až po backoffu)
+    async fn mark_freelo_offline(&self, app: &Events, error: &str) {
+        let went_offline = self.freelo_status.lock().await.record_failure(Instant::now());
+        if went_offline {
+            Self::emit_log(app, "warning", &format!("📴 Freelo nedostupné ({}), pokračuji lokálně", error));
+            self.emit_freelo_status(app).await;
+        }
+    }
+
+    /// Při výpadku zkouší (s backoffem) Freelo znovu přes načtení tasků.
+    /// Po obnovení ukončí lokální segment a odešle offline frontu.
+    async fn check_freelo_recovery(&self, app: &Events, backend: &Backend) {
+        {
+            let status = self.freelo_status.lock().await;
+            if !status.is_offline() || !status.should_attempt(Instant::now()) {
+                return;
+            }
+        }
+
+        let tasks = match backend.list_tasks().await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                self.freelo_status.lock().await.record_failure(Instant::now());
+                Self::emit_log(app, "warning", &format!("📴 Freelo stále nedostupné: {}", e));
+                return;
+            }
+        };
+
+        let count = tasks.len();
+        self.set_tasks(tasks).await;
+        Self::clear_error(app, ErrorCode::FreeloAuth);
+        let since = self.freelo_status.lock().await.record_success();
+        Self::emit_log(
+            app,
+            "success",
+            &format!(
+                "📶 Freelo opět dostupné (výpadek od {}), načteno {} tasků",
+                since.map(|t| t.format("%H:%M").to_string()).unwrap_or_default(),
+                count
+            ),
+        );
+
+        // Lokální segment uzavřít do fronty, další tick už spustí Freelo timer
+        let offline_segment = {
+            let mut tracking = self.active_tracking.lock().await;
+            if tracking.as_ref().is_some_and(|t| t.offline) {
+                tracking.take()
+            } else {
+                None
+            }
+        };
+        if let Some(segment) = offline_segment {
+            if let Err(e) = self.finish_segment(app, backend, &segment, Local::now()).await {
+                Self::emit_error(app, ErrorCode::StorageFailed, &format!("Nelze uložit offline segment: {}", e));
+            }
+        }
+
+        self.sync_outbox(app, backend).await;
+    }
+
+    /// Odešle segmenty z doby výpadku do Freelo, neodeslané nechá ve frontě
+    async fn sync_outbox(&self, app: &Events, backend: &Backend) {
+        let profile = self.current_profile().await;
+        let entries = load_outbox(&profile);
+        if entries.is_empty() {
+            self.emit_freelo_status(app).await;
+            return;
+        }
+
+        Self::emit_log(app, "info", &format!("🔄 Synchronizuji {} segmentů z offline fronty...", entries.len()));
+
+        let (entries, adjusted) = reconcile_outbox(entries);
+        if adjusted > 0 {
+            Self::emit_log(app, "info", &format!("✂️  Upraveno {} překrývajících se segmentů ve frontě", adjusted));
+        }
+
+        let mut queue: VecDeque<OutboxEntry> = entries.into();
+        let mut remaining = Vec::new();
+        let mut synced = 0;
+
+        while let Some(entry) = queue.pop_front() {
+            let result = match (&entry.uuid, entry.task_id) {
+                // Timer ve Freelo běžel celou dobu výpadku - zastavit a ověřit délku
+                (Some(uuid), _) => backend.stop(uuid).await.map(|_| {
+                    let expected = ExpectedEntry {
+                        uuid: uuid.clone(),
+                        task_id: entry.task_id,
+                        note: entry.note.clone(),
+                        started_at: entry.started_at,
+                        stopped_at: entry.stopped_at,
+                    };
+                    if let Some(freelo) = backend.freelo() {
+                        Self::spawn_entry_verification(app, freelo, &profile, expected, false);
+                    }
+                }),
+                (None, Some(task_id)) if entry.minutes() >= 1 => match backend.freelo() {
+                    Some(freelo) => freelo
+                        .create_work_report(task_id, entry.minutes(), &entry.note, entry.started_at.date_naive())
+                        .await
+                        .map(|report_id| {
+                            if let (Some(segment_id), Some(report_id)) = (&entry.segment_id, report_id) {
+                                let _ = history::attach_report(&profile, segment_id, report_id);
+                            }
+                        }),
+                    None => Err("Zpětný zápis segmentu podporuje jen Freelo".to_string()),
+                },
+                // Pod minutu Freelo nic neukládá
+                (None, Some(_)) => Ok(()),
+                (None, None) => Err("Segment bez tasku nejde do Freelo zapsat zpětně".to_string()),
+            };
+
+            match result {
+                Ok(()) => synced += 1,
+                Err(e) if is_connectivity_error(&e) => {
+                    // Po dalším výpadku už nic nezkoušíme, zbytek počká na další obnovení
+                    self.mark_freelo_offline(app, &e).await;
+                    remaining.push(entry);
+                    remaining.extend(queue.drain(..));
+                    break;
+                }
+                Err(e) => {
+                    Self::emit_log(app, "warning", &format!("⚠️  Segment z offline fronty nelze odeslat: {}", e));
+                    let correction = PendingCorrection {
+                        expected: ExpectedEntry {
+                            uuid: entry.uuid.clone().unwrap_or_default(),
+                            task_id: entry.task_id,
+                            note: entry.note.clone(),
+                            started_at: entry.started_at,
+                            stopped_at: entry.stopped_at,
+                        },
+                        found: None,
+                        problems: vec![e],
+                        created_at: Local::now(),
+                    };
+                    if let Err(e) = verification::queue_correction(&profile, correction) {
+                        Self::emit_error(app, ErrorCode::StorageFailed, &format!("Nelze uložit opravu do fronty: {}", e));
+                    }
+                }
+            }
+
+            // Průběžně - po pádu aplikace se odeslané segmenty nesmí poslat znovu
+            let _ = save_outbox(&profile, queue.make_contiguous());
+        }
+
+        if let Err(e) = save_outbox(&profile, &remaining) {
+            Self::emit_error(app, ErrorCode::StorageFailed, &format!("Nelze uložit offline frontu: {}", e));
+        }
+        Self::emit_log(
+            app,
+            "success",
+            &format!("✅ Odesláno {} segmentů z offline fronty, {} čeká", synced, remaining.len()),
+        );
+        self.emit_freelo_status(app).await;
+    }
+
+    /// Stav Freelo pro UI (online/offline od, počet segmentů ve frontě)
+    pub async fn get_freelo_status(&self) -> FreeloStatus {
+        let offline_since = self.freelo_status.lock().await.offline_since();
+        let write_routing = self.current_write_routing();
+        let sandbox_email = match write_routing {
+            WriteRouting::Sandbox => self
+                .config
+                .lock()
+                .await
+                .as_ref()
+                .and_then(|c| c.sandbox_credentials.as_ref())
+                .map(|c| c.email.clone()),
+            _ => None,
+        };
+        FreeloStatus {
+            online: offline_since.is_none(),
+            offline_since: offline_since.map(|t| t.format("%H:%M").to_string()),
+            queued: load_outbox(&self.current_profile().await).len(),
+            write_routing,
+            sandbox_email,
+        }
+    }
+
+    fn current_write_routing(&self) -> WriteRouting {
+        self.write_routing.read().map(|r| *r).unwrap_or_default()
+    }
+
+    /// Přepne zápisy do Freelo (ostrý workspace / sandbox / dry-run) za běhu.
+    /// Běžící segment se nejdřív ukončí tam, kde byl spuštěn.
+    pub async fn set_write_routing(&self, app: &Events, routing: WriteRouting) -> Result<FreeloStatus, String> {
+        let cfg = self
+            .config
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        if routing == WriteRouting::Sandbox && cfg.sandbox_credentials.is_none() {
+            return Err("Sandbox nemá nastavený email a API key".to_string());
+        }
+
+        if routing != self.current_write_routing() {
+            self.stop_active_tracking(app, &self.backend(&cfg), Local::now()).await;
+            *self
+                .write_routing
+                .write()
+                .map_err(|_| "Přepínač zápisů není dostupný".to_string())? = routing;
+
+            let message = match routing {
+                WriteRouting::Live => "✅ Zápisy jdou znovu do ostrého Freelo".to_string(),
+                WriteRouting::Sandbox => format!(
+                    "🧪 SANDBOX: zápisy do Freelo jdou do testovacího workspace ({})",
+                    cfg.sandbox_credentials.as_ref().map(|c| c.email.as_str()).unwrap_or_default()
+                ),
+                WriteRouting::DryRun => "🧪 DRY-RUN: do Freelo se nic nezapisuje, segmenty jen do lokální historie".to_string(),
+            };
+            Self::emit_log(app, if routing == WriteRouting::Live { "success" } else { "warning" }, &message);
+        }
+
+        self.emit_freelo_status(app).await;
+        Ok(self.get_freelo_status().await)
+    }
+
+    async fn emit_freelo_status(&self, app: &Events) {
+        let status = self.get_freelo_status().await;
+        let _ = app.emit("freelo-status", status);
+    }
+
+    async fn current_profile(&self) -> String {
+        self.config
+            .lock()
+            .await
+            .as_ref()
+            .map(|c| c.profile.clone())
+            .unwrap_or_else(|| crate::profiles::DEFAULT_PROFILE.to_string())
+    }
+
+    /// Souhrnný komentář k tasku po ukončení bloku práce (na pozadí, zastavení nečeká).
+    /// Komentář vidí celý tým - citlivé údaje se vždy skryjí.
+    fn spawn_activity_comment(app: &Events, freelo: &FreeloClient, tracking: &ActiveTracking) {
+        let Ok(task_id) = tracking.task_id.parse::<i32>() else {
+            return;
+        };
+        let activities: Vec<String> = tracking.activities.iter().map(|a| redaction::redact(a).text).collect();
+        let Some(content) = activity_comment::comment(&activities, DateTime::<Local>::from(tracking.start_time), Local::now())
+        else {
+            return;
+        };
+
+        let app = app.clone();
+        let freelo = freelo.clone();
+        tokio::spawn(async move {
+            match freelo.add_comment(task_id, &content).await {
+                Ok(()) => Self::emit_log(&app, "info", &format!("💬 Souhrn práce přidán do komentářů tasku {}", task_id)),
+                Err(e) => Self::emit_log(&app, "warning", &format!("⚠️  Komentář k tasku {} nelze přidat: {}", task_id, e)),
+            }
+        });
+    }
+
+    /// Po zastavení timeru zpětně načte záznam z Freelo a ověří, že sedí task,
+    /// délka i poznámka. Nesoulad zaloguje a zařadí do fronty oprav profilu.
+    /// S `trim` zkrátí záznam, který je delší než segment (segment skončil před zastavením timeru).
+    fn spawn_entry_verification(app: &Events, freelo: &FreeloClient, profile: &str, expected: ExpectedEntry, trim: bool) {
+        // Freelo krátké segmenty (pod minutu) vůbec neukládá
+        if expected.minutes() < 1 {
+            return;
+        }
+
+        let app = app.clone();
+        let freelo = freelo.clone();
+        let profile = profile.to_string();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(VERIFY_DELAY_SECONDS)).await;
+
+            let task_filter = expected.task_id.map(|id| id.to_string());
+            let reports = match freelo
+                .get_work_reports(expected.started_at.date_naive(), task_filter.as_deref())
+                .await
+            {
+                Ok(reports) => reports,
+                Err(e) => {
+                    Self::emit_log(&app, "warning", &format!("⚠️  Ověření záznamu {} selhalo: {}", expected.uuid, e));
+                    return;
+                }
+            };
+
+            let (mut found, mut problems) = verification::verify(&expected, &reports);
+
+            // Segment skončil dřív, než se timer zastavil - záznam se zkrátí na délku segmentu
+            if let Some(report) = found
+                .as_mut()
+                .filter(|r| trim && r.task_id == expected.task_id && r.minutes > expected.minutes())
+            {
+                let date = report.date.unwrap_or(expected.started_at.date_naive());
+                match freelo.update_work_report(report.id, expected.minutes(), &report.note, date).await {
+                    Ok(()) => {
+                        report.minutes = expected.minutes();
+                        problems = verification::verify(&expected, std::slice::from_ref(report)).1;
+                    }
+                    Err(e) => Self::emit_log(&app, "warning", &format!("⚠️  Záznam {} nelze zkrátit: {}", report.id, e)),
+                }
+            }
+
+            // Propojit lokální historii se záznamem ve Freelo (pro pozdější opravy)
+            if let Some(report) = found.as_ref().filter(|r| r.task_id == expected.task_id) {
+                if let Err(e) = history::attach_report(&profile, &expected.uuid, report.id) {
+                    Self::emit_log(&app, "warning", &format!("⚠️  Nelze propojit segment s Freelo záznamem: {}", e));
+                }
+            }
+
+            if problems.is_empty() {
+                Self::emit_log(&app, "info", &format!("🔎 Záznam ověřen ve Freelo ({} min)", expected.minutes()));
+                return;
+            }
+
+            Self::emit_log(&app, "warning", &format!("⚠️  Freelo uložilo jiný záznam než agent zamýšlel (UUID: {})", expected.uuid));
+            for problem in &problems {
+                Self::emit_log(&app, "warning", &format!("   {}", problem));
+            }
+
+            let correction = PendingCorrection {
+                expected,
+                found,
+                problems,
+                created_at: Local::now(),
+            };
+            if let Err(e) = verification::queue_correction(&profile, correction) {
+                Self::emit_error(&app, ErrorCode::StorageFailed, &format!("Nelze uložit opravu do fronty: {}", e));
+            }
+        });
+    }
+
+    /// Co agent zamýšlel zapsat za právě ukončený segment
+    fn expected_entry(tracking: &ActiveTracking, stopped_at: DateTime<Local>) -> ExpectedEntry {
+        ExpectedEntry {
+            uuid: tracking.uuid.clone(),
+            task_id: tracking.task_id.parse().ok(),
+            note: tracking.full_note(),
+            started_at: DateTime::<Local>::from(tracking.start_time),
+            stopped_at,
+        }
+    }
+
+    /// Nesoulady se záznamy ve Freelo, které čekají na opravu
+    pub async fn get_pending_corrections(&self) -> Vec<PendingCorrection> {
+        verification::load_corrections(&self.current_profile().await)
+    }
+
+    /// Metadata projektu (barva, avatar) pro task - z cache, případně jen ID a název z tasku
+    async fn project_for_task(&self, task_id: Option<i32>, tasks: &[FreeloTask]) -> Option<ProjectMeta> {
+        let task = tasks.iter().find(|t| Some(t.id) == task_id)?;
+
+        let cached = self.project_meta_cache.lock().await.get(&task.project_id).cloned();
+        Some(cached.unwrap_or_else(|| ProjectMeta {
+            id: task.project_id,
+            name: task.project_name.clone(),
+            color: None,
+            avatar_url: None,
+        }))
+    }
+
+    /// Síťová brána před tickem - vrací true, pokud se má tick přeskočit
+    async fn check_network_gate(&self, app: &Events, backend: &Backend, cfg: &TrackerConfig) -> bool {
+        if cfg.network_rules.is_empty() {
+            return false;
+        }
+
+        let ctx = tokio::task::spawn_blocking(network::current_context)
+            .await
+            .unwrap_or_default();
+        let action = network::evaluate(&cfg.network_rules, &ctx, Local::now().time());
+
+        let mut paused = self.network_paused.lock().await;
+        let should_pause = action == NetworkAction::Pause;
+
+        if should_pause != *paused {
+            *paused = should_pause;
+            let network_label = format!(
+                "{}{}",
+                ctx.ssid.as_deref().unwrap_or("bez Wi-Fi"),
+                if ctx.vpn_active { " + VPN" } else { "" }
+            );
+
+            if should_pause {
+                Self::emit_log(app, "warning", &format!("📶 Síť {} - tracking pozastaven podle pravidel", network_label));
+                self.apply_gates(app, backend, TickGates { network_paused: true, ..Default::default() }, Local::now())
+                    .await;
+                Self::emit_tracking_update(app, "📶 Pozastaveno (síť)", &network_label, None, None);
+            } else {
+                Self::emit_log(app, "info", &format!("📶 Síť {} - tracking pokračuje", network_label));
+            }
+        }
+
+        should_pause
+    }
+
+    /// Brána pracovní doby - vrací true, pokud se má tick přeskočit. Po začátku okna pokračuje sama.
+    async fn check_schedule_gate(&self, app: &Events, backend: &Backend, cfg: &TrackerConfig) -> bool {
+        let now = Local::now().naive_local();
+        let outside = cfg.schedule.as_ref().is_some_and(|s| !s.is_active(now));
+
+        let mut paused = self.schedule_paused.lock().await;
+        if outside != *paused {
+            *paused = outside;
+            if outside {
+                let next = cfg
+                    .schedule
+                    .as_ref()
+                    .and_then(|s| s.next_start(now))
+                    .map(|t| format!("pokračuje {}", t.format("%d.%m. %H:%M")))
+                    .unwrap_or_else(|| "žádná další pracovní doba".to_string());
+                Self::emit_log(app, "info", &format!("🌙 Mimo pracovní dobu - tracking pozastaven ({})", next));
+                self.apply_gates(app, backend, TickGates { outside_schedule: true, ..Default::default() }, Local::now())
+                    .await;
+                Self::emit_tracking_update(app, "🌙 Mimo pracovní dobu", &next, None, None);
+            } else {
+                Self::emit_log(app, "info", "🌅 Začala pracovní doba - tracking pokračuje");
+            }
+        }
+
+        outside
+    }
+
+    /// Detekce zapomenutého timeru - segment běží dlouho bez jakéhokoliv vstupu uživatele
+    async fn check_inactivity_spike(&self, app: &Events, backend: &Backend, cfg: &TrackerConfig) {
+        let Some(idle) = idle_duration() else {
+            return;
+        };
+        let now = Local::now();
+        let max_inactive = Duration::from_secs(cfg.max_inactive_minutes * 60);
+
+        let mut tracking = self.active_tracking.lock().await;
+        let Some(active) = tracking.as_mut() else {
+            return;
+        };
+
+        let inactivity = longest_inactivity(active.last_input_at, now, idle);
+        active.last_input_at = active
+            .last_input_at
+            .max(now - chrono::Duration::from_std(idle).unwrap_or_default());
+
+        if inactivity < max_inactive || active.spike_flagged {
+            return;
+        }
+
+        active.spike_flagged = true;
+        let hours = inactivity.as_secs_f32() / 3600.0;
+        Self::emit_log(
+            app,
+            "error",
+            &format!(
+                "🚨 Segment {} běží {:.1} h bez jakékoliv aktivity - pravděpodobně zapomenutý timer!",
+                active.task_id, hours
+            ),
+        );
+        let _ = app.emit("spike-event", serde_json::json!({
+            "task_id": active.task_id,
+            "uuid": active.uuid,
+            "inactive_hours": hours,
+            "auto_stopped": cfg.spike_auto_stop,
+        }));
+        drop(tracking);
+
+        if cfg.spike_auto_stop {
+            Self::emit_log(app, "warning", "⏹️  Podezřelý segment automaticky ukončen");
+            self.apply_gates(app, backend, TickGates { inactivity_spike: true, ..Default::default() }, Local::now())
+                .await;
+        }
+    }
+
+    /// Rozdělí segment o půlnoci (zastaví a znovu spustí Freelo timer),
+    /// aby se hodiny ve výkazech připsaly ke správnému dni
+    async fn check_midnight_split(&self, app: &Events, backend: &Backend) {
+        let today = Local::now().date_naive();
+        let mut tracking = self.active_tracking.lock().await;
+
+        let crossed = tracking
+            .as_ref()
+            .is_some_and(|active| DateTime::<Local>::from(active.start_time).date_naive() < today);
+        if !crossed {
+            return;
+        }
+        let Some(previous) = tracking.take() else {
+            return;
+        };
+
+        if let Err(e) = self.finish_segment(app, backend, &previous, Local::now()).await {
+            Self::emit_error(app, errors::classify_freelo(&e), &format!("Chyba při zastavení segmentu o půlnoci: {}", e));
+        }
+
+        match self
+            .start_segment(
+                app,
+                backend,
+                previous.task_id.clone(),
+                previous.last_application.clone(),
+                previous.last_activity_description.clone(),
+                &previous.note,
+            )
+            .await
+        {
+            Ok(mut next) => {
+                next.source = previous.source;
+                next.confidence = previous.confidence;
+                next.last_input_at = previous.last_input_at;
+                next.spike_flagged = previous.spike_flagged;
+                *tracking = Some(next);
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("🌙 Půlnoc - segment rozdělen, nový záznam pro {}", today.format("%d.%m.%Y")),
+                );
+            }
+            Err(e) => {
+                Self::emit_error(
+                    app,
+                    errors::classify_freelo(&e),
+                    &format!("Chyba při spuštění segmentu po půlnoci: {}", e),
+                );
+            }
+        }
+    }
+
+    /// Detekce pauzy na oběd - vrací true, pokud se má tick přeskočit
+    async fn check_lunch_break(&self, app: &Events, backend: &Backend) -> bool {
+        let Some(idle) = idle_duration() else {
+            return false;
+        };
+
+        let mut lunch_break = self.lunch_break.lock().await;
+
+        if let Some(break_start) = *lunch_break {
+            if idle > ACTIVITY_RESUMED_IDLE {
+                // Pořád pryč - nic neanalyzujeme
+                return true;
+            }
+
+            // Uživatel je zpět - pauza končí, analýza pokračuje
+            let break_end = Local::now() - chrono::Duration::from_std(idle).unwrap_or_default();
+            *lunch_break = None;
+            Self::emit_log(
+                app,
+                "info",
+                &format!(
+                    "☕ Konec pauzy na oběd ({} - {}), pokračuji v analýze",
+                    break_start.format("%H:%M"),
+                    break_end.format("%H:%M")
+                ),
+            );
+            Self::emit_break_event(app, break_start, Some(break_end));
+            return false;
+        }
+
+        if idle < LUNCH_BREAK_MIN_IDLE {
+            return false;
+        }
+
+        let idle_start = Local::now() - chrono::Duration::from_std(idle).unwrap_or_default();
+        if !is_lunch_time(idle_start) {
+            return false;
+        }
+
+        Self::emit_log(
+            app,
+            "info",
+            &format!("☕ Detekována pauza na oběd (nečinnost od {})", idle_start.format("%H:%M")),
+        );
+
+        // Uzavři běžící záznam k začátku nečinnosti, nečinnost se neúčtuje
+        self.apply_gates(app, backend, TickGates { lunch_break: true, idle: true, ..Default::default() }, idle_start)
+            .await;
+
+        *lunch_break = Some(idle_start);
+        Self::emit_break_event(app, idle_start, None);
+        Self::emit_tracking_update(app, "☕ Pauza na oběd", &format!("Od {}", idle_start.format("%H:%M")), None, None);
+
+        true
+    }
+
+    /// Pauza při nečinnosti - zastaví Freelo timer, po návratu uživatele naváže
+    /// na přerušený task. Vrací true, pokud se má tick přeskočit.
+    async fn check_idle_pause(&self, app: &Events, backend: &Backend, cfg: &TrackerConfig) -> bool {
+        let Some(idle) = idle_duration() else {
+            return false;
+        };
+        let threshold = Duration::from_secs(cfg.idle_threshold_minutes * 60);
+        let mut pause = self.idle_pause.lock().await;
+        let idle_start = Local::now() - chrono::Duration::from_std(idle).unwrap_or_default();
+
+        match idle_action(pause.is_some(), idle, threshold) {
+            IdleAction::Stay => pause.is_some(),
+            IdleAction::Pause => {
+                let interrupted = self.active_tracking.lock().await.clone();
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("⏸️  Nečinnost od {} - pozastavuji tracking", idle_start.format("%H:%M")),
+                );
+                self.apply_gates(app, backend, TickGates { idle: true, ..Default::default() }, Local::now()).await;
+                Self::emit_tracking_update(app, "⏸️  Pryč od počítače", &format!("Od {}", idle_start.format("%H:%M")), None, None);
+                Self::notify_desktop(
+                    app,
+                    DesktopEvent::IdlePause,
+                    "Tracking pozastaven",
+                    &format!("Nečinnost od {}", idle_start.format("%H:%M")),
+                );
+
+                *pause = Some(IdlePause { since: idle_start, interrupted });
+                true
+            }
+            IdleAction::Resume => {
+                let Some(paused) = pause.take() else {
+                    return false;
+                };
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "▶️  Zpět u počítače (pryč {} - {})",
+                        paused.since.format("%H:%M"),
+                        idle_start.format("%H:%M")
+                    ),
+                );
+
+                Self::notify_desktop(
+                    app,
+                    DesktopEvent::IdlePause,
+                    "Tracking pokračuje",
+                    &format!("Pryč {} - {}", paused.since.format("%H:%M"), idle_start.format("%H:%M")),
+                );
+                self.resume_interrupted(app, backend, paused.interrupted).await;
+                false
+            }
+        }
+    }
+
+    /// Po pauze naváže na přerušený segment (stejný task, poznámka i kontext)
+    async fn resume_interrupted(&self, app: &Events, backend: &Backend, interrupted: Option<ActiveTracking>) {
+        // Mezitím mohl uživatel task přepnout ručně
+        let mut tracking = self.active_tracking.lock().await;
+        let (Some(previous), None) = (interrupted, tracking.as_ref()) else {
+            return;
+        };
+
+        match self
+            .start_segment(
+                app,
+                backend,
+                previous.task_id.clone(),
+                previous.last_application.clone(),
+                previous.last_activity_description.clone(),
+                &previous.note,
+            )
+            .await
+        {
+            Ok(mut next) => {
+                next.source = previous.source;
+                next.confidence = previous.confidence;
+                *tracking = Some(next);
+                Self::emit_log(app, "success", &format!("▶️  Tracking obnoven (task {})", previous.task_id));
+            }
+            Err(e) => {
+                Self::emit_error(app, errors::classify_freelo(&e), &format!("Chyba při obnovení trackingu: {}", e));
+            }
+        }
+    }
+
+    /// Ruční pauza (např. oběd) - smyčka běží dál, ale nic nesnímá a Freelo timer stojí
+    pub async fn pause(&self, app: &Events) -> Result<(), String> {
+        if !*self.is_running.lock().await {
+            return Err("Tracker neběží".to_string());
+        }
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+
+        let mut pause = self.user_pause.lock().await;
+        if pause.is_some() {
+            return Err("Tracking už je pozastavený".to_string());
+        }
+
+        let interrupted = self.active_tracking.lock().await.clone();
+        self.stop_active_tracking(app, &self.backend(&cfg), Local::now()).await;
+        let since = Local::now();
+        *pause = Some(IdlePause { since, interrupted });
+
+        Self::emit_log(app, "info", &format!("⏸️  Tracking pozastaven ({})", since.format("%H:%M")));
+        Self::emit_tracking_update(app, "⏸️  Pozastaveno", &format!("Od {}", since.format("%H:%M")), None, None);
+        let _ = app.emit("tracking-paused", true);
+        app.set_tray_state(TrayState::Paused);
+        Ok(())
+    }
+
+    /// Konec ruční pauzy - pokračuje přerušený task
+    pub async fn resume(&self, app: &Events) -> Result<(), String> {
+        let cfg = self.config.lock().await.clone().ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let paused = self
+            .user_pause
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| "Tracking není pozastavený".to_string())?;
+
+        Self::emit_log(
+            app,
+            "info",
+            &format!("▶️  Pauza ukončena ({} - {})", paused.since.format("%H:%M"), Local::now().format("%H:%M")),
+        );
+        self.resume_interrupted(app, &self.backend(&cfg), paused.interrupted).await;
+        let _ = app.emit("tracking-paused", false);
+        app.set_tray_state(TrayState::Running);
+        Ok(())
+    }
+
+    /// Prodleva po probuzení ze spánku - vrací true, pokud se má tick přeskočit
+    async fn check_resume_warmup(&self, app: &Events, cfg: &TrackerConfig) -> bool {
+        let gate = self
+            .resume_guard
+            .lock()
+            .await
+            .on_tick(Local::now(), cfg.interval_seconds, cfg.resume_warmup_seconds);
+
+        match gate {
+            ResumeGate::Run => false,
+            ResumeGate::JustResumed(slept) => {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "💤 Probuzení ze spánku (pauza {} min), analýza začne za {}s",
+                        slept.num_minutes(),
+                        cfg.resume_warmup_seconds
+                    ),
+                );
+                true
+            }
+            ResumeGate::WarmingUp(remaining) => {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("💤 Po probuzení čekám ještě {}s", remaining.num_seconds()),
+                );
+                true
+            }
+        }
+    }
+
+    /// Na prvních tickách po probuzení nezpracovávat zamykací obrazovku
+    async fn is_lock_screen(&self, app: &Events, ocr_text: Option<&str>, window: Option<&WindowInfo>) -> bool {
+        if !self.resume_guard.lock().await.check_lock_screen() {
+            return false;
+        }
+
+        if !looks_like_lock_screen(ocr_text, window) {
+            return false;
+        }
+
+        Self::emit_log(app, "info", "🔒 Snímek vypadá jako zamykací obrazovka, tick přeskočen");
+        true
+    }
+
+    /// Načte focus bloky z kalendáře do politiky oznámení, vrací jejich počet
+    pub fn apply_focus_calendar(cfg: &TrackerConfig) -> Result<usize, String> {
+        let blocks = match cfg.focus_calendar {
+            Some(ref path) => notifications::load_calendar(path, &cfg.focus_keyword)?,
+            None => Vec::new(),
+        };
+        let count = blocks.len();
+        if let Ok(mut policy) = notifications::POLICY.lock() {
+            policy.set_scheduled(blocks);
+        }
+        Ok(count)
+    }
+
+    /// Ohlásí začátek/konec focus bloku do UI (mimo politiku - musí projít vždy)
+    fn check_focus_transition(app: &Events) {
+        let now = Local::now();
+        let Ok(mut policy) = notifications::POLICY.lock() else {
+            return;
+        };
+        let Some(transition) = policy.poll_transition(now) else {
+            return;
+        };
+        let status = policy.status(now);
+        drop(policy);
+
+        let _ = app.emit("focus-state", &status);
+        match transition {
+            FocusTransition::Started => {
+                tracing::info!(until = ?status.until, "focus blok začal, nekritická oznámení se odkládají");
+            }
+            FocusTransition::Ended { deferred } => {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("🎯 Focus blok skončil, {} oznámení čeká na kontrolu", deferred),
+                );
+            }
+        }
+    }
+
+    pub fn start_focus_block(&self, app: &Events, minutes: i64) -> Result<FocusStatus, String> {
+        if minutes <= 0 {
+            return Err("Délka focus bloku musí být alespoň 1 minuta".to_string());
+        }
+        let now = Local::now();
+        let mut policy = notifications::POLICY
+            .lock()
+            .map_err(|_| "Politika oznámení není dostupná".to_string())?;
+        policy.start_manual(now, minutes);
+        drop(policy);
+
+        Self::check_focus_transition(app);
+        Ok(self.get_focus_status())
+    }
+
+    pub fn end_focus_block(&self, app: &Events) -> Result<(), String> {
+        notifications::POLICY
+            .lock()
+            .map_err(|_| "Politika oznámení není dostupná".to_string())?
+            .end_now(Local::now());
+
+        Self::check_focus_transition(app);
+        Ok(())
+    }
+
+    pub fn get_focus_status(&self) -> FocusStatus {
+        let now = Local::now();
+        match notifications::POLICY.lock() {
+            Ok(policy) => policy.status(now),
+            Err(_) => FocusStatus { active: false, until: None, title: None, deferred: 0 },
+        }
+    }
+
+    /// Oznámení odložená během focus bloků (po přečtení se smažou)
+    pub fn take_focus_review(&self) -> Vec<DeferredNotification> {
+        notifications::POLICY
+            .lock()
+            .map(|mut policy| policy.take_review())
+            .unwrap_or_default()
+    }
+
+    /// Ruční přepnutí na zvolený task (zastaví aktuální Freelo tracking a spustí nový)
+    pub async fn switch_task(&self, app: Events, task_id: i32) -> Result<(), String> {
+        let cfg = self
+            .config
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let backend = self.backend(&cfg);
+
+        let task_name = self
+            .freelo_tasks_cache
+            .lock()
+            .await
+            .iter()
+            .find(|t| t.id == task_id)
+            .map(|t| t.name.clone())
+            .unwrap_or_else(|| task_id.to_string());
+
+        let mut tracking = self.active_tracking.lock().await;
+        let previous = tracking.take();
+
+        if let Some(ref active) = previous {
+            // Ruční přepnutí na jiný task = matcher se spletl
+            let task_key = task_id.to_string();
+            self.record_matcher_outcome(active, active.task_id == task_key).await;
+            if let Err(e) = self.finish_segment(&app, &backend, active, Local::now()).await {
+                Self::emit_error(&app, errors::classify_freelo(&e), &format!("CHYBA STOP TRACKING: {}", e));
+            }
+        }
+
+        let (last_application, last_activity) = previous
+            .map(|p| (p.last_application, p.last_activity_description))
+            .unwrap_or_default();
+        let note = if last_activity.is_empty() {
+            format!("Ruční výběr: {}", task_name)
+        } else {
+            last_activity.clone()
+        };
+        let note = self.note_for(&task_id.to_string(), &note).await;
+
+        let segment = self
+            .start_segment(&app, &backend, task_id.to_string(), last_application, last_activity, &note)
+            .await?;
+        let uuid = segment.uuid.clone();
+        *tracking = Some(segment);
+        drop(tracking);
+
+        self.mark_manual_action().await;
+        Self::emit_log(&app, "success", &format!("👆 TRACKING: Ručně přepnuto na task {} (UUID: {})", task_name, uuid));
+
+        // Ruční přepnutí na jiný task ruší zámek
+        let mut lock = self.manual_lock.lock().await;
+        if lock.as_ref().is_some_and(|l| l.task_id != task_id) {
+            *lock = None;
+            let _ = app.emit("manual-lock", None::<ManualLock>);
+            Self::emit_log(&app, "info", "🔓 Zámek tasku zrušen ručním přepnutím");
+        }
+
+        Ok(())
+    }
+
+    /// Zamkne tracking na zvolený task na `duration_minutes` (None = do zrušení).
+    /// Pokud task neběží, hned na něj přepne.
+    pub async fn set_manual_task(
+        &self,
+        app: Events,
+        task_id: i32,
+        duration_minutes: Option<u64>,
+    ) -> Result<ManualLock, String> {
+        if duration_minutes == Some(0) {
+            return Err("Délka zámku musí být alespoň 1 minuta".to_string());
+        }
+
+        let task_name = self
+            .freelo_tasks_cache
+            .lock()
+            .await
+            .iter()
+            .find(|t| t.id == task_id)
+            .map(|t| t.name.clone())
+            .ok_or_else(|| format!("Task {} není v seznamu tasků", task_id))?;
+
+        let running = self
+            .active_tracking
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|t| t.task_id == task_id.to_string());
+        if !running {
+            self.switch_task(app.clone(), task_id).await?;
+        }
+
+        let lock = ManualLock {
+            task_id,
+            task_name,
+            until: duration_minutes.map(|m| Local::now() + chrono::Duration::minutes(m as i64)),
+        };
+        *self.manual_lock.lock().await = Some(lock.clone());
+        let _ = app.emit("manual-lock", Some(&lock));
+
+        let until = lock
+            .until
+            .map(|u| format!("do {}", u.format("%H:%M")))
+            .unwrap_or_else(|| "do zrušení".to_string());
+        Self::emit_log(&app, "success", &format!("🔒 Tracking zamčen na task {} ({})", lock.task_name, until));
+        Ok(lock)
+    }
+
+    /// Zruší zámek tasku, vrací zda nějaký byl
+    pub async fn clear_manual_task(&self, app: &Events) -> bool {
+        let cleared = self.manual_lock.lock().await.take().is_some();
+        if cleared {
+            let _ = app.emit("manual-lock", None::<ManualLock>);
+            Self::emit_log(app, "info", "🔓 Zámek tasku zrušen, matcher opět rozhoduje");
+        }
+        cleared
+    }
+
+    /// Aktuální zámek tasku (vypršený se rovnou zruší)
+    pub async fn get_manual_lock(&self, app: &Events) -> Option<ManualLock> {
+        let mut lock = self.manual_lock.lock().await;
+        if lock.as_ref().is_some_and(|l| l.expired(Local::now())) {
+            let expired = lock.take();
+            let _ = app.emit("manual-lock", None::<ManualLock>);
+            if let Some(expired) = expired {
+                Self::emit_log(app, "info", &format!("🔓 Zámek na task {} vypršel", expired.task_name));
+            }
+        }
+        lock.clone()
+    }
+
+    /// Tick během zámku - zamčený task běží dál, případně se znovu spustí
+    async fn hold_manual_lock(&self, app: &Events, lock: &ManualLock, match_result: &MatchResult) -> TickAction {
+        let running = self
+            .active_tracking
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|t| t.task_id == lock.task_id.to_string());
+
+        if running {
+            if match_result.task_id.is_some_and(|id| id != lock.task_id) {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "🔒 Task zamčen, ignoruji návrh {} ({} pokračuje)",
+                        match_result.task_name.as_deref().unwrap_or("?"),
+                        lock.task_name
+                    ),
+                );
+                return TickAction::HeldByManual;
+            }
+            return TickAction::Continued;
+        }
+
+        match self.switch_task(app.clone(), lock.task_id).await {
+            Ok(()) => TickAction::Started,
+            Err(e) => {
+                Self::emit_error(app, errors::classify_freelo(&e), &format!("CHYBA START TRACKING: {}", e));
+                TickAction::Failed
+            }
+        }
+    }
+
+    /// Připojí ruční poznámku k běžícímu segmentu a promítne ji do Freelo poznámky
+    pub async fn annotate_current_segment(&self, app: Events, text: &str) -> Result<(), String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Poznámka je prázdná".to_string());
+        }
+
+        let cfg = self
+            .config
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| "Konfigurace není nastavena".to_string())?;
+        let backend = self.backend(&cfg);
+
+        let mut tracking = self.active_tracking.lock().await;
+        let active = tracking
+            .as_mut()
+            .ok_or_else(|| "Žádný segment neběží".to_string())?;
+
+        active.annotations.push(text.to_string());
+        let note = active.full_note();
+
+        // Lokální segment si poznámku nese sám, do Freelo se dostane přes offline frontu (nebo vůbec)
+        if active.offline || active.local_only {
+            Self::emit_log(&app, "success", &format!("📝 Poznámka k segmentu (offline): {}", text));
+            return Ok(());
+        }
+
+        if let Err(e) = backend.annotate(&active.uuid, &note).await {
+            active.annotations.pop();
+            return Err(format!("Nepodařilo se uložit poznámku do Freelo: {}", e));
+        }
+
+        Self::emit_log(&app, "success", &format!("📝 Poznámka k segmentu: {}", text));
+        Ok(())
+    }
+
+    /// Zaznamená ruční zásah uživatele - spustí ochrannou dobu pro automatiku
+    pub async fn mark_manual_action(&self) {
+        *self.last_manual_action.lock().await = Some(Instant::now());
+    }
+
+    /// Smaže data profilu - aktivní profil nelze smazat, dokud tracker běží
+    pub async fn delete_profile_data(&self, profile: &str) -> Result<(), String> {
+        let active_profile = self.config.lock().await.as_ref().map(|c| c.profile.clone());
+        if *self.is_running.lock().await && active_profile.as_deref() == Some(profile) {
+            return Err(format!("Profil '{}' se právě používá, nejdřív zastavte tracking", profile));
+        }
+
+        delete_profile(profile)
+    }
+
+    /// Soubor pravidel nebo šablona promptu se změnily mimo aplikaci - ověří je a platné načte.
+    /// Při chybě zůstává poslední platná verze a UI dostane řádek a sloupec.
+    pub async fn reload_config_file(&self, app: &Events, file: ConfigFile) {
+        let path = file.path();
+        // Smazaný soubor = výchozí pravidla / vestavěný prompt
+        let content = std::fs::read_to_string(&path).ok();
+
+        let result = match file {
+            ConfigFile::RulePack => match content.as_deref().map(RulePack::parse).transpose() {
+                Ok(pack) => {
+                    // Ze společného souboru platí jen signatury, pravidla tasků jsou u profilu
+                    let signatures = pack.unwrap_or_default().app_signatures;
+                    let mut current = self.rule_pack.lock().await;
+                    // Uložení z aplikace (import) soubor změní, ale obsah je už načtený
+                    let changed = current.app_signatures != signatures;
+                    current.app_signatures = signatures;
+                    Ok(changed)
+                }
+                Err(e) => Err(e),
+            },
+            ConfigFile::PromptTemplate => match content.as_deref().map(prompt_template::validate).transpose() {
+                Ok(_) => {
+                    let changed = prompt_template::active() != content;
+                    prompt_template::set_active(content);
+                    Ok(changed)
+                }
+                Err(e) => Err(e),
+            },
+        };
+
+        let event = ConfigFileEvent {
+            file: file.label().to_string(),
+            path: path.display().to_string(),
+            valid: result.is_ok(),
+            error: result.as_ref().err().cloned(),
+        };
+        match result {
+            Ok(changed) => {
+                Self::clear_error(app, file.error_code());
+                if changed {
+                    Self::emit_log(app, "success", &format!("🔁 Znovu načteno: {}", file.label()));
+                }
+            }
+            Err(e) => Self::emit_error(app, file.error_code(), &format!("❌ Neplatný soubor {}: {}", file.label(), e)),
+        }
+        let _ = app.emit("config-file-event", event);
+    }
+
+    /// Exportuje aktuální rule pack do souboru pro sdílení v týmu (včetně naučených asociací profilu)
+    pub async fn export_rule_pack(&self, path: &Path) -> Result<(), String> {
+        let profile = self.current_profile().await;
+        let mut pack = self.rule_pack.lock().await.clone();
+        pack.learned = LearnedAssociations::of_profile(&profile);
+        pack.export_to(path)
+    }
+
+    /// Importuje rule pack ze souboru, sloučí ho s aktuálním a uloží
+    /// (signatury pro všechny profily, pravidla tasků a naučené asociace k aktivnímu profilu)
+    pub async fn import_rule_pack(&self, path: &Path) -> Result<usize, String> {
+        let mut imported = RulePack::read_from(path)?;
+        let profile = self.current_profile().await;
+        let learned = std::mem::take(&mut imported.learned);
+        let mut changed = {
+            let mut pack = self.rule_pack.lock().await;
+            let changed = pack.merge(imported);
+            pack.save(&profile)?;
+            changed
+        };
+
+        if !learned.is_empty() {
+            changed += learned.import(&profile)?;
+            let tasks = self.task_index.lock().await.tasks().to_vec();
+            let weights = corrections::profile_weights(&profile);
+            *self.task_index.lock().await = Arc::new(TaskIndex::build(&tasks).with_keyword_weights(weights));
+        }
+        Ok(changed)
+    }
+
+    /// Pravidla tasků z aktivního rule packu (v pořadí priority)
+    pub async fn list_task_rules(&self) -> Vec<TaskRule> {
+        self.rule_pack.lock().await.task_rules.clone()
+    }
+
+    /// Uloží pravidlo - stejný název přepíše, nové se přidá na konec (s nejnižší prioritou)
+    pub async fn save_task_rule(&self, rule: TaskRule) -> Result<Vec<TaskRule>, String> {
+        task_rules::validate(&rule)?;
+        let profile = self.current_profile().await;
+        let mut pack = self.rule_pack.lock().await;
+        match pack.task_rules.iter_mut().find(|r| r.name == rule.name) {
+            Some(existing) => *existing = rule,
+            None => pack.task_rules.push(rule),
+        }
+        pack.save(&profile)?;
+        Ok(pack.task_rules.clone())
+    }
+
+    pub async fn delete_task_rule(&self, name: &str) -> Result<Vec<TaskRule>, String> {
+        let profile = self.current_profile().await;
+        let mut pack = self.rule_pack.lock().await;
+        let before = pack.task_rules.len();
+        pack.task_rules.retain(|r| r.name != name);
+        if pack.task_rules.len() == before {
+            return Err(format!("Pravidlo '{}' neexistuje", name));
+        }
+        pack.save(&profile)?;
+        Ok(pack.task_rules.clone())
+    }
+
+    async fn tracking_loop(&self, app: Events) {
+        // Get config
+        let cfg = {
+            let config_guard = self.config.lock().await;
+            match config_guard.as_ref() {
+                Some(c) => c.clone(),
+                None => {
+                    Self::emit_error(&app, ErrorCode::ConfigMissing, "Konfigurace není nastavena");
+                    return;
+                }
+            }
+        };
+
+        let backend = self.backend(&cfg);
+
+        *self.matcher_stats.lock().await = MatcherStats::load(&cfg.profile);
+        *self.embedding_matcher.lock().await = EmbeddingMatcher::load(&cfg.profile);
+
+        // Cenový limit ticku potřebuje cenu modelu - neznámou doplní katalog OpenRouter
+        if cfg.tick_budget.max_cost_usd.is_some()
+            && cfg.llm.as_ref().is_some_and(|llm| llm.is_cloud())
+            && ai_usage::model_price(&cfg.ai_params.model).is_none()
+        {
+            tokio::spawn(async {
+                if let Err(e) = ai_matcher::list_models().await {
+                    tracing::warn!(error = %e, "ceník modelů nenačten");
+                }
+            });
+        }
+
+        // Auto výběr PSM začíná s každým spuštěním znovu
+        *self.psm_selector.lock().await = PsmAutoSelector::default();
+        *self.resume_guard.lock().await = ResumeGuard::default();
+
+        // Kalendář se mohl od uložení nastavení změnit
+        if let Err(e) = Self::apply_focus_calendar(&cfg) {
+            Self::emit_log(&app, "warning", &format!("⚠️  Focus bloky z kalendáře nenačteny: {}", e));
+        }
+
+        if let Err(e) = self.compact_history(&app).await {
+            Self::emit_log(&app, "warning", &format!("⚠️  {}", e));
+        }
+
+        if self.current_write_routing() == WriteRouting::DryRun {
+            Self::emit_log(&app, "warning", "🧪 DRY-RUN: segmenty se ukládají jen do lokální historie, timery se nespouští");
+        }
+
+        // Load Freelo tasks
+        Self::emit_log(&app, "info", &format!("Načítám tasky z {}...", backend.name()));
+        match backend.list_tasks().await {
+            Ok(tasks) => {
+                let update = self.set_tasks(tasks).await;
+                Self::emit_log(&app, "success", &format!("Načteno {} aktivních tasků", update.count));
+                Self::clear_error(&app, ErrorCode::FreeloAuth);
+                let _ = app.emit("tasks-updated", &update);
+            }
+            Err(e) if is_connectivity_error(&e) => {
+                // Freelo je dole - sledujeme lokálně s posledními známými tasky
+                self.mark_freelo_offline(&app, &e).await;
+            }
+            Err(e) => {
+                Self::emit_error(&app, errors::classify_freelo(&e), &format!("Chyba při načítání tasků: {}", e));
+                return;
+            }
+        }
+
+        // Segmenty z minulého výpadku, případně úvodní import historie (jen z Freelo)
+        if !self.freelo_status.lock().await.is_offline() {
+            self.sync_outbox(&app, &backend).await;
+            if backend.freelo().is_some() {
+                self.spawn_initial_import(&app, &cfg.profile);
+            }
+        }
+
+        // Metadata projektů (barvy/avatary) - jen kosmetika, chyba není fatální
+        if let Some(freelo) = backend.freelo() {
+            match freelo.get_projects().await {
+                Ok(projects) => {
+                    *self.project_meta_cache.lock().await =
+                        projects.iter().map(|p| (p.id, p.meta())).collect();
+                }
+                Err(e) => {
+                    Self::emit_log(&app, "warning", &format!("⚠️  Nelze načíst metadata projektů: {}", e));
+                }
+            }
+        }
+
+        // Main loop
+        let mut ticker = interval(Duration::from_secs(cfg.interval_seconds));
+        
+        Self::emit_log(&app, "info", &format!("Tracking spuštěn (interval: {}s)", cfg.interval_seconds));
+
+        if cfg.adaptive_interval {
+            Self::emit_log(
+                &app,
+                "info",
+                &format!("⏱️  Adaptivní interval: po změně kratší, při stabilní práci až {}s", cfg.max_interval_seconds),
+            );
+        }
+        let mut adaptive = AdaptiveInterval::default();
+
+        let mut tick: u64 = 0;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.wake_signal.notified() => {}
+            }
+
+            // Check if still running
+            if !*self.is_running.lock().await {
+                Self::emit_log(&app, "info", "Tracking loop ukončen");
+                break;
+            }
+
+            // Nouzové vypnutí - okamžitě zastavit timer i snímání
+            if let Some(reason) = kill_switch::disabled_reason() {
+                self.kill_switch_stop(&app, &cfg, &reason).await;
+                break;
+            }
+
+            self.refresh_tasks_if_due(&app, &cfg).await;
+
+            tick += 1;
+            let tick_started = Local::now();
+            TimelineEvent::TickStarted { at: tick_started, tick }.emit(&app);
+            let record = self
+                .run_tick(&app, &backend, &cfg)
+                .instrument(tracing::info_span!("tick", tick))
+                .await;
+            if let Err(e) = history::record_tick(&cfg.profile, &record) {
+                tracing::warn!(error = %e, "nelze zapsat rozhodnutí do historie");
+            }
+            self.flush_ai_usage(&cfg.profile).await;
+            self.update_tray_task(&app).await;
+
+            // Po změně kontextu kratší interval, při dlouho stejné práci delší
+            let base_interval = if cfg.adaptive_interval {
+                adaptive.next(record.action, cfg.interval_seconds, cfg.max_interval_seconds)
+            } else {
+                cfg.interval_seconds
+            };
+
+            // Při vysoké zátěži delší interval (a menší snímky v dalším ticku)
+            let level = self.update_resource_level(&app, &cfg).await;
+            let interval_seconds = resources::effective_interval(level, base_interval);
+            if interval_seconds != cfg.interval_seconds {
+                let elapsed = (Local::now() - tick_started).to_std().unwrap_or_default();
+                ticker.reset_after(Duration::from_secs(interval_seconds).saturating_sub(elapsed));
+            }
+            self.schedule_next_tick(&app, tick_started, interval_seconds).await;
+        }
+    }
+
+    /// Zastaví tracking kvůli nouzovému vypnutí a zapíše to do auditní historie
+    async fn kill_switch_stop(&self, app: &Events, cfg: &TrackerConfig, reason: &str) {
+        tracing::warn!(%reason, "tracking zastaven - nouzové vypnutí");
+        Self::emit_error(app, ErrorCode::KillSwitch, &format!("🛑 Tracking nouzově vypnut ({})", reason));
+        if let Err(e) = history::record_tick(&cfg.profile, &TickRecord::skipped("kill_switch")) {
+            tracing::warn!(error = %e, "nelze zapsat rozhodnutí do historie");
+        }
+        let _ = self.stop(app.clone()).await;
+        let _ = app.emit("kill-switch", reason);
+    }
+
+    /// Poloha aktivního okna a kurzoru pro výběr monitoru (bez snímání)
+    fn capture_hint(app: &Events) -> CaptureHint {
+        CaptureHint {
+            window: get_active_window().ok().and_then(|w| w.rect),
+            cursor: app.cursor_position(),
+        }
+    }
+
+    /// Tooltip v liště ukazuje task, na který právě běží Freelo timer
+    async fn update_tray_task(&self, app: &Events) {
+        let task_id = self.active_tracking.lock().await.as_ref().map(|t| t.task_id.clone());
+        let name = match task_id {
+            Some(key) if key == "general_work" => Some("Obecná práce".to_string()),
+            Some(key) if categories::label(&key).is_some() => categories::label(&key).map(str::to_string),
+            Some(key) => Some(
+                self.freelo_tasks_cache
+                    .lock()
+                    .await
+                    .iter()
+                    .find(|t| t.id.to_string() == key)
+                    .map(|t| t.name.clone())
+                    .unwrap_or(key),
+            ),
+            None => None,
+        };
+        app.set_tray_task(name.as_deref());
+    }
+
+    /// Vyhodnotí paměť a délku OCR, při změně stupně degradace informuje uživatele
+    async fn update_resource_level(&self, app: &Events, cfg: &TrackerConfig) -> usize {
+        let mut governor = self.resource_governor.lock().await;
+        let load = Load {
+            rss_mb: resources::current_rss_mb(),
+            ocr_seconds: governor.last_ocr_seconds,
+        };
+        let level = resources::next_level(governor.level, load, cfg.memory_limit_mb, cfg.interval_seconds);
+
+        if level != governor.level {
+            let direction = if level > governor.level { "🐢 Vysoká zátěž" } else { "🐇 Zátěž klesla" };
+            Self::emit_log(
+                app,
+                "warning",
+                &format!(
+                    "{} (paměť {} MB, OCR {:.1}s) - snímky {:.0}%, interval {}s",
+                    direction,
+                    load.rss_mb.map(|m| m.to_string()).unwrap_or_else(|| "?".to_string()),
+                    load.ocr_seconds,
+                    resources::capture_scale(level) * 100.0,
+                    resources::effective_interval(level, cfg.interval_seconds)
+                ),
+            );
+            governor.level = level;
+        }
+        level
+    }
+
+    /// Zapamatuje a pošle do UI čas dalšího ticku (tick, který se nestihl, běží hned)
+    async fn schedule_next_tick(&self, app: &Events, tick_started: DateTime<Local>, interval_seconds: u64) {
+        let next = (tick_started + chrono::Duration::seconds(interval_seconds as i64)).max(Local::now());
+        *self.next_tick_at.lock().await = Some(next);
+
+        Self::emit_log(app, "info", &format!("⏭️  Další analýza v {}", next.format("%H:%M:%S")));
+        let _ = app.emit("tick-schedule", self.get_next_tick_eta().await);
+    }
+
+    pub async fn get_next_tick_eta(&self) -> TickEta {
+        let next = *self.next_tick_at.lock().await;
+        TickEta {
+            next_at: next.map(|n| n.format("%H:%M:%S").to_string()),
+            seconds_remaining: next.map(|n| (n - Local::now()).num_seconds().max(0)),
+        }
+    }
+
+    pub async fn get_tracking_status(&self) -> TrackingStatus {
+        TrackingStatus {
+            running: *self.is_running.lock().await,
+            paused: self.user_pause.lock().await.is_some(),
+            degradation: self.degradation.lock().await.status(),
+        }
+    }
+
+    /// Výsledek součásti (AI, OCR, okno, screenshot) pro žebříček degradace
+    async fn record_health(&self, app: &Events, component: Component, result: Result<(), &str>) {
+        let change = self.degradation.lock().await.record(component, result, Local::now());
+        self.announce_degradation(app, change).await;
+    }
+
+    /// Změna stupně degradace do logu a UI (event `degradation-level`)
+    async fn announce_degradation(&self, app: &Events, change: Option<LevelChange>) {
+        let Some(change) = change else {
+            return;
+        };
+        let status = self.degradation.lock().await.status();
+        let reason = status.reason.as_deref().map(|r| format!(" ({})", r)).unwrap_or_default();
+        let level = if change.to > change.from { "warning" } else { "info" };
+        Self::emit_log(
+            app,
+            level,
+            &format!("🪜 Režim analýzy: {} → {}{}", change.from.label(), change.to.label(), reason),
+        );
+        let _ = app.emit("degradation-level", status);
+    }
+
+    /// Plán ticku podle rozpočtu - změna omezení se hlásí do logu a eventem `budget-event`
+    async fn plan_tick(&self, app: &Events, cfg: &TrackerConfig) -> BudgetPlan {
+        let daily_exhausted = match cfg.ai_daily_budget_usd {
+            Some(limit) => self.ai_spent_today(&cfg.profile).await >= limit,
+            None => false,
+        };
+        if cfg.tick_budget.is_unlimited() && !daily_exhausted && self.budget.lock().await.reasons.is_empty() {
+            return BudgetPlan::default();
+        }
+        // Lokální Ollama nic nestojí
+        let costs = match cfg.llm.as_ref().filter(|llm| llm.is_cloud()) {
+            Some(_) => {
+                let chars = budget::prompt_chars(self.task_index.lock().await.tasks());
+                budget::estimate_ai_cost(&cfg.ai_params.model, chars, false, cfg.ai_params.max_tokens)
+                    .zip(budget::estimate_ai_cost(&cfg.ai_params.model, chars, true, cfg.ai_params.max_tokens))
+            }
+            None => Some((0.0, 0.0)),
+        };
+
+        let mut state = self.budget.lock().await;
+        let mut plan = budget::plan(&cfg.tick_budget, &state.timings, costs);
+        if daily_exhausted && cfg.llm.as_ref().is_some_and(|llm| llm.is_cloud()) {
+            plan.allow_ai = false;
+            plan.allow_vision = false;
+            plan.reasons.push("denní rozpočet AI vyčerpán, jen textový matching".to_string());
+        }
+        if plan.reasons != state.reasons {
+            if plan.reasons.is_empty() {
+                Self::emit_log(app, "info", "💰 Rozpočet ticku: analýza opět v plné kvalitě");
+            } else {
+                Self::emit_log(app, "warning", &format!("💰 Rozpočet ticku: {}", plan.reasons.join(", ")));
+            }
+            state.reasons = plan.reasons.clone();
+            let _ = app.emit("budget-event", BudgetEvent { plan: plan.clone(), overrun_seconds: None });
+        }
+        plan
+    }
+
+    /// Dnešní útrata za AI v USD
+    async fn ai_spent_today(&self, profile: &str) -> f64 {
+        let today = Local::now().date_naive();
+        let mut state = self.budget.lock().await;
+        if let Some((_, spent)) = state.spent_today.filter(|(day, _)| *day == today) {
+            return spent;
+        }
+        let spent = match history::ai_usage_in_range(profile, Some(today), Some(today)) {
+            Ok(days) => days.iter().map(|d| d.cost_usd).sum(),
+            Err(e) => {
+                tracing::warn!(error = %e, "nelze načíst spotřebu AI");
+                0.0
+            }
+        };
+        state.spent_today = Some((today, spent));
+        spent
+    }
+
+    /// Zapíše volání AI od minulého ticku do historie a přičte je k dnešní útratě
+    async fn flush_ai_usage(&self, profile: &str) {
+        let calls = ai_usage::take_pending();
+        if calls.is_empty() {
+            return;
+        }
+        if let Err(e) = history::record_ai_usage(profile, &calls) {
+            tracing::warn!(error = %e, "nelze zapsat spotřebu AI do historie");
+        }
+        let mut state = self.budget.lock().await;
+        if let Some((day, ref mut spent)) = state.spent_today {
+            *spent += calls.iter().filter(|c| c.day == day).map(|c| c.cost_usd).sum::<f64>();
+        }
+    }
+
+    /// Spotřeba AI po dnech a modelech (výchozí rozsah: posledních 30 dní) a stav denního rozpočtu
+    pub async fn get_ai_usage(&self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Result<AiUsageReport, String> {
+        let profile = self.current_profile().await;
+        self.flush_ai_usage(&profile).await;
+        let today = Local::now().date_naive();
+        let days = history::ai_usage_in_range(
+            &profile,
+            Some(from.unwrap_or(today - chrono::Duration::days(ai_usage::DEFAULT_USAGE_DAYS))),
+            to,
+        )?;
+        let today_cost_usd = days.iter().filter(|d| d.day == today).map(|d| d.cost_usd).sum();
+        let daily_budget_usd = self.config.lock().await.as_ref().and_then(|c| c.ai_daily_budget_usd);
+        Ok(AiUsageReport {
+            days,
+            today_cost_usd,
+            daily_budget_usd,
+            budget_exhausted: daily_budget_usd.is_some_and(|limit| today_cost_usd >= limit),
+        })
+    }
+
+    /// Zapíše délku fáze do klouzavého průměru
+    async fn record_stage(&self, stage: Stage, started: Instant) {
+        self.budget.lock().await.timings.record(stage, started.elapsed().as_secs_f64());
+    }
+
+    /// Tick i přes plán trval déle než limit - hlásí se, další plán už počítá s novým měřením
+    fn check_tick_overrun(&self, app: &Events, cfg: &TrackerConfig, started: Instant, plan: &BudgetPlan) {
+        let Some(max_seconds) = cfg.tick_budget.max_seconds else {
+            return;
+        };
+        let elapsed = started.elapsed().as_secs_f64();
+        if elapsed > max_seconds {
+            Self::emit_log(
+                app,
+                "warning",
+                &format!("💰 Tick trval {:.1}s, limit je {:.1}s", elapsed, max_seconds),
+            );
+            let _ = app.emit("budget-event", BudgetEvent { plan: plan.clone(), overrun_seconds: Some(elapsed) });
+        }
+    }
+
+    /// Jeden tick pipeline: brány (síť, nečinnost, oběd) → capture → OCR → match → Freelo.
+    /// Vrací rozhodnutí pro audit v historii.
+    async fn run_tick(&self, app: &Events, backend: &Backend, cfg: &TrackerConfig) -> TickRecord {
+        let tick_started = Instant::now();
+
+        // Začátek/konec focus bloku (ručního nebo z kalendáře)
+        Self::check_focus_transition(app);
+
+        // Ruční pauza - žádné snímky ani OCR
+        if self.user_pause.lock().await.is_some() {
+            return TickRecord::skipped("paused");
+        }
+
+        // Po probuzení ze spánku chvíli počkat (zamykací obrazovka, starý frame)
+        if self.check_resume_warmup(app, cfg).await {
+            return TickRecord::skipped("resume_warmup");
+        }
+
+        // Obnovení spojení s Freelo po výpadku (s backoffem)
+        self.check_freelo_recovery(app, backend).await;
+
+        // Pracovní doba - mimo ni žádné snímky ani běžící timer
+        if self.check_schedule_gate(app, backend, cfg).await {
+            return TickRecord::skipped("schedule");
+        }
+
+        // Síťová pravidla (Wi-Fi/VPN)
+        if self.check_network_gate(app, backend, cfg).await {
+            return TickRecord::skipped("network_rules");
+        }
+
+        // Zapomenutý timer (např. přes noc)
+        self.check_inactivity_spike(app, backend, cfg).await;
+
+        // Segment přes půlnoc - každý den vlastní záznam
+        self.check_midnight_split(app, backend).await;
+
+        // Pauza na oběd - nic nesnímáme, záznam je uzavřený
+        if self.check_lunch_break(app, backend).await {
+            return TickRecord::skipped("lunch_break");
+        }
+
+        // Uživatel je pryč od počítače - timer stojí, nic nesnímáme
+        if self.check_idle_pause(app, backend, cfg).await {
+            return TickRecord::skipped("idle");
+        }
+
+        // Co je vypnuté v nastavení určuje nejvyšší dosažitelný stupeň analýzy
+        let change = self.degradation.lock().await.configure(
+            cfg.llm.is_some(),
+            cfg.context_providers.is_enabled(context_providers::OCR),
+            Local::now(),
+        );
+        self.announce_degradation(app, change).await;
+
+        // Kde uživatel pracuje - OCR/AI poběží jen nad tímto monitorem
+        let hint = Self::capture_hint(app);
+
+        // Rozpočet ticku (čas, cena AI) - podle naměřených fází vynechat AI, zmenšit snímek, vzít minulé rozhodnutí
+        let plan = self.plan_tick(app, cfg).await;
+
+        // Ukázkový režim - nahraná obrazovka místo snímku, okna a OCR
+        let demo_scene = backend.demo().map(|demo| demo.next_scene());
+
+        // Nefunkční snímání se zkouší jen občas, mezitím stačí titulek okna
+        let attempt_capture = demo_scene.is_none() && self.degradation.lock().await.should_attempt(Component::Capture);
+
+        // Vyloučit okno ze snímku bez skrývání, případně skrýt podle nastavené politiky
+        let exclude = if attempt_capture { window_policy::capture_exclusion(app.as_ref(), cfg.hide_policy) } else { None };
+        let hide_window = attempt_capture && window_policy::should_hide(app.as_ref(), cfg.hide_policy, cfg.monitor_selection, &hint);
+        if hide_window {
+            Self::emit_log(app, "info", "📸 Skrývám okno pro screenshot...");
+            if let Err(e) = window_policy::hide_main_window(app.as_ref()).await {
+                Self::emit_error(app, ErrorCode::WindowInfo, &format!("Chyba při skrývání okna: {}", e));
+            }
+        }
+
+        // Capture screenshot
+        let capture = if attempt_capture {
+            Self::emit_log(app, "info", "📸 Zachytávám screenshot...");
+            let scale = resources::capture_scale(self.resource_governor.lock().await.level) * plan.scale_factor;
+            let started = Instant::now();
+            let result = capture_and_encode(scale, cfg.monitor_selection, &hint, exclude);
+            self.record_stage(Stage::Capture, started).await;
+            match result {
+                Ok(capture) => {
+                    Self::clear_error(app, ErrorCode::CaptureFailed);
+                    self.record_health(app, Component::Capture, Ok(())).await;
+                    let previous = self.captured_monitor.lock().await.replace(capture.monitor.clone());
+                    if previous.is_some_and(|p| p != capture.monitor) {
+                        Self::emit_log(app, "info", &format!("🖥️  Snímám monitor {}", capture.monitor));
+                    }
+                    Some(capture)
+                }
+                Err(e) => {
+                    Self::emit_error(app, ErrorCode::CaptureFailed, &format!("Chyba při screenshotu: {}", e));
+                    self.record_health(app, Component::Capture, Err(&e)).await;
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let (screenshot, screen_hash, window_area) = match capture {
+            Some(capture) => (Some(capture.image), Some(capture.hash), capture.window_area),
+            None => (None, None, None),
+        };
+
+        // Aktivní okno ve chvíli snímku (ještě před zobrazením našeho okna)
+        let window = if let Some(scene) = demo_scene {
+            Some(scene.window())
+        } else if cfg.context_providers.is_enabled(context_providers::WINDOW)
+            && self.degradation.lock().await.should_attempt(Component::Window)
+        {
+            match get_active_window() {
+                Ok(window) => {
+                    self.record_health(app, Component::Window, Ok(())).await;
+                    Some(window)
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "aktivní okno nedostupné");
+                    self.record_health(app, Component::Window, Err(&e)).await;
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Zobrazit okno zpět (i když screenshot selhal)
+        if hide_window {
+            if let Err(e) = app.show_main_window() {
+                Self::emit_error(app, ErrorCode::WindowInfo, &format!("Chyba při zobrazení okna: {}", e));
+            }
+        }
+
+        // Bez screenshotu i okna není co analyzovat
+        if screenshot.is_none() && window.is_none() {
+            let paused = self.degradation.lock().await.level() == DegradationLevel::Paused;
+            return TickRecord::skipped(if paused { "degraded" } else { "capture_failed" });
+        }
+
+        // Důkazní screenshot (opt-in)
+        if let (Some(interval_minutes), Some(screenshot)) = (cfg.evidence_interval_minutes, screenshot.as_deref()) {
+            self.sample_evidence(app, cfg, screenshot, interval_minutes).await;
+        }
+
+        // Get tasks (index se staví jen při načtení tasků)
+        let task_index = self.task_index.lock().await.clone();
+        let tasks = task_index.tasks();
+        let (signatures, rules) = {
+            let pack = self.rule_pack.lock().await;
+            (pack.app_signatures.clone(), pack.task_rules.clone())
+        };
+
+        // Obrazovka ani okno se od minulé analýzy nezměnily - OCR/AI se přeskočí a platí minulé rozhodnutí
+        let unchanged = self
+            .unchanged_screen(app, cfg, screen_hash, window.as_ref(), plan.prefer_cache)
+            .await;
+
+        let cached = unchanged.is_some();
+        let (match_result, context_label, ocr_text) = match unchanged {
+            Some((result, label)) => (result, label, None),
+            None => {
+                // AI a OCR podle stupně degradace - OCR v ai_vision jen jako náhrada za nefunkční AI
+                // a pro polohu citlivých údajů k zakrytí, nefunkční součást se zkouší jen občas
+                let (use_ai, mode_uses_ocr, attempt_ocr) = {
+                    let mut ladder = self.degradation.lock().await;
+                    let use_ai = plan.allow_ai && ladder.should_attempt(Component::Ai);
+                    let mode_uses_ocr = cfg.analysis_mode != AnalysisMode::AiVision || !use_ai || !plan.allow_vision;
+                    let wants_ocr = mode_uses_ocr || cfg.redact_ai_input;
+                    (use_ai, mode_uses_ocr, screenshot.is_some() && wants_ocr && ladder.should_attempt(Component::Ocr))
+                };
+
+                let (ocr_text, ocr_words) = match (screenshot.as_deref(), demo_scene) {
+                    (_, Some(scene)) => (Some(scene.text.to_string()), None),
+                    (Some(screenshot), None) if attempt_ocr => {
+                        let started = Instant::now();
+                        let result = self.extract_ocr_text(app, cfg, screenshot, window_area).await;
+                        self.record_stage(Stage::Ocr, started).await;
+                        match result {
+                            Ok(output) => {
+                                TimelineEvent::OcrCompleted {
+                                    at: Local::now(),
+                                    chars: output.as_ref().map_or(0, |o| o.text.chars().count()),
+                                    duration_ms: started.elapsed().as_millis() as u64,
+                                }
+                                .emit(app);
+                                output.map(|o| (Some(o.text), Some(o.words))).unwrap_or_default()
+                            }
+                            Err(reason) => return TickRecord::skipped(reason),
+                        }
+                    }
+                    _ => (None, None),
+                };
+
+                if let Some(ref text) = ocr_text {
+                    *self.last_ocr_text.lock().await = (!cfg.privacy_mode).then(|| LastOcrText {
+                        at: Local::now(),
+                        text: text.clone(),
+                    });
+                }
+
+                if self.is_lock_screen(app, ocr_text.as_deref(), window.as_ref()).await {
+                    return TickRecord::skipped("lock_screen");
+                }
+
+                // Doplňující kontext (kalendář, git, procesy) se matchuje spolu s OCR textem
+                let extra_context = self.collect_context(app, cfg, window.as_ref()).await;
+
+                // Uživatelská pravidla rozhodují deterministicky - OCR/AI matching se pak nevolá
+                let rule_match = {
+                    let text = match (&extra_context, &ocr_text) {
+                        (Some(extra), Some(ocr)) => Some(format!("{}\n{}", extra, ocr)),
+                        (extra, ocr) => extra.clone().or_else(|| ocr.clone()),
+                    };
+                    Self::match_task_rule(app, &rules, window.as_ref(), text.as_deref(), tasks)
+                };
+
+                // Délka AI fáze (text i screenshot) pro plán dalších ticků
+                let ai_started = Instant::now();
+
+                // V režimu AI nad screenshotem slouží OCR jen k zakrytí citlivých údajů
+                let ocr_match = match ocr_text {
+                    Some(ref ocr_text) if rule_match.is_none() && mode_uses_ocr => {
+                        Self::emit_log(app, "info", &format!("✅ OCR: Extrahováno {} znaků", ocr_text.len()));
+                        let match_text = match extra_context {
+                            Some(ref extra) => format!("{}\n{}", extra, ocr_text),
+                            None => ocr_text.clone(),
+                        };
+                        let result = self
+                            .match_ocr_text(app, cfg, &match_text, window.as_ref(), &task_index, use_ai)
+                            .await;
+                        Some((result, format!("OCR: {} znaků", ocr_text.len())))
+                    }
+                    _ => None,
+                };
+
+                // AI nad screenshotem - v hybridním režimu jen když OCR selhalo nebo nenašlo jistý task
+                let needs_vision = use_ai && plan.allow_vision && rule_match.is_none() && match cfg.analysis_mode {
+                    AnalysisMode::OcrText => false,
+                    AnalysisMode::AiVision => true,
+                    AnalysisMode::Hybrid => ocr_match
+                        .as_ref()
+                        .is_none_or(|(r, _)| r.task_id.is_none() || r.confidence <= cfg.min_confidence),
+                };
+                let vision_match = match screenshot.as_deref() {
+                    Some(screenshot) if needs_vision => self
+                        .match_screenshot(app, cfg, screenshot, ocr_words.as_deref(), window.as_ref(), tasks)
+                        .await
+                        .map(|result| (result, "AI: screenshot".to_string())),
+                    _ => None,
+                };
+                if use_ai && rule_match.is_none() {
+                    self.record_stage(Stage::Ai, ai_started).await;
+                }
+
+                let (match_result, context_label) = match rule_match.or(vision_match).or(ocr_match) {
+                    Some((mut result, label)) => {
+                        // Aplikaci bereme z aktivního okna, odhad z OCR/AI jen když okno neznáme
+                        if let Some(w) = window.as_ref().filter(|w| !w.app_name.is_empty()) {
+                            result.detected_application = w.app_name.clone();
+                        }
+                        (result, label)
+                    }
+                    None => {
+                        // Degradovaný režim - bez OCR matchujeme jen podle aktivního okna
+                        match Self::match_window_only(app, window.as_ref(), &task_index, &signatures) {
+                            Some(result) => (result, "Bez OCR: jen titulek okna".to_string()),
+                            None => return TickRecord::skipped("no_screen_context"),
+                        }
+                    }
+                };
+
+                // Výsledek jen z titulku okna se nepamatuje - další tick zkusí OCR znovu
+                let analyzed = screen_hash.filter(|_| match_result.source != MatchSource::Window);
+                self.remember_screen(analyzed, window.as_ref(), &match_result, &context_label).await;
+                (match_result, context_label, ocr_text)
+            }
+        };
+        let over_budget = self.check_task_budget(app, cfg, tasks, &match_result).await;
+        TimelineEvent::match_completed(&match_result, cached).emit(app);
+        let ocr_chars = ocr_text.as_ref().map(|t| t.len());
+
+        // Log match result
+        Self::emit_log(
+            app,
+            "info",
+            &format!(
+                "📊 Aplikace: {} | Task: {} | Confidence: {:.0}%",
+                match_result.detected_application,
+                match_result.task_name.as_deref().unwrap_or("Žádný"),
+                match_result.confidence * 100.0
+            ),
+        );
+
+        if !match_result.matched_keywords.is_empty() {
+            Self::emit_log(
+                app,
+                "info",
+                &format!("🔑 Matched keywords: {}", match_result.matched_keywords.join(", ")),
+            );
+        }
+
+        self.activity_context.lock().await.record(
+            Local::now(),
+            &match_result.detected_application,
+            &match_result.activity_description,
+            match_result.task_name.as_deref(),
+        );
+
+        // Kde v OCR textu klíčová slova jsou - pro zvýraznění "proč tento task" v UI
+        let highlights = ocr_text
+            .as_deref()
+            .map(|text| keyword_highlights(text, &match_result.matched_keywords))
+            .unwrap_or_default();
+        let _ = app.emit(
+            "match-highlights",
+            serde_json::json!({
+                "task_id": match_result.task_id,
+                "source": match_result.source,
+                "highlights": highlights,
+            }),
+        );
+
+        // Update tracking info in UI
+        let project = self.project_for_task(match_result.task_id, tasks).await;
+        Self::emit_tracking_update(
+            app,
+            &match_result.detected_application,
+            &context_label,
+            match_result.task_name.as_deref(),
+            project.as_ref(),
+        );
+
+        // Handle tracking logic (zámek tasku má přednost před matcherem)
+        let action = match self.get_manual_lock(app).await {
+            Some(lock) => self.hold_manual_lock(app, &lock, &match_result).await,
+            None => {
+                let category = Self::work_category(app, cfg, window.as_ref(), &match_result);
+                // Kategorie práce rozpočet tasku nemá
+                let gates = TickGates { over_budget: over_budget && category.is_none(), ..Default::default() };
+                self.handle_tracking_logic(app, backend, cfg, &match_result, category, gates)
+                    .await
+            }
+        };
+
+        self.check_tick_overrun(app, cfg, tick_started, &plan);
+
+        let tracking = self.active_tracking.lock().await;
+        TickRecord {
+            id: None,
+            at: Local::now(),
+            action,
+            // Bez brány nečinnosti a pracovní doby (ty tick přeskočí) zastavuje jen rozpočet
+            reason: (action == TickAction::Stopped).then(|| "over_budget".to_string()),
+            application: Some(match_result.detected_application),
+            ocr_chars,
+            task_id: match_result.task_id,
+            task_name: match_result.task_name,
+            confidence: Some(match_result.confidence),
+            source: Some(match_result.source),
+            freelo_task_id: tracking.as_ref().map(|t| t.task_id.clone()),
+            freelo_uuid: tracking.as_ref().map(|t| t.uuid.clone()),
+        }
+    }
+
+    /// Minulé rozhodnutí, pokud se obrazovka (percepční hash) ani aktivní okno nezměnily
+    async fn unchanged_screen(
+        &self,
+        app: &Events,
+        cfg: &TrackerConfig,
+        hash: Option<ScreenHash>,
+        window: Option<&WindowInfo>,
+        prefer_cache: bool,
+    ) -> Option<(MatchResult, String)> {
+        let hash = hash.filter(|_| prefer_cache || cfg.screen_diff_threshold > 0)?;
+        let mut memory = self.last_screen.lock().await;
+        let last = memory.as_mut()?;
+        let distance = last.hash.distance(&hash);
+        if (distance >= cfg.screen_diff_threshold && !prefer_cache)
+            || last.window_key != Self::window_key(window)
+            || last.reused_ticks >= MAX_REUSED_TICKS
+        {
+            return None;
+        }
+
+        last.reused_ticks += 1;
+        if distance >= cfg.screen_diff_threshold {
+            Self::emit_log(app, "info", &format!("♻️  Rozpočet ticku: stejné okno, minulé rozhodnutí (rozdíl {})", distance));
+            return Some((last.result.clone(), format!("{} (rozpočet)", last.context_label)));
+        }
+        Self::emit_log(app, "info", &format!("♻️  Obrazovka beze změny (rozdíl {}), OCR přeskočeno", distance));
+        Some((last.result.clone(), format!("{} (beze změny)", last.context_label)))
+    }
+
+    /// Zapamatuje si analyzovanou obrazovku, bez snímku se paměť zahodí
+    async fn remember_screen(&self, hash: Option<ScreenHash>, window: Option<&WindowInfo>, result: &MatchResult, context_label: &str) {
+        *self.last_screen.lock().await = hash.map(|hash| ScreenMemory {
+            hash,
+            window_key: Self::window_key(window),
+            result: result.clone(),
+            context_label: context_label.to_string(),
+            reused_ticks: 0,
+        });
+    }
+
+    fn window_key(window: Option<&WindowInfo>) -> String {
+        window.map(|w| format!("{}\n{}", w.app_name, w.title)).unwrap_or_default()
+    }
+
+    /// Uloží zmenšený zašifrovaný screenshot, pokud od posledního uplynulo N minut,
+    /// a smaže vzorky starší než retention
+    async fn sample_evidence(&self, app: &Events, cfg: &TrackerConfig, screenshot: &str, interval_minutes: u64) {
+        let now = Local::now();
+        {
+            let mut last = self.last_evidence_at.lock().await;
+            if !evidence::is_due(*last, now, interval_minutes) {
+                return;
+            }
+            *last = Some(now);
+        }
+
+        let profile = cfg.profile.clone();
+        let retention_days = cfg.evidence_retention_days;
+        let screenshot = screenshot.to_string();
+        let result = tokio::task::spawn_blocking(move || {
+            let stored = evidence::store_screenshot(&profile, &screenshot, now);
+            let purged = evidence::purge_expired(&profile, retention_days, now);
+            stored.map(|_| purged)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(purged)) => {
+                Self::emit_log(app, "info", "🔒 Uložen důkazní screenshot (zašifrovaný)");
+                if purged > 0 {
+                    Self::emit_log(app, "info", &format!("🗑️  Smazáno {} expirovaných důkazních screenshotů", purged));
+                }
+            }
+            Ok(Err(e)) => Self::emit_log(app, "error", &format!("Chyba při ukládání důkazního screenshotu: {}", e)),
+            Err(e) => Self::emit_log(app, "error", &format!("Důkazní screenshot task chyba: {}", e)),
+        }
+    }
+
+    /// Dešifruje důkazní screenshoty aktivního profilu do zvoleného adresáře
+    pub async fn export_evidence(&self, destination: &Path) -> Result<(PathBuf, usize), String> {
+        let profile = self.current_profile().await;
+        let destination = destination.to_path_buf();
+
+        tokio::task::spawn_blocking(move || evidence::export(&profile, &destination))
+            .await
+            .map_err(|e| format!("Export task chyba: {}", e))?
+    }
+
+    /// Kopie debug artefaktů aktivního profilu se zakrytým obsahem obrazovky (pro sdílení)
+    pub async fn redact_debug_artifacts(&self, destination: &Path) -> Result<(PathBuf, usize), String> {
+        let profile = self.current_profile().await;
+        let destination = destination.to_path_buf();
+
+        tokio::task::spawn_blocking(move || debug_artifacts::redact(&profile, &destination))
+            .await
+            .map_err(|e| format!("Redakce task chyba: {}", e))?
+    }
+
+    /// Zaznamená, zda automaticky zvolený task segmentu byl správně (ruční segmenty se nepočítají)
+    async fn record_matcher_outcome(&self, tracking: &ActiveTracking, correct: bool) {
+        let Some(source) = tracking.source else {
+            return;
+        };
+
+        let mut stats = self.matcher_stats.lock().await;
+        stats.record(source, correct);
+        if let Err(e) = stats.save(&self.current_profile().await) {
+            tracing::warn!(error = %e, "nelze uložit statistiky matcherů");
+        }
+    }
+
+    /// Klouzavá přesnost jednotlivých matcherů
+    pub async fn get_matcher_stats(&self) -> Vec<MatcherAccuracy> {
+        self.matcher_stats.lock().await.summary()
+    }
+
+    /// OCR screenshotu v samostatném vlákně (text i pozice slov pro začernění), Err = důvod přeskočení ticku
+    async fn extract_ocr_text(
+        &self,
+        app: &Events,
+        cfg: &TrackerConfig,
+        screenshot: &str,
+        window_area: Option<ImageArea>,
+    ) -> Result<Option<OcrOutput>, &'static str> {
+        // DEBUG MODE: save_debug = true pro ukládání mezikroků
+        let tuning = OcrTuning {
+            engine: cfg.ocr_engine,
+            psm_candidates: match cfg.ocr_psm {
+                Some(psm) => vec![psm],
+                None => self.psm_selector.lock().await.psm_candidates(),
+            },
+            oem: cfg.ocr_oem,
+            tile_size: cfg.ocr_tile_size,
+            scale: cfg.ocr_scale,
+            languages: cfg.ocr_languages.clone(),
+        };
+        let Some(ocr_slot) = resources::try_ocr_slot() else {
+            Self::emit_log(app, "warning", "⏳ Předchozí OCR ještě běží, tick přeskočen");
+            return Err("ocr_busy");
+        };
+        Self::emit_log(app, "info", "📖 Spouštím OCR (debug mode)...");
+        let screenshot_clone = screenshot.to_string();
+        let debug_profile = cfg.profile.clone();
+        let tick_span = tracing::Span::current();
+        let ocr_started = Instant::now();
+        let ocr_result = tokio::task::spawn_blocking(move || {
+            let _tick = tick_span.enter();
+            extract_text_from_screenshot(&screenshot_clone, Some(&debug_profile), window_area, &tuning) // Some = debug mode
+        })
+        .await;
+        drop(ocr_slot);
+        self.resource_governor.lock().await.last_ocr_seconds = ocr_started.elapsed().as_secs_f64();
+
+        Ok(match ocr_result {
+            Ok(Ok(output)) => {
+                Self::clear_error(app, ErrorCode::OcrFailed);
+                self.record_health(app, Component::Ocr, Ok(())).await;
+                // Nativní OCR žádné skóre PSM nevrací
+                if cfg.ocr_psm.is_none() && !output.psm_scores.is_empty() {
+                    if let Some(psm) = self.psm_selector.lock().await.record(&output.psm_scores) {
+                        Self::emit_log(app, "info", &format!("🔧 OCR: Automaticky zvolen PSM {}", psm));
+                    }
+                }
+                Some(output)
+            }
+            Ok(Err(e)) => {
+                Self::emit_error(app, ErrorCode::OcrFailed, &format!("OCR chyba: {}", e));
+                self.record_health(app, Component::Ocr, Err(&e)).await;
+                None
+            }
+            Err(e) => {
+                Self::emit_error(app, ErrorCode::OcrFailed, &format!("OCR task chyba: {}", e));
+                self.record_health(app, Component::Ocr, Err(&e.to_string())).await;
+                None
+            }
+        })
+    }
+
+    /// Text ze zapnutých zdrojů kontextu (blokující čtení běží mimo async runtime)
+    async fn collect_context(&self, app: &Events, cfg: &TrackerConfig, window: Option<&WindowInfo>) -> Option<String> {
+        let registry = ProviderRegistry::builtin(cfg.focus_calendar.clone());
+        let toggles = cfg.context_providers.clone();
+        let input = TickInput {
+            window: window.cloned(),
+            now: Local::now(),
+        };
+        let fragments = tokio::task::spawn_blocking(move || registry.collect(&input, &toggles))
+            .await
+            .unwrap_or_default();
+        if fragments.is_empty() {
+            return None;
+        }
+
+        let sources: Vec<&str> = fragments.iter().map(|f| f.provider).collect();
+        Self::emit_log(app, "info", &format!("🧩 Kontext: {}", sources.join(", ")));
+        Some(context_providers::fragments_text(&fragments))
+    }
+
+    /// Matching ze screenshotu multimodálním modelem, None = nejde (bez klíče, chyba) → fallback.
+    /// Volá se jen když žebříček degradace AI pouští. Se skrýváním citlivých údajů se slova
+    /// z OCR (`ocr_words`) s e-maily, hesly apod. ve snímku rozmažou.
+    async fn match_screenshot(
+        &self,
+        app: &Events,
+        cfg: &TrackerConfig,
+        screenshot: &str,
+        ocr_words: Option<&[OcrWord]>,
+        window: Option<&WindowInfo>,
+        tasks: &[FreeloTask],
+    ) -> Option<MatchResult> {
+        let Some(ref llm) = cfg.llm else {
+            Self::emit_log(app, "warning", "⚠️  Analýza screenshotu přes AI vyžaduje OpenRouter API key nebo Ollamu");
+            return None;
+        };
+
+        let redacted;
+        let (screenshot, window, recent_activity) = if cfg.redact_ai_input {
+            // Bez OCR nevíme, co ve snímku zakrýt - neodesílá se
+            let Some(words) = ocr_words else {
+                Self::emit_log(app, "warning", "⚠️  Bez OCR nejde ve screenshotu skrýt citlivé údaje, AI analýza vynechána");
+                return None;
+            };
+            let areas = ocr::sensitive_areas(words);
+            redacted = if areas.is_empty() {
+                screenshot.to_string()
+            } else {
+                match pixelate_areas(screenshot, &areas) {
+                    Ok(image) => {
+                        Self::emit_log(app, "info", &format!("🔒 Ve screenshotu rozmazáno {} slov s citlivými údaji", areas.len()));
+                        image
+                    }
+                    Err(e) => {
+                        Self::emit_log(app, "warning", &format!("⚠️  Citlivé údaje ve screenshotu nejde skrýt ({}), AI analýza vynechána", e));
+                        return None;
+                    }
+                }
+            };
+            let window = window.map(|w| WindowInfo {
+                title: redaction::redact(&w.title).text,
+                ..w.clone()
+            });
+            let recent_activity = self.activity_context.lock().await.summary(Local::now());
+            (redacted.as_str(), window, recent_activity.map(|s| redaction::redact(&s).text))
+        } else {
+            let recent_activity = self.activity_context.lock().await.summary(Local::now());
+            (screenshot, window.cloned(), recent_activity)
+        };
+
+        Self::emit_log(app, "info", "🖼️  Analyzuji screenshot přes AI...");
+        let ai_params = self
+            .config
+            .lock()
+            .await
+            .as_ref()
+            .map(|c| c.ai_params.clone())
+            .unwrap_or_else(|| cfg.ai_params.clone());
+
+        match analyze_screenshot(screenshot, window.as_ref(), tasks, recent_activity.as_deref(), llm, &ai_params).await {
+            Ok(ai_result) => {
+                Self::clear_error(app, ErrorCode::AiAuth);
+                self.record_health(app, Component::Ai, Ok(())).await;
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("✅ AI (screenshot): confidence={}%, activity={}", ai_result.confidence, ai_result.activity_description),
+                );
+                Some(MatchResult {
+                    task_id: ai_result.task_id,
+                    task_name: ai_result
+                        .task_id
+                        .and_then(|id| tasks.iter().find(|t| t.id == id).map(|t| t.name.clone())),
+                    confidence: ai_result.confidence / 100.0,
+                    detected_application: "AI Detection".to_string(),
+                    matched_keywords: vec![],
+                    activity_description: ai_result.activity_description,
+                    source: MatchSource::Vision,
+                })
+            }
+            Err(e) => {
+                let message = format!("⚠️  AI analýza screenshotu selhala: {}. Používám fallback.", e);
+                if errors::is_auth_error(&e) {
+                    Self::emit_error(app, ErrorCode::AiAuth, &message);
+                } else {
+                    Self::emit_log(app, "warning", &message);
+                }
+                self.record_health(app, Component::Ai, Err(&e)).await;
+                None
+            }
+        }
+    }
+
+    /// Sémantická shoda přes embeddingy, None = nejistá nebo chyba (pokračuje se na LLM)
+    async fn match_with_embeddings(
+        &self,
+        app: &Events,
+        text_match: &MatchResult,
+        text: &str,
+        tasks: &[FreeloTask],
+        api_key: &str,
+    ) -> Option<MatchResult> {
+        Self::emit_log(app, "info", "🧭 Zkouším sémantický matching (embeddingy)...");
+        let found = self.embedding_matcher.lock().await.find_match(text, tasks, api_key).await;
+        match found {
+            Ok(Some(found)) if found.confidence >= TIER_CONFIDENCE => {
+                let task_name = tasks.iter().find(|t| t.id == found.task_id).map(|t| t.name.clone());
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "✅ Embedding Match: {} (podobnost {:.2})",
+                        task_name.as_deref().unwrap_or("?"),
+                        found.similarity
+                    ),
+                );
+                Some(MatchResult {
+                    task_id: Some(found.task_id),
+                    task_name,
+                    confidence: found.confidence,
+                    detected_application: text_match.detected_application.clone(),
+                    matched_keywords: vec![],
+                    activity_description: text_match.activity_description.clone(),
+                    source: MatchSource::Embedding,
+                })
+            }
+            Ok(_) => None,
+            Err(e) => {
+                Self::emit_log(app, "warning", &format!("⚠️  Embedding matching selhal: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Matching OCR textu - AI pokud máme OpenRouter API key, jinak (nebo při chybě) textové porovnání.
+    /// S `priority_stats` se při neshodě AI a textového matcheru použije přesnější z nich.
+    async fn match_ocr_text(
+        &self,
+        app: &Events,
+        cfg: &TrackerConfig,
+        ocr_text: &str,
+        window: Option<&WindowInfo>,
+        task_index: &TaskIndex,
+        use_ai: bool,
+    ) -> MatchResult {
+        let tasks = task_index.tasks();
+        let signatures = self.rule_pack.lock().await.app_signatures.clone();
+        let signatures = signatures.as_slice();
+        let Some(llm) = cfg.llm.as_ref().filter(|_| use_ai) else {
+            // Bez AI (nebo s nefunkční AI) - použij klasický text matching
+            Self::emit_log(app, "info", "🔍 Hledám matching task (textové porovnání)...");
+            return find_best_matching_task(ocr_text, task_index, signatures);
+        };
+
+        Self::emit_log(app, "info", "🤖 Zkouším AI matching...");
+
+        // AI parametry platí hned po uložení nastavení, bez restartu trackingu
+        let ai_params = self
+            .config
+            .lock()
+            .await
+            .as_ref()
+            .map(|c| c.ai_params.clone())
+            .unwrap_or_else(|| cfg.ai_params.clone());
+        let priority_stats = if cfg.matcher_auto_priority {
+            Some(self.matcher_stats.lock().await.clone())
+        } else {
+            None
+        };
+
+        // E-maily, hesla, čísla účtů a klíče nesmí odejít do OpenRouter
+        let recent_activity = self.activity_context.lock().await.summary(Local::now());
+        let (ai_text, ai_window, recent_activity) = if cfg.redact_ai_input {
+            let (text, window) = Self::redact_for_ai(app, ocr_text, window);
+            (text, window, recent_activity.map(|s| redaction::redact(&s).text))
+        } else {
+            (ocr_text.to_string(), window.cloned(), recent_activity)
+        };
+
+        // Levnější stupně před LLM - jistý textový match, pak embeddingy
+        if cfg.embedding_matching {
+            let text_match = find_best_matching_task(ocr_text, task_index, signatures);
+            if text_match.task_id.is_some() && text_match.confidence >= TIER_CONFIDENCE {
+                Self::emit_log(app, "info", "✅ Jistý textový match, AI se nevolá");
+                return text_match;
+            }
+            // Embeddingy zatím jen přes OpenRouter - s lokální Ollamou se jde rovnou na LLM
+            if let Some(api_key) = llm.openrouter_key() {
+                if let Some(result) = self.match_with_embeddings(app, &text_match, &ai_text, tasks, api_key).await {
+                    return result;
+                }
+            }
+        }
+
+        // Ukázky z ručních oprav - přednostně ze stejné aplikace
+        let examples = corrections::few_shot_examples(
+            &corrections::load(&cfg.profile),
+            tasks,
+            window.map(|w| w.app_name.as_str()),
+        );
+        let recent_activity = recent_activity.as_deref();
+        match match_task_with_ai(&ai_text, ai_window.as_ref(), tasks, recent_activity, examples.as_deref(), llm, &ai_params).await {
+            Ok(ai_result) => {
+                Self::clear_error(app, ErrorCode::AiAuth);
+                self.record_health(app, Component::Ai, Ok(())).await;
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("✅ AI Match: confidence={}%, activity={}", ai_result.confidence, ai_result.activity_description)
+                );
+
+                // Převeď AI výsledek na MatchResult
+                let task_name = ai_result.task_id.and_then(|id| {
+                    tasks.iter().find(|t| t.id == id).map(|t| t.name.clone())
+                });
+
+                let ai_match = MatchResult {
+                    task_id: ai_result.task_id,
+                    task_name,
+                    confidence: ai_result.confidence / 100.0, // AI vrací 0-100, MatchResult očekává 0-1
+                    detected_application: "AI Detection".to_string(),
+                    matched_keywords: vec![],
+                    activity_description: ai_result.activity_description,
+                    source: MatchSource::Ai,
+                };
+
+                let Some(stats) = priority_stats else {
+                    return ai_match;
+                };
+
+                // Neshoda AI a textového matcheru - rozhodne naměřená přesnost
+                let text_match = find_best_matching_task(ocr_text, task_index, signatures);
+                let confident = |r: &MatchResult| r.task_id.is_some() && r.confidence > cfg.min_confidence;
+                if confident(&ai_match)
+                    && confident(&text_match)
+                    && ai_match.task_id != text_match.task_id
+                    && stats.preferred(MatchSource::Ai, MatchSource::Text) == MatchSource::Text
+                {
+                    Self::emit_log(
+                        app,
+                        "info",
+                        &format!(
+                            "⚖️  AI a text se neshodují, textový matcher je přesnější - používám task {}",
+                            text_match.task_name.as_deref().unwrap_or("?")
+                        ),
+                    );
+                    return MatchResult {
+                        activity_description: ai_match.activity_description,
+                        ..text_match
+                    };
+                }
+
+                ai_match
+            }
+            Err(e) => {
+                let message = format!("⚠️  AI matching selhal: {}. Používám fallback.", e);
+                if errors::is_auth_error(&e) {
+                    Self::emit_error(app, ErrorCode::AiAuth, &message);
+                } else {
+                    Self::emit_log(app, "warning", &message);
+                }
+                self.record_health(app, Component::Ai, Err(&e)).await;
+                Self::emit_log(app, "info", "🔍 Fallback: Textové porovnání...");
+                find_best_matching_task(ocr_text, task_index, signatures)
+            }
+        }
+    }
+
+    /// Kopie OCR textu a okna bez citlivých údajů (do logu jen počty, ne hodnoty)
+    fn redact_for_ai(app: &Events, ocr_text: &str, window: Option<&WindowInfo>) -> (String, Option<WindowInfo>) {
+        let text = redaction::redact(ocr_text);
+        let title = window.map(|w| redaction::redact(&w.title));
+        let total = text.total() + title.as_ref().map(|t| t.total()).unwrap_or(0);
+        if total > 0 {
+            let summary = [Some(text.summary()), title.as_ref().map(|t| t.summary())]
+                .into_iter()
+                .flatten()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Self::emit_log(app, "info", &format!("🔒 Před odesláním do AI skryto: {}", summary));
+        }
+
+        let window = window.zip(title).map(|(w, title)| WindowInfo {
+            title: title.text,
+            ..w.clone()
+        });
+        (text.text, window)
+    }
+
+    /// Degradovaný matching bez OCR - jen podle názvu aplikace a titulku aktivního okna
+    fn match_window_only(
+        app: &Events,
+        window: Option<&WindowInfo>,
+        task_index: &TaskIndex,
+        signatures: &[AppSignature],
+    ) -> Option<MatchResult> {
+        Self::emit_log(app, "warning", "⚠️  OCR nedostupné, matching jen podle aktivního okna...");
+
+        let Some(window) = window else {
+            Self::emit_error(app, ErrorCode::WindowInfo, "Nelze zjistit aktivní okno");
+            return None;
+        };
+
+        let mut result = find_best_matching_task(&window.as_context_text(), task_index, signatures);
+
+        // Titulek okna je méně spolehlivý než celá obrazovka - sniž confidence
+        result.confidence *= WINDOW_ONLY_CONFIDENCE_FACTOR;
+        if !window.app_name.is_empty() {
+            result.detected_application = window.app_name.clone();
+        }
+        result.activity_description = format!("{} - {}", result.detected_application, window.title);
+        result.source = MatchSource::Window;
+
+        Some(result)
+    }
+
+    /// Task podle prvního platného uživatelského pravidla (aplikace, titulek, slova)
+    fn match_task_rule(
+        app: &Events,
+        rules: &[TaskRule],
+        window: Option<&WindowInfo>,
+        text: Option<&str>,
+        tasks: &[FreeloTask],
+    ) -> Option<(MatchResult, String)> {
+        let input = RuleInput {
+            application: window.map_or("", |w| w.app_name.as_str()),
+            title: window.map_or("", |w| w.title.as_str()),
+            text,
+        };
+        let (rule, task) = task_rules::find_rule(rules, &input, tasks)?;
+        Self::emit_log(app, "info", &format!("📐 Pravidlo '{}' → {}", rule.name, task.name));
+
+        let result = MatchResult {
+            task_id: Some(task.id),
+            task_name: Some(task.name.clone()),
+            confidence: 1.0,
+            detected_application: input.application.to_string(),
+            matched_keywords: rule.keywords.clone(),
+            activity_description: window.map_or_else(|| rule.name.clone(), |w| format!("{} - {}", w.app_name, w.title)),
+            source: MatchSource::Rule,
+        };
+        Some((result, format!("Pravidlo: {}", rule.name)))
+    }
+
+    /// Kategorie práce podle aplikace, titulku okna a popisu aktivity
+    fn work_category<'a>(
+        app: &Events,
+        cfg: &'a TrackerConfig,
+        window: Option<&WindowInfo>,
+        match_result: &MatchResult,
+    ) -> Option<&'a WorkCategory> {
+        let text = format!(
+            "{} {} {}",
+            window.map(|w| w.as_context_text()).unwrap_or_default(),
+            match_result.detected_application,
+            match_result.activity_description
+        );
+        let category = categories::find(&cfg.work_categories, &text)?;
+        Self::emit_log(app, "info", &format!("🏷️  Kategorie práce: {}", category.name));
+        Some(category)
+    }
+
+    /// Odpracované minuty tasku - historie plus právě běžící segment
+    async fn tracked_task_minutes(&self, profile: &str, task_id: i32) -> i64 {
+        let recorded = {
+            let mut watch = self.task_budget.lock().await;
+            let tracked = watch
+                .tracked
+                .get_or_insert_with(|| task_budget::tracked_minutes(&history::load(profile)));
+            tracked.get(&task_id).copied().unwrap_or(0)
+        };
+        let running = self
+            .active_tracking
+            .lock()
+            .await
+            .as_ref()
+            .filter(|t| t.task_id == task_id.to_string())
+            .map(|t| t.start_time.elapsed().unwrap_or_default().as_secs() as i64 / 60)
+            .unwrap_or(0);
+        recorded + running
+    }
+
+    /// Hlídá rozpočet hodin matchnutého tasku - upozorní při blížícím se a vyčerpaném
+    /// rozpočtu. Vrací true, pokud se má task se `stop_over_budget` přestat sledovat.
+    async fn check_task_budget(
+        &self,
+        app: &Events,
+        cfg: &TrackerConfig,
+        tasks: &[FreeloTask],
+        match_result: &MatchResult,
+    ) -> bool {
+        let Some(task_id) = match_result.task_id else {
+            return false;
+        };
+        let Some(budget_minutes) = task_budget::budget_minutes(task_id, &cfg.task_budgets, tasks, cfg.budget_from_estimates)
+        else {
+            return false;
+        };
+        let tracked_minutes = self.tracked_task_minutes(&cfg.profile, task_id).await;
+        let level = task_budget::level(tracked_minutes, budget_minutes);
+        let stop = level == BudgetLevel::Exceeded && cfg.stop_over_budget;
+
+        if self.task_budget.lock().await.should_alert(task_id, level) {
+            let task_name = match_result.task_name.clone().unwrap_or_else(|| format!("Task #{}", task_id));
+            let hours = |minutes: i64| minutes as f64 / 60.0;
+            let message = match level {
+                BudgetLevel::Exceeded => format!(
+                    "🛑 {}: rozpočet vyčerpán ({:.1} / {:.1} h){}",
+                    task_name,
+                    hours(tracked_minutes),
+                    hours(budget_minutes),
+                    if stop { ", tracking tasku se zastaví" } else { "" }
+                ),
+                _ => format!(
+                    "⏳ {}: blíží se rozpočet ({:.1} / {:.1} h)",
+                    task_name,
+                    hours(tracked_minutes),
+                    hours(budget_minutes)
+                ),
+            };
+            Self::emit_log(app, "warning", &message);
+            Self::notify_desktop(app, DesktopEvent::TaskBudget, "Rozpočet tasku", &message);
+            let _ = app.emit(
+                "task-budget",
+                BudgetStatus {
+                    task_id,
+                    task_name,
+                    budget_minutes,
+                    tracked_minutes,
+                    level,
+                },
+            );
+        }
+
+        stop
+    }
+
+    /// Stav tasků s rozpočtem (podle nastavení, bez běžícího trackeru)
+    pub async fn get_task_budgets(&self, budgets: &[TaskBudget], use_estimates: bool) -> Vec<BudgetStatus> {
+        let profile = self.current_profile().await;
+        let tasks = self.freelo_tasks_cache.lock().await.clone();
+        let mut tracked = task_budget::tracked_minutes(&history::load(&profile));
+        if let Some(tracking) = self.active_tracking.lock().await.as_ref() {
+            if let Ok(task_id) = tracking.task_id.parse::<i32>() {
+                *tracked.entry(task_id).or_default() += tracking.start_time.elapsed().unwrap_or_default().as_secs() as i64 / 60;
+            }
+        }
+        task_budget::statuses(budgets, &tasks, use_estimates, &tracked)
+    }
+
+    /// Provede rozhodnutí stavového automatu - logy, úpravy segmentu a zápisy do Freelo
+    async fn handle_tracking_logic(
+        &self,
+        app: &Events,
+        backend: &Backend,
+        cfg: &TrackerConfig,
+        match_result: &MatchResult,
+        category: Option<&WorkCategory>,
+        gates: TickGates,
+    ) -> TickAction {
+        // Ochranná doba po ručním zásahu
+        let in_grace_period = self
+            .last_manual_action
+            .lock()
+            .await
+            .is_some_and(|at| at.elapsed() < Duration::from_secs(cfg.manual_grace_seconds));
+
+        let machine = TrackingStateMachine {
+            min_confidence: cfg.min_confidence,
+            unstable_ticks_needed: cfg.unstable_ticks_needed,
+            restart_cooldown: Duration::from_secs(cfg.restart_cooldown_seconds),
+        };
+        let needed = cfg.unstable_ticks_needed.max(1);
+        let observation = machine.observe(match_result, category.map(categories::tracking_key));
+        let tracking_key = observation.key.clone();
+
+        let mut tracking_guard = self.active_tracking.lock().await;
+        let segment = tracking_guard.as_ref().map(|tracking| SegmentState {
+            key: &tracking.task_id,
+            application: &tracking.last_application,
+            activity: &tracking.last_activity_description,
+            unstable_count: tracking.unstable_count,
+            age: tracking.start_time.elapsed().unwrap_or_default(),
+        });
+        let action = machine.decide(segment.as_ref(), &observation, gates, in_grace_period);
+
+        if let Some(segment) = segment.as_ref().filter(|_| !in_grace_period) {
+            let change = TrackingStateMachine::change(segment, &observation);
+            let unstable_count = match action {
+                TrackingAction::Continue { unstable_count, .. } | TrackingAction::Wait { unstable_count, .. } => unstable_count,
+                _ => segment.unstable_count + 1,
+            };
+            if change.application && change.activity {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "🔍 Aplikace i aktivita se změnily: {} → {} | {} → {} (nestabilní tick: {}/{})",
+                        segment.application, observation.application, segment.activity, observation.activity, unstable_count, needed
+                    ),
+                );
+            } else if change.application {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "🔍 Aplikace se změnila: {} → {} (nestabilní tick: {}/{})",
+                        segment.application, observation.application, unstable_count, needed
+                    ),
+                );
+            } else if change.activity {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "🔍 Aktivita se změnila: {} → {} (nestabilní tick: {}/{})",
+                        segment.activity, observation.activity, unstable_count, needed
+                    ),
+                );
+            } else if change.task {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "🔍 Návrh jiného tasku: {} → {} (nestabilní tick: {}/{})",
+                        segment.key, tracking_key, unstable_count, needed
+                    ),
+                );
+            } else {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!("✅ Aplikace i aktivita stejné: {} (reset počítadla)", observation.application),
+                );
+            }
+
+            // Čerstvý segment se nerestartuje, ani když je kontext nestabilní
+            if machine.cooling_down(segment, unstable_count) {
+                Self::emit_log(
+                    app,
+                    "info",
+                    &format!(
+                        "⏳ Restart odložen - segment běží teprve {}s (min. {}s)",
+                        segment.age.as_secs(),
+                        cfg.restart_cooldown_seconds
+                    ),
+                );
+            }
+        }
+
+        match action {
+            TrackingAction::Stop { reason } => {
+                let message = match reason {
+                    StopReason::OverBudget => format!("🛑 TRACKING: Task {} má vyčerpaný rozpočet, čas se mu nepřiřazuje", tracking_key),
+                    StopReason::Idle => "⏸️  TRACKING: Nečinnost, tracking zastaven".to_string(),
+                    StopReason::OutsideSchedule => "🌙 TRACKING: Mimo pracovní dobu, tracking zastaven".to_string(),
+                    StopReason::Network => "📶 TRACKING: Síť podle pravidel, tracking zastaven".to_string(),
+                    StopReason::LunchBreak => "☕ TRACKING: Pauza na oběd, tracking zastaven".to_string(),
+                    StopReason::InactivitySpike => "⏹️  TRACKING: Segment bez aktivity, tracking zastaven".to_string(),
+                };
+                Self::emit_log(app, "warning", &message);
+                if let Some(tracking) = tracking_guard.take() {
+                    self.record_matcher_outcome(&tracking, true).await;
+                    if let Err(e) = self.finish_segment(app, backend, &tracking, Local::now()).await {
+                        Self::emit_error(app, errors::classify_freelo(&e), &format!("CHYBA STOP TRACKING: {}", e));
+                        return TickAction::Failed;
+                    }
+                }
+                TickAction::Stopped
+            }
+            TrackingAction::Hold { overridden } => {
+                if !overridden {
+                    return TickAction::Continued;
+                }
+                if let Some(ref tracking) = *tracking_guard {
+                    Self::emit_log(
+                        app,
+                        "info",
+                        &format!("🛡️  Ruční volba má přednost, ignoruji návrh {} (task {} pokračuje)", tracking_key, tracking.task_id),
+                    );
+                }
+                TickAction::HeldByManual
+            }
+            TrackingAction::Continue { change, unstable_count } => {
+                // Segment běží dál, změněný kontext se převezme
+                if let Some(ref mut tracking) = *tracking_guard {
+                    tracking.unstable_count = unstable_count;
+                    if change.any() {
+                        tracking.last_application = observation.application.clone();
+                        tracking.last_activity_description = observation.activity.clone();
+                        tracking.record_activity(&observation.activity);
+                        Self::emit_log(
+                            app,
+                            "warning",
+                            &format!(
+                                "⚠️  Kontext se mění, ale čekáme na stabilizaci ({}/{})",
+                                tracking.unstable_count, needed
+                            ),
+                        );
+                        if note_templates::is_richer(&tracking.note_activity, &observation.activity) {
+                            let note = self.segment_note(&tracking_key, category, &observation.activity).await;
+                            self.refresh_note(app, backend, tracking, note, &observation.activity).await;
+                        }
+                    }
+
+                    if let Some(category) = category {
+                        Self::emit_log(app, "success", &format!("✅ TRACKING: Kategorie {} pokračuje", category.name));
+                    } else if observation.matched_task {
+                        Self::emit_log(app, "success", &format!("✅ TRACKING: Task {} pokračuje", tracking_key));
+                    } else {
+                        Self::emit_log(app, "success", "✅ TRACKING: Obecná práce pokračuje");
+                    }
+                }
+                TickAction::Continued
+            }
+            TrackingAction::Wait { unstable_count, .. } => {
+                // Jiný task, ale kontext ještě není stabilní
+                if let Some(ref mut tracking) = *tracking_guard {
+                    tracking.unstable_count = unstable_count;
+                }
+                TickAction::Waiting
+            }
+            TrackingAction::Restart { change } => {
+                let Some(tracking) = tracking_guard.take() else {
+                    return TickAction::Failed;
+                };
+                // Segment doběhl bez ruční opravy - matcher měl pravdu
+                self.record_matcher_outcome(&tracking, true).await;
+                Self::emit_log(app, "info", "🔄 TRACKING: Kontext se změnil, restartuji tracking");
+                if change.application {
+                    Self::emit_log(app, "info", &format!("   Stará aplikace: {}", tracking.last_application));
+                    Self::emit_log(app, "info", &format!("   Nová aplikace: {}", observation.application));
+                }
+                if change.activity {
+                    Self::emit_log(app, "info", &format!("   Stará aktivita: {}", tracking.last_activity_description));
+                    Self::emit_log(app, "info", &format!("   Nová aktivita: {}", observation.activity));
+                }
+
+                if let Err(e) = self.finish_segment(app, backend, &tracking, Local::now()).await {
+                    Self::emit_error(app, errors::classify_freelo(&e), &format!("CHYBA STOP TRACKING: {}", e));
+                }
+
+                match self.start_observed_segment(app, backend, &observation, match_result, category).await {
+                    Ok(segment) => {
+                        let uuid = segment.uuid.clone();
+                        *tracking_guard = Some(segment);
+                        Self::emit_log(app, "success", &format!("▶️  TRACKING: Start s novým kontextem (UUID: {})", uuid));
+                        TickAction::Switched
+                    }
+                    Err(e) => {
+                        Self::emit_error(app, errors::classify_freelo(&e), &format!("CHYBA START TRACKING: {}", e));
+                        TickAction::Failed
+                    }
+                }
+            }
+            TrackingAction::Start => match self.start_observed_segment(app, backend, &observation, match_result, category).await {
+                Ok(segment) => {
+                    let uuid = segment.uuid.clone();
+                    *tracking_guard = Some(segment);
+
+                    if let Some(category) = category {
+                        Self::emit_log(app, "success", &format!("▶️  TRACKING: Start kategorie {} (UUID: {})", category.name, uuid));
+                    } else if observation.matched_task {
+                        Self::emit_log(app, "success", &format!("▶️  TRACKING: Start s taskem {} (UUID: {})", tracking_key, uuid));
+                    } else {
+                        Self::emit_log(app, "success", &format!("▶️  TRACKING: Start obecné práce (UUID: {})", uuid));
+                    }
+                    TickAction::Started
+                }
+                Err(e) => {
+                    Self::emit_error(app, errors::classify_freelo(&e), &format!("CHYBA START TRACKING: {}", e));
+                    TickAction::Failed
+                }
+            },
+        }
+    }
+
+    /// Nový segment pro pozorovaný kontext (start i restart)
+    async fn start_observed_segment(
+        &self,
+        app: &Events,
+        backend: &Backend,
+        observation: &Observation,
+        match_result: &MatchResult,
+        category: Option<&WorkCategory>,
+    ) -> Result<ActiveTracking, String> {
+        let note = self.segment_note(&observation.key, category, &observation.activity).await;
+        let mut segment = self
+            .start_segment(app, backend, observation.key.clone(), observation.application.clone(), observation.activity.clone(), &note)
+            .await?;
+        // Kategorie neurčil matcher - nepočítá se do jeho statistik ani kontroly
+        if category.is_none() {
+            segment.source = Some(match_result.source);
+            segment.confidence = Some(match_result.confidence);
+            if observation.matched_task {
+                let task = match_result.task_name.as_deref().unwrap_or(&observation.key);
+                Self::notify_desktop(app, DesktopEvent::TaskStarted, "Tracking spuštěn", task);
+            } else if let Some(ref task) = match_result.task_name {
+                Self::notify_desktop(
+                    app,
+                    DesktopEvent::GeneralWork,
+                    "Obecná práce",
+                    &format!("Návrh {} ({:.0} %) je pod prahem jistoty", task, match_result.confidence * 100.0),
+                );
+            }
+        }
+        Ok(segment)
+    }
+
+    fn emit_log(app: &Events, level: &str, message: &str) {
+        tracing::info!(ui_level = level, "{}", message);
+        // Během focus bloku se nekritická oznámení jen odloží ke kontrole
+        if !notifications::should_deliver(level, message) {
+            return;
+        }
+        let _ = app.emit("log-event", serde_json::json!({
+            "level": level,
+            "message": message,
+        }));
+    }
+
+    /// Chyba do logu i na kanál `error-event` (kód, závažnost, náprava) pro banner v UI
+    fn emit_error(app: &Events, code: ErrorCode, message: &str) {
+        let level = match code.severity() {
+            Severity::Warning => "warning",
+            Severity::Error | Severity::Critical => "error",
+        };
+        Self::emit_log(app, level, message);
+        let event = ErrorEvent::new(code, message);
+        // Systémové oznámení jen při prvním výskytu, opakovaná chyba se neohlašuje
+        if errors::raise(&event) && matches!(code, ErrorCode::FreeloAuth | ErrorCode::FreeloRequest) {
+            Self::notify_desktop(app, DesktopEvent::FreeloError, "Chyba Freelo", message);
+        }
+        let _ = app.emit("error-event", &event);
+    }
+
+    /// Systémové oznámení, pokud je událost zapnutá a neběží focus blok
+    fn notify_desktop(app: &Events, event: DesktopEvent, title: &str, body: &str) {
+        if !notifications::desktop_allowed(event) {
+            return;
+        }
+        if let Err(e) = app.notify(title, body) {
+            tracing::warn!(error = %e, "systémové oznámení se nepodařilo zobrazit");
+        }
+    }
+
+    /// Problém pominul - UI banner skryje
+    fn clear_error(app: &Events, code: ErrorCode) {
+        if errors::resolve(code.as_str()) {
+            let _ = app.emit("error-cleared", code.as_str());
+        }
+    }
+
+    fn emit_break_event(app: &Events, start: DateTime<Local>, end: Option<DateTime<Local>>) {
+        let _ = app.emit("break-event", serde_json::json!({
+            "kind": "lunch",
+            "start": start.format("%H:%M:%S").to_string(),
+            "end": end.map(|e| e.format("%H:%M:%S").to_string()),
+        }));
+    }
+
+    fn emit_tracking_update(
+        app: &Events,
+        application: &str,
+        activity: &str,
+        task: Option<&str>,
+        project: Option<&ProjectMeta>,
+    ) {
+        let _ = app.emit("tracking-update", serde_json::json!({
+            "application": application,
+            "activity": activity,
+            "task": task.unwrap_or("Žádný"),
+            "project": project,
+            "since": chrono::Local::now().format("%H:%M:%S").to_string(),
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::NoopSink;
+    use crate::profiles::TestProfile;
+    use crate::settings::Settings;
+
+    fn config(profile: &str) -> TrackerConfig {
+        let settings: Settings = serde_json::from_value(serde_json::json!({
+            "interval": 10,
+            "freelo_email": "a@b.cz",
+            "freelo_key": "fk",
+            "profile": profile,
+        }))
+        .unwrap();
+        TrackerConfig {
+            backend: BackendConfig::Demo,
+            ..settings.tracker_config().unwrap()
+        }
+    }
+
+    fn task(id: i32) -> FreeloTask {
+        FreeloTask {
+            id,
+            name: format!("Task {}", id),
+            project_id: 1,
+            project_name: "Projekt".to_string(),
+            estimate_minutes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_profile_switch_keeps_matcher_stats() {
+        let (profile_a, profile_b) = (TestProfile::new("stats-a"), TestProfile::new("stats-b"));
+        let (a, b) = (profile_a.name.as_str(), profile_b.name.as_str());
+        let app: Events = Arc::new(NoopSink);
+        let tracker = Tracker::new();
+
+        let mut stats_a = MatcherStats::default();
+        stats_a.record(MatchSource::Text, true);
+        stats_a.record(MatchSource::Text, true);
+        stats_a.save(a).unwrap();
+        tracker.set_config(config(a)).await;
+        tracker.reset_profile_state().await;
+
+        // Oprava v profilu B se nesmí propsat do statistik A (ani A do B)
+        tracker.switch_profile(&app, config(b), Some(false)).await.unwrap();
+        history::record_tick(b, &TickRecord {
+            task_id: Some(1),
+            source: Some(MatchSource::Text),
+            ..TickRecord::skipped("test")
+        })
+        .unwrap();
+        let tick_id = history::ticks_in_range(b, None, None).unwrap()[0].id.unwrap();
+        *tracker.task_index.lock().await = Arc::new(TaskIndex::build(&[task(1), task(2)]));
+        tracker.submit_correction(&app, tick_id, 2).await.unwrap();
+
+        let text = |stats: &[MatcherAccuracy]| {
+            let text = stats.iter().find(|s| s.source == MatchSource::Text).unwrap();
+            (text.samples, text.correct)
+        };
+        assert_eq!(text(&MatcherStats::load(b).summary()), (1, 0));
+
+        tracker.switch_profile(&app, config(a), Some(false)).await.unwrap();
+        assert_eq!(text(&tracker.get_matcher_stats().await), (2, 2));
+        assert_eq!(text(&MatcherStats::load(a).summary()), (2, 2));
+
+        tracker.switch_profile(&app, config(b), Some(false)).await.unwrap();
+        assert_eq!(text(&tracker.get_matcher_stats().await), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_records_history_without_backend() {
+        let test_profile = TestProfile::new("dry-run");
+        let profile = test_profile.name.as_str();
+        let app: Events = Arc::new(NoopSink);
+        let tracker = Tracker::new();
+        let cfg = TrackerConfig {
+            backend: BackendConfig::Freelo,
+            ..config(profile)
+        };
+        tracker.set_config(cfg.clone()).await;
+        tracker.set_write_routing(&app, WriteRouting::DryRun).await.unwrap();
+        let backend = tracker.backend(&cfg);
+
+        // Volání Freelo by vrátilo jeho UUID nebo (bez sítě) offline segment
+        let segment = tracker
+            .start_segment(&app, &backend, "42".to_string(), "VS Code".to_string(), "Píše kód".to_string(), "Login")
+            .await
+            .unwrap();
+        assert!(segment.local_only && !segment.offline);
+        assert!(segment.uuid.starts_with("dry-run-"));
+
+        tracker.finish_segment(&app, &backend, &segment, Local::now()).await.unwrap();
+        let history = history::load(profile);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].task_id, Some(42));
+        assert!(load_outbox(profile).is_empty());
+    }
+}
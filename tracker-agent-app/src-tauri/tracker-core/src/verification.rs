@@ -0,0 +1,139 @@
+use crate::freelo::WorkReport;
+use crate::profiles::profile_dir;
+use crate::profile_crypto;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Soubor s frontou oprav v adresáři profilu
+const CORRECTIONS_FILE: &str = "pending_corrections.json";
+
+/// Freelo ukládá celé minuty - menší rozdíl v délce nepovažujeme za chybu
+const MINUTES_TOLERANCE: i64 = 2;
+
+/// Co agent zamýšlel zapsat, když zastavoval timer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedEntry {
+    pub uuid: String,
+    pub task_id: Option<i32>,
+    pub note: String,
+    pub started_at: DateTime<Local>,
+    pub stopped_at: DateTime<Local>,
+}
+
+impl ExpectedEntry {
+    pub fn minutes(&self) -> i64 {
+        (self.stopped_at - self.started_at).num_seconds().max(0) / 60
+    }
+}
+
+/// Nesoulad mezi zamýšleným a skutečně uloženým záznamem, čeká na opravu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingCorrection {
+    pub expected: ExpectedEntry,
+    pub found: Option<WorkReport>,
+    pub problems: Vec<String>,
+    pub created_at: DateTime<Local>,
+}
+
+/// Najde záznam, který nejlépe odpovídá zamýšlenému (stejný task, poznámka, nejbližší délka)
+fn best_candidate<'a>(expected: &ExpectedEntry, reports: &'a [WorkReport]) -> Option<&'a WorkReport> {
+    let score = |r: &WorkReport| {
+        (
+            r.task_id != expected.task_id,
+            r.note.trim() != expected.note.trim(),
+            (r.minutes - expected.minutes()).abs(),
+        )
+    };
+
+    reports.iter().min_by_key(|r| score(r))
+}
+
+/// Porovná zamýšlený záznam se záznamy z Freelo.
+/// Vrací nejbližší nalezený záznam a seznam nesouladů (prázdný = vše sedí).
+pub fn verify(expected: &ExpectedEntry, reports: &[WorkReport]) -> (Option<WorkReport>, Vec<String>) {
+    let Some(report) = best_candidate(expected, reports) else {
+        return (None, vec!["Záznam ve Freelo nebyl nalezen".to_string()]);
+    };
+
+    let mut problems = Vec::new();
+
+    if report.task_id != expected.task_id {
+        problems.push(format!(
+            "Jiný task: očekáváno {:?}, Freelo má {:?}",
+            expected.task_id, report.task_id
+        ));
+    }
+
+    let diff = report.minutes - expected.minutes();
+    if diff.abs() > MINUTES_TOLERANCE {
+        problems.push(format!(
+            "Jiná délka: očekáváno {} min, Freelo má {} min",
+            expected.minutes(),
+            report.minutes
+        ));
+    }
+
+    if report.note.trim() != expected.note.trim() {
+        problems.push(format!(
+            "Jiná poznámka: očekáváno '{}', Freelo má '{}'",
+            expected.note, report.note
+        ));
+    }
+
+    (Some(report.clone()), problems)
+}
+
+/// Načte frontu oprav profilu
+pub fn load_corrections(profile: &str) -> Vec<PendingCorrection> {
+    profile_crypto::load_json(profile, &profile_dir(profile).join(CORRECTIONS_FILE)).unwrap_or_default()
+}
+
+/// Přidá nesoulad do fronty oprav profilu
+pub fn queue_correction(profile: &str, correction: PendingCorrection) -> Result<(), String> {
+    let mut corrections = load_corrections(profile);
+    corrections.push(correction);
+    profile_crypto::save_json(profile, &profile_dir(profile).join(CORRECTIONS_FILE), &corrections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn expected() -> ExpectedEntry {
+        ExpectedEntry {
+            uuid: "abc".to_string(),
+            task_id: Some(42),
+            note: "Code review".to_string(),
+            started_at: Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap(),
+            stopped_at: Local.with_ymd_and_hms(2025, 3, 12, 9, 45, 30).unwrap(),
+        }
+    }
+
+    fn report(id: i32, task_id: Option<i32>, minutes: i64, note: &str) -> WorkReport {
+        WorkReport { id, task_id, task_name: None, minutes, note: note.to_string(), date: None }
+    }
+
+    #[test]
+    fn test_verify_matching_entry() {
+        let reports = vec![
+            report(1, Some(7), 45, "Code review"),
+            report(2, Some(42), 46, "Code review "),
+        ];
+
+        let (found, problems) = verify(&expected(), &reports);
+        assert_eq!(found.map(|r| r.id), Some(2));
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn test_verify_reports_discrepancies() {
+        let (found, problems) = verify(&expected(), &[]);
+        assert!(found.is_none());
+        assert_eq!(problems.len(), 1);
+
+        let reports = vec![report(3, Some(42), 15, "Obecná práce")];
+        let (_, problems) = verify(&expected(), &reports);
+        assert_eq!(problems.len(), 2);
+    }
+}
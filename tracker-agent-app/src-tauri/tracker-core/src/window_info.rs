@@ -0,0 +1,49 @@
+use crate::screenshot::ScreenRect;
+use tracing::info;
+use xcap::Window;
+
+/// Informace o aktivním (focused) okně
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    /// Název aplikace (procesu), který vlastní okno
+    pub app_name: String,
+    pub title: String,
+    /// Poloha okna na ploše (pro výběr snímaného monitoru)
+    pub rect: Option<ScreenRect>,
+}
+
+impl WindowInfo {
+    /// Text použitelný pro matching místo OCR (název aplikace + titulek okna)
+    pub fn as_context_text(&self) -> String {
+        format!("{} {}", self.app_name, self.title)
+    }
+}
+
+fn window_rect(window: &Window) -> Option<ScreenRect> {
+    Some(ScreenRect {
+        x: window.x().ok()?,
+        y: window.y().ok()?,
+        width: window.width().ok()?,
+        height: window.height().ok()?,
+    })
+}
+
+/// Zjistí aktuálně aktivní okno
+pub fn get_active_window() -> Result<WindowInfo, String> {
+    let windows = Window::all().map_err(|e| format!("Nelze získat seznam oken: {}", e))?;
+
+    let window = windows
+        .into_iter()
+        .find(|w| w.is_focused().unwrap_or(false) && !w.is_minimized().unwrap_or(false))
+        .ok_or_else(|| "Žádné aktivní okno".to_string())?;
+
+    let info = WindowInfo {
+        app_name: window.app_name().unwrap_or_default(),
+        title: window.title().unwrap_or_default(),
+        rect: window_rect(&window),
+    };
+
+    info!("🪟 Aktivní okno: {} | {}", info.app_name, info.title);
+
+    Ok(info)
+}
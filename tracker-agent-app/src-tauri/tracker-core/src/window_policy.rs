@@ -0,0 +1,98 @@
+use crate::events::EventSink;
+use crate::screenshot::{captured_monitor_bounds, CaptureHint, MonitorSelection, ScreenRect};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// Kolik počkat, než se okno po skrytí opravdu zmizí z obrazovky
+const HIDE_SETTLE_MS: u64 = 300;
+
+/// Systém umí okno vynechat ze snímku obrazovky (Windows WDA_EXCLUDEFROMCAPTURE, macOS sharingType)
+const PLATFORM_EXCLUSION: bool = cfg!(any(target_os = "windows", target_os = "macos"));
+
+/// Kdy skrývat okno trackeru před snímkem obrazovky
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HidePolicy {
+    /// Neskrývat, okno se ze snímku vyloučí (bez blikání). Kde to systém neumí,
+    /// jeho plocha se ve snímku začerní.
+    #[default]
+    Exclude,
+    /// Nikdy (např. okno je trvale na jiném monitoru nebo desktopu)
+    Never,
+    /// Jen když okno zasahuje do snímaného monitoru
+    WhenOnCapturedMonitor,
+    /// Vždy - okno zmizí i u systémů, kde vyloučení nefunguje
+    Always,
+}
+
+/// Rozhodnutí podle politiky - při neznámé poloze okna radši skrýt
+fn should_hide_rect(policy: HidePolicy, window: Option<ScreenRect>, monitor: Option<ScreenRect>) -> bool {
+    match policy {
+        HidePolicy::Exclude | HidePolicy::Never => false,
+        HidePolicy::Always => true,
+        HidePolicy::WhenOnCapturedMonitor => match (window, monitor) {
+            (Some(window), Some(monitor)) => window.overlaps(&monitor),
+            _ => true,
+        },
+    }
+}
+
+/// Zda se má hlavní okno před snímkem skrýt
+pub fn should_hide(app: &dyn EventSink, policy: HidePolicy, selection: MonitorSelection, hint: &CaptureHint) -> bool {
+    let Some(window) = app.main_window() else {
+        return false;
+    };
+    if policy != HidePolicy::WhenOnCapturedMonitor {
+        return should_hide_rect(policy, None, None);
+    }
+
+    let window_rect = window.rect;
+    let monitor_rect = captured_monitor_bounds(selection, hint).ok();
+    let hide = should_hide_rect(policy, window_rect, monitor_rect);
+    debug!(?window_rect, ?monitor_rect, hide, "poloha okna vůči snímanému monitoru");
+    hide
+}
+
+/// Vyloučení okna ze snímků podle politiky - vrací plochu okna k začernění,
+/// pokud ho systém ze snímku vynechat neumí (a okno je vidět)
+pub fn capture_exclusion(app: &dyn EventSink, policy: HidePolicy) -> Option<ScreenRect> {
+    let window = app.main_window()?;
+    let exclude = policy == HidePolicy::Exclude;
+    // Ochrana obsahu skryje okno i při sdílení obrazovky, proto jen s touto politikou
+    if let Err(e) = app.set_content_protected(exclude) {
+        debug!(error = %e, "vyloučení okna ze snímku nenastaveno");
+    }
+    if !exclude || PLATFORM_EXCLUSION {
+        return None;
+    }
+    window.visible.then_some(window.rect).flatten()
+}
+
+/// Skryje hlavní okno a počká, než zmizí z obrazovky
+pub async fn hide_main_window(app: &dyn EventSink) -> Result<(), String> {
+    if app.main_window().is_none() {
+        return Ok(());
+    }
+    let result = app.hide_main_window();
+    tokio::time::sleep(tokio::time::Duration::from_millis(HIDE_SETTLE_MS)).await;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_hide_rect() {
+        let monitor = ScreenRect { x: 0, y: 0, width: 1920, height: 1080 };
+        let on_monitor = ScreenRect { x: 100, y: 100, width: 800, height: 600 };
+        let second_monitor = ScreenRect { x: 1920, y: 0, width: 800, height: 600 };
+
+        assert!(!should_hide_rect(HidePolicy::Never, Some(on_monitor), Some(monitor)));
+        assert!(!should_hide_rect(HidePolicy::Exclude, None, Some(monitor)));
+        assert!(should_hide_rect(HidePolicy::Always, Some(second_monitor), Some(monitor)));
+        assert!(should_hide_rect(HidePolicy::WhenOnCapturedMonitor, Some(on_monitor), Some(monitor)));
+        assert!(!should_hide_rect(HidePolicy::WhenOnCapturedMonitor, Some(second_monitor), Some(monitor)));
+        assert!(should_hide_rect(HidePolicy::WhenOnCapturedMonitor, None, Some(monitor)));
+    }
+}
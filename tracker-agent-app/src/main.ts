@@ -4,56 +4,273 @@ import { listen } from "@tauri-apps/api/event";
 // UI Elements
 let statusIndicator: HTMLElement;
 let statusText: HTMLElement;
+let routingBadge: HTMLElement;
 let currentApp: HTMLElement;
 let currentActivity: HTMLElement;
 let currentTask: HTMLElement;
 let trackingSince: HTMLElement;
+let nextTick: HTMLElement;
+let degradationLevel: HTMLElement;
+let taskCount: HTMLElement;
 let logContainer: HTMLElement;
+let timelineContainer: HTMLElement;
+let errorBanners: HTMLElement;
 let startButton: HTMLButtonElement;
 let stopButton: HTMLButtonElement;
+let demoButton: HTMLButtonElement;
+let pauseButton: HTMLButtonElement;
+let paused = false;
 let saveSettingsButton: HTMLButtonElement;
+let focusButton: HTMLButtonElement;
+let focusActive = false;
 
 // Settings inputs
 let intervalInput: HTMLInputElement;
+let adaptiveIntervalInput: HTMLInputElement;
+let maxIntervalInput: HTMLInputElement;
 let freeloEmailInput: HTMLInputElement;
 let freeloKeyInput: HTMLInputElement;
+let backendSelect: HTMLSelectElement;
+let backendTokenInput: HTMLInputElement;
+let backendWorkspaceInput: HTMLInputElement;
 let openrouterKeyInput: HTMLInputElement;
+let llmProviderSelect: HTMLSelectElement;
+let ollamaUrlInput: HTMLInputElement;
+let ollamaModelInput: HTMLInputElement;
+let redactAiInput: HTMLInputElement;
+let embeddingMatching: HTMLInputElement;
+let postActivityCommentsInput: HTMLInputElement;
+let privacyModeInput: HTMLInputElement;
+let aiModelInput: HTMLInputElement;
+let aiTemperatureInput: HTMLInputElement;
+let aiMaxTokensInput: HTMLInputElement;
+let aiTopPInput: HTMLInputElement;
+let tickMaxSecondsInput: HTMLInputElement;
+let tickMaxCostInput: HTMLInputElement;
+let aiDailyBudgetInput: HTMLInputElement;
+let hidePolicySelect: HTMLSelectElement;
+let monitorSelectionSelect: HTMLSelectElement;
+let analysisModeSelect: HTMLSelectElement;
+let ocrEngineSelect: HTMLSelectElement;
+let focusCalendarInput: HTMLInputElement;
+let debugDirInput: HTMLInputElement;
+let focusKeywordInput: HTMLInputElement;
+let sandboxEmailInput: HTMLInputElement;
+let sandboxKeyInput: HTMLInputElement;
+// Kategorie práce se upravují v souboru nastavení, UI je jen zachová
+let workCategories: unknown[] = [];
+// Profil se mění přes switch_profile, uložení nastavení ho jen zachová
+let activeProfile: string | null = null;
+// Výběr projektů platí i bez načteného seznamu (zachová se uložený)
+let selectedProjectIds: number[] = [];
+let projectList: HTMLElement;
+let hotkeyToggleInput: HTMLInputElement;
+let hotkeyRescanInput: HTMLInputElement;
+let hotkeySwitchInput: HTMLInputElement;
+let notifyTaskStartedInput: HTMLInputElement;
+let notifyGeneralWorkInput: HTMLInputElement;
+let notifyFreeloErrorInput: HTMLInputElement;
+let notifyIdlePauseInput: HTMLInputElement;
+let notifyTaskBudgetInput: HTMLInputElement;
+let taskBudgetsInput: HTMLInputElement;
+let budgetFromEstimatesInput: HTMLInputElement;
+let stopOverBudgetInput: HTMLInputElement;
+let quickSwitcher: HTMLDialogElement;
+let quickSwitcherFilter: HTMLInputElement;
+let quickSwitcherList: HTMLElement;
+let quickSwitcherTasks: any[] = [];
+let writeRoutingSelect: HTMLSelectElement;
+let ocrLanguagesInput: HTMLInputElement;
+let ocrLanguageSelect: HTMLSelectElement;
+let ocrLanguageButton: HTMLButtonElement;
+let ocrLanguageStatus: HTMLElement;
 
 // Initialize app
 window.addEventListener("DOMContentLoaded", async () => {
   // Get UI elements
   statusIndicator = document.getElementById("status-indicator")!;
   statusText = document.getElementById("status-text")!;
+  routingBadge = document.getElementById("routing-badge")!;
   currentApp = document.getElementById("current-app")!;
   currentActivity = document.getElementById("current-activity")!;
   currentTask = document.getElementById("current-task")!;
   trackingSince = document.getElementById("tracking-since")!;
+  nextTick = document.getElementById("next-tick")!;
+  degradationLevel = document.getElementById("degradation-level")!;
+  taskCount = document.getElementById("task-count")!;
   logContainer = document.getElementById("log-container")!;
+  timelineContainer = document.getElementById("timeline-container")!;
+  errorBanners = document.getElementById("error-banners")!;
   startButton = document.getElementById("start-tracking") as HTMLButtonElement;
   stopButton = document.getElementById("stop-tracking") as HTMLButtonElement;
+  demoButton = document.getElementById("start-demo") as HTMLButtonElement;
+  pauseButton = document.getElementById("pause-tracking") as HTMLButtonElement;
   saveSettingsButton = document.getElementById("save-settings") as HTMLButtonElement;
+  focusButton = document.getElementById("toggle-focus") as HTMLButtonElement;
 
   intervalInput = document.getElementById("interval") as HTMLInputElement;
+  adaptiveIntervalInput = document.getElementById("adaptive-interval") as HTMLInputElement;
+  maxIntervalInput = document.getElementById("max-interval") as HTMLInputElement;
   freeloEmailInput = document.getElementById("freelo-email") as HTMLInputElement;
   freeloKeyInput = document.getElementById("freelo-key") as HTMLInputElement;
+  backendSelect = document.getElementById("backend") as HTMLSelectElement;
+  backendTokenInput = document.getElementById("backend-token") as HTMLInputElement;
+  backendWorkspaceInput = document.getElementById("backend-workspace") as HTMLInputElement;
   openrouterKeyInput = document.getElementById("openrouter-key") as HTMLInputElement;
+  llmProviderSelect = document.getElementById("llm-provider") as HTMLSelectElement;
+  ollamaUrlInput = document.getElementById("ollama-url") as HTMLInputElement;
+  ollamaModelInput = document.getElementById("ollama-model") as HTMLInputElement;
+  redactAiInput = document.getElementById("redact-ai-input") as HTMLInputElement;
+  embeddingMatching = document.getElementById("embedding-matching") as HTMLInputElement;
+  postActivityCommentsInput = document.getElementById("post-activity-comments") as HTMLInputElement;
+  privacyModeInput = document.getElementById("privacy-mode") as HTMLInputElement;
+  aiModelInput = document.getElementById("ai-model") as HTMLInputElement;
+  aiTemperatureInput = document.getElementById("ai-temperature") as HTMLInputElement;
+  aiMaxTokensInput = document.getElementById("ai-max-tokens") as HTMLInputElement;
+  aiTopPInput = document.getElementById("ai-top-p") as HTMLInputElement;
+  tickMaxSecondsInput = document.getElementById("tick-max-seconds") as HTMLInputElement;
+  tickMaxCostInput = document.getElementById("tick-max-cost") as HTMLInputElement;
+  aiDailyBudgetInput = document.getElementById("ai-daily-budget") as HTMLInputElement;
+  hidePolicySelect = document.getElementById("hide-policy") as HTMLSelectElement;
+  monitorSelectionSelect = document.getElementById("monitor-selection") as HTMLSelectElement;
+  analysisModeSelect = document.getElementById("analysis-mode") as HTMLSelectElement;
+  ocrEngineSelect = document.getElementById("ocr-engine") as HTMLSelectElement;
+  focusCalendarInput = document.getElementById("focus-calendar") as HTMLInputElement;
+  debugDirInput = document.getElementById("debug-dir") as HTMLInputElement;
+  focusKeywordInput = document.getElementById("focus-keyword") as HTMLInputElement;
+  sandboxEmailInput = document.getElementById("sandbox-email") as HTMLInputElement;
+  sandboxKeyInput = document.getElementById("sandbox-key") as HTMLInputElement;
+  writeRoutingSelect = document.getElementById("write-routing") as HTMLSelectElement;
+  ocrLanguagesInput = document.getElementById("ocr-languages") as HTMLInputElement;
+  ocrLanguageSelect = document.getElementById("ocr-language") as HTMLSelectElement;
+  ocrLanguageButton = document.getElementById("download-ocr-language") as HTMLButtonElement;
+  ocrLanguageStatus = document.getElementById("ocr-language-status")!;
+  projectList = document.getElementById("project-list")!;
+  hotkeyToggleInput = document.getElementById("hotkey-toggle") as HTMLInputElement;
+  hotkeyRescanInput = document.getElementById("hotkey-rescan") as HTMLInputElement;
+  hotkeySwitchInput = document.getElementById("hotkey-switch") as HTMLInputElement;
+  notifyTaskStartedInput = document.getElementById("notify-task-started") as HTMLInputElement;
+  notifyGeneralWorkInput = document.getElementById("notify-general-work") as HTMLInputElement;
+  notifyFreeloErrorInput = document.getElementById("notify-freelo-error") as HTMLInputElement;
+  notifyIdlePauseInput = document.getElementById("notify-idle-pause") as HTMLInputElement;
+  notifyTaskBudgetInput = document.getElementById("notify-task-budget") as HTMLInputElement;
+  taskBudgetsInput = document.getElementById("task-budgets") as HTMLInputElement;
+  budgetFromEstimatesInput = document.getElementById("budget-from-estimates") as HTMLInputElement;
+  stopOverBudgetInput = document.getElementById("stop-over-budget") as HTMLInputElement;
+  quickSwitcher = document.getElementById("quick-switcher") as HTMLDialogElement;
+  quickSwitcherFilter = document.getElementById("quick-switcher-filter") as HTMLInputElement;
+  quickSwitcherList = document.getElementById("quick-switcher-list")!;
 
   // Event listeners
   startButton.addEventListener("click", startTracking);
   stopButton.addEventListener("click", stopTracking);
+  demoButton.addEventListener("click", startDemo);
+  pauseButton.addEventListener("click", togglePause);
+  document.getElementById("refresh-tasks")!.addEventListener("click", refreshTasks);
   saveSettingsButton.addEventListener("click", saveSettings);
+  document.getElementById("test-connection")!.addEventListener("click", testConnection);
+  focusButton.addEventListener("click", toggleFocus);
+  writeRoutingSelect.addEventListener("change", setWriteRouting);
+  ocrLanguageButton.addEventListener("click", downloadOcrLanguage);
+  document.getElementById("load-projects")!.addEventListener("click", loadProjects);
+  document.getElementById("load-ai-models")!.addEventListener("click", loadAiModels);
+  document.getElementById("export-csv")!.addEventListener("click", () => exportReport("csv"));
+  document.getElementById("export-xlsx")!.addEventListener("click", () => exportReport("xlsx"));
+  setExportWeek();
+  quickSwitcherFilter.addEventListener("input", renderQuickSwitcher);
+  quickSwitcherFilter.addEventListener("keydown", (event) => {
+    const first = quickSwitcherList.querySelector<HTMLElement>("li");
+    if (event.key === "Enter" && first) {
+      switchToTask(Number(first.dataset.taskId));
+    }
+  });
 
   // Listen for backend events
   await listen("log-event", (event: any) => {
     addLogEntry(event.payload.level, event.payload.message);
   });
 
+  await listen("timeline-event", (event: any) => {
+    addTimelineEntry(event.payload);
+  });
+
+  // Chyby s kódem zůstávají jako banner, dokud nepominou nebo je uživatel nezavře
+  await listen("error-event", (event: any) => {
+    showErrorBanner(event.payload);
+  });
+
+  // Globální zkratka pro rychlý výběr tasku
+  await listen("quick-switcher", openQuickSwitcher);
+
+  // Po přepnutí profilu platí jeho nastavení (přihlašovací údaje, projekty...)
+  await listen("profile-switched", async () => {
+    await loadSettings();
+  });
+
+  await listen("error-cleared", (event: any) => {
+    removeErrorBanner(event.payload as string);
+  });
+  for (const error of (await invoke("get_active_errors")) as any[]) {
+    showErrorBanner(error);
+  }
+
   await listen("tracking-update", (event: any) => {
     updateTrackingInfo(event.payload);
   });
 
+  await listen("freelo-status", (event: any) => {
+    updateFreeloStatus(event.payload);
+  });
+
+  await listen("degradation-level", (event: any) => {
+    updateDegradation(event.payload);
+  });
+  const trackingStatus: any = await invoke("get_tracking_status");
+  if (trackingStatus.running) {
+    updateDegradation(trackingStatus.degradation);
+  }
+
+  await listen("focus-state", (event: any) => {
+    updateFocusState(event.payload);
+  });
+
+  await listen("ocr-language-progress", (event: any) => {
+    const { language, downloaded, total } = event.payload;
+    const percent = total ? Math.round((downloaded / total) * 100) : 0;
+    ocrLanguageStatus.textContent = `${language}: ${percent} %`;
+  });
+  await loadOcrLanguages();
+
+  // Start/stop může přijít i z menu v systémové liště
+  await listen("tracking-state", (event: any) => {
+    const running = event.payload !== "stopped";
+    startButton.disabled = running;
+    demoButton.disabled = running;
+    stopButton.disabled = !running;
+    pauseButton.disabled = !running;
+    if (!running) {
+      updateStatus("inactive", "Zastaveno");
+    } else if (event.payload === "running") {
+      updateStatus("active", "Tracking aktivní");
+    }
+  });
+
+  await listen("tracking-paused", (event: any) => {
+    updatePaused(event.payload as boolean);
+  });
+
+  await listen("tasks-updated", (event: any) => {
+    taskCount.textContent = String(event.payload.count);
+  });
+
+  await listen("tick-schedule", (event: any) => {
+    updateNextTick(event.payload);
+  });
+  updateNextTick(await invoke("get_next_tick_eta"));
+
   // Load saved settings (async)
   await loadSettings();
+  await checkOrphanedTracking();
 
   addLogEntry("info", "Aplikace inicializována");
   updateStatus("inactive", "Připraveno");
@@ -64,7 +281,9 @@ async function startTracking() {
   try {
     await invoke("start_tracking");
     startButton.disabled = true;
+    demoButton.disabled = true;
     stopButton.disabled = false;
+    pauseButton.disabled = false;
     updateStatus("active", "Tracking aktivní");
     addLogEntry("success", "Tracking spuštěn");
   } catch (error) {
@@ -72,12 +291,29 @@ async function startTracking() {
   }
 }
 
+// Ukázka bez Freelo/OpenRouter klíčů - po zastavení platí zase uložené nastavení
+async function startDemo() {
+  try {
+    await invoke("start_demo");
+    startButton.disabled = true;
+    demoButton.disabled = true;
+    stopButton.disabled = false;
+    pauseButton.disabled = false;
+    updateStatus("active", "Ukázkový režim");
+    addLogEntry("success", "Ukázkový režim spuštěn (profil demo)");
+  } catch (error) {
+    addLogEntry("error", `Chyba při spuštění ukázky: ${error}`);
+  }
+}
+
 // Stop tracking
 async function stopTracking() {
   try {
     await invoke("stop_tracking");
     startButton.disabled = false;
+    demoButton.disabled = false;
     stopButton.disabled = true;
+    pauseButton.disabled = true;
     updateStatus("inactive", "Zastaveno");
     addLogEntry("warning", "Tracking zastaven");
   } catch (error) {
@@ -85,44 +321,274 @@ async function stopTracking() {
   }
 }
 
-// Save settings
-async function saveSettings() {
-  const settings = {
+// Pauza bez zastavení smyčky (oběd apod.) - po obnovení pokračuje stejný task
+async function togglePause() {
+  try {
+    await invoke(paused ? "resume_tracking" : "pause_tracking");
+  } catch (error) {
+    addLogEntry("error", `Chyba při pauze: ${error}`);
+  }
+}
+
+function updatePaused(value: boolean) {
+  paused = value;
+  pauseButton.textContent = paused ? "▶️ Pokračovat" : "☕ Pauza";
+  if (!stopButton.disabled) {
+    updateStatus(paused ? "warning" : "active", paused ? "Pozastaveno" : "Tracking aktivní");
+  }
+}
+
+// Nastavení z formuláře (pro uložení i test připojení)
+function collectSettings() {
+  const backend = backendSelect.value;
+  const backendToken = backendTokenInput.value || null;
+  return {
     interval: parseInt(intervalInput.value),
+    adaptive_interval: adaptiveIntervalInput.checked,
+    max_interval: optionalNumber(maxIntervalInput),
     freelo_email: freeloEmailInput.value,
     freelo_key: freeloKeyInput.value,
+    backend: backendSelect.value,
+    toggl_api_token: backend === "toggl" ? backendToken : null,
+    toggl_workspace_id: backend === "toggl" ? optionalNumber(backendWorkspaceInput) : null,
+    clockify_api_key: backend === "clockify" ? backendToken : null,
+    clockify_workspace_id: backend === "clockify" ? backendWorkspaceInput.value || null : null,
     openrouter_key: openrouterKeyInput.value || null,
+    llm_provider: llmProviderSelect.value,
+    ollama_url: ollamaUrlInput.value || null,
+    ollama_model: ollamaModelInput.value || null,
+    redact_ai_input: redactAiInput.checked,
+    embedding_matching: embeddingMatching.checked,
+    post_activity_comments: postActivityCommentsInput.checked,
+    privacy_mode: privacyModeInput.checked,
+    ai_model: aiModelInput.value.trim() || null,
+    ai_temperature: optionalNumber(aiTemperatureInput),
+    ai_max_tokens: optionalNumber(aiMaxTokensInput),
+    ai_top_p: optionalNumber(aiTopPInput),
+    tick_max_seconds: optionalNumber(tickMaxSecondsInput),
+    tick_max_cost_usd: optionalNumber(tickMaxCostInput),
+    ai_daily_budget_usd: optionalNumber(aiDailyBudgetInput),
+    hide_policy: hidePolicySelect.value,
+    monitor_selection: monitorSelectionSelect.value,
+    analysis_mode: analysisModeSelect.value,
+    ocr_engine: ocrEngineSelect.value,
+    ocr_languages: ocrLanguagesInput.value.trim() || null,
+    focus_calendar_path: focusCalendarInput.value || null,
+    debug_dir: debugDirInput.value.trim() || null,
+    focus_keyword: focusKeywordInput.value || null,
+    sandbox_freelo_email: sandboxEmailInput.value || null,
+    sandbox_freelo_key: sandboxKeyInput.value || null,
+    work_categories: workCategories,
+    profile: activeProfile,
+    hotkeys: {
+      toggle_tracking: hotkeyToggleInput.value.trim() || null,
+      rescan: hotkeyRescanInput.value.trim() || null,
+      quick_switch: hotkeySwitchInput.value.trim() || null,
+    },
+    desktop_notifications: {
+      task_started: notifyTaskStartedInput.checked,
+      general_work: notifyGeneralWorkInput.checked,
+      freelo_error: notifyFreeloErrorInput.checked,
+      idle_pause: notifyIdlePauseInput.checked,
+      task_budget: notifyTaskBudgetInput.checked,
+    },
+    task_budgets: taskBudgets(),
+    budget_from_estimates: budgetFromEstimatesInput.checked,
+    stop_over_budget: stopOverBudgetInput.checked,
+    selected_project_ids: selectedProjects(),
+    schedule: workSchedule(),
+    context_providers: Object.fromEntries(
+      contextProviderInputs().map((input) => [input.dataset.provider, input.checked]),
+    ),
   };
+}
 
+// Save settings
+async function saveSettings() {
+  const settings = collectSettings();
   try {
     await invoke("save_settings", { settings });
     addLogEntry("success", "Nastavení uloženo");
-
-    // Save to localStorage
-    localStorage.setItem("tracker-settings", JSON.stringify(settings));
   } catch (error) {
     addLogEntry("error", `Chyba při ukládání: ${error}`);
   }
 }
 
-// Load settings from localStorage
+// Výchozí rozsah exportu - od pondělí do dneška
+function setExportWeek() {
+  const day = (date: Date) => date.toLocaleDateString("sv-SE");
+  const today = new Date();
+  const monday = new Date(today);
+  monday.setDate(today.getDate() - ((today.getDay() + 6) % 7));
+  (document.getElementById("export-from") as HTMLInputElement).value = day(monday);
+  (document.getElementById("export-to") as HTMLInputElement).value = day(today);
+}
+
+async function exportReport(format: "csv" | "xlsx") {
+  const range = {
+    from: (document.getElementById("export-from") as HTMLInputElement).value,
+    to: (document.getElementById("export-to") as HTMLInputElement).value,
+  };
+  const includeFreelo = (document.getElementById("export-include-freelo") as HTMLInputElement).checked;
+  try {
+    const path: string | null = await invoke("export_report", { range, format, includeFreelo });
+    if (path === null) {
+      addLogEntry("info", "Export zrušen");
+    }
+  } catch (error) {
+    addLogEntry("error", `Chyba při exportu: ${error}`);
+  }
+}
+
+// Ověření údajů z formuláře ještě před uložením a startem trackingu
+async function testConnection() {
+  try {
+    const report: any = await invoke("test_connection", { settings: collectSettings() });
+    if (report.settings_error) {
+      addLogEntry("error", `Nastavení: ${report.settings_error}`);
+    }
+    for (const check of report.services as any[]) {
+      const latency = check.latency_ms !== null ? ` (${check.latency_ms} ms)` : "";
+      addLogEntry(check.ok ? "success" : "error", `${check.service}: ${check.message}${latency}`);
+    }
+  } catch (error) {
+    addLogEntry("error", `Chyba při testu připojení: ${error}`);
+  }
+}
+
+function contextProviderInputs(): HTMLInputElement[] {
+  return Array.from(document.querySelectorAll<HTMLInputElement>(".context-provider"));
+}
+
+function scheduleInputs(): HTMLInputElement[] {
+  return Array.from(document.querySelectorAll<HTMLInputElement>(".schedule-day"));
+}
+
+// Okna po dnech, žádné okno v žádném dni = bez omezení (null)
+function workSchedule(): { days: Record<string, string[]> } | null {
+  const days: Record<string, string[]> = {};
+  for (const input of scheduleInputs()) {
+    const windows = input.value.split(",").map((w) => w.trim()).filter((w) => w);
+    if (windows.length) days[input.dataset.day!] = windows;
+  }
+  return Object.keys(days).length ? { days } : null;
+}
+
+// Prázdné pole = null (backend použije výchozí hodnotu)
+// Rozpočty "12345=10, 67890=2.5" - neplatné položky odmítne až backend při uložení
+function taskBudgets(): { task_id: number; hours: number }[] {
+  return taskBudgetsInput.value
+    .split(",")
+    .map((item) => item.trim())
+    .filter((item) => item)
+    .map((item) => {
+      const [taskId, hours] = item.split("=");
+      return { task_id: Number(taskId), hours: Number(hours) };
+    });
+}
+
+function optionalNumber(input: HTMLInputElement): number | null {
+  return input.value === "" ? null : Number(input.value);
+}
+
+// Load settings from disk (starší verze je držely v localStorage)
 async function loadSettings() {
-  const saved = localStorage.getItem("tracker-settings");
-  if (saved) {
-    try {
-      const settings = JSON.parse(saved);
+  try {
+    let settings: any = await invoke("load_settings");
+    const legacy = localStorage.getItem("tracker-settings");
+    if (!settings && legacy) {
+      settings = JSON.parse(legacy);
+    }
+    if (settings) {
       intervalInput.value = settings.interval || "10";
+      adaptiveIntervalInput.checked = settings.adaptive_interval ?? false;
+      maxIntervalInput.value = settings.max_interval ?? "";
       freeloEmailInput.value = settings.freelo_email || "";
       freeloKeyInput.value = settings.freelo_key || "";
+      backendSelect.value = settings.backend || "freelo";
+      backendTokenInput.value = settings.toggl_api_token || settings.clockify_api_key || "";
+      backendWorkspaceInput.value = settings.toggl_workspace_id ?? settings.clockify_workspace_id ?? "";
       openrouterKeyInput.value = settings.openrouter_key || "";
+      llmProviderSelect.value = settings.llm_provider || "open_router";
+      ollamaUrlInput.value = settings.ollama_url || "";
+      ollamaModelInput.value = settings.ollama_model || "";
+      redactAiInput.checked = settings.redact_ai_input ?? true;
+      embeddingMatching.checked = settings.embedding_matching ?? false;
+      postActivityCommentsInput.checked = settings.post_activity_comments ?? false;
+      privacyModeInput.checked = settings.privacy_mode ?? false;
+      aiModelInput.value = settings.ai_model ?? "";
+      aiTemperatureInput.value = settings.ai_temperature ?? "";
+      aiMaxTokensInput.value = settings.ai_max_tokens ?? "";
+      aiTopPInput.value = settings.ai_top_p ?? "";
+      tickMaxSecondsInput.value = settings.tick_max_seconds ?? "";
+      tickMaxCostInput.value = settings.tick_max_cost_usd ?? "";
+      aiDailyBudgetInput.value = settings.ai_daily_budget_usd ?? "";
+      hidePolicySelect.value = settings.hide_policy || "exclude";
+      monitorSelectionSelect.value = settings.monitor_selection || "active";
+      analysisModeSelect.value = settings.analysis_mode || "ocr_text";
+      ocrEngineSelect.value = settings.ocr_engine || "auto";
+      ocrLanguagesInput.value = settings.ocr_languages || "";
+      focusCalendarInput.value = settings.focus_calendar_path || "";
+      debugDirInput.value = settings.debug_dir || "";
+      focusKeywordInput.value = settings.focus_keyword || "";
+      sandboxEmailInput.value = settings.sandbox_freelo_email || "";
+      sandboxKeyInput.value = settings.sandbox_freelo_key || "";
+      workCategories = settings.work_categories || [];
+      activeProfile = settings.profile ?? null;
+      hotkeyToggleInput.value = settings.hotkeys?.toggle_tracking || "";
+      hotkeyRescanInput.value = settings.hotkeys?.rescan || "";
+      hotkeySwitchInput.value = settings.hotkeys?.quick_switch || "";
+      notifyTaskStartedInput.checked = settings.desktop_notifications?.task_started ?? true;
+      notifyGeneralWorkInput.checked = settings.desktop_notifications?.general_work ?? false;
+      notifyFreeloErrorInput.checked = settings.desktop_notifications?.freelo_error ?? true;
+      notifyIdlePauseInput.checked = settings.desktop_notifications?.idle_pause ?? true;
+      notifyTaskBudgetInput.checked = settings.desktop_notifications?.task_budget ?? true;
+      taskBudgetsInput.value = (settings.task_budgets || [])
+        .map((budget: any) => `${budget.task_id}=${budget.hours}`)
+        .join(", ");
+      budgetFromEstimatesInput.checked = settings.budget_from_estimates ?? false;
+      stopOverBudgetInput.checked = settings.stop_over_budget ?? false;
+      selectedProjectIds = settings.selected_project_ids || [];
+      for (const input of scheduleInputs()) {
+        input.value = (settings.schedule?.days?.[input.dataset.day!] || []).join(", ");
+      }
+      for (const input of contextProviderInputs()) {
+        input.checked = settings.context_providers?.[input.dataset.provider!] ?? input.defaultChecked;
+      }
 
-      // Automaticky pošli nastavení do backendu
+      // Automaticky pošli nastavení do backendu (uloží je i na disk)
       await invoke("save_settings", { settings });
-      addLogEntry("info", "Nastavení načteno z localStorage");
-    } catch (error) {
-      console.error("Failed to load settings:", error);
-      addLogEntry("warning", "Nepodařilo se načíst uložená nastavení");
+      localStorage.removeItem("tracker-settings");
+      addLogEntry("info", "Nastavení načteno");
+    }
+  } catch (error) {
+    console.error("Failed to load settings:", error);
+    addLogEntry("warning", "Nepodařilo se načíst uložená nastavení");
+  }
+}
+
+// Timer, který po pádu aplikace zůstal běžet - převzít, nebo zastavit
+async function checkOrphanedTracking() {
+  try {
+    const orphan: any = await invoke("get_orphaned_tracking");
+    if (!orphan) {
+      return;
+    }
+    const since = new Date(orphan.started_at).toLocaleString();
+    const adopt = confirm(
+      `Po minulém ukončení zůstal v ${orphan.backend} běžet timer (${orphan.note || orphan.task_id}, od ${since}).\n\n` +
+        "OK = pokračovat v něm, Zrušit = zastavit ho"
+    );
+    await invoke("recover_tracking", { action: adopt ? "adopt" : "stop" });
+    if (adopt) {
+      startButton.disabled = true;
+      demoButton.disabled = true;
+      stopButton.disabled = false;
+      pauseButton.disabled = false;
+      updateStatus("active", "Tracking aktivní");
     }
+  } catch (error) {
+    addLogEntry("error", `Neukončený timer nelze obnovit: ${error}`);
   }
 }
 
@@ -132,14 +598,245 @@ function updateStatus(status: "active" | "inactive" | "warning", text: string) {
   statusText.textContent = text;
 }
 
+// Freelo výpadek - trvalý "degradovaný" stav, dokud se Freelo neobnoví
+// Sandbox / dry-run - zápisy do Freelo jinam než do ostrého workspace
+// OCR jazyky - nainstalované jsou označené, stáhnout jde jen chybějící
+async function loadOcrLanguages() {
+  const packs: any[] = await invoke("list_ocr_languages");
+  ocrLanguageSelect.innerHTML = "";
+  for (const pack of packs) {
+    const option = document.createElement("option");
+    option.value = pack.code;
+    option.textContent = `${pack.name} (${pack.code})${pack.installed ? " ✓" : ""}`;
+    ocrLanguageSelect.appendChild(option);
+  }
+}
+
+async function downloadOcrLanguage() {
+  const language = ocrLanguageSelect.value;
+  ocrLanguageButton.disabled = true;
+  ocrLanguageStatus.textContent = `${language}: stahuji...`;
+  try {
+    await invoke("download_ocr_language", { language });
+    ocrLanguageStatus.textContent = `${language}: ověřeno ✓`;
+    await loadOcrLanguages();
+    ocrLanguageSelect.value = language;
+  } catch (error) {
+    ocrLanguageStatus.textContent = "";
+    addLogEntry("error", `Chyba stažení OCR jazyka: ${error}`);
+  } finally {
+    ocrLanguageButton.disabled = false;
+  }
+}
+
+async function setWriteRouting() {
+  try {
+    const status = await invoke("set_write_routing", { routing: writeRoutingSelect.value });
+    updateFreeloStatus(status);
+  } catch (error) {
+    addLogEntry("error", `Chyba přepnutí zápisů: ${error}`);
+    updateFreeloStatus(await invoke("get_freelo_status"));
+  }
+}
+
+function updateFreeloStatus(status: any) {
+  writeRoutingSelect.value = status.write_routing;
+  routingBadge.hidden = status.write_routing === "live";
+  if (status.write_routing === "sandbox") {
+    routingBadge.textContent = `🧪 SANDBOX: ${status.sandbox_email || "-"}`;
+  } else if (status.write_routing === "dry_run") {
+    routingBadge.textContent = "🧪 DRY-RUN: nic se nezapisuje";
+  }
+
+  if (!status.online) {
+    const queued = status.queued ? ` (${status.queued} ve frontě)` : "";
+    updateStatus("warning", `Degradováno: Freelo offline od ${status.offline_since}${queued}`);
+  } else if (!startButton.disabled) {
+    updateStatus("inactive", "Zastaveno");
+  } else {
+    updateStatus("active", "Tracking aktivní");
+  }
+}
+
+// Focus blok - nekritická oznámení se odkládají a ukážou se po skončení
+async function toggleFocus() {
+  try {
+    if (focusActive) {
+      await invoke("end_focus_block");
+    } else {
+      await invoke("start_focus_block", { minutes: 60 });
+    }
+  } catch (error) {
+    addLogEntry("error", `Chyba focus bloku: ${error}`);
+  }
+}
+
+async function updateFocusState(status: any) {
+  focusActive = status.active;
+  focusButton.textContent = status.active ? `🎯 Ukončit focus (do ${status.until})` : "🎯 Focus 60 min";
+
+  if (!status.active && status.deferred > 0) {
+    const review: any[] = await invoke("get_focus_review");
+    for (const entry of review) {
+      addLogEntry(entry.level, `[focus ${entry.at}] ${entry.message}`);
+    }
+  }
+}
+
 // Update tracking info
 function updateTrackingInfo(info: any) {
   currentApp.textContent = info.application || "-";
   currentActivity.textContent = info.activity || "-";
   currentTask.textContent = info.task || "Žádný";
+  // Barva projektu jako ve Freelo (pokud ji API vrátilo)
+  currentTask.style.color = info.project?.color || "";
+  currentTask.title = info.project?.name || "";
   trackingSince.textContent = info.since || "-";
 }
 
+// Kdy agent znovu analyzuje obrazovku
+function updateNextTick(eta: any) {
+  nextTick.textContent = eta?.next_at || "-";
+}
+
+// Stupeň degradace analýzy (AI + OCR → jen OCR → jen titulek okna → pozastaveno)
+function updateDegradation(status: any) {
+  const since = status.since ? ` od ${status.since}` : "";
+  degradationLevel.textContent = `${status.label}${since}`;
+  degradationLevel.title = status.reason || "";
+  degradationLevel.classList.toggle("degraded", status.level !== "full");
+}
+
+// Seznam projektů z Freelo - zaškrtnuté omezují matching jen na jejich tasky
+async function loadProjects() {
+  try {
+    const projects: any[] = await invoke("get_projects");
+    projectList.replaceChildren(
+      ...projects.map((project) => {
+        const label = document.createElement("label");
+        label.className = "checkbox-label";
+        const input = document.createElement("input");
+        input.type = "checkbox";
+        input.className = "project-choice";
+        input.value = String(project.id);
+        input.checked = selectedProjectIds.includes(project.id);
+        label.append(input, ` ${project.name}`);
+        return label;
+      }),
+    );
+  } catch (error) {
+    addLogEntry("error", `Chyba při načítání projektů: ${error}`);
+  }
+}
+
+// Katalog OpenRouter do nabídky modelu - u modelů bez obrázků nepůjde analýza screenshotu
+async function loadAiModels() {
+  try {
+    const models: any[] = await invoke("list_available_models");
+    const price = (value: number | null) => (value !== null ? `$${value.toFixed(2)}` : "?");
+    document.getElementById("ai-model-options")!.replaceChildren(
+      ...models.map((model) => {
+        const option = document.createElement("option");
+        option.value = model.id;
+        option.label = `${model.name} (${price(model.prompt_price)} / ${price(model.completion_price)} za 1M tokenů${model.vision ? ", obrázky" : ""})`;
+        return option;
+      }),
+    );
+    addLogEntry("info", `Načteno ${models.length} AI modelů`);
+  } catch (error) {
+    addLogEntry("error", `Chyba při načítání modelů: ${error}`);
+  }
+}
+
+function selectedProjects(): number[] {
+  const inputs = Array.from(projectList.querySelectorAll<HTMLInputElement>(".project-choice"));
+  if (inputs.length > 0) {
+    selectedProjectIds = inputs.filter((input) => input.checked).map((input) => Number(input.value));
+  }
+  return selectedProjectIds;
+}
+
+// Rychlý výběr tasku - filtr podle názvu nebo projektu, Enter vybere první
+async function openQuickSwitcher() {
+  try {
+    quickSwitcherTasks = await invoke("get_tasks");
+  } catch (error) {
+    addLogEntry("error", `${error}`);
+    return;
+  }
+  quickSwitcherFilter.value = "";
+  renderQuickSwitcher();
+  quickSwitcher.showModal();
+  quickSwitcherFilter.focus();
+}
+
+function renderQuickSwitcher() {
+  const query = quickSwitcherFilter.value.trim().toLowerCase();
+  const matching = quickSwitcherTasks.filter((task) =>
+    `${task.name} ${task.project_name}`.toLowerCase().includes(query),
+  );
+  quickSwitcherList.replaceChildren(
+    ...matching.map((task) => {
+      const item = document.createElement("li");
+      item.dataset.taskId = String(task.id);
+      item.textContent = `${task.name} (${task.project_name})`;
+      item.addEventListener("click", () => switchToTask(task.id));
+      return item;
+    }),
+  );
+}
+
+async function switchToTask(taskId: number) {
+  quickSwitcher.close();
+  try {
+    await invoke("switch_task", { taskId });
+  } catch (error) {
+    addLogEntry("error", `Chyba při přepnutí tasku: ${error}`);
+  }
+}
+
+// Ruční obnova tasků (nové tasky z Freelo bez restartu trackingu)
+async function refreshTasks() {
+  try {
+    await invoke("refresh_tasks");
+  } catch (error) {
+    addLogEntry("error", `${error}`);
+  }
+}
+
+// Banner pro chybu - jeden na dedup klíč
+function showErrorBanner(error: any) {
+  removeErrorBanner(error.dedup_key);
+
+  const banner = document.createElement("div");
+  banner.className = `error-banner ${error.severity}`;
+  banner.dataset.key = error.dedup_key;
+
+  const text = document.createElement("div");
+  text.className = "error-text";
+  text.textContent = error.message;
+  const remediation = document.createElement("span");
+  remediation.className = "error-remediation";
+  remediation.textContent = error.remediation;
+  text.appendChild(remediation);
+
+  const close = document.createElement("button");
+  close.className = "btn btn-link";
+  close.textContent = "✕";
+  close.title = "Zavřít";
+  close.addEventListener("click", async () => {
+    banner.remove();
+    await invoke("dismiss_error", { dedupKey: error.dedup_key });
+  });
+
+  banner.append(text, close);
+  errorBanners.appendChild(banner);
+}
+
+function removeErrorBanner(key: string) {
+  errorBanners.querySelector(`[data-key="${key}"]`)?.remove();
+}
+
 // Add log entry
 function addLogEntry(level: string, message: string) {
   const time = new Date().toLocaleTimeString("cs-CZ");
@@ -160,3 +857,48 @@ function addLogEntry(level: string, message: string) {
     logContainer.removeChild(logContainer.firstChild!);
   }
 }
+
+// Krok pipeline trackeru na časové ose
+function timelineText(event: any): [string, string] {
+  switch (event.type) {
+    case "TickStarted":
+      return ["info", `Tick #${event.tick}`];
+    case "OcrCompleted":
+      return ["info", `OCR: ${event.chars} znaků za ${event.duration_ms} ms`];
+    case "MatchCompleted": {
+      const task = event.task_name ?? "bez tasku";
+      const cached = event.cached ? ", beze změny obrazovky" : "";
+      return ["info", `${event.application} → ${task} (${Math.round(event.confidence * 100)} %, ${event.source}${cached})`];
+    }
+    case "TrackingStarted":
+      return ["success", `Start ${event.task_id}${event.local ? " (lokálně)" : ""}: ${event.note}`];
+    case "TrackingStopped":
+      return ["warning", `Stop ${event.task_id} po ${event.minutes} min`];
+    default:
+      return ["info", event.type];
+  }
+}
+
+function addTimelineEntry(event: any) {
+  const [level, message] = timelineText(event);
+  const entry = document.createElement("div");
+  entry.className = `log-entry ${level}`;
+  const time = document.createElement("span");
+  time.className = "log-time";
+  time.textContent = new Date(event.at).toLocaleTimeString("cs-CZ");
+  const text = document.createElement("span");
+  text.className = "log-message";
+  text.textContent = message;
+  entry.append(time, text);
+
+  if (!timelineContainer.querySelector("[data-at]")) {
+    timelineContainer.replaceChildren();
+  }
+  entry.dataset.at = event.at;
+  timelineContainer.appendChild(entry);
+  timelineContainer.scrollTop = timelineContainer.scrollHeight;
+
+  while (timelineContainer.children.length > 200) {
+    timelineContainer.removeChild(timelineContainer.firstChild!);
+  }
+}